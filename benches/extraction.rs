@@ -0,0 +1,64 @@
+//! Measures the main stages of the extraction over the corpus fixtures and a generated
+//! single-file package.
+//!
+//! Run with `cargo bench --features testing`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use daipendency_extractor::{get_parser, Extractor};
+use daipendency_extractor_typescript::testing::{
+    get_corpus_fixture_path, write_single_file_package, CORPUS_FIXTURES,
+};
+use daipendency_extractor_typescript::TypeScriptExtractor;
+use daipendency_testing::tempdir::TempDir;
+
+/// The number of declaration groups in the generated single-file packages, by size.
+const SINGLE_FILE_SIZES: [(&str, usize); 2] = [("medium", 500), ("huge", 5000)];
+
+fn bench_metadata(c: &mut Criterion) {
+    let extractor = TypeScriptExtractor::default();
+    let mut group = c.benchmark_group("metadata");
+    for name in CORPUS_FIXTURES {
+        let package_path = get_corpus_fixture_path(name).join("package");
+        group.bench_function(name, |b| {
+            b.iter(|| extractor.get_library_metadata(black_box(&package_path)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_single_file(c: &mut Criterion) {
+    let extractor = TypeScriptExtractor::default();
+    let mut parser = get_parser(&extractor.get_parser_language()).unwrap();
+    let mut group = c.benchmark_group("single_file");
+    group.sample_size(10);
+    for (size, declaration_count) in SINGLE_FILE_SIZES {
+        let temp_dir = TempDir::new();
+        write_single_file_package(&temp_dir.path, declaration_count).unwrap();
+        let metadata = extractor.get_library_metadata(&temp_dir.path).unwrap();
+        group.bench_function(size, |b| {
+            b.iter(|| extractor.extract_public_api(black_box(&metadata), &mut parser))
+        });
+    }
+    group.finish();
+}
+
+fn bench_full_graph(c: &mut Criterion) {
+    let extractor = TypeScriptExtractor::default();
+    let mut parser = get_parser(&extractor.get_parser_language()).unwrap();
+    let mut group = c.benchmark_group("full_graph");
+    for name in CORPUS_FIXTURES {
+        let package_path = get_corpus_fixture_path(name).join("package");
+        let metadata = extractor.get_library_metadata(&package_path).unwrap();
+        if metadata.entry_point.is_empty() {
+            // There's nothing to extract (e.g. the package doesn't ship declarations).
+            continue;
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| extractor.extract_public_api(black_box(&metadata), &mut parser))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_metadata, bench_single_file, bench_full_graph);
+criterion_main!(benches);
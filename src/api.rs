@@ -1,123 +1,489 @@
+mod assets;
+pub(crate) mod declaration;
+mod dependency_weights;
+mod detail;
+mod enum_like;
+mod extensions;
+mod externals;
+mod file_kinds;
+mod generated;
+mod health;
+mod heritage;
+mod import_hints;
+pub(crate) mod jsdoc;
+mod lookup;
+mod members;
 mod module;
 mod module_set;
+mod parameters;
 mod parsing;
+mod pruning;
+pub(crate) mod queries;
+mod readme;
+mod renaming;
+mod resolution;
 #[cfg(test)]
-mod test_helpers;
+mod robustness;
+mod source_edits;
+mod source_file;
+mod stability;
+mod streaming;
+pub(crate) mod symbol_kind;
+mod symbol_parts;
+pub(crate) mod synthesis;
+#[cfg(test)]
+pub(crate) mod test_helpers;
+mod type_alias;
+pub(crate) mod value_shape;
+mod visitor;
+
+use std::collections::BTreeMap;
 
 use daipendency_extractor::{ExtractionError, Namespace, Symbol};
-use tree_sitter::{Node, Parser};
+use tree_sitter::Parser;
+
+use crate::diagnostics::Diagnostic;
+use crate::metadata::{PackageType, TSEntryPoint, TSLibraryMetadata};
+use crate::options::{DetailLevel, ExtractionOptions};
+use crate::report::{AugmentationReport, ModuleMetrics, ModuleReport};
+use dependency_weights::measure_dependency_weights;
+use enum_like::merge_enum_like_objects;
+use import_hints::make_import_hint;
+use members::remove_members;
+use pruning::find_reachable_modules;
+use renaming::rename_symbol;
+use resolution::{factor_out_named_exports, ExportResolver};
 
-use crate::metadata::TSLibraryMetadata;
+pub use assets::AssetReference;
+pub use dependency_weights::DependencyWeight;
+pub use detail::reduce_detail;
+pub use extensions::{Capture, CustomSymbol, QueryExtension};
+pub use externals::{ExternalExport, UnresolvedExternal, UnresolvedReason};
+pub use file_kinds::{
+    find_file_extension, FileExtension, FileKind, FileKinds, Grammar, FILE_EXTENSIONS,
+};
+pub use generated::Generator;
+pub use health::{check_entry_points, EntryPointHealth, EntryPointStatus};
+pub use heritage::{get_heritage, Heritage};
+pub use import_hints::ImportHint;
+pub use jsdoc::{
+    collect_examples, extract_examples, extract_links, resolve_links, ApiExample, CrossReference,
+    Example, Link, LinkKind, LinkResolution,
+};
+pub use lookup::{
+    find_export_locations, find_internal_symbols, resolve_specifier, ExportLocation, ImportedItem,
+    InternalSymbol,
+};
+pub use members::{get_members, Accessibility, Member, MemberKind};
+pub use module::{
+    Augmentation, ExportTarget, ImportTarget, Module, Pragma, Specifier, TypeScriptSymbol,
+};
+pub use module_set::ModuleSet;
+pub use parameters::{extract_call_signatures, CallSignature, Parameter};
+pub use readme::{check_readme, ReadmeMismatch, ReadmeReference, ReadmeReport, ReadmeSnippet};
+pub use resolution::{ExportedItem, ResolvedExport};
+pub use stability::{get_stability, Stability};
+pub use streaming::PublicApiIter;
+pub use symbol_kind::{get_symbol_kind, SymbolKind};
+pub use symbol_parts::SymbolParts;
+pub use type_alias::{get_type_alias_target, TypeAliasTarget};
+pub use value_shape::{get_value_shape, ValueShape};
+pub use visitor::{visit_modules, SymbolVisitor, VisitContext};
 
+/// Extracts the public API, including the custom symbols produced by the query extensions.
 pub fn extract_public_api(
     library_metadata: &TSLibraryMetadata,
     parser: &mut Parser,
+    options: &ExtractionOptions,
 ) -> Result<Vec<Namespace>, ExtractionError> {
-    let types_path = library_metadata
-        .entry_point
+    let module_set = load_module_set(library_metadata, parser, options)?;
+    let entry_points = resolve_entry_points(library_metadata, &module_set)?;
+    let mut namespaces = build_namespaces(library_metadata, &entry_points, options);
+    add_global_namespace(&mut namespaces, &module_set, options);
+    Ok(namespaces)
+}
+
+/// The public API along with what we know about the modules it was extracted from.
+pub(crate) struct ExtractedPublicApi {
+    pub namespaces: Vec<Namespace>,
+    /// A summary of every module, sorted by path.
+    pub modules: Vec<ModuleReport>,
+    /// The problems encountered whilst loading the modules.
+    pub diagnostics: Vec<Diagnostic>,
+    pub unresolved_externals: Vec<UnresolvedExternal>,
+    /// The size of every module and the time it took to parse, sorted by path.
+    pub module_metrics: Vec<ModuleMetrics>,
+    /// The modules augmented by `declare module` blocks, sorted by specifier.
+    pub augmentations: Vec<AugmentationReport>,
+    /// How to import every export of each entry point, keyed by external path.
+    pub import_hints: BTreeMap<String, Vec<ImportHint>>,
+    /// The names of the namespaces exported by each entry point, keyed by external path.
+    pub exported_namespaces: BTreeMap<String, Vec<String>>,
+    /// The packages whose types the modules refer to, sorted by package name.
+    pub dependency_weights: Vec<DependencyWeight>,
+    /// Whether the public API only consists of global declarations.
+    pub is_global_only: bool,
+}
+
+/// Extracts the public API along with a summary of every module it was extracted from.
+pub(crate) fn extract_public_api_with_modules(
+    library_metadata: &TSLibraryMetadata,
+    parser: &mut Parser,
+    options: &ExtractionOptions,
+) -> Result<ExtractedPublicApi, ExtractionError> {
+    let mut module_set = load_module_set(library_metadata, parser, options)?;
+    let mut resolver = ExportResolver::new(&module_set);
+    let entry_points = resolve_entry_points_with(&mut resolver, library_metadata, &module_set)?;
+    let mut diagnostics = module_set.diagnostics().to_vec();
+    diagnostics.extend_from_slice(resolver.diagnostics());
+    let mut namespaces = build_namespaces(library_metadata, &entry_points, options);
+    let is_global_only = add_global_namespace(&mut namespaces, &module_set, options);
+    if options.prune_unreachable_modules {
+        let reachable_paths = find_reachable_modules(&module_set, &entry_points);
+        module_set.retain_modules(&reachable_paths);
+    }
+    let import_hints = entry_points
+        .iter()
+        .map(|entry_point| {
+            let specifier =
+                get_entry_point_namespace_name(&library_metadata.name, entry_point.entry_point);
+            let hints = entry_point
+                .exports
+                .iter()
+                .map(|export| {
+                    make_import_hint(
+                        &specifier,
+                        export,
+                        entry_point.package_type,
+                        library_metadata.entry_point.is_type_only,
+                    )
+                })
+                .collect();
+            (entry_point.entry_point.external_path.clone(), hints)
+        })
+        .collect();
+    let exported_namespaces = entry_points
         .iter()
-        .find(|entry| entry.external_path == ".")
-        .map(|entry| &entry.internal_path)
-        .ok_or_else(|| ExtractionError::Malformed("No main types path specified".to_string()))?;
+        .map(|entry_point| {
+            let names = entry_point
+                .exports
+                .iter()
+                .filter(|export| matches!(export.item, ExportedItem::Namespace { .. }))
+                .map(|export| export.name.clone())
+                .collect();
+            (entry_point.entry_point.external_path.clone(), names)
+        })
+        .collect();
 
-    let source_code = std::fs::read_to_string(types_path).map_err(ExtractionError::Io)?;
+    let mut modules = module_set
+        .iter()
+        .map(|module| ModuleReport {
+            path: module.path.clone(),
+            lib_references: module.lib_references.clone(),
+            no_default_lib: module.no_default_lib,
+            generator: module.generator,
+        })
+        .collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let tree = parser
-        .parse(&source_code, None)
-        .ok_or_else(|| ExtractionError::Malformed("Failed to parse source".to_string()))?;
+    let mut module_metrics = module_set.metrics().to_vec();
+    module_metrics.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let mut namespaces = vec![Namespace {
-        name: library_metadata.name.clone(),
-        symbols: Vec::new(),
-        doc_comment: None,
-    }];
+    let mut augmentations: BTreeMap<String, AugmentationReport> = BTreeMap::new();
+    let mut sorted_modules = module_set.iter().collect::<Vec<_>>();
+    sorted_modules.sort_by(|a, b| a.path.cmp(&b.path));
+    for module in sorted_modules {
+        for augmentation in &module.augmentations {
+            let report = augmentations
+                .entry(augmentation.specifier.clone())
+                .or_insert_with(|| AugmentationReport {
+                    specifier: augmentation.specifier.clone(),
+                    declared_in: vec![],
+                    symbols: vec![],
+                });
+            if !report.declared_in.contains(&module.path) {
+                report.declared_in.push(module.path.clone());
+            }
+            report.symbols.extend(augmentation.symbols.iter().cloned());
+        }
+    }
 
-    process_node(tree.root_node(), &source_code, &mut namespaces)?;
+    Ok(ExtractedPublicApi {
+        namespaces,
+        modules,
+        diagnostics,
+        unresolved_externals: module_set.unresolved_externals(),
+        module_metrics,
+        augmentations: augmentations.into_values().collect(),
+        import_hints,
+        exported_namespaces,
+        dependency_weights: measure_dependency_weights(&module_set, &library_metadata.name),
+        is_global_only,
+    })
+}
 
-    Ok(namespaces)
+/// Turns the exports of every entry point into namespaces.
+fn build_namespaces(
+    library_metadata: &TSLibraryMetadata,
+    entry_points: &[ResolvedEntryPoint],
+    options: &ExtractionOptions,
+) -> Vec<Namespace> {
+    let mut namespaces = vec![];
+    for entry_point in entry_points {
+        add_namespaces(
+            &mut namespaces,
+            get_entry_point_namespace_name(&library_metadata.name, entry_point.entry_point),
+            entry_point.jsdoc.clone(),
+            &entry_point.exports,
+            None,
+        );
+    }
+    apply_symbol_options(&mut namespaces, options);
+    namespaces
 }
 
-fn process_node(
-    node: Node,
-    source_code: &str,
+/// The name of the namespace with the global declarations of packages that export nothing.
+pub(crate) const GLOBAL_NAMESPACE_NAME: &str = "global";
+
+/// Adds the declarations in the `declare global` blocks of every module as a namespace, if the
+/// entry points export nothing (e.g. polyfills with `export {};`), returning whether it was added.
+///
+/// Globals are otherwise left out, as they're incidental to the exports.
+fn add_global_namespace(
     namespaces: &mut Vec<Namespace>,
-) -> Result<(), ExtractionError> {
-    if node.kind() == "export_statement" {
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            match child.kind() {
-                "enum_declaration"
-                | "interface_declaration"
-                | "class_declaration"
-                | "function_declaration"
-                | "type_alias_declaration" => {
-                    let name = get_declaration_name(&child, source_code).ok_or_else(|| {
-                        ExtractionError::Malformed("Declaration without name".to_string())
-                    })?;
-                    namespaces[0].symbols.push(Symbol {
-                        name,
-                        source_code: get_node_text(node, source_code),
-                    });
-                }
-                "lexical_declaration" => {
-                    let mut var_cursor = child.walk();
-                    for var_child in child.children(&mut var_cursor) {
-                        if var_child.kind() == "variable_declarator" {
-                            let name =
-                                get_declaration_name(&var_child, source_code).ok_or_else(|| {
-                                    ExtractionError::Malformed("Variable without name".to_string())
-                                })?;
-                            namespaces[0].symbols.push(Symbol {
-                                name,
-                                source_code: get_node_text(node, source_code),
-                            });
-                        }
-                    }
-                }
-                "internal_module" => {
-                    let name = get_declaration_name(&child, source_code).ok_or_else(|| {
-                        ExtractionError::Malformed("Namespace without name".to_string())
-                    })?;
-                    namespaces.push(Namespace {
-                        name,
-                        symbols: Vec::new(),
-                        doc_comment: None,
-                    });
-                }
-                _ => {}
+    module_set: &ModuleSet,
+    options: &ExtractionOptions,
+) -> bool {
+    let has_exports = namespaces
+        .iter()
+        .any(|namespace| !namespace.symbols.is_empty());
+    if has_exports {
+        return false;
+    }
+    let mut modules = module_set.iter().collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+    let globals = modules
+        .into_iter()
+        .flat_map(|module| module.globals.iter().cloned())
+        .collect::<Vec<_>>();
+    if globals.is_empty() {
+        return false;
+    }
+    let mut global_namespace = vec![Namespace {
+        name: GLOBAL_NAMESPACE_NAME.to_string(),
+        symbols: globals,
+        doc_comment: None,
+    }];
+    apply_symbol_options(&mut global_namespace, options);
+    namespaces.extend(global_namespace);
+    true
+}
+
+/// Leaves out the class members and the details that the options exclude from the public API.
+fn apply_symbol_options(namespaces: &mut [Namespace], options: &ExtractionOptions) {
+    let excluded_accessibilities = [
+        (options.exclude_protected_members, Accessibility::Protected),
+        (options.exclude_private_members, Accessibility::Private),
+    ]
+    .into_iter()
+    .filter_map(|(is_excluded, accessibility)| is_excluded.then_some(accessibility))
+    .collect::<Vec<_>>();
+    for symbol in namespaces
+        .iter_mut()
+        .flat_map(|namespace| namespace.symbols.iter_mut())
+    {
+        if !excluded_accessibilities.is_empty() {
+            if let Some(filtered_symbol) = remove_members(symbol, &excluded_accessibilities) {
+                *symbol = filtered_symbol;
             }
         }
+        if options.detail_level != DetailLevel::FullSource {
+            *symbol = reduce_detail(symbol, options.detail_level);
+        }
     }
+}
 
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        process_node(child, source_code, namespaces)?;
+/// An entry point along with the declarations it exports.
+struct ResolvedEntryPoint<'a> {
+    entry_point: &'a TSEntryPoint,
+    /// The JSDoc of the entry point module.
+    jsdoc: Option<String>,
+    /// The named exports, followed by the default export (as `default`) if any.
+    exports: Vec<ResolvedExport>,
+    /// The module format of the entry point module.
+    package_type: PackageType,
+}
+
+/// Parses the modules reachable from the entry points of a library, so they can be queried
+/// directly (e.g. with [ModuleSet::find_symbol]).
+pub fn load_module_set(
+    library_metadata: &TSLibraryMetadata,
+    parser: &mut Parser,
+    options: &ExtractionOptions,
+) -> Result<ModuleSet, ExtractionError> {
+    if library_metadata.entry_point.is_empty() {
+        return Err(ExtractionError::Malformed(
+            "No types entry point specified".to_string(),
+        ));
     }
+    ModuleSet::from_entrypoints(&library_metadata.entry_point, parser, options)
+}
 
-    Ok(())
+/// Resolves the exports of each entry point, in order of external path.
+fn resolve_entry_points<'a>(
+    library_metadata: &'a TSLibraryMetadata,
+    module_set: &ModuleSet,
+) -> Result<Vec<ResolvedEntryPoint<'a>>, ExtractionError> {
+    let mut resolver = ExportResolver::new(module_set);
+    resolve_entry_points_with(&mut resolver, library_metadata, module_set)
 }
 
-fn get_declaration_name(node: &Node, source_code: &str) -> Option<String> {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        match child.kind() {
-            "identifier" | "type_identifier" => {
-                return Some(get_node_text(child, source_code));
+/// Resolves the exports of each entry point with an existing resolver, so that the problems it
+/// encounters can be reported.
+fn resolve_entry_points_with<'a>(
+    resolver: &mut ExportResolver,
+    library_metadata: &'a TSLibraryMetadata,
+    module_set: &ModuleSet,
+) -> Result<Vec<ResolvedEntryPoint<'a>>, ExtractionError> {
+    let mut entry_points = library_metadata.entry_point.iter().collect::<Vec<_>>();
+    entry_points.sort_by(|a, b| a.external_path.cmp(&b.external_path));
+
+    entry_points
+        .into_iter()
+        .map(|entry_point| resolve_entry_point(resolver, module_set, entry_point))
+        .collect()
+}
+
+/// Resolves the exports of an entry point, failing if its module isn't in the module set.
+fn resolve_entry_point<'a>(
+    resolver: &mut ExportResolver,
+    module_set: &ModuleSet,
+    entry_point: &'a TSEntryPoint,
+) -> Result<ResolvedEntryPoint<'a>, ExtractionError> {
+    let module = module_set.get(&entry_point.internal_path).ok_or_else(|| {
+        ExtractionError::Malformed(format!(
+            "Entry point '{}' couldn't be loaded from '{}'",
+            entry_point.external_path,
+            entry_point.internal_path.display()
+        ))
+    })?;
+
+    let mut exports = resolver.resolve_exports(module);
+    let default_exports = resolver
+        .resolve_default_export(module)
+        .into_iter()
+        .map(|item| ResolvedExport {
+            name: "default".to_string(),
+            item: factor_out_named_exports(item, &exports),
+            is_type_only: false,
+        })
+        .collect::<Vec<_>>();
+    exports.extend(default_exports);
+
+    Ok(ResolvedEntryPoint {
+        entry_point,
+        jsdoc: module.jsdoc.clone(),
+        exports,
+        package_type: module_set.package_type(module),
+    })
+}
+
+/// Renders the public API as an outline of namespaces and the names of their symbols.
+///
+/// This is meant for debugging extraction results, so the output format is not stable.
+pub fn pretty_print(namespaces: &[Namespace]) -> String {
+    let outlines = namespaces
+        .iter()
+        .map(|namespace| {
+            let mut outline = namespace.name.clone();
+            for symbol in &namespace.symbols {
+                outline.push_str(&format!("\n  {}", symbol.name));
             }
-            _ => {}
-        }
+            outline
+        })
+        .collect::<Vec<_>>();
+    outlines.join("\n\n")
+}
+
+/// Names the namespace of an entry point after the import path users would write (e.g. `pkg/utils`).
+pub(crate) fn get_entry_point_namespace_name(
+    package_name: &str,
+    entry_point: &TSEntryPoint,
+) -> String {
+    match entry_point.external_path.trim_start_matches('.') {
+        "" => package_name.to_string(),
+        subpath => format!("{package_name}{subpath}"),
     }
-    None
 }
 
-fn get_node_text(node: Node, source_code: &str) -> String {
-    source_code[node.start_byte()..node.end_byte()].to_string()
+/// Adds a namespace with the symbols in `exports`, followed by a namespace for each exported namespace.
+///
+/// Exported namespaces are named after their path from the entry point (e.g. `Outer.Inner`), and
+/// symbols exported under another name are presented under that name. Declarations re-exported
+/// from other packages are presented as re-exports noting the package they come from. Enum-like
+/// objects are merged with the types derived from them.
+fn add_namespaces(
+    namespaces: &mut Vec<Namespace>,
+    name: String,
+    doc_comment: Option<String>,
+    exports: &[ResolvedExport],
+    parent_namespace_path: Option<&str>,
+) {
+    let mut namespace = Namespace {
+        name,
+        symbols: Vec::new(),
+        doc_comment,
+    };
+    let mut inner_namespaces = vec![];
+
+    for export in exports {
+        match &export.item {
+            ExportedItem::Symbol(symbol) => {
+                let symbol = if export.name == symbol.name || export.name == "default" {
+                    symbol.clone()
+                } else {
+                    rename_symbol(symbol, &export.name)
+                };
+                if !namespace.symbols.contains(&symbol) {
+                    namespace.symbols.push(symbol);
+                }
+            }
+            ExportedItem::External(external_export) => {
+                let symbol = Symbol {
+                    name: export.name.clone(),
+                    source_code: external_export.render(&export.name, export.is_type_only),
+                };
+                if !namespace.symbols.contains(&symbol) {
+                    namespace.symbols.push(symbol);
+                }
+            }
+            ExportedItem::Namespace { jsdoc, exports } => {
+                let path = match parent_namespace_path {
+                    Some(parent_path) => format!("{parent_path}.{}", export.name),
+                    None => export.name.clone(),
+                };
+                inner_namespaces.push((path, jsdoc, exports));
+            }
+        }
+    }
+
+    merge_enum_like_objects(&mut namespace.symbols);
+    namespaces.push(namespace);
+    for (path, jsdoc, exports) in inner_namespaces {
+        add_namespaces(
+            namespaces,
+            path.clone(),
+            jsdoc.clone(),
+            exports,
+            Some(&path),
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::metadata::TSEntryPoint;
-    use std::collections::HashSet;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use std::collections::BTreeMap;
 
     use super::test_helpers::make_parser;
     use super::*;
@@ -133,9 +499,10 @@ mod tests {
             .unwrap();
         temp_dir.create_file("index.d.ts", content).unwrap();
 
-        let entrypoints = HashSet::from([TSEntryPoint {
+        let entrypoints = TSEntryPointSet::from_iter([TSEntryPoint {
             external_path: ".".to_string(),
             internal_path: temp_dir.path.join("index.d.ts"),
+            conditions: BTreeMap::new(),
         }]);
 
         let library_metadata = TSLibraryMetadata {
@@ -148,6 +515,33 @@ mod tests {
         (temp_dir, library_metadata)
     }
 
+    #[test]
+    fn asset_entry_point() {
+        let temp_dir = TempDir::new();
+        temp_dir.create_file("index.json", "{}").unwrap();
+        let library_metadata = TSLibraryMetadata {
+            name: "test-pkg".to_string(),
+            version: None,
+            documentation: String::new(),
+            entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: temp_dir.path.join("index.json"),
+                conditions: BTreeMap::new(),
+            }]),
+        };
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut make_parser(),
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert!(namespaces
+            .iter()
+            .all(|namespace| namespace.symbols.is_empty()));
+    }
+
     #[test]
     fn exported_interface() {
         let (_temp_dir, library_metadata) =
@@ -168,7 +562,12 @@ mod tests {
             )
         );
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].name, "test-pkg");
@@ -186,26 +585,190 @@ mod tests {
             setup_test_dir("export enum Status { Active = 'active', Inactive = 'inactive' }");
         let mut parser = make_parser();
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].symbols.len(), 1);
         assert_eq!(namespaces[0].symbols[0].name, "Status");
     }
 
+    #[test]
+    fn enum_like_object() {
+        let object = "export declare const Status: {\n    readonly Active: \"active\";\n};";
+        let derived_type = "export type Status = (typeof Status)[keyof typeof Status];";
+        let (_temp_dir, library_metadata) = setup_test_dir(&format!("{object}\n{derived_type}"));
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(namespaces[0].symbols.len(), 1);
+        assert_eq!(namespaces[0].symbols[0].name, "Status");
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            format!("{object}\n{derived_type}")
+        );
+    }
+
+    #[test]
+    fn reexported_external_symbol() {
+        let (temp_dir, library_metadata) =
+            setup_test_dir("export { z } from 'zod';\nexport declare const foo: string;");
+        temp_dir
+            .create_file(
+                "node_modules/zod/package.json",
+                r#"{"name": "zod", "version": "3.23.8"}"#,
+            )
+            .unwrap();
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(namespaces[0].symbols.len(), 2);
+        let symbol = namespaces[0]
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == "z")
+            .unwrap();
+        assert_eq!(
+            symbol.source_code,
+            "// Re-exported from `zod@3.23.8`.\nexport { z } from 'zod';"
+        );
+    }
+
     #[test]
     fn exported_class() {
         let (_temp_dir, library_metadata) =
             setup_test_dir("export class User { constructor(public name: string) {} }");
         let mut parser = make_parser();
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].symbols.len(), 1);
         assert_eq!(namespaces[0].symbols[0].name, "User");
     }
 
+    #[test]
+    fn excluded_protected_members() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "export declare class User {\n  name: string;\n  protected validate(): void;\n}",
+        );
+        let options = ExtractionOptions {
+            exclude_protected_members: true,
+            ..Default::default()
+        };
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(&library_metadata, &mut parser, &options).unwrap();
+
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "export declare class User {\n  name: string;\n}"
+        );
+    }
+
+    #[test]
+    fn excluded_private_members() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "export declare class User {\n  #private;\n  name: string;\n  private token;\n  protected validate(): void;\n}",
+        );
+        let options = ExtractionOptions {
+            exclude_private_members: true,
+            ..Default::default()
+        };
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(&library_metadata, &mut parser, &options).unwrap();
+
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "export declare class User {\n  name: string;\n  protected validate(): void;\n}"
+        );
+    }
+
+    #[test]
+    fn global_declarations_only() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "export {};\ndeclare global {\n  interface Array<T> { findLast(): T | undefined; }\n}",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        let global_namespace = namespaces.last().unwrap();
+        assert_eq!(global_namespace.name, GLOBAL_NAMESPACE_NAME);
+        assert_eq!(
+            global_namespace.symbols,
+            vec![Symbol {
+                name: "Array".to_string(),
+                source_code: "interface Array<T> { findLast(): T | undefined; }".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn global_declarations_with_exports() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "export declare function polyfill(): void;\ndeclare global {\n  interface Window { polyfilled: boolean; }\n}",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert!(namespaces
+            .iter()
+            .all(|namespace| namespace.name != GLOBAL_NAMESPACE_NAME));
+    }
+
+    #[test]
+    fn reduced_detail() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "/** A user. */\nexport declare class User {\n  // The full name\n  name: string;\n}",
+        );
+        let options = ExtractionOptions {
+            detail_level: DetailLevel::Signatures,
+            ..Default::default()
+        };
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(&library_metadata, &mut parser, &options).unwrap();
+
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "export declare class User {\n  name: string;\n}"
+        );
+    }
+
     #[test]
     fn exported_function() {
         let (_temp_dir, library_metadata) = setup_test_dir(
@@ -213,25 +776,131 @@ mod tests {
         );
         let mut parser = make_parser();
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].symbols.len(), 1);
         assert_eq!(namespaces[0].symbols[0].name, "greet");
     }
 
+    #[test]
+    fn source_entry_point() {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "src/index.ts",
+                "export { Client } from './client';\nexport function greet(name: string): string {\n  return `Hello ${name}`;\n}",
+            )
+            .unwrap();
+        temp_dir
+            .create_file(
+                "src/client.ts",
+                "export class Client {\n  send(): void {\n    fetch('/');\n  }\n}",
+            )
+            .unwrap();
+        let library_metadata = TSLibraryMetadata {
+            name: "test-pkg".to_string(),
+            version: Some("1.0.0".to_string()),
+            documentation: String::new(),
+            entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: temp_dir.path.join("src/index.ts"),
+                conditions: BTreeMap::new(),
+            }]),
+        };
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        let source_codes = namespaces[0]
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.source_code.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            source_codes,
+            BTreeMap::from([
+                ("Client", "export class Client {\n  send(): void;\n}"),
+                ("greet", "export function greet(name: string): string;"),
+            ])
+        );
+    }
+
     #[test]
     fn exported_type_alias() {
         let (_temp_dir, library_metadata) = setup_test_dir("export type UserId = string;");
         let mut parser = make_parser();
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].symbols.len(), 1);
         assert_eq!(namespaces[0].symbols[0].name, "UserId");
     }
 
+    #[test]
+    fn aliased_exports() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "declare class InternalClient {}\nexport { InternalClient };\nexport { InternalClient as Client };",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        let symbols = &namespaces[0].symbols;
+        assert_eq!(
+            symbols
+                .iter()
+                .map(|symbol| symbol.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["InternalClient", "Client"]
+        );
+        assert_eq!(
+            symbols[1].source_code,
+            "// Declared as `InternalClient`.\ndeclare class Client {}"
+        );
+    }
+
+    #[test]
+    fn aliased_default_export() {
+        let (_temp_dir, library_metadata) =
+            setup_test_dir("declare const VERSION: string;\nexport default VERSION;");
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(namespaces[0].symbols[0].name, "VERSION");
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "declare const VERSION: string;"
+        );
+    }
+
     #[test]
     fn exported_namespace() {
         let (_temp_dir, library_metadata) =
@@ -252,12 +921,62 @@ mod tests {
             )
         );
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 2);
         assert_eq!(namespaces[1].name, "Utils");
     }
 
+    #[test]
+    fn reexported_symbol() {
+        let (temp_dir, library_metadata) = setup_test_dir("export { Foo } from './foo';");
+        temp_dir
+            .create_file("foo.d.ts", "export declare class Foo {}")
+            .unwrap();
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(namespaces.len(), 1);
+        assert_eq!(namespaces[0].symbols.len(), 1);
+        assert_eq!(namespaces[0].symbols[0].name, "Foo");
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "export declare class Foo {}"
+        );
+    }
+
+    #[test]
+    fn imported_then_exported_symbol() {
+        let (temp_dir, library_metadata) =
+            setup_test_dir("import { Foo } from './foo';\nexport { Foo };");
+        temp_dir
+            .create_file("foo.d.ts", "export declare class Foo {}")
+            .unwrap();
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(namespaces.len(), 1);
+        assert_eq!(namespaces[0].symbols.len(), 1);
+        assert_eq!(namespaces[0].symbols[0].name, "Foo");
+    }
+
     #[test]
     fn exported_variable() {
         let (_temp_dir, library_metadata) =
@@ -272,10 +991,135 @@ mod tests {
             debug_node(&tree.root_node(), "export const VERSION: string = '1.0.0';")
         );
 
-        let namespaces = extract_public_api(&library_metadata, &mut parser).unwrap();
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(namespaces.len(), 1);
         assert_eq!(namespaces[0].symbols.len(), 1);
         assert_eq!(namespaces[0].symbols[0].name, "VERSION");
     }
+
+    #[test]
+    fn package_documentation() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "/**\n * The test package.\n * @packageDocumentation\n */\n\nexport type UserId = string;",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            namespaces[0].doc_comment.as_deref(),
+            Some("/**\n * The test package.\n * @packageDocumentation\n */")
+        );
+        assert_eq!(
+            namespaces[0].symbols[0].source_code,
+            "export type UserId = string;"
+        );
+    }
+
+    #[test]
+    fn declaration_and_export_list() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "export interface A {}\nexport namespace N { const b: string; }\nexport { A, N };",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(pretty_print(&namespaces), "test-pkg\n  A\n\nN\n  b");
+    }
+
+    #[test]
+    fn default_and_named_exports() {
+        let (_temp_dir, library_metadata) = setup_test_dir(
+            "declare namespace React { const useState: string; const version: string; }\nexport import useState = React.useState;\nexport default React;",
+        );
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(
+            &library_metadata,
+            &mut parser,
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            pretty_print(&namespaces),
+            "test-pkg\n  useState\n\ndefault\n  version"
+        );
+        assert_eq!(
+            namespaces[1].doc_comment.as_deref(),
+            Some("/** Also provides the named exports `useState` as members of the default export. */")
+        );
+    }
+
+    #[test]
+    fn query_extensions() {
+        let (_temp_dir, library_metadata) =
+            setup_test_dir("export declare const a: number;\ndeclare const routes: ['/users'];");
+        let extension =
+            QueryExtension::new("(tuple_type (literal_type (string (string_fragment) @route)))")
+                .unwrap()
+                .on_capture("route", |capture| {
+                    Some(CustomSymbol {
+                        kind: "route".to_string(),
+                        name: capture.text().to_string(),
+                        source_code: format!("route {}", capture.text()),
+                    })
+                });
+        let options = ExtractionOptions {
+            query_extensions: vec![extension],
+            ..Default::default()
+        };
+        let mut parser = make_parser();
+
+        let namespaces = extract_public_api(&library_metadata, &mut parser, &options).unwrap();
+
+        assert_eq!(pretty_print(&namespaces), "test-pkg\n  a\n  /users");
+        assert_eq!(namespaces[0].symbols[1].source_code, "route /users");
+    }
+
+    mod pretty_print {
+        use super::*;
+
+        #[test]
+        fn no_namespaces() {
+            let output = pretty_print(&[]);
+
+            assert_eq!(output, "");
+        }
+
+        #[test]
+        fn namespaces_with_symbols() {
+            let (_temp_dir, library_metadata) = setup_test_dir(
+                "export type UserId = string;\nexport interface User { id: UserId; }\nexport namespace Utils {}",
+            );
+            let mut parser = make_parser();
+            let namespaces = extract_public_api(
+                &library_metadata,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let output = pretty_print(&namespaces);
+
+            assert_eq!(output, "test-pkg\n  UserId\n  User\n\nUtils");
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::api::file_kinds::find_file_extension;
+
+/// The extensions of files that bundlers and runtimes let modules import, but which aren't
+/// TypeScript.
+const ASSET_EXTENSIONS: [&str; 20] = [
+    "json", "wasm", "node", "css", "scss", "sass", "less", "svg", "png", "jpg", "jpeg", "gif",
+    "webp", "avif", "ico", "woff", "woff2", "ttf", "otf", "html",
+];
+
+/// The first bytes of common binary formats.
+const BINARY_SIGNATURES: [&[u8]; 9] = [
+    b"\0asm",            // WebAssembly
+    b"\x7fELF",          // Native addons on Linux
+    b"\xcf\xfa\xed\xfe", // Native addons on macOS (64-bit)
+    b"\xce\xfa\xed\xfe", // Native addons on macOS (32-bit)
+    b"\xca\xfe\xba\xbe", // Universal binaries on macOS
+    b"\x89PNG",          // PNG
+    b"GIF8",             // GIF
+    b"\xff\xd8\xff",     // JPEG
+    b"PK\x03\x04",       // ZIP archives
+];
+
+/// How many bytes at the start of a file are inspected to tell whether it's binary.
+const SNIFFED_BYTE_COUNT: usize = 512;
+
+/// A file that the modules refer to but which isn't TypeScript (e.g. `.wasm` or `.json`), so it
+/// wasn't parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetReference {
+    pub path: PathBuf,
+    /// The modules importing or re-exporting the file, sorted by path.
+    pub referencing_files: Vec<PathBuf>,
+}
+
+/// Checks whether a file is an asset rather than a module, going by its extension or, if it has
+/// no extension that modules are loaded from, by its first bytes.
+///
+/// Files that can't be read aren't assets, so that the error surfaces when they're loaded.
+pub(crate) fn is_asset_file(path: &Path) -> bool {
+    if find_file_extension(path).is_some() {
+        return false;
+    }
+    let is_asset_extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+    is_asset_extension || read_start(path).is_some_and(|bytes| is_binary(&bytes))
+}
+
+fn read_start(path: &Path) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let mut bytes = Vec::with_capacity(SNIFFED_BYTE_COUNT);
+    file.take(SNIFFED_BYTE_COUNT as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    Some(bytes)
+}
+
+/// Checks whether the start of a file is that of a binary file: it has the signature of a known
+/// binary format or contains a null byte, which text never does.
+fn is_binary(bytes: &[u8]) -> bool {
+    BINARY_SIGNATURES
+        .iter()
+        .any(|signature| bytes.starts_with(signature))
+        || bytes.contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_testing::tempdir::TempDir;
+
+    #[test]
+    fn asset_extension() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir.create_file("data.json", "{}").unwrap();
+
+        assert!(is_asset_file(&path));
+    }
+
+    #[test]
+    fn module_extension() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir.create_file("index.d.ts", "export {};").unwrap();
+
+        assert!(!is_asset_file(&path));
+    }
+
+    #[test]
+    fn text_without_extension() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir
+            .create_file("exact-file", "export interface Foo {}")
+            .unwrap();
+
+        assert!(!is_asset_file(&path));
+    }
+
+    #[test]
+    fn binary_without_extension() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir.path.join("module");
+        std::fs::write(&path, b"\0asm\x01\0\0\0").unwrap();
+
+        assert!(is_asset_file(&path));
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(!is_asset_file(Path::new("/nonexistent/module")));
+    }
+
+    #[test]
+    fn binary_signatures() {
+        assert!(is_binary(b"\x7fELF\x02\x01\x01"));
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n"));
+        assert!(!is_binary(b"export const foo: string;"));
+    }
+}
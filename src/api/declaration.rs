@@ -0,0 +1,163 @@
+use tree_sitter::Node;
+
+/// A declaration along with the outermost statement wrapping it in modifiers, as found by
+/// [wrap_declaration].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WrappedDeclaration<'a> {
+    /// The statement spanning the declaration and its modifiers, which is the declaration itself
+    /// if it has none.
+    pub(crate) statement: Node<'a>,
+    /// Whether one of the modifiers is `export`.
+    pub(crate) is_exported: bool,
+}
+
+/// Checks whether a node only wraps a declaration with modifiers: `export`, `declare` or the
+/// expression statement that namespaces are parsed as.
+pub(crate) fn is_modifier_wrapper(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "export_statement" | "ambient_declaration" | "expression_statement"
+    )
+}
+
+/// Finds the declaration in the source code of a symbol, looking past comments and modifiers.
+pub(crate) fn find_declaration(root: Node) -> Option<Node> {
+    let mut cursor = root.walk();
+    let statement = root
+        .named_children(&mut cursor)
+        .find(|child| child.kind() != "comment")?;
+    unwrap_declaration(statement)
+}
+
+/// Gets the declaration in a statement, looking past any combination of modifiers (e.g. the
+/// `export` and `declare` in `export declare abstract class Foo {}`).
+pub(crate) fn unwrap_declaration(statement: Node) -> Option<Node> {
+    let mut node = statement;
+    while is_modifier_wrapper(node) {
+        node = match node.kind() {
+            "export_statement" => node
+                .child_by_field_name("declaration")
+                .or_else(|| node.child_by_field_name("value"))?,
+            _ => {
+                let mut cursor = node.walk();
+                let child = node
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() != "comment")?;
+                child
+            }
+        };
+    }
+    Some(node)
+}
+
+/// Climbs from a declaration to the outermost statement wrapping it in modifiers, which is the
+/// inverse of [unwrap_declaration].
+pub(crate) fn wrap_declaration(declaration: Node) -> WrappedDeclaration {
+    let mut wrapped_declaration = WrappedDeclaration {
+        statement: declaration,
+        is_exported: false,
+    };
+    while let Some(parent) = wrapped_declaration.statement.parent() {
+        if !is_modifier_wrapper(parent) {
+            break;
+        }
+        wrapped_declaration.statement = parent;
+        wrapped_declaration.is_exported |= parent.kind() == "export_statement";
+    }
+    wrapped_declaration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use tree_sitter::Tree;
+
+    fn parse(source_code: &str) -> Tree {
+        make_parser().parse(source_code, None).unwrap()
+    }
+
+    mod unwrap_declaration {
+        use super::*;
+
+        #[test]
+        fn without_modifiers() {
+            let tree = parse("interface Foo {}");
+
+            let declaration = find_declaration(tree.root_node()).unwrap();
+
+            assert_eq!(declaration.kind(), "interface_declaration");
+        }
+
+        #[test]
+        fn nested_modifiers() {
+            let tree = parse("/** Docs */\nexport declare abstract class Foo {}");
+
+            let declaration = find_declaration(tree.root_node()).unwrap();
+
+            assert_eq!(declaration.kind(), "abstract_class_declaration");
+        }
+
+        #[test]
+        fn namespace() {
+            let tree = parse("namespace Foo {}");
+
+            let declaration = find_declaration(tree.root_node()).unwrap();
+
+            assert_eq!(declaration.kind(), "internal_module");
+        }
+
+        #[test]
+        fn default_export() {
+            let tree = parse("export default function foo(): void;");
+
+            let declaration = find_declaration(tree.root_node()).unwrap();
+
+            assert_eq!(declaration.kind(), "function_signature");
+        }
+    }
+
+    mod wrap_declaration {
+        use super::*;
+
+        #[test]
+        fn without_modifiers() {
+            let tree = parse("declare namespace N { const enum E { A } }");
+            let namespace = find_declaration(tree.root_node()).unwrap();
+            let body = namespace.child_by_field_name("body").unwrap();
+            let declaration = body.named_child(0).unwrap();
+
+            let wrapped_declaration = wrap_declaration(declaration);
+
+            assert_eq!(wrapped_declaration.statement.id(), declaration.id());
+            assert!(!wrapped_declaration.is_exported);
+        }
+
+        #[test]
+        fn nested_modifiers() {
+            let tree = parse("declare namespace N { export declare const enum E { A } }");
+            let namespace = find_declaration(tree.root_node()).unwrap();
+            let body = namespace.child_by_field_name("body").unwrap();
+            let statement = body.named_child(0).unwrap();
+            let declaration = unwrap_declaration(statement).unwrap();
+
+            let wrapped_declaration = wrap_declaration(declaration);
+
+            assert_eq!(declaration.kind(), "enum_declaration");
+            assert_eq!(wrapped_declaration.statement.id(), statement.id());
+            assert!(wrapped_declaration.is_exported);
+        }
+
+        #[test]
+        fn stops_at_container() {
+            let tree = parse("export declare namespace N {}");
+            let statement = tree.root_node().named_child(0).unwrap();
+            let namespace = find_declaration(tree.root_node()).unwrap();
+
+            let wrapped_declaration = wrap_declaration(namespace);
+
+            assert_eq!(wrapped_declaration.statement.id(), statement.id());
+            assert!(wrapped_declaration.is_exported);
+        }
+    }
+}
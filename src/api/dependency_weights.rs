@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::{Node, Parser};
+
+use crate::api::externals::{get_package_name, is_node_builtin};
+use crate::api::module::{ImportTarget, Module, TypeScriptSymbol};
+use crate::api::module_set::ModuleSet;
+use crate::api::queries::make_parser;
+
+/// How entangled the types of a package are with those of another package, to help judge the
+/// cost of inlining or trimming the dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyWeight {
+    /// The name of the package (e.g. `zod`), or of the module for Node.js builtin modules (e.g.
+    /// `node:fs`).
+    pub package_name: String,
+    /// The number of declarations referring to types from the package.
+    pub symbol_count: usize,
+    /// The modules with such declarations, sorted by path.
+    pub referencing_files: Vec<PathBuf>,
+}
+
+/// Counts the declarations referring to types from each package other than the extracted one,
+/// sorted by package name.
+///
+/// A declaration refers to a package if it names a type imported from it in a type position
+/// (e.g. `Plugin` in `(): Plugin` after `import type { Plugin } from 'vite';`), including
+/// qualified names (e.g. `z.ZodType`) and `typeof` queries, or if it imports a type inline
+/// (e.g. `import('vite').Plugin`).
+pub(crate) fn measure_dependency_weights(
+    module_set: &ModuleSet,
+    package_name: &str,
+) -> Vec<DependencyWeight> {
+    let mut parser = make_parser();
+
+    let mut weights: BTreeMap<String, (usize, BTreeSet<PathBuf>)> = BTreeMap::new();
+    let mut imported_packages: HashMap<PathBuf, HashMap<String, String>> = HashMap::new();
+    for (module, symbol) in module_set.iter_declarations() {
+        let imported_packages = imported_packages
+            .entry(module.path.clone())
+            .or_insert_with(|| get_imported_packages(module));
+        for dependency in get_referenced_packages(symbol, imported_packages, &mut parser) {
+            if dependency == package_name {
+                continue;
+            }
+            let (symbol_count, referencing_files) = weights.entry(dependency).or_default();
+            *symbol_count += 1;
+            referencing_files.insert(module.path.clone());
+        }
+    }
+
+    weights
+        .into_iter()
+        .map(
+            |(package_name, (symbol_count, referencing_files))| DependencyWeight {
+                package_name,
+                symbol_count,
+                referencing_files: referencing_files.into_iter().collect(),
+            },
+        )
+        .collect()
+}
+
+/// Maps the names bound by the imports of other packages in a module to the package names.
+fn get_imported_packages(module: &Module) -> HashMap<String, String> {
+    let mut imported_packages = HashMap::new();
+    for symbol in &module.symbols {
+        let TypeScriptSymbol::ModuleImport {
+            source_module,
+            target,
+            ..
+        } = symbol
+        else {
+            continue;
+        };
+        let Some(dependency) = get_dependency_name(source_module) else {
+            continue;
+        };
+        match target {
+            ImportTarget::Default { name } | ImportTarget::Namespace { name } => {
+                imported_packages.insert(name.clone(), dependency);
+            }
+            ImportTarget::Named { specifiers } => {
+                for specifier in specifiers {
+                    imported_packages
+                        .insert(specifier.alias_or_name().to_string(), dependency.clone());
+                }
+            }
+        }
+    }
+    imported_packages
+}
+
+/// Gets the name of the package a bare specifier refers to, prefixing builtin modules with
+/// `node:` so that `fs` and `node:fs` are counted together.
+fn get_dependency_name(specifier: &str) -> Option<String> {
+    if is_node_builtin(specifier) {
+        let module_name = specifier.strip_prefix("node:").unwrap_or(specifier);
+        return Some(format!("node:{module_name}"));
+    }
+    get_package_name(specifier).map(str::to_string)
+}
+
+/// Lists the packages a declaration refers to, without duplicates.
+fn get_referenced_packages(
+    symbol: &Symbol,
+    imported_packages: &HashMap<String, String>,
+    parser: &mut Parser,
+) -> BTreeSet<String> {
+    let mut packages = BTreeSet::new();
+    let Ok(parsed_file) = ParsedFile::parse(&symbol.source_code, parser) else {
+        return packages;
+    };
+    let source_code = symbol.source_code.as_str();
+    let mut stack = vec![parsed_file.root_node()];
+    while let Some(node) = stack.pop() {
+        let referenced_name = match node.kind() {
+            "type_identifier" => Some(node),
+            "nested_type_identifier" => node.child_by_field_name("module").map(get_leftmost_name),
+            "type_query" => node.named_child(0).map(get_leftmost_name),
+            "call_expression" => {
+                let specifier = get_inline_import_specifier(node, source_code);
+                packages.extend(specifier.and_then(get_dependency_name));
+                None
+            }
+            _ => None,
+        };
+        if let Some(package) =
+            referenced_name.and_then(|name| imported_packages.get(&source_code[name.byte_range()]))
+        {
+            packages.insert(package.clone());
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    packages
+}
+
+/// Gets the first identifier of a qualified name (e.g. `z` in `z.infer`).
+fn get_leftmost_name(node: Node) -> Node {
+    let mut node = node;
+    while let Some(object) = node
+        .child_by_field_name("object")
+        .or_else(|| node.child_by_field_name("module"))
+    {
+        node = object;
+    }
+    node
+}
+
+/// Gets the specifier of an inline import (e.g. `vite` in `import('vite').Plugin`).
+fn get_inline_import_specifier<'a>(node: Node, source_code: &'a str) -> Option<&'a str> {
+    let function = node.child_by_field_name("function")?;
+    if function.kind() != "import" {
+        return None;
+    }
+    let argument = node.child_by_field_name("arguments")?.named_child(0)?;
+    if argument.kind() != "string" {
+        return None;
+    }
+    Some(source_code[argument.byte_range()].trim_matches(['"', '\'']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use crate::options::ExtractionOptions;
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    fn measure(files: &[(&str, &str)]) -> (TempDir, Vec<DependencyWeight>) {
+        let temp_dir = TempDir::new();
+        for (path, content) in files {
+            temp_dir.create_file(path, content).unwrap();
+        }
+        let mut entry_points = TSEntryPointSet::default();
+        entry_points.insert(TSEntryPoint {
+            external_path: ".".to_string(),
+            internal_path: temp_dir.path.join(files[0].0),
+            conditions: BTreeMap::new(),
+        });
+        let mut parser = make_parser();
+        let module_set =
+            ModuleSet::from_entrypoints(&entry_points, &mut parser, &ExtractionOptions::default())
+                .unwrap();
+
+        let weights = measure_dependency_weights(&module_set, "pkg");
+        (temp_dir, weights)
+    }
+
+    #[test]
+    fn named_imports() {
+        let (temp_dir, weights) = measure(&[(
+            "index.d.ts",
+            "import type { Plugin, UserConfig as Config } from 'vite';\nexport declare function plugin(): Plugin;\nexport declare function config(): Config;\nexport declare const VERSION: string;",
+        )]);
+
+        assert_eq!(
+            weights,
+            vec![DependencyWeight {
+                package_name: "vite".to_string(),
+                symbol_count: 2,
+                referencing_files: vec![temp_dir.path.join("index.d.ts")],
+            }]
+        );
+    }
+
+    #[test]
+    fn namespace_and_default_imports() {
+        let (_temp_dir, weights) = measure(&[(
+            "index.d.ts",
+            "import * as z from 'zod';\nimport React from 'react';\nexport declare const schema: z.ZodType<React.ReactNode>;\nexport type Schema = typeof z.string;",
+        )]);
+
+        assert_eq!(
+            weights
+                .iter()
+                .map(|weight| (weight.package_name.as_str(), weight.symbol_count))
+                .collect::<Vec<_>>(),
+            vec![("react", 1), ("zod", 2)]
+        );
+    }
+
+    #[test]
+    fn inline_imports() {
+        let (_temp_dir, weights) = measure(&[(
+            "index.d.ts",
+            "export declare const plugin: import('@vitejs/plugin-react').Options;",
+        )]);
+
+        assert_eq!(weights[0].package_name, "@vitejs/plugin-react");
+        assert_eq!(weights[0].symbol_count, 1);
+    }
+
+    #[test]
+    fn builtin_modules() {
+        let (_temp_dir, weights) = measure(&[
+            (
+                "index.d.ts",
+                "import { Readable } from 'stream';\nexport * from './utils';\nexport declare function read(): Readable;",
+            ),
+            (
+                "utils.d.ts",
+                "import type { Writable } from 'node:stream';\nexport declare function write(): Writable;",
+            ),
+        ]);
+
+        assert_eq!(weights.len(), 1);
+        assert_eq!(weights[0].package_name, "node:stream");
+        assert_eq!(weights[0].referencing_files.len(), 2);
+    }
+
+    #[test]
+    fn shadowed_value_names() {
+        let (_temp_dir, weights) = measure(&[(
+            "index.d.ts",
+            "import { Plugin } from 'vite';\nexport declare function plugin(Plugin: string): void;",
+        )]);
+
+        assert_eq!(weights, vec![]);
+    }
+
+    #[test]
+    fn self_reference() {
+        let (_temp_dir, weights) = measure(&[(
+            "index.d.ts",
+            "import type { Options } from 'pkg/options';\nexport declare function run(options: Options): void;",
+        )]);
+
+        assert_eq!(weights, vec![]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::ops::Range;
+
+use daipendency_extractor::{ParsedFile, Symbol};
+
+use crate::api::members::is_trailing_member_doc;
+use crate::api::queries::make_parser;
+use crate::api::source_edits::remove_ranges;
+use crate::options::DetailLevel;
+
+/// Reduces the source code of a symbol to the given level of detail.
+///
+/// Comments are removed along with the lines they're on, unless they share them with code. The
+/// source code is kept as is if it doesn't parse.
+///
+/// When documentation is kept, so are the comments trailing members without JSDoc on the same
+/// line (e.g. `timeout: number; // in milliseconds`), as they're all the documentation those
+/// members have.
+pub fn reduce_detail(symbol: &Symbol, level: DetailLevel) -> Symbol {
+    let source_code = match level {
+        DetailLevel::FullSource => symbol.source_code.clone(),
+        DetailLevel::NamesOnly => String::new(),
+        DetailLevel::Signatures => remove_comments(&symbol.source_code, false),
+        DetailLevel::SignaturesWithDocs => remove_comments(&symbol.source_code, true),
+    };
+    Symbol {
+        name: symbol.name.clone(),
+        source_code,
+    }
+}
+
+fn remove_comments(source_code: &str, keep_jsdoc: bool) -> String {
+    if !source_code.contains("//") && !source_code.contains("/*") {
+        return source_code.to_string();
+    }
+    let mut parser = make_parser();
+    let Ok(parsed_file) = ParsedFile::parse(source_code, &mut parser) else {
+        return source_code.to_string();
+    };
+
+    let mut removed_ranges = vec![];
+    let mut stack = vec![parsed_file.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "comment" {
+            let is_doc = source_code[node.byte_range()].starts_with("/**")
+                || is_trailing_member_doc(node, source_code);
+            if !(keep_jsdoc && is_doc) {
+                removed_ranges.push(extend_to_lines(node.byte_range(), source_code));
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children = node.children(&mut cursor).collect::<Vec<_>>();
+        stack.extend(children.into_iter().rev());
+    }
+
+    // Comments may share a line, in which case their ranges overlap
+    remove_ranges(source_code, removed_ranges)
+        .trim()
+        .to_string()
+}
+
+/// Extends the range of a comment to the whole line if it has it to itself, or to the whitespace
+/// before it if it ends a line of code.
+fn extend_to_lines(range: Range<usize>, source_code: &str) -> Range<usize> {
+    let line_start = source_code[..range.start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line_end = source_code[range.end..]
+        .find('\n')
+        .map_or(source_code.len(), |index| range.end + index + 1);
+    let is_followed_by_code = !source_code[range.end..line_end].trim().is_empty();
+    if is_followed_by_code {
+        range
+    } else if source_code[line_start..range.start].trim().is_empty() {
+        line_start..line_end
+    } else {
+        source_code[..range.start]
+            .trim_end_matches([' ', '\t'])
+            .len()..range.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reduce(source_code: &str, level: DetailLevel) -> String {
+        let symbol = Symbol {
+            name: "Foo".to_string(),
+            source_code: source_code.to_string(),
+        };
+        reduce_detail(&symbol, level).source_code
+    }
+
+    const SOURCE_CODE: &str = "/** A foo. */\nexport interface Foo {\n  // Internal note\n  /** The bar. */\n  bar: string; // Trailing note\n  baz: /* inline */ number;\n}";
+
+    #[test]
+    fn full_source() {
+        assert_eq!(reduce(SOURCE_CODE, DetailLevel::FullSource), SOURCE_CODE);
+    }
+
+    #[test]
+    fn signatures_with_docs() {
+        assert_eq!(
+            reduce(SOURCE_CODE, DetailLevel::SignaturesWithDocs),
+            "/** A foo. */\nexport interface Foo {\n  /** The bar. */\n  bar: string;\n  baz:  number;\n}"
+        );
+    }
+
+    #[test]
+    fn signatures() {
+        assert_eq!(
+            reduce(SOURCE_CODE, DetailLevel::Signatures),
+            "export interface Foo {\n  bar: string;\n  baz:  number;\n}"
+        );
+    }
+
+    #[test]
+    fn names_only() {
+        assert_eq!(reduce(SOURCE_CODE, DetailLevel::NamesOnly), "");
+    }
+
+    #[test]
+    fn trailing_member_docs() {
+        let source_code = "export interface Foo {\n  readonly timeout: number; // in milliseconds\n  /** The bar. */\n  bar: string; // Trailing note\n}";
+
+        assert_eq!(
+            reduce(source_code, DetailLevel::SignaturesWithDocs),
+            "export interface Foo {\n  readonly timeout: number; // in milliseconds\n  /** The bar. */\n  bar: string;\n}"
+        );
+        assert_eq!(
+            reduce(source_code, DetailLevel::Signatures),
+            "export interface Foo {\n  readonly timeout: number;\n  bar: string;\n}"
+        );
+    }
+
+    #[test]
+    fn comment_like_string() {
+        let source_code = "export declare const URL: \"https://example.com\";";
+
+        assert_eq!(reduce(source_code, DetailLevel::Signatures), source_code);
+    }
+}
@@ -0,0 +1,180 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+
+use crate::api::queries::make_parser;
+use crate::api::value_shape::find_declarator;
+use crate::api::{get_symbol_kind, get_type_alias_target, get_value_shape, SymbolKind};
+
+/// Merges each enum-like object with the type derived from it, since together they stand in
+/// for an `enum`:
+///
+/// ```typescript
+/// export const Status = { Active: 'active', Inactive: 'inactive' } as const;
+/// export type Status = typeof Status[keyof typeof Status];
+/// ```
+///
+/// The merged symbol takes the place of the first of the two, with the source code of the object
+/// followed by that of the type. Declaration files declare the object with its type instead
+/// (e.g. `declare const Status: { readonly Active: "active"; ... };`), which is supported too.
+pub(crate) fn merge_enum_like_objects(symbols: &mut Vec<Symbol>) {
+    let mut index = 0;
+    while index < symbols.len() {
+        let derived_type_index = is_enum_like_object(&symbols[index])
+            .then(|| {
+                symbols.iter().position(|symbol| {
+                    symbol.name == symbols[index].name && is_derived_type(symbol)
+                })
+            })
+            .flatten();
+        if let Some(derived_type_index) = derived_type_index {
+            let derived_type = symbols[derived_type_index].clone();
+            let object = &symbols[index];
+            let merged = Symbol {
+                name: object.name.clone(),
+                source_code: format!("{}\n{}", object.source_code, derived_type.source_code),
+            };
+            symbols[index.min(derived_type_index)] = merged;
+            symbols.remove(index.max(derived_type_index));
+            if derived_type_index < index {
+                // The symbol after the object has taken its place
+                continue;
+            }
+        }
+        index += 1;
+    }
+}
+
+/// Checks whether a symbol is a constant initialised with an object literal asserted with
+/// `as const`, or declared with an object type.
+fn is_enum_like_object(symbol: &Symbol) -> bool {
+    if get_symbol_kind(symbol) != Some(SymbolKind::Variable { is_const: true }) {
+        return false;
+    }
+    if let Some(shape) = get_value_shape(symbol) {
+        return shape.is_const_assertion && shape.literal.starts_with('{');
+    }
+
+    let mut parser = make_parser();
+    let Ok(parsed_file) = ParsedFile::parse(&symbol.source_code, &mut parser) else {
+        return false;
+    };
+    find_declarator(parsed_file.root_node(), &symbol.name, &symbol.source_code)
+        .and_then(|declarator| declarator.child_by_field_name("type"))
+        .and_then(|type_annotation| type_annotation.named_child(0))
+        .is_some_and(|type_node| type_node.kind() == "object_type")
+}
+
+/// Checks whether a symbol is a type alias for the union of the values of the object with the
+/// same name (e.g. `type Status = (typeof Status)[keyof typeof Status];`).
+fn is_derived_type(symbol: &Symbol) -> bool {
+    let Some(target) = get_type_alias_target(symbol) else {
+        return false;
+    };
+    let type_text = target
+        .type_text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '(' && *c != ')')
+        .collect::<String>();
+    type_text == format!("typeof{0}[keyoftypeof{0}]", symbol.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_symbol;
+
+    const OBJECT: &str =
+        "export const Status = { Active: 'active', Inactive: 'inactive' } as const;";
+    const DERIVED_TYPE: &str = "export type Status = typeof Status[keyof typeof Status];";
+
+    #[test]
+    fn const_assertion() {
+        let mut symbols = vec![
+            make_symbol("Status", OBJECT),
+            make_symbol("other", "export declare function other(): void;"),
+            make_symbol("Status", DERIVED_TYPE),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(
+            symbols,
+            vec![
+                make_symbol("Status", &format!("{OBJECT}\n{DERIVED_TYPE}")),
+                make_symbol("other", "export declare function other(): void;"),
+            ]
+        );
+    }
+
+    #[test]
+    fn type_before_object() {
+        let mut symbols = vec![
+            make_symbol("Status", DERIVED_TYPE),
+            make_symbol("Status", OBJECT),
+            make_symbol(
+                "Mode",
+                "export declare const Mode: { readonly On: \"on\" };",
+            ),
+            make_symbol("Mode", "export type Mode = typeof Mode[keyof typeof Mode];"),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].source_code, format!("{OBJECT}\n{DERIVED_TYPE}"));
+        assert_eq!(symbols[1].name, "Mode");
+    }
+
+    #[test]
+    fn declared_object_type() {
+        let object = "export declare const Status: {\n    readonly Active: \"active\";\n};";
+        let derived_type = "export type Status = (typeof Status)[keyof typeof Status];";
+        let mut symbols = vec![
+            make_symbol("Status", object),
+            make_symbol("Status", derived_type),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].source_code, format!("{object}\n{derived_type}"));
+    }
+
+    #[test]
+    fn unrelated_type() {
+        let mut symbols = vec![
+            make_symbol("Status", OBJECT),
+            make_symbol("Status", "export type Status = string;"),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(symbols.len(), 2);
+    }
+
+    #[test]
+    fn mutable_object() {
+        let mut symbols = vec![
+            make_symbol(
+                "Status",
+                "export let Status = { Active: 'active' } as const;",
+            ),
+            make_symbol("Status", DERIVED_TYPE),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(symbols.len(), 2);
+    }
+
+    #[test]
+    fn keys_of_object() {
+        let mut symbols = vec![
+            make_symbol("Status", OBJECT),
+            make_symbol("Status", "export type Status = keyof typeof Status;"),
+        ];
+
+        merge_enum_like_objects(&mut symbols);
+
+        assert_eq!(symbols.len(), 2);
+    }
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::sync::Arc;
+
+use daipendency_extractor::ExtractionError;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor};
+
+use crate::api::queries::get_typescript_language;
+
+/// A handler turning a captured node into a custom symbol, if it's relevant.
+type CaptureHandler = Arc<dyn Fn(&Capture) -> Option<CustomSymbol> + Send + Sync>;
+
+/// A tree-sitter query run on every declaration file, whose captures can be turned into custom
+/// symbols by handlers registered by capture name.
+///
+/// This is an escape hatch for constructs specific to an organisation or framework that the
+/// extractor doesn't otherwise surface. Custom symbols are always part of the public API of the
+/// module they're found in.
+#[derive(Clone)]
+pub struct QueryExtension {
+    query: Arc<Query>,
+    handlers: Vec<(String, CaptureHandler)>,
+}
+
+/// A node captured by a [QueryExtension].
+pub struct Capture<'a> {
+    pub node: Node<'a>,
+    /// The source code of the whole file.
+    pub source_code: &'a str,
+}
+
+/// A symbol produced by a [QueryExtension] handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomSymbol {
+    /// What the symbol represents (e.g. `route`), which is up to the extension.
+    pub kind: String,
+    pub name: String,
+    pub source_code: String,
+}
+
+impl QueryExtension {
+    /// Compiles a query against the TypeScript grammar.
+    pub fn new(query: &str) -> Result<Self, ExtractionError> {
+        let query = Query::new(get_typescript_language(), query).map_err(|error| {
+            ExtractionError::Malformed(format!("Invalid query extension: {error}"))
+        })?;
+        Ok(Self {
+            query: Arc::new(query),
+            handlers: vec![],
+        })
+    }
+
+    /// Registers a handler for the nodes captured under `capture_name` (without the `@`).
+    pub fn on_capture<F>(mut self, capture_name: &str, handler: F) -> Self
+    where
+        F: Fn(&Capture) -> Option<CustomSymbol> + Send + Sync + 'static,
+    {
+        self.handlers
+            .push((capture_name.to_string(), Arc::new(handler)));
+        self
+    }
+
+    /// Runs the query on the tree of a file and collects the symbols produced by the handlers.
+    pub(crate) fn run(&self, root: Node, source_code: &str) -> Vec<CustomSymbol> {
+        let capture_names = self.query.capture_names();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, root, source_code.as_bytes());
+
+        let mut symbols = vec![];
+        while let Some(query_match) = matches.next() {
+            for capture in query_match.captures {
+                let capture_name = capture_names[capture.index as usize];
+                let capture = Capture {
+                    node: capture.node,
+                    source_code,
+                };
+                symbols.extend(
+                    self.handlers
+                        .iter()
+                        .filter(|(name, _)| name == capture_name)
+                        .filter_map(|(_, handler)| handler(&capture)),
+                );
+            }
+        }
+        symbols
+    }
+}
+
+impl Capture<'_> {
+    /// The source code of the captured node.
+    pub fn text(&self) -> &str {
+        &self.source_code[self.node.byte_range()]
+    }
+}
+
+impl fmt::Debug for QueryExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryExtension")
+            .field("captures", &self.query.capture_names())
+            .field(
+                "handlers",
+                &self
+                    .handlers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Extensions are only equal to their clones, as handlers can't be compared.
+impl PartialEq for QueryExtension {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.query, &other.query)
+            && self.handlers.len() == other.handlers.len()
+            && self
+                .handlers
+                .iter()
+                .zip(&other.handlers)
+                .all(|((a_name, a), (b_name, b))| a_name == b_name && Arc::ptr_eq(a, b))
+    }
+}
+
+impl Eq for QueryExtension {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+
+    fn run(extension: &QueryExtension, source_code: &str) -> Vec<CustomSymbol> {
+        let mut parser = make_parser();
+        let tree = parser.parse(source_code, None).unwrap();
+        extension.run(tree.root_node(), source_code)
+    }
+
+    fn make_route_extension() -> QueryExtension {
+        QueryExtension::new(
+            r#"(call_expression
+                function: (identifier) @function (#eq? @function "route")
+                arguments: (arguments (string (string_fragment) @path))) @route"#,
+        )
+        .unwrap()
+        .on_capture("path", |capture| {
+            Some(CustomSymbol {
+                kind: "route".to_string(),
+                name: capture.text().to_string(),
+                source_code: capture.text().to_string(),
+            })
+        })
+    }
+
+    #[test]
+    fn invalid_query() {
+        let result = QueryExtension::new("(not_a_node) @capture");
+
+        assert!(matches!(
+            result,
+            Err(ExtractionError::Malformed(message)) if message.starts_with("Invalid query extension")
+        ));
+    }
+
+    #[test]
+    fn handler_output() {
+        let extension = make_route_extension();
+
+        let symbols = run(&extension, "route('/users');\nroute('/posts');");
+
+        assert_eq!(
+            symbols
+                .iter()
+                .map(|symbol| symbol.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/users", "/posts"]
+        );
+        assert_eq!(symbols[0].kind, "route");
+    }
+
+    #[test]
+    fn captures_without_handlers() {
+        let extension = QueryExtension::new("(string) @string").unwrap();
+
+        let symbols = run(&extension, "route('/users');");
+
+        assert!(symbols.is_empty());
+    }
+
+    #[test]
+    fn handler_skipping_capture() {
+        let extension = QueryExtension::new("(identifier) @identifier")
+            .unwrap()
+            .on_capture("identifier", |capture| {
+                (capture.text() == "wanted").then(|| CustomSymbol {
+                    kind: "identifier".to_string(),
+                    name: capture.text().to_string(),
+                    source_code: capture.text().to_string(),
+                })
+            });
+
+        let symbols = run(&extension, "wanted; unwanted;");
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "wanted");
+    }
+
+    #[test]
+    fn equality() {
+        let extension = make_route_extension();
+
+        assert_eq!(extension, extension.clone());
+        assert_ne!(extension, make_route_extension());
+    }
+}
@@ -0,0 +1,641 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::dependencies::DependencyResolver;
+use crate::jsonc::parse_jsonc;
+
+/// The Node.js builtin modules that can be imported without the `node:` prefix.
+const NODE_BUILTIN_MODULES: &[&str] = &[
+    "assert",
+    "assert/strict",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "dns/promises",
+    "domain",
+    "events",
+    "fs",
+    "fs/promises",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "inspector/promises",
+    "module",
+    "net",
+    "os",
+    "path",
+    "path/posix",
+    "path/win32",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "readline/promises",
+    "repl",
+    "stream",
+    "stream/consumers",
+    "stream/promises",
+    "stream/web",
+    "string_decoder",
+    "sys",
+    "timers",
+    "timers/promises",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "util/types",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// The package providing the types of the Node.js builtin modules.
+const NODE_TYPES_PACKAGE: &str = "@types/node";
+
+/// An import or re-export of another package (e.g. `import { Plugin } from 'vite';`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExternalReference {
+    pub specifier: String,
+    /// The module importing or re-exporting the package.
+    pub importer: PathBuf,
+    /// The directory of the package, if it was found. For builtin modules, this is the
+    /// directory of `@types/node`.
+    pub package_path: Option<PathBuf>,
+    /// The version of the package, if it was found and it has one. Builtin modules have none.
+    pub package_version: Option<String>,
+    pub is_builtin: bool,
+}
+
+/// A declaration of another package that is re-exported (e.g. `z` in `export { z } from 'zod';`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExternalExport {
+    /// The specifier of the module the declaration is re-exported from (e.g. `zod/v4`).
+    pub specifier: String,
+    /// The name of the package the declaration comes from (e.g. `zod`), or the name of the
+    /// module for Node.js builtin modules (e.g. `node:fs`).
+    pub package_name: String,
+    /// The version of the package, if it was found. For packages that only provide their types
+    /// through DefinitelyTyped, this is the version of the `@types` package.
+    pub version: Option<String>,
+    /// The name of the declaration in the module (`default` for its default export), or `None` if
+    /// the whole module is re-exported as a namespace (e.g. `export * as z from 'zod';`).
+    pub name: Option<String>,
+}
+
+impl ExternalExport {
+    pub(crate) fn new(reference: &ExternalReference, name: Option<&str>) -> Self {
+        let package_name = get_package_name(&reference.specifier).unwrap_or(&reference.specifier);
+        Self {
+            specifier: reference.specifier.clone(),
+            package_name: package_name.to_string(),
+            version: reference.package_version.clone(),
+            name: name.map(str::to_string),
+        }
+    }
+
+    /// Renders the re-export under `exported_name`, noting where the declaration comes from
+    /// (e.g. `// Re-exported from `zod@3.23.8`.` followed by `export { z } from 'zod';`).
+    pub(crate) fn render(&self, exported_name: &str, is_type_only: bool) -> String {
+        let origin = match &self.version {
+            Some(version) => format!("{}@{version}", self.package_name),
+            None => self.package_name.clone(),
+        };
+        let type_keyword = if is_type_only { " type" } else { "" };
+        let clause = match self.name.as_deref() {
+            None => format!("* as {exported_name}"),
+            Some(name) if name == exported_name => format!("{{ {name} }}"),
+            Some(name) => format!("{{ {name} as {exported_name} }}"),
+        };
+        format!(
+            "// Re-exported from `{origin}`.\nexport{type_keyword} {clause} from '{}';",
+            self.specifier
+        )
+    }
+}
+
+/// A bare import specifier (e.g. `vite/client`) whose types are missing from the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedExternal {
+    pub specifier: String,
+    pub reason: UnresolvedReason,
+    /// The number of imports and re-exports of the specifier, counting each kind of import
+    /// target in a statement separately (e.g. twice for `import React, { useState } from 'react';`).
+    pub reference_count: usize,
+    /// The modules importing or re-exporting the specifier, sorted by path.
+    pub referencing_files: Vec<PathBuf>,
+}
+
+/// Why the types a specifier refers to are missing from the output.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnresolvedReason {
+    /// The package couldn't be found (e.g. an optional dependency that isn't installed).
+    Missing,
+    /// The package was found, but the extraction doesn't follow imports into other packages.
+    ///
+    /// When several copies of the package are installed (e.g. in nested `node_modules`
+    /// directories), each importer uses the nearest one, so the copies are reported separately.
+    NotFollowed {
+        package_path: PathBuf,
+        /// The version of the copy that was found, if its manifest has one.
+        package_version: Option<String>,
+    },
+    /// The specifier is a Node.js builtin module, whose types are in `@types/node` if it's
+    /// installed.
+    Builtin { types_package_path: Option<PathBuf> },
+}
+
+impl UnresolvedExternal {
+    /// Groups references by specifier and reason, sorted by specifier.
+    pub(crate) fn from_references(references: &[ExternalReference]) -> Vec<Self> {
+        let mut groups = BTreeMap::<(&str, UnresolvedReason), Vec<&Path>>::new();
+        for reference in references {
+            let reason = match &reference.package_path {
+                _ if reference.is_builtin => UnresolvedReason::Builtin {
+                    types_package_path: reference.package_path.clone(),
+                },
+                Some(package_path) => UnresolvedReason::NotFollowed {
+                    package_path: package_path.clone(),
+                    package_version: reference.package_version.clone(),
+                },
+                None => UnresolvedReason::Missing,
+            };
+            groups
+                .entry((&reference.specifier, reason))
+                .or_default()
+                .push(&reference.importer);
+        }
+
+        groups
+            .into_iter()
+            .map(|((specifier, reason), importers)| {
+                let mut referencing_files = importers
+                    .iter()
+                    .map(|importer| importer.to_path_buf())
+                    .collect::<Vec<_>>();
+                referencing_files.sort();
+                referencing_files.dedup();
+                Self {
+                    specifier: specifier.to_string(),
+                    reason,
+                    reference_count: importers.len(),
+                    referencing_files,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The subset of a `package.json` needed to find packages referring to themselves and to tell
+/// their versions.
+#[derive(Debug, Deserialize)]
+struct PackageNameManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// Finds the directory of the package that a bare specifier (e.g. `vite/client`) refers to, which
+/// is `@types/node` for the Node.js builtin modules.
+///
+/// The package is looked up with the dependency resolver from the package containing the
+/// importer, unless it's that package referring to itself by name. Packages that only provide
+/// their types through DefinitelyTyped (e.g. `@types/react`) count as found.
+pub(crate) fn resolve_external_package(
+    dependency_resolver: &DependencyResolver,
+    importer: &Path,
+    specifier: &str,
+    host_root: Option<&Path>,
+) -> Option<PathBuf> {
+    let package_name = if is_node_builtin(specifier) {
+        NODE_TYPES_PACKAGE
+    } else {
+        get_package_name(specifier)?
+    };
+    let dependant_path = importer
+        .ancestors()
+        .skip(1)
+        .find(|directory| directory.join("package.json").is_file())?;
+    if read_package_manifest(dependant_path)?.name.as_deref() == Some(package_name) {
+        return Some(dependant_path.to_path_buf());
+    }
+
+    let types_package_name = format!("@types/{}", get_types_package_name(package_name));
+    let package_path = [package_name, types_package_name.as_str()]
+        .into_iter()
+        .find_map(|name| {
+            dependency_resolver
+                .resolve(name, dependant_path, host_root)
+                .ok()
+        });
+    package_path
+}
+
+/// Reads the version of the package in a directory, if its manifest has one.
+pub(crate) fn read_package_version(package_path: &Path) -> Option<String> {
+    read_package_manifest(package_path)?.version
+}
+
+fn read_package_manifest(package_path: &Path) -> Option<PackageNameManifest> {
+    let manifest = std::fs::read_to_string(package_path.join("package.json")).ok()?;
+    parse_jsonc::<PackageNameManifest>(&manifest).ok()
+}
+
+/// Gets the name of the package a specifier refers to (e.g. `@scope/pkg` in `@scope/pkg/sub`),
+/// or `None` if it isn't a bare specifier.
+pub(crate) fn get_package_name(specifier: &str) -> Option<&str> {
+    if specifier.is_empty()
+        || specifier.starts_with('.')
+        || specifier.starts_with('/')
+        || specifier.starts_with('#')
+        || specifier.contains(':')
+    {
+        return None;
+    }
+    let mut separators = specifier.match_indices('/').map(|(index, _)| index);
+    let end = if specifier.starts_with('@') {
+        separators.nth(1)
+    } else {
+        separators.next()
+    };
+    Some(&specifier[..end.unwrap_or(specifier.len())])
+}
+
+/// Checks whether a specifier refers to a Node.js builtin module (e.g. `node:fs` or `path`).
+///
+/// Builtin modules take precedence over installed packages with the same name, as in Node.js.
+pub(crate) fn is_node_builtin(specifier: &str) -> bool {
+    specifier.starts_with("node:") || NODE_BUILTIN_MODULES.contains(&specifier)
+}
+
+/// Gets the name of the DefinitelyTyped package of a package, without the `@types/` prefix
+/// (e.g. `babel__core` for `@babel/core`).
+fn get_types_package_name(package_name: &str) -> String {
+    package_name
+        .strip_prefix('@')
+        .map(|scoped_name| scoped_name.replacen('/', "__", 1))
+        .unwrap_or_else(|| package_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_testing::tempdir::TempDir;
+
+    mod package_names {
+        use super::*;
+
+        #[test]
+        fn unscoped_package() {
+            assert_eq!(get_package_name("vite"), Some("vite"));
+            assert_eq!(get_package_name("vite/client"), Some("vite"));
+        }
+
+        #[test]
+        fn scoped_package() {
+            assert_eq!(get_package_name("@scope/pkg"), Some("@scope/pkg"));
+            assert_eq!(get_package_name("@scope/pkg/sub/path"), Some("@scope/pkg"));
+        }
+
+        #[test]
+        fn non_bare_specifiers() {
+            for specifier in ["./foo", "../foo", "/abs/foo", "#internal", "node:fs", ""] {
+                assert_eq!(get_package_name(specifier), None, "{specifier}");
+            }
+        }
+
+        #[test]
+        fn node_builtins() {
+            for specifier in ["node:fs", "node:test", "fs", "fs/promises", "path"] {
+                assert!(is_node_builtin(specifier), "{specifier}");
+            }
+            for specifier in ["fs-extra", "node-fetch", "pathe", "./fs"] {
+                assert!(!is_node_builtin(specifier), "{specifier}");
+            }
+        }
+
+        #[test]
+        fn types_package_names() {
+            assert_eq!(get_types_package_name("react"), "react");
+            assert_eq!(get_types_package_name("@babel/core"), "babel__core");
+        }
+    }
+
+    mod resolution {
+        use super::*;
+        use crate::options::ExtractionOptions;
+
+        fn resolve(importer: &Path, specifier: &str) -> Option<PathBuf> {
+            resolve_external_package(&DependencyResolver::default(), importer, specifier, None)
+        }
+
+        #[test]
+        fn package_in_ancestor_node_modules() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", "{}")
+                .unwrap();
+            let importer = temp_dir.create_file("src/lib/index.d.ts", "").unwrap();
+
+            let directory = resolve(&importer, "vite/client");
+
+            assert_eq!(directory, Some(temp_dir.path.join("node_modules/vite")));
+        }
+
+        #[test]
+        fn types_package() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/@types/babel__core/index.d.ts", "")
+                .unwrap();
+            let importer = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            let directory = resolve(&importer, "@babel/core");
+
+            assert_eq!(
+                directory,
+                Some(temp_dir.path.join("node_modules/@types/babel__core"))
+            );
+        }
+
+        #[test]
+        fn self_reference() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"name": "zod"}"#)
+                .unwrap();
+            let importer = temp_dir.create_file("lib/index.d.ts", "").unwrap();
+
+            let directory = resolve(&importer, "zod/v4");
+
+            assert_eq!(directory, Some(temp_dir.path.clone()));
+        }
+
+        #[test]
+        fn builtin_module() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/@types/node/index.d.ts", "")
+                .unwrap();
+            let importer = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            let directory = resolve(&importer, "node:fs");
+
+            assert_eq!(
+                directory,
+                Some(temp_dir.path.join("node_modules/@types/node"))
+            );
+        }
+
+        #[test]
+        fn nearest_copy() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", r#"{"version": "5.0.0"}"#)
+                .unwrap();
+            temp_dir
+                .create_file("node_modules/plugin/package.json", "{}")
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "node_modules/plugin/node_modules/vite/package.json",
+                    r#"{"version": "4.0.0"}"#,
+                )
+                .unwrap();
+            let root_importer = temp_dir.create_file("index.d.ts", "").unwrap();
+            let plugin_importer = temp_dir
+                .create_file("node_modules/plugin/index.d.ts", "")
+                .unwrap();
+            let resolver = DependencyResolver::default();
+
+            let root_directory =
+                resolve_external_package(&resolver, &root_importer, "vite", None).unwrap();
+            let plugin_directory =
+                resolve_external_package(&resolver, &plugin_importer, "vite", None).unwrap();
+
+            assert_eq!(root_directory, temp_dir.path.join("node_modules/vite"));
+            assert_eq!(
+                plugin_directory,
+                temp_dir.path.join("node_modules/plugin/node_modules/vite")
+            );
+            assert_eq!(
+                read_package_version(&root_directory).as_deref(),
+                Some("5.0.0")
+            );
+            assert_eq!(
+                read_package_version(&plugin_directory).as_deref(),
+                Some("4.0.0")
+            );
+        }
+
+        #[test]
+        fn workspace_package() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("packages/shared/package.json", "{}")
+                .unwrap();
+            let importer = temp_dir.create_file("index.d.ts", "").unwrap();
+            let options = ExtractionOptions {
+                workspace_packages: BTreeMap::from([(
+                    "@acme/shared".to_string(),
+                    temp_dir.path.join("packages/shared"),
+                )]),
+                ..ExtractionOptions::default()
+            };
+
+            let directory = resolve_external_package(
+                &DependencyResolver::new(&options),
+                &importer,
+                "@acme/shared",
+                None,
+            );
+
+            assert_eq!(directory, Some(temp_dir.path.join("packages/shared")));
+        }
+
+        #[test]
+        fn missing_package() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            let importer = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            let directory = resolve(&importer, "missing-pkg");
+
+            assert_eq!(directory, None);
+        }
+    }
+
+    mod external_exports {
+        use super::*;
+
+        fn make_export(
+            specifier: &str,
+            version: Option<&str>,
+            name: Option<&str>,
+        ) -> ExternalExport {
+            let reference = ExternalReference {
+                specifier: specifier.to_string(),
+                importer: PathBuf::from("/pkg/index.d.ts"),
+                package_path: None,
+                package_version: version.map(str::to_string),
+                is_builtin: is_node_builtin(specifier),
+            };
+            ExternalExport::new(&reference, name)
+        }
+
+        #[test]
+        fn package_name() {
+            assert_eq!(
+                make_export("@scope/pkg/sub", None, None).package_name,
+                "@scope/pkg"
+            );
+            assert_eq!(make_export("node:fs", None, None).package_name, "node:fs");
+        }
+
+        #[test]
+        fn named_export() {
+            let external_export = make_export("zod", Some("3.23.8"), Some("z"));
+
+            assert_eq!(
+                external_export.render("z", false),
+                "// Re-exported from `zod@3.23.8`.\nexport { z } from 'zod';"
+            );
+        }
+
+        #[test]
+        fn renamed_type_only_export() {
+            let external_export = make_export("vite", None, Some("Plugin"));
+
+            assert_eq!(
+                external_export.render("VitePlugin", true),
+                "// Re-exported from `vite`.\nexport type { Plugin as VitePlugin } from 'vite';"
+            );
+        }
+
+        #[test]
+        fn namespace_export() {
+            let external_export = make_export("zod/v4", Some("3.23.8"), None);
+
+            assert_eq!(
+                external_export.render("z", false),
+                "// Re-exported from `zod@3.23.8`.\nexport * as z from 'zod/v4';"
+            );
+        }
+    }
+
+    mod unresolved_externals {
+        use super::*;
+
+        fn make_reference(
+            specifier: &str,
+            importer: &str,
+            package_path: Option<&str>,
+        ) -> ExternalReference {
+            ExternalReference {
+                specifier: specifier.to_string(),
+                importer: PathBuf::from(importer),
+                package_path: package_path.map(PathBuf::from),
+                package_version: package_path.map(|_| "1.0.0".to_string()),
+                is_builtin: is_node_builtin(specifier),
+            }
+        }
+
+        #[test]
+        fn grouping_by_specifier() {
+            let references = [
+                make_reference("vite", "/pkg/b.d.ts", Some("/node_modules/vite")),
+                make_reference("optional-dep", "/pkg/a.d.ts", None),
+                make_reference("vite", "/pkg/a.d.ts", Some("/node_modules/vite")),
+                make_reference("vite", "/pkg/a.d.ts", Some("/node_modules/vite")),
+            ];
+
+            let externals = UnresolvedExternal::from_references(&references);
+
+            assert_eq!(
+                externals,
+                vec![
+                    UnresolvedExternal {
+                        specifier: "optional-dep".to_string(),
+                        reason: UnresolvedReason::Missing,
+                        reference_count: 1,
+                        referencing_files: vec![PathBuf::from("/pkg/a.d.ts")],
+                    },
+                    UnresolvedExternal {
+                        specifier: "vite".to_string(),
+                        reason: UnresolvedReason::NotFollowed {
+                            package_path: PathBuf::from("/node_modules/vite"),
+                            package_version: Some("1.0.0".to_string()),
+                        },
+                        reference_count: 3,
+                        referencing_files: vec![
+                            PathBuf::from("/pkg/a.d.ts"),
+                            PathBuf::from("/pkg/b.d.ts"),
+                        ],
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn builtins() {
+            let references = [
+                make_reference("node:fs", "/pkg/a.d.ts", Some("/node_modules/@types/node")),
+                make_reference("path", "/pkg/a.d.ts", None),
+            ];
+
+            let externals = UnresolvedExternal::from_references(&references);
+
+            assert_eq!(
+                externals
+                    .into_iter()
+                    .map(|external| external.reason)
+                    .collect::<Vec<_>>(),
+                vec![
+                    UnresolvedReason::Builtin {
+                        types_package_path: Some(PathBuf::from("/node_modules/@types/node")),
+                    },
+                    UnresolvedReason::Builtin {
+                        types_package_path: None,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn same_specifier_with_different_outcomes() {
+            let references = [
+                make_reference(
+                    "vite",
+                    "/pkg/a/index.d.ts",
+                    Some("/pkg/a/node_modules/vite"),
+                ),
+                make_reference("vite", "/other/index.d.ts", None),
+            ];
+
+            let externals = UnresolvedExternal::from_references(&references);
+
+            assert_eq!(externals.len(), 2);
+            assert_eq!(externals[0].reason, UnresolvedReason::Missing);
+        }
+    }
+}
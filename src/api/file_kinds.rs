@@ -0,0 +1,250 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use tree_sitter::Language;
+
+use crate::api::queries::{get_tsx_language, get_typescript_language};
+use crate::metadata::PackageType;
+
+/// A kind of file that modules can be loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FileKind {
+    /// Declaration files (e.g. `index.d.ts`).
+    Declaration,
+    /// TypeScript source files (e.g. `index.ts`), as found in packages published without being
+    /// built.
+    TypeScript,
+    /// TypeScript source files with JSX (e.g. `Button.tsx`).
+    Tsx,
+    /// JavaScript files (e.g. `index.js`), which are only used when they have no declarations.
+    JavaScript,
+    /// JavaScript files with JSX (e.g. `Button.jsx`).
+    Jsx,
+}
+
+/// The tree-sitter grammar that a kind of file is parsed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grammar {
+    TypeScript,
+    /// TypeScript with JSX, which JavaScript with JSX also parses with.
+    Tsx,
+}
+
+/// How the files with a given extension are loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileExtension {
+    /// The end of the file name, including the dot (e.g. `.d.mts`).
+    pub suffix: &'static str,
+    pub kind: FileKind,
+    pub grammar: Grammar,
+    /// The module format that the extension forces regardless of the package type, if any (e.g.
+    /// ES modules for `.mts`).
+    pub module_format: Option<PackageType>,
+}
+
+/// Every file extension that modules can be loaded from.
+///
+/// Declaration extensions come first, as they'd otherwise be mistaken for their source
+/// counterparts (e.g. `.d.ts` for `.ts`).
+pub const FILE_EXTENSIONS: [FileExtension; 11] = [
+    FileExtension {
+        suffix: ".d.ts",
+        kind: FileKind::Declaration,
+        grammar: Grammar::TypeScript,
+        module_format: None,
+    },
+    FileExtension {
+        suffix: ".d.mts",
+        kind: FileKind::Declaration,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::Module),
+    },
+    FileExtension {
+        suffix: ".d.cts",
+        kind: FileKind::Declaration,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::CommonJs),
+    },
+    FileExtension {
+        suffix: ".ts",
+        kind: FileKind::TypeScript,
+        grammar: Grammar::TypeScript,
+        module_format: None,
+    },
+    FileExtension {
+        suffix: ".mts",
+        kind: FileKind::TypeScript,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::Module),
+    },
+    FileExtension {
+        suffix: ".cts",
+        kind: FileKind::TypeScript,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::CommonJs),
+    },
+    FileExtension {
+        suffix: ".tsx",
+        kind: FileKind::Tsx,
+        grammar: Grammar::Tsx,
+        module_format: None,
+    },
+    FileExtension {
+        suffix: ".js",
+        kind: FileKind::JavaScript,
+        grammar: Grammar::TypeScript,
+        module_format: None,
+    },
+    FileExtension {
+        suffix: ".mjs",
+        kind: FileKind::JavaScript,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::Module),
+    },
+    FileExtension {
+        suffix: ".cjs",
+        kind: FileKind::JavaScript,
+        grammar: Grammar::TypeScript,
+        module_format: Some(PackageType::CommonJs),
+    },
+    FileExtension {
+        suffix: ".jsx",
+        kind: FileKind::Jsx,
+        grammar: Grammar::Tsx,
+        module_format: None,
+    },
+];
+
+impl FileKind {
+    /// Whether the files of this kind have implementations, which are turned into declarations
+    /// before they're parsed.
+    pub fn is_implementation(self) -> bool {
+        self != FileKind::Declaration
+    }
+
+    /// Whether the files of this kind are JavaScript, with or without JSX.
+    pub fn is_javascript(self) -> bool {
+        matches!(self, FileKind::JavaScript | FileKind::Jsx)
+    }
+}
+
+impl Grammar {
+    /// Gets the tree-sitter language of the grammar.
+    pub fn language(self) -> &'static Language {
+        match self {
+            Grammar::TypeScript => get_typescript_language(),
+            Grammar::Tsx => get_tsx_language(),
+        }
+    }
+}
+
+/// Finds the registered extension of a file, if any.
+pub fn find_file_extension(path: &Path) -> Option<&'static FileExtension> {
+    let file_name = path.file_name()?.to_str()?;
+    FILE_EXTENSIONS
+        .iter()
+        .find(|extension| file_name.ends_with(extension.suffix))
+}
+
+/// The kinds of files that are followed from the entry points.
+///
+/// Entry points are loaded whatever their kind, but the files they import are only loaded if
+/// their kind is followed. Every kind is followed by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileKinds(BTreeSet<FileKind>);
+
+impl FileKinds {
+    /// Follows the given kinds only (e.g. [FileKind::Declaration] to ignore the source files that
+    /// some packages ship alongside their declarations).
+    pub fn only(kinds: impl IntoIterator<Item = FileKind>) -> Self {
+        Self(kinds.into_iter().collect())
+    }
+
+    pub fn contains(&self, kind: FileKind) -> bool {
+        self.0.contains(&kind)
+    }
+
+    /// Checks whether a file is followed, going by its extension. Files with unregistered
+    /// extensions are followed as written, as they can only be reached by importing them exactly.
+    pub fn is_followed(&self, path: &Path) -> bool {
+        find_file_extension(path).is_none_or(|extension| self.contains(extension.kind))
+    }
+}
+
+impl Default for FileKinds {
+    fn default() -> Self {
+        Self::only([
+            FileKind::Declaration,
+            FileKind::TypeScript,
+            FileKind::Tsx,
+            FileKind::JavaScript,
+            FileKind::Jsx,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod find_file_extension {
+        use super::*;
+
+        #[test]
+        fn declaration() {
+            let extension = find_file_extension(Path::new("dist/index.d.mts")).unwrap();
+
+            assert_eq!(extension.kind, FileKind::Declaration);
+            assert_eq!(extension.module_format, Some(PackageType::Module));
+        }
+
+        #[test]
+        fn source() {
+            let extension = find_file_extension(Path::new("src/index.cts")).unwrap();
+
+            assert_eq!(extension.kind, FileKind::TypeScript);
+            assert_eq!(extension.module_format, Some(PackageType::CommonJs));
+        }
+
+        #[test]
+        fn jsx() {
+            let extension = find_file_extension(Path::new("src/Button.jsx")).unwrap();
+
+            assert_eq!(extension.kind, FileKind::Jsx);
+            assert_eq!(extension.grammar, Grammar::Tsx);
+        }
+
+        #[test]
+        fn unregistered() {
+            assert_eq!(find_file_extension(Path::new("styles.css")), None);
+        }
+    }
+
+    mod file_kinds {
+        use super::*;
+
+        #[test]
+        fn all_followed_by_default() {
+            let file_kinds = FileKinds::default();
+
+            assert!(FILE_EXTENSIONS
+                .iter()
+                .all(|extension| file_kinds.contains(extension.kind)));
+        }
+
+        #[test]
+        fn restricted() {
+            let file_kinds = FileKinds::only([FileKind::Declaration]);
+
+            assert!(file_kinds.is_followed(Path::new("index.d.ts")));
+            assert!(!file_kinds.is_followed(Path::new("index.ts")));
+        }
+
+        #[test]
+        fn unregistered() {
+            let file_kinds = FileKinds::only([FileKind::Declaration]);
+
+            assert!(file_kinds.is_followed(Path::new("src/exact-file")));
+        }
+    }
+}
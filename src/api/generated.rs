@@ -0,0 +1,148 @@
+use std::fmt;
+
+/// Markers that any code generator may leave in the comments leading a file.
+const GENERIC_MARKERS: [&str; 7] = [
+    "@generated",
+    "generated by",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+    "code generated",
+    "do not edit",
+];
+
+const PROTOBUF_MARKERS: [&str; 4] = [
+    "protoc-gen-",
+    "protocol buffer compiler",
+    "ts-proto",
+    ".proto",
+];
+
+const GRAPHQL_CODEGEN_MARKERS: [&str; 2] = ["graphql-codegen", "graphql code generator"];
+
+/// The licence banner of `tslib`, the TypeScript runtime helpers, which is copied verbatim into
+/// the files it generates.
+const TSLIB_MARKERS: [&str; 2] = [
+    "copyright (c) microsoft corporation",
+    "permission to use, copy, modify, and/or distribute this software",
+];
+
+/// The tool that generated a declaration file, going by the comments leading the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Generator {
+    /// `tslib`, going by its licence banner.
+    Tslib,
+    /// A Protocol Buffers compiler or plugin (e.g. `protoc-gen-es` or `ts-proto`).
+    Protobuf,
+    /// GraphQL Code Generator.
+    GraphqlCodegen,
+    /// A tool that only left a generic marker (e.g. `// @generated` or `DO NOT EDIT`).
+    Unknown,
+}
+
+impl fmt::Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Generator::Tslib => write!(f, "tslib"),
+            Generator::Protobuf => write!(f, "protobuf"),
+            Generator::GraphqlCodegen => write!(f, "graphql-codegen"),
+            Generator::Unknown => write!(f, "unknown tool"),
+        }
+    }
+}
+
+/// Detects whether the comments leading a file mark it as machine-generated, and by what.
+///
+/// Comments elsewhere are ignored, since the markers could just as well be part of handwritten
+/// documentation there.
+pub(crate) fn detect_generator(leading_comments: &str) -> Option<Generator> {
+    let comments = leading_comments.to_lowercase();
+    let contains_all = |markers: &[&str]| markers.iter().all(|marker| comments.contains(marker));
+    let contains_any = |markers: &[&str]| markers.iter().any(|marker| comments.contains(marker));
+
+    if contains_all(&TSLIB_MARKERS) {
+        Some(Generator::Tslib)
+    } else if contains_any(&GRAPHQL_CODEGEN_MARKERS) {
+        Some(Generator::GraphqlCodegen)
+    } else if !contains_any(&GENERIC_MARKERS) {
+        None
+    } else if contains_any(&PROTOBUF_MARKERS) {
+        Some(Generator::Protobuf)
+    } else {
+        Some(Generator::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handwritten_file() {
+        let comments = "/** Utilities for parsing dates. */";
+
+        assert_eq!(detect_generator(comments), None);
+    }
+
+    #[test]
+    fn no_comments() {
+        assert_eq!(detect_generator(""), None);
+    }
+
+    #[test]
+    fn generic_marker() {
+        let comments = "// This file was automatically generated. DO NOT EDIT.";
+
+        assert_eq!(detect_generator(comments), Some(Generator::Unknown));
+    }
+
+    #[test]
+    fn generated_annotation() {
+        let comments = "/* @generated */";
+
+        assert_eq!(detect_generator(comments), Some(Generator::Unknown));
+    }
+
+    #[test]
+    fn protoc_plugin() {
+        let comments = "// @generated by protoc-gen-es v1.10.0 with parameter \"target=dts\"\n// @generated from file api/user.proto (package api, syntax proto3)";
+
+        assert_eq!(detect_generator(comments), Some(Generator::Protobuf));
+    }
+
+    #[test]
+    fn ts_proto() {
+        let comments =
+            "// Code generated by protoc-gen-ts_proto. DO NOT EDIT.\n// source: user.proto";
+
+        assert_eq!(detect_generator(comments), Some(Generator::Protobuf));
+    }
+
+    #[test]
+    fn proto_mention_without_marker() {
+        let comments = "/** Hand-written helpers for the messages in user.proto. */";
+
+        assert_eq!(detect_generator(comments), None);
+    }
+
+    #[test]
+    fn graphql_codegen() {
+        let comments = "/* eslint-disable */\n// Generated with @graphql-codegen/typescript";
+
+        assert_eq!(detect_generator(comments), Some(Generator::GraphqlCodegen));
+    }
+
+    #[test]
+    fn tslib_banner() {
+        let comments = "/******************************************************************************\nCopyright (c) Microsoft Corporation.\n\nPermission to use, copy, modify, and/or distribute this software for any\npurpose with or without fee is hereby granted.\n***************************************************************************** */";
+
+        assert_eq!(detect_generator(comments), Some(Generator::Tslib));
+    }
+
+    #[test]
+    fn microsoft_copyright_alone() {
+        let comments = "// Copyright (c) Microsoft Corporation. All rights reserved.";
+
+        assert_eq!(detect_generator(comments), None);
+    }
+}
@@ -0,0 +1,244 @@
+use tree_sitter::{Node, Parser};
+
+use crate::api::module_set::ModuleSet;
+use crate::api::queries::make_parser;
+use crate::api::resolution::ExportResolver;
+use crate::api::resolve_entry_point;
+use crate::api::source_file::SourceFile;
+use crate::metadata::{TSEntryPoint, TSEntryPointSet, TSLibraryMetadata};
+use crate::options::ExtractionOptions;
+
+/// Whether the public API of an entry point can be extracted, as found by [check_entry_points].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPointHealth {
+    pub entry_point: TSEntryPoint,
+    pub status: EntryPointStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryPointStatus {
+    /// The entry point exports something, including its default export if any.
+    Ok { export_count: usize },
+    /// The file of the entry point doesn't exist.
+    MissingFile,
+    /// The file of the entry point has syntax errors, or the modules it imports couldn't be
+    /// loaded.
+    ParseError { message: String },
+    /// The entry point parses but exports nothing.
+    EmptyApi,
+}
+
+/// Checks every entry point of a library on its own, in order of external path, so that the
+/// ones that can't be extracted can be told apart before extracting the whole public API.
+///
+/// Syntax errors are only looked for in the entry point modules, as the extraction tolerates
+/// them elsewhere.
+pub fn check_entry_points(
+    library_metadata: &TSLibraryMetadata,
+    options: &ExtractionOptions,
+) -> Vec<EntryPointHealth> {
+    let mut parser = make_parser();
+
+    let mut entry_points = library_metadata.entry_point.iter().collect::<Vec<_>>();
+    entry_points.sort_by(|a, b| a.external_path.cmp(&b.external_path));
+    entry_points
+        .into_iter()
+        .map(|entry_point| EntryPointHealth {
+            entry_point: entry_point.clone(),
+            status: check_entry_point(
+                entry_point,
+                &library_metadata.entry_point,
+                options,
+                &mut parser,
+            ),
+        })
+        .collect()
+}
+
+fn check_entry_point(
+    entry_point: &TSEntryPoint,
+    entry_points: &TSEntryPointSet,
+    options: &ExtractionOptions,
+    parser: &mut Parser,
+) -> EntryPointStatus {
+    if !entry_point.internal_path.is_file() {
+        return EntryPointStatus::MissingFile;
+    }
+    // SAFETY: Packages aren't expected to change during the extraction.
+    let source_file = match unsafe { SourceFile::read(&entry_point.internal_path) } {
+        Ok(source_file) => source_file,
+        Err(error) => {
+            return EntryPointStatus::ParseError {
+                message: error.to_string(),
+            }
+        }
+    };
+    let Some(tree) = parser.parse(source_file.as_str(), None) else {
+        return EntryPointStatus::ParseError {
+            message: "Failed to parse the file".to_string(),
+        };
+    };
+    if let Some(error_node) = find_syntax_error(tree.root_node()) {
+        let position = error_node.start_position();
+        return EntryPointStatus::ParseError {
+            message: format!(
+                "Syntax error at line {}, column {}",
+                position.row + 1,
+                position.column + 1
+            ),
+        };
+    }
+
+    let mut single_entry_point = TSEntryPointSet::from_iter([entry_point.clone()]);
+    single_entry_point.package_path = entry_points.package_path.clone();
+    single_entry_point.published_files = entry_points.published_files.clone();
+    single_entry_point.browser_remappings = entry_points.browser_remappings.clone();
+    single_entry_point.package_type = entry_points.package_type;
+    let module_set = match ModuleSet::from_entrypoints(&single_entry_point, parser, options) {
+        Ok(module_set) => module_set,
+        Err(error) => {
+            return EntryPointStatus::ParseError {
+                message: error.to_string(),
+            }
+        }
+    };
+    let mut resolver = ExportResolver::new(&module_set);
+    let export_count = match resolve_entry_point(&mut resolver, &module_set, entry_point) {
+        Ok(resolved_entry_point) => resolved_entry_point.exports.len(),
+        Err(error) => {
+            return EntryPointStatus::ParseError {
+                message: error.to_string(),
+            }
+        }
+    };
+    if export_count == 0 {
+        EntryPointStatus::EmptyApi
+    } else {
+        EntryPointStatus::Ok { export_count }
+    }
+}
+
+/// Finds the first node that the parser had to skip or make up, in document order.
+fn find_syntax_error(root_node: Node) -> Option<Node> {
+    if !root_node.has_error() {
+        return None;
+    }
+    let mut node = root_node;
+    'descend: loop {
+        if node.is_error() || node.is_missing() {
+            return Some(node);
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.has_error() {
+                node = child;
+                continue 'descend;
+            }
+        }
+        return Some(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    fn check(files: &[(&str, &str)], entry_points: &[(&str, &str)]) -> Vec<EntryPointStatus> {
+        let temp_dir = TempDir::new();
+        for (path, content) in files {
+            temp_dir.create_file(path, content).unwrap();
+        }
+        let library_metadata = TSLibraryMetadata {
+            name: "pkg".to_string(),
+            version: None,
+            documentation: String::new(),
+            entry_point: entry_points
+                .iter()
+                .map(|(external_path, internal_path)| TSEntryPoint {
+                    external_path: external_path.to_string(),
+                    internal_path: temp_dir.path.join(internal_path),
+                    conditions: BTreeMap::new(),
+                })
+                .collect(),
+        };
+
+        check_entry_points(&library_metadata, &ExtractionOptions::default())
+            .into_iter()
+            .map(|health| health.status)
+            .collect()
+    }
+
+    #[test]
+    fn exports() {
+        let statuses = check(
+            &[(
+                "index.d.ts",
+                "export declare const foo: string;\ndeclare const bar: number;\nexport default bar;",
+            )],
+            &[(".", "index.d.ts")],
+        );
+
+        assert_eq!(statuses, vec![EntryPointStatus::Ok { export_count: 2 }]);
+    }
+
+    #[test]
+    fn missing_file() {
+        let statuses = check(&[], &[(".", "index.d.ts")]);
+
+        assert_eq!(statuses, vec![EntryPointStatus::MissingFile]);
+    }
+
+    #[test]
+    fn syntax_error() {
+        let statuses = check(
+            &[(
+                "index.d.ts",
+                "export declare const foo: string;\nexport declare function bar(: void;",
+            )],
+            &[(".", "index.d.ts")],
+        );
+
+        assert_eq!(
+            statuses,
+            vec![EntryPointStatus::ParseError {
+                message: "Syntax error at line 2, column 8".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn empty_api() {
+        let statuses = check(
+            &[("index.d.ts", "declare const foo: string;\nexport {};")],
+            &[(".", "index.d.ts")],
+        );
+
+        assert_eq!(statuses, vec![EntryPointStatus::EmptyApi]);
+    }
+
+    #[test]
+    fn order_of_external_paths() {
+        let statuses = check(
+            &[
+                ("index.d.ts", "export * from './utils';"),
+                ("utils.d.ts", "export declare function util(): void;"),
+            ],
+            &[
+                ("./utils", "utils.d.ts"),
+                (".", "index.d.ts"),
+                ("./missing", "missing.d.ts"),
+            ],
+        );
+
+        assert_eq!(
+            statuses,
+            vec![
+                EntryPointStatus::Ok { export_count: 1 },
+                EntryPointStatus::MissingFile,
+                EntryPointStatus::Ok { export_count: 1 },
+            ]
+        );
+    }
+}
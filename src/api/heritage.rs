@@ -0,0 +1,162 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::declaration::find_declaration;
+use crate::api::queries::make_parser;
+
+/// The types a class or interface builds upon.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Heritage {
+    /// The names of the base class or interfaces, without type arguments (e.g. `Base` or
+    /// `events.EventEmitter`).
+    ///
+    /// Classes extending the result of a call (e.g. `extends Mixin(Base)`) have no base here.
+    pub extends: Vec<String>,
+    /// The names of the interfaces a class implements, without type arguments.
+    pub implements: Vec<String>,
+}
+
+/// Gets the `extends` and `implements` clauses of a class or interface, or `None` if the symbol
+/// isn't one.
+pub fn get_heritage(symbol: &Symbol) -> Option<Heritage> {
+    let mut parser = make_parser();
+    let parsed_file = ParsedFile::parse(&symbol.source_code, &mut parser).ok()?;
+    let declaration = find_declaration(parsed_file.root_node())?;
+    let source_code = symbol.source_code.as_str();
+
+    let mut heritage = Heritage::default();
+    match declaration.kind() {
+        "class_declaration" | "abstract_class_declaration" | "class" => {
+            let mut cursor = declaration.walk();
+            let Some(class_heritage) = declaration
+                .named_children(&mut cursor)
+                .find(|child| child.kind() == "class_heritage")
+            else {
+                return Some(heritage);
+            };
+            let mut cursor = class_heritage.walk();
+            for clause in class_heritage.named_children(&mut cursor) {
+                match clause.kind() {
+                    "extends_clause" => {
+                        let base = clause
+                            .child_by_field_name("value")
+                            .filter(|value| {
+                                matches!(value.kind(), "identifier" | "member_expression")
+                            })
+                            .map(|value| source_code[value.byte_range()].to_string());
+                        heritage.extends.extend(base);
+                    }
+                    "implements_clause" => {
+                        let mut cursor = clause.walk();
+                        heritage.implements.extend(
+                            clause
+                                .named_children(&mut cursor)
+                                .filter_map(|type_node| get_type_name(type_node, source_code)),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        "interface_declaration" => {
+            let mut cursor = declaration.walk();
+            let clauses = declaration
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "extends_type_clause")
+                .collect::<Vec<_>>();
+            for clause in clauses {
+                let mut cursor = clause.walk();
+                heritage.extends.extend(
+                    clause
+                        .children_by_field_name("type", &mut cursor)
+                        .filter_map(|type_node| get_type_name(type_node, source_code)),
+                );
+            }
+        }
+        _ => return None,
+    }
+    Some(heritage)
+}
+
+/// Gets the name of a type reference, leaving out its type arguments.
+fn get_type_name(type_node: Node, source_code: &str) -> Option<String> {
+    let name_node = match type_node.kind() {
+        "type_identifier" | "nested_type_identifier" => type_node,
+        "generic_type" => type_node.child_by_field_name("name")?,
+        _ => return None,
+    };
+    Some(source_code[name_node.byte_range()].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(source_code: &str) -> Option<Heritage> {
+        get_heritage(&Symbol {
+            name: "Foo".to_string(),
+            source_code: source_code.to_string(),
+        })
+    }
+
+    fn make_heritage(extends: &[&str], implements: &[&str]) -> Heritage {
+        Heritage {
+            extends: extends.iter().map(ToString::to_string).collect(),
+            implements: implements.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn class_with_base_and_interfaces() {
+        let heritage = get(
+            "export declare class Foo extends Base<string> implements Transport, io.Closeable<Foo> {}",
+        );
+
+        assert_eq!(
+            heritage,
+            Some(make_heritage(&["Base"], &["Transport", "io.Closeable"]))
+        );
+    }
+
+    #[test]
+    fn class_with_qualified_base() {
+        let heritage = get("export declare class Foo extends events.EventEmitter {}");
+
+        assert_eq!(heritage, Some(make_heritage(&["events.EventEmitter"], &[])));
+    }
+
+    #[test]
+    fn class_with_mixin() {
+        let heritage = get("export declare class Foo extends Mixin(Base) implements Bar {}");
+
+        assert_eq!(heritage, Some(make_heritage(&[], &["Bar"])));
+    }
+
+    #[test]
+    fn abstract_class() {
+        let heritage = get("/** Docs. */\nexport declare abstract class Foo implements Bar {}");
+
+        assert_eq!(heritage, Some(make_heritage(&[], &["Bar"])));
+    }
+
+    #[test]
+    fn class_without_heritage() {
+        let heritage = get("export declare class Foo {}");
+
+        assert_eq!(heritage, Some(Heritage::default()));
+    }
+
+    #[test]
+    fn interface() {
+        let heritage = get("export interface Foo extends Bar, Baz<string> {}");
+
+        assert_eq!(heritage, Some(make_heritage(&["Bar", "Baz"], &[])));
+    }
+
+    #[test]
+    fn non_class() {
+        let heritage = get("export declare function foo(): void;");
+
+        assert_eq!(heritage, None);
+    }
+}
@@ -0,0 +1,210 @@
+use daipendency_extractor::Symbol;
+
+use crate::api::get_symbol_kind;
+use crate::api::resolution::{ExportedItem, ResolvedExport};
+use crate::metadata::PackageType;
+
+/// How to import an export of an entry point, so that renderers needn't work it out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportHint {
+    /// The name the export is bound to: its exported name, or the name of the declaration for
+    /// the default export.
+    pub name: String,
+    /// The ES module import (e.g. `import { createClient } from 'pkg/client';`), which is
+    /// `import type` for types.
+    pub statement: String,
+    /// The CommonJS equivalent (e.g. `const { createClient } = require('pkg/client');`), for
+    /// values exported by CommonJS entry points.
+    pub require_statement: Option<String>,
+}
+
+/// Works out how to import an export of the entry point imported with `specifier` (e.g.
+/// `pkg/client`).
+///
+/// Types (and everything exported by type-only packages or with type-only exports) are imported
+/// with `import type`, and can't be required.
+pub(crate) fn make_import_hint(
+    specifier: &str,
+    export: &ResolvedExport,
+    package_type: PackageType,
+    is_type_only_package: bool,
+) -> ImportHint {
+    let is_type = is_type_only_package || export.is_type_only || is_type(&export.item);
+    let type_keyword = if is_type { " type" } else { "" };
+    let is_default = export.name == "default";
+    let name = if is_default {
+        get_default_import_name(specifier, &export.item)
+    } else {
+        export.name.clone()
+    };
+
+    let statement = if is_default {
+        format!("import{type_keyword} {name} from '{specifier}';")
+    } else {
+        format!("import{type_keyword} {{ {name} }} from '{specifier}';")
+    };
+    let require_statement = (!is_type && package_type == PackageType::CommonJs).then(|| {
+        if is_default {
+            format!("const {name} = require('{specifier}').default;")
+        } else {
+            format!("const {{ {name} }} = require('{specifier}');")
+        }
+    });
+    ImportHint {
+        name,
+        statement,
+        require_statement,
+    }
+}
+
+fn is_type(item: &ExportedItem) -> bool {
+    match item {
+        ExportedItem::Symbol(symbol) => {
+            get_symbol_kind(symbol).is_some_and(|kind| !kind.exists_at_runtime())
+        }
+        ExportedItem::Namespace { .. } | ExportedItem::External(_) => false,
+    }
+}
+
+/// Names the default import after the declaration behind it, or after the last segment of the
+/// specifier in camel case (e.g. `myPkg` for `@scope/my-pkg`).
+fn get_default_import_name(specifier: &str, item: &ExportedItem) -> String {
+    if let ExportedItem::Symbol(Symbol { name, .. }) = item {
+        if name != "default" {
+            return name.clone();
+        }
+    }
+    let last_segment = specifier.rsplit('/').next().unwrap_or(specifier);
+    let mut name = String::new();
+    let mut is_word_start = false;
+    for character in last_segment.chars() {
+        if !(character.is_alphanumeric() || character == '_' || character == '$') {
+            is_word_start = !name.is_empty();
+            continue;
+        }
+        if name.is_empty() && character.is_ascii_digit() {
+            name.push('_');
+        }
+        if is_word_start {
+            name.extend(character.to_uppercase());
+            is_word_start = false;
+        } else {
+            name.push(character);
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_export(name: &str, source_code: &str, is_type_only: bool) -> ResolvedExport {
+        ResolvedExport {
+            name: name.to_string(),
+            item: ExportedItem::Symbol(Symbol {
+                name: name.to_string(),
+                source_code: source_code.to_string(),
+            }),
+            is_type_only,
+        }
+    }
+
+    #[test]
+    fn named_value() {
+        let export = make_export(
+            "createClient",
+            "export declare function createClient(): void;",
+            false,
+        );
+
+        let hint = make_import_hint("pkg/client", &export, PackageType::Module, false);
+
+        assert_eq!(
+            hint,
+            ImportHint {
+                name: "createClient".to_string(),
+                statement: "import { createClient } from 'pkg/client';".to_string(),
+                require_statement: None,
+            }
+        );
+    }
+
+    #[test]
+    fn commonjs_value() {
+        let export = make_export("VERSION", "export declare const VERSION: string;", false);
+
+        let hint = make_import_hint("pkg", &export, PackageType::CommonJs, false);
+
+        assert_eq!(
+            hint.require_statement.as_deref(),
+            Some("const { VERSION } = require('pkg');")
+        );
+    }
+
+    #[test]
+    fn type_declaration() {
+        let export = make_export("Options", "export interface Options {}", false);
+
+        let hint = make_import_hint("pkg", &export, PackageType::CommonJs, false);
+
+        assert_eq!(hint.statement, "import type { Options } from 'pkg';");
+        assert_eq!(hint.require_statement, None);
+    }
+
+    #[test]
+    fn type_only_export() {
+        let export = make_export("Client", "export declare class Client {}", true);
+
+        let hint = make_import_hint("pkg", &export, PackageType::Module, false);
+
+        assert_eq!(hint.statement, "import type { Client } from 'pkg';");
+    }
+
+    #[test]
+    fn type_only_package() {
+        let export = make_export("Client", "export declare class Client {}", false);
+
+        let hint = make_import_hint("@types/pkg", &export, PackageType::CommonJs, true);
+
+        assert_eq!(hint.statement, "import type { Client } from '@types/pkg';");
+        assert_eq!(hint.require_statement, None);
+    }
+
+    #[test]
+    fn default_export() {
+        let mut export = make_export(
+            "createClient",
+            "export declare function createClient(): void;",
+            false,
+        );
+        export.name = "default".to_string();
+
+        let hint = make_import_hint("pkg", &export, PackageType::CommonJs, false);
+
+        assert_eq!(
+            hint,
+            ImportHint {
+                name: "createClient".to_string(),
+                statement: "import createClient from 'pkg';".to_string(),
+                require_statement: Some("const createClient = require('pkg').default;".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn default_namespace_export() {
+        let export = ResolvedExport {
+            name: "default".to_string(),
+            item: ExportedItem::Namespace {
+                jsdoc: None,
+                exports: vec![],
+            },
+            is_type_only: false,
+        };
+
+        let hint = make_import_hint("@scope/my-pkg", &export, PackageType::Module, false);
+
+        assert_eq!(hint.statement, "import myPkg from '@scope/my-pkg';");
+    }
+}
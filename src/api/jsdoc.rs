@@ -0,0 +1,1049 @@
+use daipendency_extractor::{Namespace, Symbol};
+
+/// A code example from an `@example` JSDoc tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// The title of the example, from a JSDoc `<caption>` or the text before a fenced code block.
+    pub caption: Option<String>,
+    /// The language of the code (e.g. `ts`), as set by its fence or inferred from the code.
+    pub language: String,
+    /// The code, without the comment decorations, fences and common indentation.
+    pub code: String,
+}
+
+/// An example in the public API of a package, along with the item it documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiExample {
+    /// The name of the namespace containing the item.
+    pub namespace: String,
+    /// The name of the symbol, or `None` if the example is in the documentation of the namespace.
+    pub symbol: Option<String>,
+    pub example: Example,
+}
+
+/// An inline `{@link}`, `{@linkcode}` or `{@linkplain}` tag in a JSDoc comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub kind: LinkKind,
+    /// The reference to the linked item (e.g. `Foo.bar` in `{@link Foo.bar | the bar}`).
+    pub target: String,
+    /// The text to display instead of the target, if any (e.g. `the bar` in `{@link Foo.bar | the bar}`).
+    pub text: Option<String>,
+}
+
+/// The way a link should be displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// `{@link}`.
+    Default,
+    /// `{@linkcode}`, whose text is displayed as code.
+    Code,
+    /// `{@linkplain}`, whose text is displayed as plain text.
+    Plain,
+}
+
+/// What a link points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkResolution {
+    /// A symbol in the public API of the package.
+    Symbol { namespace: String, symbol: String },
+    /// A namespace in the public API of the package.
+    Namespace { namespace: String },
+    /// An external URL.
+    Url,
+    /// An item that isn't part of the public API of the package (e.g. a symbol in a dependency).
+    Unresolved,
+}
+
+/// A link in the documentation of the public API, along with the item it's in and what it points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossReference {
+    /// The name of the namespace containing the documented item.
+    pub namespace: String,
+    /// The name of the documented symbol, or `None` if the link is in the documentation of the namespace.
+    pub symbol: Option<String>,
+    pub link: Link,
+    pub resolution: LinkResolution,
+}
+
+/// A block tag in a JSDoc comment (e.g. `@param name The name`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct JsDocTag {
+    /// The name of the tag, without the `@` (e.g. `param`).
+    pub name: String,
+    /// The text following the tag name, up to the next tag.
+    pub content: String,
+}
+
+/// Extracts the `@example` blocks from the JSDoc of a symbol.
+pub fn extract_examples(symbol: &Symbol) -> Vec<Example> {
+    get_leading_jsdoc(&symbol.source_code)
+        .map(extract_jsdoc_examples)
+        .unwrap_or_default()
+}
+
+/// Collects the examples across the public API, in the order the items appear.
+pub fn collect_examples(namespaces: &[Namespace]) -> Vec<ApiExample> {
+    let mut examples = vec![];
+    for namespace in namespaces {
+        let namespace_examples = namespace
+            .doc_comment
+            .as_deref()
+            .map(extract_jsdoc_examples)
+            .unwrap_or_default();
+        examples.extend(namespace_examples.into_iter().map(|example| ApiExample {
+            namespace: namespace.name.clone(),
+            symbol: None,
+            example,
+        }));
+
+        for symbol in &namespace.symbols {
+            examples.extend(
+                extract_examples(symbol)
+                    .into_iter()
+                    .map(|example| ApiExample {
+                        namespace: namespace.name.clone(),
+                        symbol: Some(symbol.name.clone()),
+                        example,
+                    }),
+            );
+        }
+    }
+    examples
+}
+
+/// Extracts the inline links from the JSDoc of a symbol.
+pub fn extract_links(symbol: &Symbol) -> Vec<Link> {
+    get_leading_jsdoc(&symbol.source_code)
+        .map(extract_jsdoc_links)
+        .unwrap_or_default()
+}
+
+/// Collects the links across the documentation of the public API, and resolves them against
+/// the namespaces and symbols in it.
+///
+/// Unqualified targets are looked up in the namespace of the documented item first, so that
+/// symbols with the same name in different entry points link to the nearest one.
+pub fn resolve_links(namespaces: &[Namespace]) -> Vec<CrossReference> {
+    let mut cross_references = vec![];
+    for namespace in namespaces {
+        let namespace_links = namespace
+            .doc_comment
+            .as_deref()
+            .map(extract_jsdoc_links)
+            .unwrap_or_default();
+        let symbol_links = namespace.symbols.iter().flat_map(|symbol| {
+            extract_links(symbol)
+                .into_iter()
+                .map(|link| (Some(symbol.name.clone()), link))
+        });
+
+        for (symbol, link) in namespace_links
+            .into_iter()
+            .map(|link| (None, link))
+            .chain(symbol_links)
+        {
+            let resolution = resolve_link_target(&link.target, namespace, namespaces);
+            cross_references.push(CrossReference {
+                namespace: namespace.name.clone(),
+                symbol,
+                link,
+                resolution,
+            });
+        }
+    }
+    cross_references
+}
+
+/// Gets the JSDoc comment at the start of the source code of a symbol, if any.
+pub(crate) fn get_leading_jsdoc(source_code: &str) -> Option<&str> {
+    let source_code = source_code.trim_start();
+    if !source_code.starts_with("/**") {
+        return None;
+    }
+    source_code
+        .find("*/")
+        .map(|end| &source_code[..end + "*/".len()])
+}
+
+/// Splits a JSDoc comment into its block tags, ignoring the description before the first tag.
+///
+/// Lines in fenced code blocks never start a tag, so decorators in examples are left alone.
+pub(crate) fn parse_tags(jsdoc: &str) -> Vec<JsDocTag> {
+    let mut tags: Vec<JsDocTag> = vec![];
+    let mut is_in_fence = false;
+
+    for line in get_comment_lines(jsdoc) {
+        let trimmed_line = line.trim_start();
+        if trimmed_line.starts_with("```") {
+            is_in_fence = !is_in_fence;
+        } else if !is_in_fence {
+            if let Some((name, content)) = parse_tag_line(trimmed_line) {
+                tags.push(JsDocTag {
+                    name: name.to_string(),
+                    content: content.to_string(),
+                });
+                continue;
+            }
+        }
+
+        if let Some(tag) = tags.last_mut() {
+            tag.content.push('\n');
+            tag.content.push_str(line);
+        }
+    }
+
+    for tag in &mut tags {
+        tag.content = tag.content.trim_end().to_string();
+    }
+    tags
+}
+
+/// Gets the description of a JSDoc comment, which is the text before the first block tag.
+pub(crate) fn get_description(jsdoc: &str) -> String {
+    let mut is_in_fence = false;
+    let description_lines = get_comment_lines(jsdoc)
+        .into_iter()
+        .take_while(|line| {
+            let trimmed_line = line.trim_start();
+            if trimmed_line.starts_with("```") {
+                is_in_fence = !is_in_fence;
+            }
+            is_in_fence || parse_tag_line(trimmed_line).is_none()
+        })
+        .collect::<Vec<_>>();
+    description_lines.join("\n").trim().to_string()
+}
+
+/// Gets the first line of the description in a JSDoc comment, if any.
+pub(crate) fn get_summary(jsdoc: &str) -> Option<String> {
+    get_description(jsdoc)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Strips the comment delimiters and the leading asterisks from each line of a JSDoc comment.
+fn get_comment_lines(jsdoc: &str) -> Vec<&str> {
+    let body = jsdoc.trim();
+    let body = body.strip_prefix("/**").unwrap_or(body);
+    let body = body.strip_suffix("*/").unwrap_or(body);
+
+    body.lines()
+        .map(|line| {
+            let trimmed_line = line.trim_start();
+            match trimmed_line.strip_prefix('*') {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                None => trimmed_line,
+            }
+            .trim_end()
+        })
+        .collect()
+}
+
+fn parse_tag_line(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('@')?;
+    let name_length = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    if name_length == 0 {
+        return None;
+    }
+    let (name, content) = rest.split_at(name_length);
+    Some((name, content.trim_start()))
+}
+
+fn extract_jsdoc_examples(jsdoc: &str) -> Vec<Example> {
+    parse_tags(jsdoc)
+        .into_iter()
+        .filter(|tag| tag.name == "example")
+        .filter_map(|tag| parse_example(&tag.content))
+        .collect()
+}
+
+fn extract_jsdoc_links(jsdoc: &str) -> Vec<Link> {
+    // Links in code blocks are just code
+    let mut is_in_fence = false;
+    let prose_lines = get_comment_lines(jsdoc)
+        .into_iter()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                is_in_fence = !is_in_fence;
+                return false;
+            }
+            !is_in_fence
+        })
+        .collect::<Vec<_>>();
+    let prose = prose_lines.join("\n");
+
+    let mut links = vec![];
+    let mut remainder = prose.as_str();
+    while let Some(start) = remainder.find("{@link") {
+        let tag = &remainder[start + "{@link".len()..];
+        let Some(end) = tag.find('}') else {
+            break;
+        };
+        remainder = &tag[end + 1..];
+
+        let (kind, body) = if let Some(body) = tag[..end].strip_prefix("code") {
+            (LinkKind::Code, body)
+        } else if let Some(body) = tag[..end].strip_prefix("plain") {
+            (LinkKind::Plain, body)
+        } else {
+            (LinkKind::Default, &tag[..end])
+        };
+        if !body.starts_with(char::is_whitespace) {
+            // Another tag that happens to start with `link` (e.g. `{@linksomething}`)
+            continue;
+        }
+
+        let (target, text) = match body.split_once('|') {
+            Some((target, text)) => (target.trim(), Some(text.trim())),
+            None => {
+                let body = body.trim();
+                match body.split_once(char::is_whitespace) {
+                    Some((target, text)) => (target, Some(text.trim())),
+                    None => (body, None),
+                }
+            }
+        };
+        if target.is_empty() {
+            continue;
+        }
+        links.push(Link {
+            kind,
+            target: target.to_string(),
+            text: text
+                .filter(|text| !text.is_empty())
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" ")),
+        });
+    }
+    links
+}
+
+/// Resolves a link target such as `Foo`, `Foo.bar`, `Foo#bar`, `Utils.Foo` or `pkg/utils!Foo`.
+fn resolve_link_target(
+    target: &str,
+    current_namespace: &Namespace,
+    namespaces: &[Namespace],
+) -> LinkResolution {
+    if target.contains("://") {
+        return LinkResolution::Url;
+    }
+
+    let find_symbol = |namespace: &Namespace, reference: &str| {
+        let symbol_name = get_reference_root(reference);
+        namespace
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == symbol_name)
+            .map(|symbol| LinkResolution::Symbol {
+                namespace: namespace.name.clone(),
+                symbol: symbol.name.clone(),
+            })
+    };
+
+    if let Some((namespace_name, reference)) = target.split_once('!') {
+        return namespaces
+            .iter()
+            .filter(|namespace| namespace.name == namespace_name)
+            .find_map(|namespace| find_symbol(namespace, reference))
+            .unwrap_or(LinkResolution::Unresolved);
+    }
+
+    if let Some(resolution) = find_symbol(current_namespace, target) {
+        return resolution;
+    }
+
+    // Qualified references to symbols in exported namespaces (e.g. `Outer.Inner.Foo`)
+    let segments = target.split('.').collect::<Vec<_>>();
+    for prefix_length in (1..segments.len()).rev() {
+        let namespace_name = segments[..prefix_length].join(".");
+        let reference = segments[prefix_length..].join(".");
+        let resolution = namespaces
+            .iter()
+            .filter(|namespace| namespace.name == namespace_name)
+            .find_map(|namespace| find_symbol(namespace, &reference));
+        if let Some(resolution) = resolution {
+            return resolution;
+        }
+    }
+
+    if let Some(namespace) = namespaces.iter().find(|namespace| namespace.name == target) {
+        return LinkResolution::Namespace {
+            namespace: namespace.name.clone(),
+        };
+    }
+
+    namespaces
+        .iter()
+        .find_map(|namespace| find_symbol(namespace, target))
+        .unwrap_or(LinkResolution::Unresolved)
+}
+
+/// Gets the name of the symbol a reference starts with (e.g. `Foo` in `Foo.bar()` or `Foo#bar`).
+fn get_reference_root(reference: &str) -> &str {
+    reference
+        .split(['.', '#'])
+        .next()
+        .unwrap_or(reference)
+        .trim_end_matches("()")
+}
+
+/// Parses the content of an `@example` tag, returning `None` if there's no code.
+fn parse_example(content: &str) -> Option<Example> {
+    let mut caption = None;
+    let mut content = content;
+    if let Some(rest) = content.trim_start().strip_prefix("<caption>") {
+        if let Some((caption_text, rest)) = rest.split_once("</caption>") {
+            caption = Some(caption_text.trim().to_string());
+            content = rest;
+        }
+    }
+
+    let lines = content.lines().collect::<Vec<_>>();
+    let fence_start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("```"));
+
+    let (language, code_lines) = match fence_start {
+        Some(fence_start) => {
+            let preamble = lines[..fence_start].join("\n");
+            if caption.is_none() && !preamble.trim().is_empty() {
+                caption = Some(preamble.trim().to_string());
+            }
+
+            let info = lines[fence_start].trim_start().trim_start_matches('`');
+            let language = info.split_whitespace().next().map(str::to_lowercase);
+            let code_lines = lines[fence_start + 1..]
+                .iter()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .copied()
+                .collect::<Vec<_>>();
+            (language, code_lines)
+        }
+        None => (None, lines),
+    };
+
+    let code = dedent(&code_lines);
+    if code.is_empty() {
+        return None;
+    }
+    let language = language.unwrap_or_else(|| infer_language(&code).to_string());
+
+    Some(Example {
+        caption,
+        language,
+        code,
+    })
+}
+
+/// Removes the indentation common to all the non-blank lines, as well as leading and trailing blank lines.
+fn dedent(lines: &[&str]) -> String {
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let dedented_lines = lines
+        .iter()
+        .map(|line| line.get(indentation..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>();
+    dedented_lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// Guesses the language of unfenced example code, which is usually TypeScript.
+fn infer_language(code: &str) -> &'static str {
+    const SHELL_PREFIXES: [&str; 5] = ["$ ", "npm ", "npx ", "yarn ", "pnpm "];
+
+    let is_shell = code
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| SHELL_PREFIXES.iter().any(|prefix| line.starts_with(prefix)));
+    if is_shell {
+        "sh"
+    } else if (code.starts_with('{') || code.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(code).is_ok()
+    {
+        "json"
+    } else {
+        "ts"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_symbol;
+
+    mod leading_jsdoc {
+        use super::*;
+
+        #[test]
+        fn jsdoc() {
+            let source_code = "/** Docs */\nexport declare const foo: string;";
+
+            assert_eq!(get_leading_jsdoc(source_code), Some("/** Docs */"));
+        }
+
+        #[test]
+        fn no_jsdoc() {
+            let source_code = "export declare const foo: string; /** Docs */";
+
+            assert_eq!(get_leading_jsdoc(source_code), None);
+        }
+
+        #[test]
+        fn block_comment() {
+            let source_code = "/* Not docs */\nexport declare const foo: string;";
+
+            assert_eq!(get_leading_jsdoc(source_code), None);
+        }
+    }
+
+    mod tags {
+        use super::*;
+
+        #[test]
+        fn description_only() {
+            let tags = parse_tags("/** Just a description */");
+
+            assert!(tags.is_empty());
+        }
+
+        #[test]
+        fn multiple_tags() {
+            let jsdoc = "/**\n * Greets someone.\n * @param name The name\n *   of the person\n * @returns The greeting\n */";
+
+            let tags = parse_tags(jsdoc);
+
+            assert_eq!(
+                tags,
+                vec![
+                    JsDocTag {
+                        name: "param".to_string(),
+                        content: "name The name\n  of the person".to_string(),
+                    },
+                    JsDocTag {
+                        name: "returns".to_string(),
+                        content: "The greeting".to_string(),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn decorator_in_fence() {
+            let jsdoc = "/**\n * @example\n * ```ts\n * @Component()\n * class Foo {}\n * ```\n */";
+
+            let tags = parse_tags(jsdoc);
+
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].content, "\n```ts\n@Component()\nclass Foo {}\n```");
+        }
+
+        #[test]
+        fn email_address() {
+            let jsdoc = "/**\n * @author Jane <jane@example.com>\n */";
+
+            let tags = parse_tags(jsdoc);
+
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].content, "Jane <jane@example.com>");
+        }
+    }
+
+    mod description {
+        use super::*;
+
+        #[test]
+        fn single_line() {
+            assert_eq!(get_description("/** Greets someone. */"), "Greets someone.");
+        }
+
+        #[test]
+        fn multiple_lines_and_tags() {
+            let jsdoc = "/**\n * Greets someone.\n *\n * Politely.\n * @param name The name\n */";
+
+            assert_eq!(get_description(jsdoc), "Greets someone.\n\nPolitely.");
+        }
+
+        #[test]
+        fn tags_only() {
+            assert_eq!(get_description("/** @internal */"), "");
+        }
+    }
+
+    mod examples {
+        use super::*;
+
+        #[test]
+        fn no_examples() {
+            let symbol = make_symbol("foo", "/** Docs */\nexport declare const foo: string;");
+
+            assert!(extract_examples(&symbol).is_empty());
+        }
+
+        #[test]
+        fn unfenced_example() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * Greets someone.\n * @example\n *   greet('Alice');\n *   greet('Bob');\n */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(
+                examples,
+                vec![Example {
+                    caption: None,
+                    language: "ts".to_string(),
+                    code: "greet('Alice');\ngreet('Bob');".to_string(),
+                }]
+            );
+        }
+
+        #[test]
+        fn single_line_example() {
+            let symbol = make_symbol(
+                "greet",
+                "/** @example greet('Alice'); */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(examples.len(), 1);
+            assert_eq!(examples[0].code, "greet('Alice');");
+        }
+
+        #[test]
+        fn fenced_example() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * @example Greeting someone\n * ```js\n * greet('Alice');\n * ```\n */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(
+                examples,
+                vec![Example {
+                    caption: Some("Greeting someone".to_string()),
+                    language: "js".to_string(),
+                    code: "greet('Alice');".to_string(),
+                }]
+            );
+        }
+
+        #[test]
+        fn fence_without_language() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * @example\n * ```\n * greet('Alice');\n * ```\n */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(examples[0].language, "ts");
+        }
+
+        #[test]
+        fn caption_tag() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * @example <caption>Greeting someone</caption>\n * greet('Alice');\n */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(examples[0].caption.as_deref(), Some("Greeting someone"));
+            assert_eq!(examples[0].code, "greet('Alice');");
+        }
+
+        #[test]
+        fn shell_example() {
+            let symbol = make_symbol(
+                "cli",
+                "/**\n * @example\n * $ npx my-cli --help\n */\nexport declare function cli(): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(examples[0].language, "sh");
+        }
+
+        #[test]
+        fn json_example() {
+            let symbol = make_symbol(
+                "Config",
+                "/**\n * @example\n * { \"debug\": true }\n */\nexport interface Config { debug: boolean; }",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            assert_eq!(examples[0].language, "json");
+        }
+
+        #[test]
+        fn multiple_examples() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * @example\n * greet('Alice');\n * @example\n * greet('Bob');\n * @returns Nothing\n */\nexport declare function greet(name: string): void;",
+            );
+
+            let examples = extract_examples(&symbol);
+
+            let codes = examples
+                .iter()
+                .map(|example| example.code.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(codes, vec!["greet('Alice');", "greet('Bob');"]);
+        }
+
+        #[test]
+        fn empty_example() {
+            let symbol = make_symbol(
+                "greet",
+                "/**\n * @example\n */\nexport declare function greet(name: string): void;",
+            );
+
+            assert!(extract_examples(&symbol).is_empty());
+        }
+    }
+
+    mod package_examples {
+        use super::*;
+
+        #[test]
+        fn examples_across_namespaces() {
+            let namespaces = vec![
+                Namespace {
+                    name: "pkg".to_string(),
+                    symbols: vec![
+                        make_symbol(
+                            "greet",
+                            "/** @example greet('Alice'); */\nexport declare function greet(name: string): void;",
+                        ),
+                        make_symbol("VERSION", "export declare const VERSION: string;"),
+                    ],
+                    doc_comment: Some(
+                        "/**\n * @packageDocumentation\n * @example\n * import { greet } from 'pkg';\n */"
+                            .to_string(),
+                    ),
+                },
+                Namespace {
+                    name: "pkg/utils".to_string(),
+                    symbols: vec![make_symbol(
+                        "noop",
+                        "/** @example noop(); */\nexport declare function noop(): void;",
+                    )],
+                    doc_comment: None,
+                },
+            ];
+
+            let examples = collect_examples(&namespaces);
+
+            let locations = examples
+                .iter()
+                .map(|example| {
+                    (
+                        example.namespace.as_str(),
+                        example.symbol.as_deref(),
+                        example.example.code.as_str(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                locations,
+                vec![
+                    ("pkg", None, "import { greet } from 'pkg';"),
+                    ("pkg", Some("greet"), "greet('Alice');"),
+                    ("pkg/utils", Some("noop"), "noop();"),
+                ]
+            );
+        }
+    }
+    mod links {
+        use super::*;
+
+        #[test]
+        fn no_links() {
+            let symbol = make_symbol("foo", "/** Docs */\nexport declare const foo: string;");
+
+            assert!(extract_links(&symbol).is_empty());
+        }
+
+        #[test]
+        fn link_kinds() {
+            let symbol = make_symbol(
+                "foo",
+                "/** See {@link Foo}, {@linkcode Bar} and {@linkplain Baz}. */\nexport declare const foo: string;",
+            );
+
+            let links = extract_links(&symbol);
+
+            assert_eq!(
+                links,
+                vec![
+                    Link {
+                        kind: LinkKind::Default,
+                        target: "Foo".to_string(),
+                        text: None,
+                    },
+                    Link {
+                        kind: LinkKind::Code,
+                        target: "Bar".to_string(),
+                        text: None,
+                    },
+                    Link {
+                        kind: LinkKind::Plain,
+                        target: "Baz".to_string(),
+                        text: None,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn link_text_after_pipe() {
+            let symbol = make_symbol(
+                "foo",
+                "/** See {@link Foo.bar | the bar method}. */\nexport declare const foo: string;",
+            );
+
+            let links = extract_links(&symbol);
+
+            assert_eq!(links[0].target, "Foo.bar");
+            assert_eq!(links[0].text.as_deref(), Some("the bar method"));
+        }
+
+        #[test]
+        fn link_text_after_space() {
+            let symbol = make_symbol(
+                "foo",
+                "/**\n * See {@link https://example.com the\n * website}.\n */\nexport declare const foo: string;",
+            );
+
+            let links = extract_links(&symbol);
+
+            assert_eq!(links[0].target, "https://example.com");
+            assert_eq!(links[0].text.as_deref(), Some("the website"));
+        }
+
+        #[test]
+        fn link_in_tag() {
+            let symbol = make_symbol(
+                "foo",
+                "/**\n * @returns A {@link Foo}\n */\nexport declare function foo(): Foo;",
+            );
+
+            let links = extract_links(&symbol);
+
+            assert_eq!(links.len(), 1);
+            assert_eq!(links[0].target, "Foo");
+        }
+
+        #[test]
+        fn link_in_code_block() {
+            let symbol = make_symbol(
+                "foo",
+                "/**\n * @example\n * ```ts\n * const x = {@link Foo};\n * ```\n */\nexport declare const foo: string;",
+            );
+
+            assert!(extract_links(&symbol).is_empty());
+        }
+
+        #[test]
+        fn unterminated_link() {
+            let symbol = make_symbol(
+                "foo",
+                "/** See {@link Foo */\nexport declare const foo: string;",
+            );
+
+            assert!(extract_links(&symbol).is_empty());
+        }
+    }
+
+    mod link_resolution {
+        use super::*;
+
+        fn make_namespace(name: &str, symbols: Vec<Symbol>) -> Namespace {
+            Namespace {
+                name: name.to_string(),
+                symbols,
+                doc_comment: None,
+            }
+        }
+
+        fn resolve_single_link(target: &str, namespaces: &mut [Namespace]) -> LinkResolution {
+            namespaces[0].doc_comment = Some(format!("/** See {{@link {target}}} */"));
+
+            let cross_references = resolve_links(namespaces);
+
+            assert_eq!(cross_references.len(), 1);
+            cross_references[0].resolution.clone()
+        }
+
+        #[test]
+        fn symbol_in_same_namespace() {
+            let mut namespaces = vec![
+                make_namespace("pkg", vec![make_symbol("Foo", "interface Foo {}")]),
+                make_namespace("pkg/utils", vec![make_symbol("Foo", "interface Foo {}")]),
+            ];
+            namespaces.swap(0, 1);
+
+            let resolution = resolve_single_link("Foo", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Symbol {
+                    namespace: "pkg/utils".to_string(),
+                    symbol: "Foo".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn symbol_in_other_namespace() {
+            let mut namespaces = vec![
+                make_namespace("pkg", vec![]),
+                make_namespace("pkg/utils", vec![make_symbol("Foo", "interface Foo {}")]),
+            ];
+
+            let resolution = resolve_single_link("Foo", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Symbol {
+                    namespace: "pkg/utils".to_string(),
+                    symbol: "Foo".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn member_reference() {
+            let mut namespaces = vec![make_namespace(
+                "pkg",
+                vec![make_symbol("Foo", "class Foo { bar(): void; }")],
+            )];
+
+            let resolution = resolve_single_link("Foo#bar", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Symbol {
+                    namespace: "pkg".to_string(),
+                    symbol: "Foo".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn qualified_reference() {
+            let mut namespaces = vec![
+                make_namespace("pkg", vec![]),
+                make_namespace("Outer.Inner", vec![make_symbol("Foo", "interface Foo {}")]),
+            ];
+
+            let resolution = resolve_single_link("Outer.Inner.Foo", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Symbol {
+                    namespace: "Outer.Inner".to_string(),
+                    symbol: "Foo".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn declaration_reference() {
+            let mut namespaces = vec![
+                make_namespace("pkg", vec![make_symbol("Foo", "interface Foo {}")]),
+                make_namespace("pkg/utils", vec![make_symbol("Foo", "interface Foo {}")]),
+            ];
+
+            let resolution = resolve_single_link("pkg/utils!Foo", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Symbol {
+                    namespace: "pkg/utils".to_string(),
+                    symbol: "Foo".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn namespace_reference() {
+            let mut namespaces = vec![
+                make_namespace("pkg", vec![]),
+                make_namespace("Utils", vec![]),
+            ];
+
+            let resolution = resolve_single_link("Utils", &mut namespaces);
+
+            assert_eq!(
+                resolution,
+                LinkResolution::Namespace {
+                    namespace: "Utils".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn url() {
+            let mut namespaces = vec![make_namespace("pkg", vec![])];
+
+            let resolution = resolve_single_link("https://example.com", &mut namespaces);
+
+            assert_eq!(resolution, LinkResolution::Url);
+        }
+
+        #[test]
+        fn unknown_symbol() {
+            let mut namespaces = vec![make_namespace("pkg", vec![])];
+
+            let resolution = resolve_single_link("Promise", &mut namespaces);
+
+            assert_eq!(resolution, LinkResolution::Unresolved);
+        }
+
+        #[test]
+        fn links_in_symbols() {
+            let namespaces = vec![make_namespace(
+                "pkg",
+                vec![
+                    make_symbol("Foo", "interface Foo {}"),
+                    make_symbol(
+                        "bar",
+                        "/** Returns a {@link Foo} */\ndeclare function bar(): Foo;",
+                    ),
+                ],
+            )];
+
+            let cross_references = resolve_links(&namespaces);
+
+            assert_eq!(
+                cross_references,
+                vec![CrossReference {
+                    namespace: "pkg".to_string(),
+                    symbol: Some("bar".to_string()),
+                    link: Link {
+                        kind: LinkKind::Default,
+                        target: "Foo".to_string(),
+                        text: None,
+                    },
+                    resolution: LinkResolution::Symbol {
+                        namespace: "pkg".to_string(),
+                        symbol: "Foo".to_string(),
+                    },
+                }]
+            );
+        }
+    }
+}
@@ -0,0 +1,629 @@
+use std::path::PathBuf;
+
+use daipendency_extractor::{ExtractionError, Namespace, Symbol};
+use tree_sitter::Parser;
+
+use crate::api::resolution::{ExportedItem, ResolvedExport};
+use crate::api::visitor::{walk_module, SymbolVisitor, VisitContext};
+use crate::api::{get_entry_point_namespace_name, load_module_set, resolve_entry_points};
+use crate::metadata::TSLibraryMetadata;
+use crate::options::ExtractionOptions;
+use crate::report::{EntryPointReport, ExtractionReport};
+
+/// What importing a name from a package gives the importer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportedItem<'a> {
+    /// The declarations exported under the name (several if they're merged, like a function and
+    /// its overloads or an interface and a variable).
+    Symbols(Vec<&'a Symbol>),
+    /// An exported namespace (e.g. `Utils` in `export namespace Utils {}`).
+    Namespace(&'a Namespace),
+}
+
+/// Resolves what a user would get by importing `name` from `specifier` (e.g.
+/// `import { deepMerge } from 'pkg/utils'`), or `None` if the entry point doesn't export it.
+///
+/// Only the exports of the entry point imported with `specifier` are considered, in which export
+/// conditions, subpath patterns and re-exports have already been resolved. Qualified names (e.g.
+/// `Utils.deepMerge`) are resolved through the namespaces that the entry point exports.
+pub fn resolve_specifier<'a>(
+    report: &'a ExtractionReport,
+    specifier: &str,
+    name: &str,
+) -> Option<ImportedItem<'a>> {
+    let specifier = specifier.trim_end_matches('/');
+    let entry_point = report
+        .entry_points
+        .iter()
+        .find(|entry_point| entry_point.namespace == specifier)?;
+
+    let (namespace_path, symbol_name) = match name.rsplit_once('.') {
+        Some((namespace_path, symbol_name)) => (Some(namespace_path), symbol_name),
+        None => (None, name),
+    };
+    let container = match namespace_path {
+        Some(namespace_path) => find_exported_namespace(report, entry_point, namespace_path)?,
+        None => find_namespace(&report.public_api, &entry_point.namespace)?,
+    };
+
+    let symbols = container
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.name == symbol_name)
+        .collect::<Vec<_>>();
+    if !symbols.is_empty() {
+        return Some(ImportedItem::Symbols(symbols));
+    }
+    find_exported_namespace(report, entry_point, name).map(ImportedItem::Namespace)
+}
+
+/// A place where a declaration is exported from the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportLocation {
+    /// The import specifier of the entry point (e.g. `pkg/utils`).
+    pub specifier: String,
+    /// The name under which the declaration is exported, qualified with the exported namespaces
+    /// containing it (e.g. `merge`, `deepMerge` in `export { merge as deepMerge }`, `Utils.merge`
+    /// or `default`).
+    pub exported_name: String,
+    pub symbol: Symbol,
+    /// Whether the declaration can only be imported as a type here (e.g. because it's re-exported
+    /// with `export type * from './types';`).
+    pub is_type_only: bool,
+}
+
+/// Finds every entry point and name under which a declaration is exported, given the name it's
+/// declared with (e.g. `merge` for `export { merge as deepMerge }`).
+pub fn find_export_locations(
+    library_metadata: &TSLibraryMetadata,
+    parser: &mut Parser,
+    options: &ExtractionOptions,
+    declaration_name: &str,
+) -> Result<Vec<ExportLocation>, ExtractionError> {
+    let module_set = load_module_set(library_metadata, parser, options)?;
+    let mut locations = vec![];
+    for entry_point in resolve_entry_points(library_metadata, &module_set)? {
+        let specifier =
+            get_entry_point_namespace_name(&library_metadata.name, entry_point.entry_point);
+        collect_export_locations(
+            &entry_point.exports,
+            &specifier,
+            None,
+            false,
+            declaration_name,
+            &mut locations,
+        );
+    }
+    Ok(locations)
+}
+
+/// Collects the locations of a declaration in the exports of an entry point, where
+/// `is_type_only` tells whether the namespace containing the exports is type-only.
+fn collect_export_locations(
+    exports: &[ResolvedExport],
+    specifier: &str,
+    namespace_path: Option<&str>,
+    is_type_only: bool,
+    declaration_name: &str,
+    locations: &mut Vec<ExportLocation>,
+) {
+    for export in exports {
+        let exported_name = match namespace_path {
+            Some(namespace_path) => format!("{namespace_path}.{}", export.name),
+            None => export.name.clone(),
+        };
+        let is_type_only = is_type_only || export.is_type_only;
+        match &export.item {
+            ExportedItem::Symbol(symbol) if symbol.name == declaration_name => {
+                locations.push(ExportLocation {
+                    specifier: specifier.to_string(),
+                    exported_name,
+                    symbol: symbol.clone(),
+                    is_type_only,
+                });
+            }
+            ExportedItem::Symbol(_) | ExportedItem::External(_) => {}
+            ExportedItem::Namespace { exports, .. } => collect_export_locations(
+                exports,
+                specifier,
+                Some(&exported_name),
+                is_type_only,
+                declaration_name,
+                locations,
+            ),
+        }
+    }
+}
+
+/// A declaration exported by a module that users can't import, because the module isn't an entry
+/// point and no entry point re-exports the declaration (e.g. a type only used in signatures).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalSymbol {
+    /// The path of the module declaring the symbol.
+    pub path: PathBuf,
+    pub symbol: Symbol,
+}
+
+/// Finds the declarations that modules reachable from the entry points export, but which aren't
+/// exported by any entry point, so docs shouldn't suggest importing them.
+///
+/// Symbols are sorted by the path of their module, and then listed in the order they're declared.
+pub fn find_internal_symbols(
+    library_metadata: &TSLibraryMetadata,
+    parser: &mut Parser,
+    options: &ExtractionOptions,
+) -> Result<Vec<InternalSymbol>, ExtractionError> {
+    let module_set = load_module_set(library_metadata, parser, options)?;
+    let entry_points = resolve_entry_points(library_metadata, &module_set)?;
+    let mut public_symbols = vec![];
+    for entry_point in &entry_points {
+        collect_symbols(&entry_point.exports, &mut public_symbols);
+    }
+
+    let mut modules = module_set
+        .iter()
+        .filter(|module| {
+            !library_metadata
+                .entry_point
+                .iter()
+                .any(|entry_point| entry_point.internal_path == module.path)
+        })
+        .collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut collector = InternalSymbolCollector {
+        public_symbols,
+        internal_symbols: vec![],
+    };
+    for module in modules {
+        walk_module(module, &mut collector);
+    }
+    Ok(collector.internal_symbols)
+}
+
+/// Collects the top-level declarations that are exported but aren't part of the public API.
+struct InternalSymbolCollector<'a> {
+    public_symbols: Vec<&'a Symbol>,
+    internal_symbols: Vec<InternalSymbol>,
+}
+
+impl SymbolVisitor for InternalSymbolCollector<'_> {
+    fn visit_symbol(&mut self, context: &VisitContext, symbol: &Symbol, is_exported: bool) {
+        if context.depth == 0 && is_exported && !self.public_symbols.contains(&symbol) {
+            self.internal_symbols.push(InternalSymbol {
+                path: context.module_path.to_path_buf(),
+                symbol: symbol.clone(),
+            });
+        }
+    }
+
+    fn visit_import_alias(
+        &mut self,
+        _context: &VisitContext,
+        _symbol: &Symbol,
+        _target: &[String],
+        _is_exported: bool,
+    ) {
+    }
+}
+
+fn collect_symbols<'a>(exports: &'a [ResolvedExport], symbols: &mut Vec<&'a Symbol>) {
+    for export in exports {
+        match &export.item {
+            ExportedItem::Symbol(symbol) => symbols.push(symbol),
+            ExportedItem::Namespace { exports, .. } => collect_symbols(exports, symbols),
+            ExportedItem::External(_) => {}
+        }
+    }
+}
+
+/// Finds a namespace of the public API by name.
+fn find_namespace<'a>(namespaces: &'a [Namespace], name: &str) -> Option<&'a Namespace> {
+    namespaces.iter().find(|namespace| namespace.name == name)
+}
+
+/// Finds a namespace exported by an entry point by its path from the entry point (e.g.
+/// `Outer.Inner`).
+fn find_exported_namespace<'a>(
+    report: &'a ExtractionReport,
+    entry_point: &EntryPointReport,
+    path: &str,
+) -> Option<&'a Namespace> {
+    let root_name = path.split('.').next()?;
+    if !entry_point
+        .exported_namespaces
+        .iter()
+        .any(|name| name == root_name)
+    {
+        return None;
+    }
+    find_namespace(&report.public_api, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    mod resolve_specifier {
+        use super::*;
+        use crate::TypeScriptExtractor;
+        use assertables::assert_matches;
+
+        fn extract_report(index: &str, utils: &str) -> ExtractionReport {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{
+                        "name": "pkg",
+                        "version": "1.0.0",
+                        "exports": {
+                            ".": {"types": "./index.d.ts"},
+                            "./utils": {"types": "./utils.d.ts"}
+                        }
+                    }"#,
+                )
+                .unwrap();
+            temp_dir.create_file("index.d.ts", index).unwrap();
+            temp_dir.create_file("utils.d.ts", utils).unwrap();
+            TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap()
+        }
+
+        fn get_symbol_names(item: Option<ImportedItem>) -> Vec<String> {
+            match item {
+                Some(ImportedItem::Symbols(symbols)) => {
+                    symbols.iter().map(|symbol| symbol.name.clone()).collect()
+                }
+                item => panic!("Expected symbols, got {item:?}"),
+            }
+        }
+
+        #[test]
+        fn root_entry_point() {
+            let report = extract_report("export declare function main(): void;", "");
+
+            let item = resolve_specifier(&report, "pkg", "main");
+
+            assert_eq!(get_symbol_names(item), vec!["main"]);
+        }
+
+        #[test]
+        fn subpath_entry_point() {
+            let report = extract_report("", "export declare function deepMerge(): void;");
+
+            let item = resolve_specifier(&report, "pkg/utils", "deepMerge");
+
+            assert_eq!(get_symbol_names(item), vec!["deepMerge"]);
+        }
+
+        #[test]
+        fn trailing_slash() {
+            let report = extract_report("", "export declare function deepMerge(): void;");
+
+            let item = resolve_specifier(&report, "pkg/utils/", "deepMerge");
+
+            assert!(item.is_some());
+        }
+
+        #[test]
+        fn merged_declarations() {
+            let report = extract_report(
+                "export declare function f(): void;\nexport declare function f(a: string): void;",
+                "",
+            );
+
+            let item = resolve_specifier(&report, "pkg", "f");
+
+            assert_eq!(get_symbol_names(item), vec!["f", "f"]);
+        }
+
+        #[test]
+        fn namespace() {
+            let report = extract_report(
+                "export declare namespace Utils {\n    function clone(): void;\n}",
+                "",
+            );
+
+            let item = resolve_specifier(&report, "pkg", "Utils");
+
+            assert_matches!(item, Some(ImportedItem::Namespace(namespace)) if namespace.name == "Utils");
+        }
+
+        #[test]
+        fn qualified_name() {
+            let report = extract_report(
+                "export declare namespace Utils {\n    namespace Inner {\n        function noop(): void;\n    }\n}",
+                "",
+            );
+
+            let item = resolve_specifier(&report, "pkg", "Utils.Inner.noop");
+
+            assert_eq!(get_symbol_names(item), vec!["noop"]);
+        }
+
+        #[test]
+        fn unknown_name() {
+            let report = extract_report(
+                "export declare function main(): void;",
+                "export declare function deepMerge(): void;",
+            );
+
+            let item = resolve_specifier(&report, "pkg", "deepMerge");
+
+            assert_eq!(item, None);
+        }
+
+        #[test]
+        fn namespace_of_sibling_entry_point() {
+            let report = extract_report(
+                "export declare function main(): void;",
+                "export declare namespace Utils {\n    function clone(): void;\n}",
+            );
+
+            assert_eq!(resolve_specifier(&report, "pkg", "Utils"), None);
+            assert_eq!(resolve_specifier(&report, "pkg", "Utils.clone"), None);
+            assert!(resolve_specifier(&report, "pkg/utils", "Utils.clone").is_some());
+        }
+
+        #[test]
+        fn unknown_specifier() {
+            let report = extract_report("export declare function main(): void;", "");
+
+            let item = resolve_specifier(&report, "pkg/other", "main");
+
+            assert_eq!(item, None);
+        }
+    }
+
+    mod find_export_locations {
+        use super::*;
+
+        fn find(files: &[(&str, &str)], declaration_name: &str) -> Vec<(String, String)> {
+            find_locations(files, declaration_name)
+                .into_iter()
+                .map(|location| (location.specifier, location.exported_name))
+                .collect()
+        }
+
+        fn find_locations(files: &[(&str, &str)], declaration_name: &str) -> Vec<ExportLocation> {
+            let temp_dir = TempDir::new();
+            for (path, content) in files {
+                temp_dir.create_file(path, content).unwrap();
+            }
+            let entry_point = TSEntryPointSet::from_iter([
+                TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("index.d.ts"),
+                    conditions: BTreeMap::new(),
+                },
+                TSEntryPoint {
+                    external_path: "./utils".to_string(),
+                    internal_path: temp_dir.path.join("utils.d.ts"),
+                    conditions: BTreeMap::new(),
+                },
+            ]);
+            let library_metadata = TSLibraryMetadata {
+                name: "pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point,
+            };
+
+            find_export_locations(
+                &library_metadata,
+                &mut make_parser(),
+                &ExtractionOptions::default(),
+                declaration_name,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn several_entry_points_and_aliases() {
+            let locations = find(
+                &[
+                    (
+                        "index.d.ts",
+                        "export { merge } from './utils';\nexport { merge as deepMerge } from './utils';",
+                    ),
+                    ("utils.d.ts", "export declare function merge(): void;"),
+                ],
+                "merge",
+            );
+
+            assert_eq!(
+                locations,
+                vec![
+                    ("pkg".to_string(), "merge".to_string()),
+                    ("pkg".to_string(), "deepMerge".to_string()),
+                    ("pkg/utils".to_string(), "merge".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn namespace_members() {
+            let locations = find(
+                &[
+                    ("index.d.ts", "export * as Utils from './utils';"),
+                    ("utils.d.ts", "export declare function merge(): void;"),
+                ],
+                "merge",
+            );
+
+            assert_eq!(
+                locations,
+                vec![
+                    ("pkg".to_string(), "Utils.merge".to_string()),
+                    ("pkg/utils".to_string(), "merge".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn default_export() {
+            let locations = find(
+                &[
+                    (
+                        "index.d.ts",
+                        "declare function merge(): void;\nexport default merge;",
+                    ),
+                    ("utils.d.ts", ""),
+                ],
+                "merge",
+            );
+
+            assert_eq!(locations, vec![("pkg".to_string(), "default".to_string())]);
+        }
+
+        #[test]
+        fn not_exported() {
+            let locations = find(
+                &[
+                    ("index.d.ts", "declare function merge(): void;"),
+                    ("utils.d.ts", ""),
+                ],
+                "merge",
+            );
+
+            assert_eq!(locations, vec![]);
+        }
+
+        #[test]
+        fn type_only_locations() {
+            let locations = find_locations(
+                &[
+                    (
+                        "index.d.ts",
+                        "export type * from './types';\nexport type * as Types from './types';",
+                    ),
+                    ("utils.d.ts", "export * from './types';"),
+                    ("types.d.ts", "export interface Options {}"),
+                ],
+                "Options",
+            );
+
+            let locations = locations
+                .iter()
+                .map(|location| {
+                    (
+                        location.specifier.as_str(),
+                        location.exported_name.as_str(),
+                        location.is_type_only,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                locations,
+                vec![
+                    ("pkg", "Options", true),
+                    ("pkg", "Types.Options", true),
+                    ("pkg/utils", "Options", false),
+                ]
+            );
+        }
+    }
+
+    mod find_internal_symbols {
+        use super::*;
+
+        fn find(files: &[(&str, &str)]) -> Vec<(PathBuf, String)> {
+            let temp_dir = TempDir::new();
+            for (path, content) in files {
+                temp_dir.create_file(path, content).unwrap();
+            }
+            let library_metadata = TSLibraryMetadata {
+                name: "pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("index.d.ts"),
+                    conditions: BTreeMap::new(),
+                }]),
+            };
+
+            let symbols = find_internal_symbols(
+                &library_metadata,
+                &mut make_parser(),
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            symbols
+                .into_iter()
+                .map(|internal_symbol| {
+                    (
+                        internal_symbol
+                            .path
+                            .strip_prefix(temp_dir.path.canonicalize().unwrap())
+                            .unwrap()
+                            .to_path_buf(),
+                        internal_symbol.symbol.name,
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn symbol_not_reexported() {
+            let symbols = find(&[
+                (
+                    "index.d.ts",
+                    "import { Options } from './options';\nexport declare function f(options: Options): void;",
+                ),
+                ("options.d.ts", "export interface Options {}"),
+            ]);
+
+            assert_eq!(
+                symbols,
+                vec![(PathBuf::from("options.d.ts"), "Options".to_string())]
+            );
+        }
+
+        #[test]
+        fn reexported_symbol() {
+            let symbols = find(&[
+                ("index.d.ts", "export { Options } from './options';"),
+                ("options.d.ts", "export interface Options {}"),
+            ]);
+
+            assert_eq!(symbols, vec![]);
+        }
+
+        #[test]
+        fn symbol_reexported_in_namespace() {
+            let symbols = find(&[
+                ("index.d.ts", "export * as options from './options';"),
+                ("options.d.ts", "export interface Options {}"),
+            ]);
+
+            assert_eq!(symbols, vec![]);
+        }
+
+        #[test]
+        fn private_symbol() {
+            let symbols = find(&[
+                ("index.d.ts", "export * from './options';"),
+                (
+                    "options.d.ts",
+                    "interface Hidden {}\nexport interface Options {}",
+                ),
+            ]);
+
+            assert_eq!(symbols, vec![]);
+        }
+
+        #[test]
+        fn entry_point_symbol() {
+            let symbols = find(&[("index.d.ts", "declare const a: string;")]);
+
+            assert_eq!(symbols, vec![]);
+        }
+    }
+}
@@ -0,0 +1,533 @@
+use std::ops::Range;
+
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::declaration::find_declaration;
+use crate::api::jsdoc::get_description;
+use crate::api::queries::make_parser;
+use crate::api::source_edits::remove_ranges;
+
+/// Who can access a member of a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessibility {
+    Public,
+    /// Only the class and its subclasses can access the member, so it's only part of the API for
+    /// those extending the class.
+    Protected,
+    /// Only the class can access the member, whether it's marked `private` or its name starts
+    /// with `#`.
+    Private,
+}
+
+/// The kind of a member of a class or interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Property,
+    Method,
+    Constructor,
+    Getter,
+    Setter,
+}
+
+/// A property, method or accessor of a class or interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// The name of the member, without quotes (e.g. `size` or `#secret`).
+    pub name: String,
+    pub kind: MemberKind,
+    /// The accessibility of the member, which is always public for interfaces.
+    pub accessibility: Accessibility,
+    pub is_static: bool,
+    /// Whether the member must be implemented by subclasses (e.g. `abstract run(): void;`).
+    pub is_abstract: bool,
+    pub is_readonly: bool,
+    /// Whether the member is marked with `?`.
+    pub is_optional: bool,
+    /// The description in the JSDoc of the member, or else the comment trailing it on the same
+    /// line (e.g. `readonly timeout: number; // in milliseconds`).
+    pub doc: Option<String>,
+}
+
+/// Lists the properties, methods and accessors of a class or interface, in order.
+///
+/// Index, call and construct signatures aren't members in this sense, so they're omitted, as are
+/// the symbols that aren't classes or interfaces.
+pub fn get_members(symbol: &Symbol) -> Vec<Member> {
+    let mut parser = make_parser();
+    let Ok(parsed_file) = ParsedFile::parse(&symbol.source_code, &mut parser) else {
+        return vec![];
+    };
+    let Some(body) = find_declaration(parsed_file.root_node())
+        .filter(|declaration| {
+            matches!(
+                declaration.kind(),
+                "class_declaration" | "abstract_class_declaration" | "interface_declaration"
+            )
+        })
+        .and_then(|declaration| declaration.child_by_field_name("body"))
+    else {
+        return vec![];
+    };
+
+    let source_code = symbol.source_code.as_str();
+    let mut cursor = body.walk();
+    let members = body
+        .named_children(&mut cursor)
+        .filter_map(|member| make_member(member, source_code))
+        .collect();
+    members
+}
+
+fn make_member(member: Node, source_code: &str) -> Option<Member> {
+    let kind = match member.kind() {
+        "public_field_definition" | "property_signature" => MemberKind::Property,
+        "method_definition" | "method_signature" | "abstract_method_signature" => {
+            if has_keyword(member, "get") {
+                MemberKind::Getter
+            } else if has_keyword(member, "set") {
+                MemberKind::Setter
+            } else {
+                MemberKind::Method
+            }
+        }
+        _ => return None,
+    };
+    let name_node = member.child_by_field_name("name")?;
+    let name = source_code[name_node.byte_range()]
+        .trim_matches(['"', '\''])
+        .to_string();
+    let kind = match (kind, name.as_str()) {
+        (MemberKind::Method, "constructor") => MemberKind::Constructor,
+        (kind, _) => kind,
+    };
+    let accessibility = get_member_accessibility(member, source_code);
+    Some(Member {
+        name,
+        kind,
+        accessibility,
+        is_static: has_keyword(member, "static"),
+        is_abstract: member.kind() == "abstract_method_signature"
+            || has_keyword(member, "abstract"),
+        is_readonly: has_keyword(member, "readonly"),
+        is_optional: has_keyword(member, "?"),
+        doc: get_member_doc(member, source_code),
+    })
+}
+
+fn is_member(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "public_field_definition"
+            | "property_signature"
+            | "method_definition"
+            | "method_signature"
+            | "abstract_method_signature"
+    )
+}
+
+fn get_member_doc(member: Node, source_code: &str) -> Option<String> {
+    if let Some(jsdoc) = get_member_jsdoc(member, source_code) {
+        let description = get_description(&source_code[jsdoc.byte_range()]);
+        return Some(description).filter(|description| !description.is_empty());
+    }
+    let comment = member
+        .next_named_sibling()
+        .filter(|comment| is_trailing_member_comment(*comment, source_code))?;
+    let comment = &source_code[comment.byte_range()];
+    let text = match comment.strip_prefix("//") {
+        Some(text) => text,
+        None => comment
+            .strip_prefix("/*")
+            .and_then(|text| text.strip_suffix("*/"))
+            .unwrap_or(comment),
+    };
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Gets the JSDoc comment right before a member, if any.
+fn get_member_jsdoc<'a>(member: Node<'a>, source_code: &str) -> Option<Node<'a>> {
+    member.prev_named_sibling().filter(|comment| {
+        comment.kind() == "comment"
+            && source_code[comment.byte_range()].starts_with("/**")
+            && !is_trailing_member_comment(*comment, source_code)
+    })
+}
+
+/// Checks whether a comment follows a member on the same line (e.g.
+/// `readonly timeout: number; // in milliseconds`).
+fn is_trailing_member_comment(comment: Node, source_code: &str) -> bool {
+    comment.kind() == "comment"
+        && comment.prev_named_sibling().is_some_and(|member| {
+            is_member(member) && member.end_position().row == comment.start_position().row
+        })
+        && !source_code[comment.byte_range()].starts_with("/**")
+}
+
+/// Checks whether a comment documents the member it trails on the same line, which is the case
+/// when the member has no JSDoc.
+pub(crate) fn is_trailing_member_doc(comment: Node, source_code: &str) -> bool {
+    is_trailing_member_comment(comment, source_code)
+        && comment
+            .prev_named_sibling()
+            .is_some_and(|member| get_member_jsdoc(member, source_code).is_none())
+}
+
+/// Gets the accessibility of a member of a class, which is private if it's marked `private` or
+/// its name starts with `#` (e.g. `#count: number;` or `#run(): void;`).
+pub(crate) fn get_member_accessibility(member: Node, source_code: &str) -> Accessibility {
+    let is_ecmascript_private = member
+        .child_by_field_name("name")
+        .is_some_and(|name_node| name_node.kind() == "private_property_identifier");
+    if is_ecmascript_private {
+        return Accessibility::Private;
+    }
+    let mut cursor = member.walk();
+    let modifier = member
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "accessibility_modifier")
+        .map(|modifier| &source_code[modifier.byte_range()]);
+    match modifier {
+        Some("protected") => Accessibility::Protected,
+        Some("private") => Accessibility::Private,
+        _ => Accessibility::Public,
+    }
+}
+
+fn has_keyword(node: Node, keyword: &str) -> bool {
+    let mut cursor = node.walk();
+    let has_keyword = node
+        .children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == keyword);
+    has_keyword
+}
+
+/// Removes the members of the classes in a symbol with any of the given accessibilities, along
+/// with their JSDoc, or returns `None` if there are none.
+///
+/// Parameter properties (e.g. `constructor(protected x: number)`) are kept, since removing them
+/// would change the signature of the constructor.
+pub(crate) fn remove_members(symbol: &Symbol, accessibilities: &[Accessibility]) -> Option<Symbol> {
+    let source_code = symbol.source_code.as_str();
+    let may_have_members = accessibilities
+        .iter()
+        .any(|accessibility| match accessibility {
+            Accessibility::Public => true,
+            Accessibility::Protected => source_code.contains("protected"),
+            Accessibility::Private => source_code.contains("private") || source_code.contains('#'),
+        });
+    if !may_have_members {
+        return None;
+    }
+    let mut parser = make_parser();
+    let parsed_file = ParsedFile::parse(source_code, &mut parser).ok()?;
+
+    let mut removed_ranges = vec![];
+    let mut stack = vec![parsed_file.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "class_body" {
+            removed_ranges.extend(get_member_ranges(node, source_code, accessibilities));
+        }
+        let mut cursor = node.walk();
+        let children = node.named_children(&mut cursor).collect::<Vec<_>>();
+        stack.extend(children.into_iter().rev());
+    }
+    if removed_ranges.is_empty() {
+        return None;
+    }
+
+    Some(Symbol {
+        name: symbol.name.clone(),
+        source_code: remove_ranges(source_code, removed_ranges),
+    })
+}
+
+/// Gets the source code ranges of the members in a class body with any of the given
+/// accessibilities, including their JSDoc, their terminating `;`, their trailing comments and the
+/// lines they're on.
+fn get_member_ranges(
+    body: Node,
+    source_code: &str,
+    accessibilities: &[Accessibility],
+) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut cursor = body.walk();
+    let children = body.children(&mut cursor).collect::<Vec<_>>();
+    for (index, member) in children.iter().enumerate() {
+        let is_removed = make_member(*member, source_code)
+            .is_some_and(|member| accessibilities.contains(&member.accessibility));
+        if !is_removed {
+            continue;
+        }
+
+        let mut start = member.start_byte();
+        if let Some(previous) = index.checked_sub(1).map(|index| children[index]) {
+            if previous.kind() == "comment" && source_code[previous.byte_range()].starts_with("/**")
+            {
+                start = previous.start_byte();
+            }
+        }
+        let mut end = member.end_byte();
+        if let Some(next) = children.get(index + 1) {
+            if next.kind() == ";" {
+                end = next.end_byte();
+            }
+        }
+        if let Some(comment) = member
+            .next_named_sibling()
+            .filter(|comment| is_trailing_member_comment(*comment, source_code))
+        {
+            end = comment.end_byte();
+        }
+
+        // Take the whole lines, unless other members share them
+        let line_start = source_code[..start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line_end = source_code[end..]
+            .find('\n')
+            .map_or(source_code.len(), |index| end + index + 1);
+        if source_code[line_start..start].trim().is_empty()
+            && source_code[end..line_end].trim().is_empty()
+        {
+            start = line_start;
+            end = line_end;
+        }
+        ranges.push(start..end);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_symbol(source_code: &str) -> Symbol {
+        Symbol {
+            name: "Foo".to_string(),
+            source_code: source_code.to_string(),
+        }
+    }
+
+    mod get_members {
+        use super::*;
+
+        #[test]
+        fn class_members() {
+            let members = get_members(&make_symbol(
+                "export declare class Foo {\n  static readonly bar: string;\n  protected baz?: number;\n  private secret;\n  #hidden;\n  constructor();\n  get size(): number;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| (member.name.as_str(), member.kind, member.accessibility))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("bar", MemberKind::Property, Accessibility::Public),
+                    ("baz", MemberKind::Property, Accessibility::Protected),
+                    ("secret", MemberKind::Property, Accessibility::Private),
+                    ("#hidden", MemberKind::Property, Accessibility::Private),
+                    (
+                        "constructor",
+                        MemberKind::Constructor,
+                        Accessibility::Public
+                    ),
+                    ("size", MemberKind::Getter, Accessibility::Public),
+                ]
+            );
+            assert!(members[0].is_static && members[0].is_readonly);
+            assert!(members[1].is_optional);
+        }
+
+        #[test]
+        fn ecmascript_private_members() {
+            let members = get_members(&make_symbol(
+                "export declare class Foo {\n  #private;\n  static #count: number;\n  #run(): void;\n  get #size(): number;\n  private legacy;\n  private legacyRun(): void;\n  run(): void;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| (member.name.as_str(), member.kind, member.accessibility))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("#private", MemberKind::Property, Accessibility::Private),
+                    ("#count", MemberKind::Property, Accessibility::Private),
+                    ("#run", MemberKind::Method, Accessibility::Private),
+                    ("#size", MemberKind::Getter, Accessibility::Private),
+                    ("legacy", MemberKind::Property, Accessibility::Private),
+                    ("legacyRun", MemberKind::Method, Accessibility::Private),
+                    ("run", MemberKind::Method, Accessibility::Public),
+                ]
+            );
+            assert!(members[1].is_static);
+        }
+
+        #[test]
+        fn abstract_members() {
+            let members = get_members(&make_symbol(
+                "export declare abstract class Task {\n  abstract run(): void;\n  protected abstract name: string;\n  stop(): void;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| (member.name.as_str(), member.is_abstract))
+                    .collect::<Vec<_>>(),
+                vec![("run", true), ("name", true), ("stop", false)]
+            );
+            assert_eq!(members[1].accessibility, Accessibility::Protected);
+        }
+
+        #[test]
+        fn interface_members() {
+            let members = get_members(&make_symbol(
+                "export interface Foo {\n  (value: string): void;\n  'quoted-name': string;\n  run(): void;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| (member.name.as_str(), member.kind))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("quoted-name", MemberKind::Property),
+                    ("run", MemberKind::Method)
+                ]
+            );
+        }
+
+        #[test]
+        fn docs() {
+            let members = get_members(&make_symbol(
+                "export declare class Foo {\n  /** The bar. */\n  bar: string; // Ignored\n  readonly timeout: number; // in milliseconds\n  retries: number; /* at most 3 */\n  run(): void;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| (member.name.as_str(), member.doc.as_deref()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("bar", Some("The bar.")),
+                    ("timeout", Some("in milliseconds")),
+                    ("retries", Some("at most 3")),
+                    ("run", None),
+                ]
+            );
+        }
+
+        #[test]
+        fn trailing_comment_before_next_line() {
+            let members = get_members(&make_symbol(
+                "export interface Foo {\n  bar: string;\n  // About baz\n  baz: number;\n}",
+            ));
+
+            assert_eq!(
+                members
+                    .iter()
+                    .map(|member| member.doc.as_deref())
+                    .collect::<Vec<_>>(),
+                vec![None, None]
+            );
+        }
+
+        #[test]
+        fn non_class() {
+            let members = get_members(&make_symbol("export declare function foo(): void;"));
+
+            assert_eq!(members, vec![]);
+        }
+    }
+
+    mod remove_members {
+        use super::*;
+
+        #[test]
+        fn protected_members() {
+            let symbol = make_symbol(
+                "export declare class Foo {\n  bar(): void;\n  /** Only for subclasses. */\n  protected baz(): void;\n  protected qux: number;\n}",
+            );
+
+            let filtered_symbol = remove_members(&symbol, &[Accessibility::Protected]).unwrap();
+
+            assert_eq!(
+                filtered_symbol.source_code,
+                "export declare class Foo {\n  bar(): void;\n}"
+            );
+        }
+
+        #[test]
+        fn nested_class() {
+            let symbol = make_symbol(
+                "export class A { static Inner = class { private y = 1; }; private x = 2; }",
+            );
+
+            let filtered_symbol = remove_members(&symbol, &[Accessibility::Private]).unwrap();
+
+            assert_eq!(
+                filtered_symbol.source_code,
+                "export class A { static Inner = class {  };  }"
+            );
+        }
+
+        #[test]
+        fn members_on_same_line() {
+            let symbol =
+                make_symbol("export declare class Foo { bar(): void; protected baz(): void; }");
+
+            let filtered_symbol = remove_members(&symbol, &[Accessibility::Protected]).unwrap();
+
+            assert_eq!(
+                filtered_symbol.source_code,
+                "export declare class Foo { bar(): void;  }"
+            );
+        }
+
+        #[test]
+        fn private_members() {
+            let symbol = make_symbol(
+                "export declare class Foo {\n  #private;\n  bar(): void;\n  private baz; // Internal\n  static #count: number;\n  #run(): void;\n  protected qux(): void;\n}",
+            );
+
+            let filtered_symbol = remove_members(&symbol, &[Accessibility::Private]).unwrap();
+
+            assert_eq!(
+                filtered_symbol.source_code,
+                "export declare class Foo {\n  bar(): void;\n  protected qux(): void;\n}"
+            );
+        }
+
+        #[test]
+        fn private_and_protected_members() {
+            let symbol = make_symbol(
+                "export declare class Foo {\n  #private;\n  bar(): void;\n  protected qux(): void;\n}",
+            );
+
+            let filtered_symbol =
+                remove_members(&symbol, &[Accessibility::Protected, Accessibility::Private])
+                    .unwrap();
+
+            assert_eq!(
+                filtered_symbol.source_code,
+                "export declare class Foo {\n  bar(): void;\n}"
+            );
+        }
+
+        #[test]
+        fn parameter_properties() {
+            let symbol =
+                make_symbol("export declare class Foo {\n  constructor(protected bar: string);\n}");
+
+            assert_eq!(remove_members(&symbol, &[Accessibility::Protected]), None);
+        }
+
+        #[test]
+        fn no_protected_members() {
+            let symbol = make_symbol("export declare class Foo {\n  bar(): void;\n}");
+
+            assert_eq!(remove_members(&symbol, &[Accessibility::Protected]), None);
+        }
+    }
+}
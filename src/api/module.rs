@@ -1,9 +1,10 @@
-use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::fmt;
 use std::path::PathBuf;
 
 use daipendency_extractor::Symbol;
 
+use crate::api::generated::Generator;
+
 /// A TypeScript module (i.e. a file).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Module {
@@ -11,10 +12,69 @@ pub struct Module {
     pub jsdoc: Option<String>,
     pub symbols: Vec<TypeScriptSymbol>,
     pub default_export_name: Option<String>,
+    /// The standard libraries the module assumes (e.g. `dom` in `/// <reference lib="dom" />`).
+    pub lib_references: Vec<String>,
+    /// Whether the module replaces the default standard library
+    /// (i.e. `/// <reference no-default-lib="true" />`).
+    pub no_default_lib: bool,
+    /// Whether the module has an empty export (i.e. `export {};`), which makes a file a module
+    /// without exporting anything.
+    pub has_module_marker: bool,
+    /// Whether namespaces were left out because they're nested too deeply.
+    pub has_truncated_namespaces: bool,
+    /// The tool that generated the module, if its leading comments mark it as generated.
+    pub generator: Option<Generator>,
+    /// The `declare module` blocks in the module, in order.
+    pub augmentations: Vec<Augmentation>,
+    /// The declarations in the top-level `declare global` blocks, with their JSDoc, in order.
+    pub globals: Vec<Symbol>,
+    /// The directive prologue of the module (e.g. `use strict` in `"use strict";`).
+    pub directives: Vec<String>,
+    /// The pragma comments in the module, in order.
+    pub pragmas: Vec<Pragma>,
+}
+
+/// A comment that changes how a module is interpreted (e.g. `// @ts-nocheck` or
+/// `/** @jsxImportSource preact */`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pragma {
+    /// The name of the pragma, without the `@` (e.g. `jsxImportSource`).
+    pub name: String,
+    /// The argument of the pragma, without quotes (e.g. `preact`), if any.
+    pub value: Option<String>,
+}
+
+/// A `declare module` block, which augments another module (e.g. a plugin adding methods to
+/// its host with `declare module 'fastify' { interface FastifyInstance { ... } }`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Augmentation {
+    /// The augmented module, as written (e.g. `fastify` or `./client`).
+    pub specifier: String,
+    /// The declarations in the block, with their JSDoc, in order.
+    pub symbols: Vec<Symbol>,
+}
+
+/// A name in an import or export list (e.g. `type Foo as Bar` in
+/// `import { type Foo as Bar } from './foo';`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Specifier {
+    /// The name of the symbol in the module it comes from (e.g. `Foo`).
+    pub name: String,
+    /// The name the symbol is bound to instead, if any (e.g. `Bar`).
+    pub alias: Option<String>,
+    /// Whether the specifier only imports or exports a type (e.g. `type Foo`).
+    pub is_type_only: bool,
+}
+
+impl Specifier {
+    /// Gets the name the symbol is bound to: the alias if any, or else its name.
+    pub fn alias_or_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
 }
 
 /// The target of an import in a TypeScript module.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ImportTarget {
     /// The default export from another module (e.g. `import React from 'react';`).
     Default {
@@ -28,35 +88,13 @@ pub enum ImportTarget {
     },
     /// A named import from another module (e.g. `import { useState } from 'react';`).
     Named {
-        /// The names of the symbols to import (e.g. `useState` in `import { useState } from 'react';`).
-        names: Vec<String>,
-        /// The aliases for the imported symbols (e.g. `useState: foo` in `import { useState as foo } from 'react';`).
-        aliases: HashMap<String, String>,
+        /// The symbols to import, in order (e.g. `useState as foo` in `import { useState as foo } from 'react';`).
+        specifiers: Vec<Specifier>,
     },
 }
 
-impl Hash for ImportTarget {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        match self {
-            ImportTarget::Default { name } => {
-                0.hash(state);
-                name.hash(state);
-            }
-            ImportTarget::Namespace { name } => {
-                1.hash(state);
-                name.hash(state);
-            }
-            ImportTarget::Named { names, .. } => {
-                2.hash(state);
-                names.hash(state);
-                // Skip aliases in hash calculation as HashMap doesn't implement Hash
-            }
-        }
-    }
-}
-
 /// The target of an export in a TypeScript module.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ExportTarget {
     /// A namespace export from another module (e.g. `export * as React from 'react';`).
     Namespace {
@@ -65,34 +103,13 @@ pub enum ExportTarget {
     },
     /// A named export from another module (e.g. `export { useState } from 'react';`).
     Named {
-        /// The names of the symbols to export (e.g. `useState` in `export { useState } from 'react';`).
-        names: Vec<String>,
-        /// The aliases for the exported symbols (e.g. `useState: foo` in `export { useState as foo } from 'react';`).
-        aliases: HashMap<String, String>,
+        /// The symbols to export, in order (e.g. `useState as foo` in `export { useState as foo } from 'react';`).
+        specifiers: Vec<Specifier>,
     },
     /// A barrel export from another module (e.g. `export * from './module.js';`).
     Barrel,
 }
 
-impl Hash for ExportTarget {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        match self {
-            ExportTarget::Namespace { name } => {
-                0.hash(state);
-                name.hash(state);
-            }
-            ExportTarget::Named { names, .. } => {
-                1.hash(state);
-                names.hash(state);
-                // Skip aliases in hash calculation as HashMap doesn't implement Hash
-            }
-            ExportTarget::Barrel => {
-                2.hash(state);
-            }
-        }
-    }
-}
-
 /// A symbol in a TypeScript module.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TypeScriptSymbol {
@@ -110,6 +127,15 @@ pub enum TypeScriptSymbol {
         /// Whether the symbol was exported when declared.
         is_exported: bool,
     },
+    /// An alias for an entity in scope (e.g. `export import EventEmitter = NodeJS.EventEmitter;`).
+    ImportAlias {
+        /// The source code of the alias, which stands in for the target if it can't be resolved.
+        symbol: Symbol,
+        /// The path to the aliased entity (e.g. `["NodeJS", "EventEmitter"]`).
+        target: Vec<String>,
+        /// Whether the alias was exported when declared.
+        is_exported: bool,
+    },
     /// An import from another module (e.g. `import Foo from './foo.js';`).
     ///
     /// If a single `import` statement uses multiple types of targets, it will be represented as multiple `ModuleImport` symbols.
@@ -118,6 +144,12 @@ pub enum TypeScriptSymbol {
         source_module: String,
         /// The target of the import (e.g. `Foo` in `import Foo from './foo.js';`).
         target: ImportTarget,
+        /// Whether the source is a package that couldn't be found (e.g. a missing optional
+        /// dependency), in which case the imported types are missing from the public API.
+        is_unresolved_external: bool,
+        /// Whether the source is a Node.js builtin module (e.g. `node:fs` or `path`), whose types
+        /// come from `@types/node`.
+        is_builtin: bool,
     },
     /// An export from another module (e.g. `export Foo from './foo.js';`).
     ///
@@ -129,5 +161,518 @@ pub enum TypeScriptSymbol {
         source_module: Option<String>,
         /// The target of the export (e.g. `Foo` in `export Foo from './foo.js';`).
         target: ExportTarget,
+        /// Whether the export only makes types available (e.g. `export type * from './types';`).
+        is_type_only: bool,
+    },
+    /// A symbol produced by a query extension, which is always exported.
+    Custom {
+        /// What the symbol represents, as set by the extension (e.g. `route`).
+        kind: String,
+        name: String,
+        source: String,
     },
 }
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if self.no_default_lib {
+            write!(f, "\n  no default lib")?;
+        }
+        for lib in &self.lib_references {
+            write!(f, "\n  lib {lib}")?;
+        }
+        if let Some(name) = &self.default_export_name {
+            write!(f, "\n  export default {name}")?;
+        }
+        if self.has_module_marker {
+            write!(f, "\n  export {{}}")?;
+        }
+        if let Some(generator) = self.generator {
+            write!(f, "\n  generated by {generator}")?;
+        }
+        for symbol in &self.symbols {
+            write_symbol_outline(f, symbol, 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeScriptSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeScriptSymbol::Symbol {
+                symbol,
+                is_exported,
+            } => {
+                write_export_prefix(f, *is_exported)?;
+                write!(f, "symbol {}", symbol.name)
+            }
+            TypeScriptSymbol::Namespace {
+                name, is_exported, ..
+            } => {
+                write_export_prefix(f, *is_exported)?;
+                write!(f, "namespace {name}")
+            }
+            TypeScriptSymbol::ImportAlias {
+                symbol,
+                target,
+                is_exported,
+            } => {
+                write_export_prefix(f, *is_exported)?;
+                write!(f, "import {} = {}", symbol.name, target.join("."))
+            }
+            TypeScriptSymbol::ModuleImport {
+                source_module,
+                target,
+                is_unresolved_external,
+                is_builtin,
+            } => {
+                write!(f, "import {target} from '{source_module}'")?;
+                if *is_unresolved_external {
+                    write!(f, " (unresolved)")?;
+                } else if *is_builtin {
+                    write!(f, " (builtin)")?;
+                }
+                Ok(())
+            }
+            TypeScriptSymbol::ModuleExport {
+                source_module,
+                target,
+                is_type_only,
+            } => {
+                write!(f, "export ")?;
+                if *is_type_only {
+                    write!(f, "type ")?;
+                }
+                write!(f, "{target}")?;
+                if let Some(source_module) = source_module {
+                    write!(f, " from '{source_module}'")?;
+                }
+                Ok(())
+            }
+            TypeScriptSymbol::Custom { kind, name, .. } => write!(f, "custom {kind} {name}"),
+        }
+    }
+}
+
+impl fmt::Display for ImportTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportTarget::Default { name } => write!(f, "{name}"),
+            ImportTarget::Namespace { name } => write!(f, "* as {name}"),
+            ImportTarget::Named { specifiers } => write_specifiers(f, specifiers),
+        }
+    }
+}
+
+impl fmt::Display for ExportTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportTarget::Namespace { name } => write!(f, "* as {name}"),
+            ExportTarget::Named { specifiers } => write_specifiers(f, specifiers),
+            ExportTarget::Barrel => write!(f, "*"),
+        }
+    }
+}
+
+fn write_export_prefix(f: &mut fmt::Formatter<'_>, is_exported: bool) -> fmt::Result {
+    if is_exported {
+        write!(f, "export ")?;
+    }
+    Ok(())
+}
+
+fn write_specifiers(f: &mut fmt::Formatter<'_>, specifiers: &[Specifier]) -> fmt::Result {
+    let specifiers = specifiers
+        .iter()
+        .map(|specifier| {
+            let prefix = if specifier.is_type_only { "type " } else { "" };
+            match &specifier.alias {
+                Some(alias) => format!("{prefix}{} as {alias}", specifier.name),
+                None => format!("{prefix}{}", specifier.name),
+            }
+        })
+        .collect::<Vec<_>>();
+    write!(f, "{{ {} }}", specifiers.join(", "))
+}
+
+/// Writes a symbol on its own line, followed by the content of namespaces one level deeper.
+fn write_symbol_outline(
+    f: &mut fmt::Formatter<'_>,
+    symbol: &TypeScriptSymbol,
+    depth: usize,
+) -> fmt::Result {
+    write!(f, "\n{}{symbol}", "  ".repeat(depth))?;
+    if let TypeScriptSymbol::Namespace { content, .. } = symbol {
+        for inner_symbol in content {
+            write_symbol_outline(f, inner_symbol, depth + 1)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::{make_specifier, make_symbol};
+
+    fn make_declaration(name: &str, is_exported: bool) -> TypeScriptSymbol {
+        TypeScriptSymbol::Symbol {
+            symbol: make_symbol(name, &format!("declare const {name}: string;")),
+            is_exported,
+        }
+    }
+
+    mod symbol_display {
+        use super::*;
+
+        #[test]
+        fn symbol() {
+            let symbol = make_declaration("foo", false);
+
+            assert_eq!(symbol.to_string(), "symbol foo");
+        }
+
+        #[test]
+        fn exported_symbol() {
+            let symbol = make_declaration("foo", true);
+
+            assert_eq!(symbol.to_string(), "export symbol foo");
+        }
+
+        #[test]
+        fn namespace() {
+            let symbol = TypeScriptSymbol::Namespace {
+                name: "Utils".to_string(),
+                jsdoc: None,
+                content: vec![make_declaration("helper", true)],
+                is_exported: true,
+            };
+
+            assert_eq!(symbol.to_string(), "export namespace Utils");
+        }
+
+        #[test]
+        fn import_alias() {
+            let symbol = TypeScriptSymbol::ImportAlias {
+                symbol: Symbol {
+                    name: "EventEmitter".to_string(),
+                    source_code: "export import EventEmitter = NodeJS.EventEmitter;".to_string(),
+                },
+                target: vec!["NodeJS".to_string(), "EventEmitter".to_string()],
+                is_exported: true,
+            };
+
+            assert_eq!(
+                symbol.to_string(),
+                "export import EventEmitter = NodeJS.EventEmitter"
+            );
+        }
+
+        #[test]
+        fn default_import() {
+            let symbol = TypeScriptSymbol::ModuleImport {
+                source_module: "react".to_string(),
+                target: ImportTarget::Default {
+                    name: "React".to_string(),
+                },
+                is_unresolved_external: false,
+                is_builtin: false,
+            };
+
+            assert_eq!(symbol.to_string(), "import React from 'react'");
+        }
+
+        #[test]
+        fn namespace_import() {
+            let symbol = TypeScriptSymbol::ModuleImport {
+                source_module: "react".to_string(),
+                target: ImportTarget::Namespace {
+                    name: "React".to_string(),
+                },
+                is_unresolved_external: false,
+                is_builtin: false,
+            };
+
+            assert_eq!(symbol.to_string(), "import * as React from 'react'");
+        }
+
+        #[test]
+        fn named_import() {
+            let symbol = TypeScriptSymbol::ModuleImport {
+                source_module: "react".to_string(),
+                target: ImportTarget::Named {
+                    specifiers: vec![
+                        make_specifier("useState", None),
+                        make_specifier("useEffect", Some("effect")),
+                    ],
+                },
+                is_unresolved_external: false,
+                is_builtin: false,
+            };
+
+            assert_eq!(
+                symbol.to_string(),
+                "import { useState, useEffect as effect } from 'react'"
+            );
+        }
+
+        #[test]
+        fn unresolved_external_import() {
+            let symbol = TypeScriptSymbol::ModuleImport {
+                source_module: "optional-dep".to_string(),
+                target: ImportTarget::Default {
+                    name: "Plugin".to_string(),
+                },
+                is_unresolved_external: true,
+                is_builtin: false,
+            };
+
+            assert_eq!(
+                symbol.to_string(),
+                "import Plugin from 'optional-dep' (unresolved)"
+            );
+        }
+
+        #[test]
+        fn builtin_import() {
+            let symbol = TypeScriptSymbol::ModuleImport {
+                source_module: "node:fs".to_string(),
+                target: ImportTarget::Namespace {
+                    name: "fs".to_string(),
+                },
+                is_unresolved_external: false,
+                is_builtin: true,
+            };
+
+            assert_eq!(
+                symbol.to_string(),
+                "import * as fs from 'node:fs' (builtin)"
+            );
+        }
+
+        #[test]
+        fn named_export_without_source() {
+            let symbol = TypeScriptSymbol::ModuleExport {
+                source_module: None,
+                target: ExportTarget::Named {
+                    specifiers: vec![make_specifier("foo", None)],
+                },
+                is_type_only: false,
+            };
+
+            assert_eq!(symbol.to_string(), "export { foo }");
+        }
+
+        #[test]
+        fn type_only_specifier() {
+            let symbol = TypeScriptSymbol::ModuleExport {
+                source_module: Some("./foo.js".to_string()),
+                target: ExportTarget::Named {
+                    specifiers: vec![
+                        Specifier {
+                            name: "Foo".to_string(),
+                            alias: Some("Bar".to_string()),
+                            is_type_only: true,
+                        },
+                        make_specifier("foo", None),
+                    ],
+                },
+                is_type_only: false,
+            };
+
+            assert_eq!(
+                symbol.to_string(),
+                "export { type Foo as Bar, foo } from './foo.js'"
+            );
+        }
+
+        #[test]
+        fn namespace_export() {
+            let symbol = TypeScriptSymbol::ModuleExport {
+                source_module: Some("./foo.js".to_string()),
+                target: ExportTarget::Namespace {
+                    name: "foo".to_string(),
+                },
+                is_type_only: false,
+            };
+
+            assert_eq!(symbol.to_string(), "export * as foo from './foo.js'");
+        }
+
+        #[test]
+        fn type_only_barrel_export() {
+            let symbol = TypeScriptSymbol::ModuleExport {
+                source_module: Some("./foo.js".to_string()),
+                target: ExportTarget::Barrel,
+                is_type_only: true,
+            };
+
+            assert_eq!(symbol.to_string(), "export type * from './foo.js'");
+        }
+
+        #[test]
+        fn barrel_export() {
+            let symbol = TypeScriptSymbol::ModuleExport {
+                source_module: Some("./foo.js".to_string()),
+                target: ExportTarget::Barrel,
+                is_type_only: false,
+            };
+
+            assert_eq!(symbol.to_string(), "export * from './foo.js'");
+        }
+    }
+
+    mod module_display {
+        use super::*;
+
+        #[test]
+        fn empty_module() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(module.to_string(), "/test/index.d.ts");
+        }
+
+        #[test]
+        fn default_export() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![make_declaration("foo", false)],
+                default_export_name: Some("foo".to_string()),
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(
+                module.to_string(),
+                "/test/index.d.ts\n  export default foo\n  symbol foo"
+            );
+        }
+
+        #[test]
+        fn lib_references() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec!["dom".to_string(), "es2020".to_string()],
+                no_default_lib: true,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(
+                module.to_string(),
+                "/test/index.d.ts\n  no default lib\n  lib dom\n  lib es2020"
+            );
+        }
+
+        #[test]
+        fn module_marker() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: true,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(module.to_string(), "/test/index.d.ts\n  export {}");
+        }
+
+        #[test]
+        fn generated_module() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: Some(Generator::Protobuf),
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(
+                module.to_string(),
+                "/test/index.d.ts\n  generated by protobuf"
+            );
+        }
+
+        #[test]
+        fn nested_namespaces() {
+            let module = Module {
+                path: PathBuf::from("/test/index.d.ts"),
+                jsdoc: None,
+                symbols: vec![TypeScriptSymbol::Namespace {
+                    name: "Outer".to_string(),
+                    jsdoc: None,
+                    content: vec![TypeScriptSymbol::Namespace {
+                        name: "Inner".to_string(),
+                        jsdoc: None,
+                        content: vec![make_declaration("foo", true)],
+                        is_exported: true,
+                    }],
+                    is_exported: true,
+                }],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+
+            assert_eq!(
+                module.to_string(),
+                "/test/index.d.ts\n  export namespace Outer\n    export namespace Inner\n      export symbol foo"
+            );
+        }
+    }
+}
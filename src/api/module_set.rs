@@ -1,20 +1,56 @@
-use std::collections::{HashSet, VecDeque};
-use std::fs::read_to_string;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use daipendency_extractor::ExtractionError;
+use daipendency_extractor::{ExtractionError, Symbol};
 use tree_sitter::Parser;
 
-use crate::api::module::{Module, TypeScriptSymbol};
+use crate::api::assets::{is_asset_file, AssetReference};
+use crate::api::externals::{
+    get_package_name, is_node_builtin, read_package_version, resolve_external_package,
+    ExternalReference, UnresolvedExternal,
+};
+use crate::api::file_kinds::{find_file_extension, FileKinds};
+use crate::api::module::{ExportTarget, ImportTarget, Module, TypeScriptSymbol};
 use crate::api::parsing::parse_typescript_file;
-use crate::metadata::TSEntryPointSet;
+use crate::api::resolution::{ExportResolver, ResolvedExport};
+use crate::api::source_file::SourceFile;
+use crate::api::symbol_kind::{get_symbol_kind, SymbolKind};
+use crate::api::synthesis::synthesise_declarations;
+use crate::api::visitor::{walk_module, SymbolVisitor, VisitContext};
+use crate::dependencies::DependencyResolver;
+use crate::diagnostics::Diagnostic;
+use crate::metadata::{
+    find_package_type_with, is_excluded_file, resolve_subpath_import, PackageType, TSEntryPointSet,
+};
+use crate::options::{ExtractionOptions, UnpublishedFiles};
+use crate::report::ModuleMetrics;
 
 /// Represents a set of TypeScript modules.
 ///
 /// We derive Default to allow creating an empty ModuleSet instance with ModuleSet::default().
 /// This is useful in cases where you need to initialize a ModuleSet before populating it.
 #[derive(Debug, Default)]
-pub struct ModuleSet(HashSet<Module>);
+pub struct ModuleSet {
+    modules: HashSet<Module>,
+    /// The module format of each module, keyed by module path: that of the package owning it,
+    /// unless its extension overrides it (e.g. `.mts`).
+    package_types: HashMap<PathBuf, PackageType>,
+    /// Problems encountered whilst loading the modules (e.g. missing dependencies).
+    diagnostics: Vec<Diagnostic>,
+    /// Every import or re-export of another package, in the order the modules were loaded.
+    external_references: Vec<ExternalReference>,
+    /// The size of each module and the time it took to parse, in the order they were loaded.
+    metrics: Vec<ModuleMetrics>,
+    /// The modules loaded in place of others in the browser build, keyed by the canonical path of
+    /// the module they replace.
+    browser_remappings: BTreeMap<PathBuf, PathBuf>,
+    /// The kinds of files that imports are followed to.
+    file_kinds: FileKinds,
+    /// The files reached from the modules that aren't TypeScript, sorted by path.
+    asset_references: Vec<AssetReference>,
+}
 
 impl ModuleSet {
     /// Builds a module set from the given entry points.
@@ -23,6 +59,8 @@ impl ModuleSet {
     ///
     /// * `entry_points` - A set of entry points connecting external paths to internal file paths
     /// * `parser` - A tree-sitter parser configured for TypeScript
+    /// * `options` - The options deciding which files are followed, and the query extensions to
+    ///   run on them
     ///
     /// # Returns
     ///
@@ -30,24 +68,77 @@ impl ModuleSet {
     pub fn from_entrypoints(
         entry_points: &TSEntryPointSet,
         parser: &mut Parser,
+        options: &ExtractionOptions,
     ) -> Result<Self, ExtractionError> {
         let mut modules = HashSet::new();
+        let mut package_types = HashMap::new();
         let mut queue = VecDeque::new();
         let mut visited_paths = HashSet::new();
+        let dependency_resolver = DependencyResolver::new(options);
+        let mut package_versions = HashMap::new();
+        let mut diagnostics = vec![];
+        let mut external_references = vec![];
+        let mut metrics = vec![];
+        let mut excluded_paths = vec![];
+        let mut unpublished_paths = vec![];
+        let mut asset_paths = vec![];
+        let is_checking_unpublished = options.unpublished_files != UnpublishedFiles::Include;
+        let is_skipping_unpublished = options.unpublished_files == UnpublishedFiles::Skip;
+        // The type of the package itself is already known, unlike those of nested manifests
+        let mut known_package_types = HashMap::new();
+        let package_path = &entry_points.package_path;
+        if !package_path.as_os_str().is_empty() {
+            known_package_types.insert(package_path.clone(), entry_points.package_type);
+        }
 
         for entry_point in entry_points {
             queue.push_back(entry_point.internal_path.clone());
         }
+        let entry_point_paths = entry_points
+            .iter()
+            .map(|entry_point| {
+                let path = normalise_file_path(&entry_point.internal_path)
+                    .unwrap_or_else(|| entry_point.internal_path.clone());
+                get_browser_replacement(&entry_points.browser_remappings, path)
+            })
+            .collect::<HashSet<_>>();
 
         while let Some(current_path) = queue.pop_front() {
+            // The same file may be reached through different paths (e.g. via symlinks or `..`
+            // segments), so it's identified by its canonical path.
+            let current_path = normalise_file_path(&current_path).unwrap_or(current_path);
+            let current_path =
+                get_browser_replacement(&entry_points.browser_remappings, current_path);
             if visited_paths.contains(&current_path) {
                 continue;
             }
 
             visited_paths.insert(current_path.clone());
 
-            let content = match read_to_string(&current_path) {
-                Ok(content) => content,
+            if !entry_point_paths.contains(&current_path) {
+                if is_excluded_file(&current_path, package_path, options) {
+                    excluded_paths.push(current_path);
+                    continue;
+                }
+                // Unpublished entry points are already handled when they're determined
+                if is_checking_unpublished
+                    && entry_points.published_files.is_unpublished(&current_path)
+                {
+                    unpublished_paths.push(current_path.clone());
+                    if is_skipping_unpublished {
+                        continue;
+                    }
+                }
+            }
+
+            if !entry_point_paths.contains(&current_path) && is_asset_file(&current_path) {
+                asset_paths.push(current_path);
+                continue;
+            }
+
+            // SAFETY: Packages aren't expected to change during the extraction.
+            let source_file = match unsafe { SourceFile::read(&current_path) } {
+                Ok(source_file) => source_file,
                 Err(e) => {
                     let path_str = current_path.display().to_string();
                     return Err(ExtractionError::Io(std::io::Error::new(
@@ -56,30 +147,270 @@ impl ModuleSet {
                     )));
                 }
             };
-            let module = parse_typescript_file(&content, parser, current_path.clone())?;
+            let parse_start = Instant::now();
+            let synthesised_declarations = find_file_extension(&current_path)
+                .is_some_and(|extension| extension.kind.is_implementation())
+                .then(|| synthesise_declarations(source_file.as_str(), &current_path));
+            let mut module = parse_typescript_file(
+                synthesised_declarations
+                    .as_deref()
+                    .unwrap_or(source_file.as_str()),
+                parser,
+                current_path.clone(),
+                &options.query_extensions,
+            )?;
+            metrics.push(ModuleMetrics {
+                path: current_path.clone(),
+                parse_time: parse_start.elapsed(),
+                byte_count: source_file.as_str().len(),
+                symbol_count: module.symbols.len(),
+            });
+            if module.has_truncated_namespaces {
+                diagnostics.push(Diagnostic::NamespacesTooDeep {
+                    path: current_path.clone(),
+                });
+            }
+            let module_references = mark_unresolved_externals(
+                &mut module,
+                &dependency_resolver,
+                options,
+                &mut package_versions,
+            );
+            let mut reported_specifiers = HashSet::new();
+            for reference in &module_references {
+                if reference.package_path.is_none()
+                    && !reference.is_builtin
+                    && reported_specifiers.insert(&reference.specifier)
+                {
+                    diagnostics.push(Diagnostic::UnresolvedExternal {
+                        specifier: reference.specifier.clone(),
+                        importer: reference.importer.clone(),
+                    });
+                }
+            }
+            external_references.extend(module_references);
+            let package_type = get_module_format(&current_path, &mut known_package_types);
 
-            let dependencies = get_imported_module_paths(&module);
+            let dependencies =
+                get_imported_module_paths(&module, package_type, &options.file_kinds);
             for dependency in dependencies {
                 queue.push_back(dependency);
             }
 
+            package_types.insert(current_path, package_type);
             modules.insert(module);
         }
 
-        Ok(ModuleSet(modules))
+        diagnostics.extend(dependency_resolver.diagnostics());
+        if !excluded_paths.is_empty() {
+            excluded_paths.sort();
+            diagnostics.push(Diagnostic::ExcludedFiles {
+                paths: excluded_paths,
+            });
+        }
+        if !unpublished_paths.is_empty() {
+            unpublished_paths.sort();
+            diagnostics.push(Diagnostic::UnpublishedFiles {
+                paths: unpublished_paths,
+                is_skipped: is_skipping_unpublished,
+            });
+        }
+
+        let asset_references =
+            find_asset_references(asset_paths, &modules, &package_types, &options.file_kinds);
+        if !asset_references.is_empty() {
+            diagnostics.push(Diagnostic::AssetReferences {
+                paths: asset_references
+                    .iter()
+                    .map(|asset_reference| asset_reference.path.clone())
+                    .collect(),
+            });
+        }
+
+        Ok(ModuleSet {
+            modules,
+            package_types,
+            diagnostics,
+            external_references,
+            metrics,
+            browser_remappings: entry_points.browser_remappings.clone(),
+            file_kinds: options.file_kinds.clone(),
+            asset_references,
+        })
     }
 
     /// Gets a module by its path.
     ///
     /// # Arguments
     ///
-    /// * `path` - The path of the module to find
+    /// * `path` - The path of the module to find, which needn't be canonical
     ///
     /// # Returns
     ///
     /// The module if found, or None otherwise
     pub fn get(&self, path: &Path) -> Option<&Module> {
-        self.0.iter().find(|module| module.path == path)
+        let find = |path: &Path| self.modules.iter().find(|module| module.path == path);
+        find(path).or_else(|| {
+            let path = normalise_file_path(path)?;
+            find(&path).or_else(|| find(self.browser_remappings.get(&path)?))
+        })
+    }
+
+    /// Gets the module that an import specifier refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module containing the import
+    /// * `specifier` - The import specifier (e.g. `./foo.js`)
+    ///
+    /// # Returns
+    ///
+    /// The imported module if the specifier is relative or a subpath import (e.g. `#utils`) and the
+    /// module is in the set, or None otherwise
+    pub fn resolve_import(&self, module: &Module, specifier: &str) -> Option<&Module> {
+        let path = resolve_local_import(
+            &module.path,
+            specifier,
+            self.package_type(module),
+            &self.file_kinds,
+        )?;
+        self.get(&path)
+    }
+
+    /// Gets the module format of a module, which is the type of the package that owns it unless
+    /// the module is a `.mts` or `.cts` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module whose format to look up
+    ///
+    /// # Returns
+    ///
+    /// The module format, defaulting to CommonJS for modules that weren't loaded from disk
+    pub fn package_type(&self, module: &Module) -> PackageType {
+        self.package_types
+            .get(&module.path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Lists the problems encountered whilst loading the modules, which didn't prevent it.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Lists the files reached from the modules that weren't parsed as they aren't TypeScript
+    /// (e.g. `.wasm` or `.json` files), sorted by path.
+    pub fn asset_references(&self) -> &[AssetReference] {
+        &self.asset_references
+    }
+
+    /// Lists the size of each module and the time it took to parse, in the order they were
+    /// loaded.
+    pub fn metrics(&self) -> &[ModuleMetrics] {
+        &self.metrics
+    }
+
+    /// Lists the bare specifiers whose types are missing from the output, sorted by specifier:
+    /// those whose package couldn't be found, and those of packages that aren't followed.
+    pub fn unresolved_externals(&self) -> Vec<UnresolvedExternal> {
+        UnresolvedExternal::from_references(&self.external_references)
+    }
+
+    /// Finds the reference to another package made by a module with a given specifier, if the
+    /// specifier is bare (e.g. `zod`) or refers to a Node.js builtin module.
+    pub(crate) fn find_external_reference(
+        &self,
+        importer: &Module,
+        specifier: &str,
+    ) -> Option<&ExternalReference> {
+        self.external_references.iter().find(|reference| {
+            reference.importer == importer.path && reference.specifier == specifier
+        })
+    }
+
+    /// Finds the declarations with a given name, including those in namespaces, in order of
+    /// module path and then in the order they're declared.
+    pub fn find_symbol<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (&'a Module, &'a Symbol)> + 'a {
+        self.iter_declarations()
+            .filter(move |(_, symbol)| symbol.name == name)
+    }
+
+    /// Finds the declarations of a given kind, including those in namespaces, in order of module
+    /// path and then in the order they're declared.
+    pub fn symbols_of_kind(
+        &self,
+        kind: SymbolKind,
+    ) -> impl Iterator<Item = (&Module, &Symbol)> + '_ {
+        self.iter_declarations()
+            .filter(move |(_, symbol)| get_symbol_kind(symbol) == Some(kind))
+    }
+
+    /// Resolves the named exports of a module, excluding its default export.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_path` - The path of the module, which needn't be canonical
+    ///
+    /// # Returns
+    ///
+    /// The exports, or none if the module isn't in the set
+    pub fn exports_of(&self, entry_path: &Path) -> impl Iterator<Item = ResolvedExport> {
+        let exports = match self.get(entry_path) {
+            Some(module) => ExportResolver::new(self).resolve_exports(module),
+            None => vec![],
+        };
+        exports.into_iter()
+    }
+
+    /// Finds the modules that import or re-export a module, in order of path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the imported module, which needn't be canonical
+    pub fn modules_importing(&self, path: &Path) -> impl Iterator<Item = &Module> + '_ {
+        let imported_path = self.get(path).map(|module| module.path.clone());
+        self.iter_sorted().filter(move |module| {
+            imported_path.as_ref().is_some_and(|imported_path| {
+                get_imported_module_paths(module, self.package_type(module), &self.file_kinds)
+                    .iter()
+                    .any(|path| {
+                        self.get(path)
+                            .is_some_and(|import| &import.path == imported_path)
+                    })
+            })
+        })
+    }
+
+    /// Iterates over the modules in order of path, so that the output is stable.
+    fn iter_sorted(&self) -> impl Iterator<Item = &Module> {
+        let mut modules = self.modules.iter().collect::<Vec<_>>();
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+        modules.into_iter()
+    }
+
+    /// Iterates over the declarations in every module, including those in namespaces.
+    /// Drops the modules that aren't at the given paths, along with everything recorded about them
+    /// apart from their diagnostics.
+    pub(crate) fn retain_modules(&mut self, paths: &HashSet<PathBuf>) {
+        self.modules.retain(|module| paths.contains(&module.path));
+        self.package_types.retain(|path, _| paths.contains(path));
+        self.metrics.retain(|metrics| paths.contains(&metrics.path));
+        self.external_references
+            .retain(|reference| paths.contains(&reference.importer));
+    }
+
+    pub(crate) fn iter_declarations(&self) -> impl Iterator<Item = (&Module, &Symbol)> {
+        self.iter_sorted().flat_map(|module| {
+            let mut declarations = vec![];
+            collect_declarations(&module.symbols, &mut declarations);
+            declarations
+                .into_iter()
+                .map(move |declaration| (module, declaration))
+        })
     }
 }
 
@@ -88,11 +419,97 @@ impl std::ops::Deref for ModuleSet {
     type Target = HashSet<Module>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.modules
+    }
+}
+
+/// Lists every module in the set, sorted by path so that the output is stable.
+impl fmt::Display for ModuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outlines = self
+            .iter_sorted()
+            .map(|module| module.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", outlines.join("\n\n"))
+    }
+}
+
+/// Marks the imports from packages that can't be found, which is tolerated as they may well be
+/// optional or peer dependencies that aren't installed, as well as the imports of Node.js builtin
+/// modules, and lists the references to packages.
+///
+/// The versions of the packages are cached in `package_versions`, keyed by package directory.
+fn mark_unresolved_externals(
+    module: &mut Module,
+    dependency_resolver: &DependencyResolver,
+    options: &ExtractionOptions,
+    package_versions: &mut HashMap<PathBuf, Option<String>>,
+) -> Vec<ExternalReference> {
+    let mut references = vec![];
+    for symbol in &mut module.symbols {
+        let source_module = match symbol {
+            TypeScriptSymbol::ModuleImport { source_module, .. } => source_module,
+            TypeScriptSymbol::ModuleExport {
+                source_module: Some(source_module),
+                ..
+            } => source_module,
+            _ => continue,
+        };
+        let is_builtin = is_node_builtin(source_module);
+        if !is_builtin && get_package_name(source_module).is_none() {
+            continue;
+        }
+        let package_path = resolve_external_package(
+            dependency_resolver,
+            &module.path,
+            source_module,
+            options.host_root.as_deref(),
+        );
+        let package_version =
+            package_path
+                .as_ref()
+                .filter(|_| !is_builtin)
+                .and_then(|package_path| {
+                    package_versions
+                        .entry(package_path.clone())
+                        .or_insert_with(|| read_package_version(package_path))
+                        .clone()
+                });
+        references.push(ExternalReference {
+            specifier: source_module.clone(),
+            importer: module.path.clone(),
+            package_path: package_path.clone(),
+            package_version,
+            is_builtin,
+        });
+
+        if let TypeScriptSymbol::ModuleImport {
+            is_unresolved_external,
+            is_builtin: is_builtin_import,
+            ..
+        } = symbol
+        {
+            // Builtin modules are never missing, even if `@types/node` isn't installed.
+            *is_unresolved_external = !is_builtin && package_path.is_none();
+            *is_builtin_import = is_builtin;
+        }
     }
+    references
 }
 
-fn normalise_file_path(path: &PathBuf) -> Option<PathBuf> {
+fn collect_declarations<'a>(symbols: &'a [TypeScriptSymbol], declarations: &mut Vec<&'a Symbol>) {
+    for symbol in symbols {
+        match symbol {
+            TypeScriptSymbol::Symbol { symbol, .. } => declarations.push(symbol),
+            TypeScriptSymbol::Namespace { content, .. } => {
+                collect_declarations(content, declarations)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn normalise_file_path(path: &Path) -> Option<PathBuf> {
     if let Ok(path) = path.canonicalize() {
         if path.is_file() {
             return Some(path);
@@ -101,74 +518,216 @@ fn normalise_file_path(path: &PathBuf) -> Option<PathBuf> {
     None
 }
 
-fn get_imported_module_paths(module: &Module) -> Vec<PathBuf> {
-    let mut dependencies = Vec::new();
-    let path = &module.path;
+/// Finds the modules referring to each asset, which are only known once every module is loaded.
+fn find_asset_references(
+    mut asset_paths: Vec<PathBuf>,
+    modules: &HashSet<Module>,
+    package_types: &HashMap<PathBuf, PackageType>,
+    file_kinds: &FileKinds,
+) -> Vec<AssetReference> {
+    asset_paths.sort();
+    let mut asset_references = asset_paths
+        .into_iter()
+        .map(|path| AssetReference {
+            path,
+            referencing_files: vec![],
+        })
+        .collect::<Vec<_>>();
+    if asset_references.is_empty() {
+        return asset_references;
+    }
 
-    for symbol in &module.symbols {
-        if let TypeScriptSymbol::ModuleImport { source_module, .. } = symbol {
-            if let Some(resolved_path) = resolve_relative_import(path, source_module) {
-                dependencies.push(resolved_path);
-            }
-        } else if let TypeScriptSymbol::ModuleExport {
-            source_module: Some(source_module),
-            ..
-        } = symbol
-        {
-            if let Some(resolved_path) = resolve_relative_import(path, source_module) {
-                dependencies.push(resolved_path);
+    for module in modules {
+        let package_type = package_types.get(&module.path).copied().unwrap_or_default();
+        for imported_path in get_imported_module_paths(module, package_type, file_kinds) {
+            let imported_path = normalise_file_path(&imported_path).unwrap_or(imported_path);
+            if let Some(asset_reference) = asset_references
+                .iter_mut()
+                .find(|asset_reference| asset_reference.path == imported_path)
+            {
+                if !asset_reference.referencing_files.contains(&module.path) {
+                    asset_reference.referencing_files.push(module.path.clone());
+                }
             }
         }
     }
+    for asset_reference in &mut asset_references {
+        asset_reference.referencing_files.sort();
+    }
+    asset_references
+}
 
-    dependencies
+/// Gets the module to load in place of another, which is itself unless the browser build replaces
+/// it.
+fn get_browser_replacement(
+    browser_remappings: &BTreeMap<PathBuf, PathBuf>,
+    path: PathBuf,
+) -> PathBuf {
+    browser_remappings.get(&path).cloned().unwrap_or(path)
 }
 
-fn resolve_relative_import(module_path: &Path, import_path: &str) -> Option<PathBuf> {
-    if import_path.starts_with("./") || import_path.starts_with("../") {
-        let parent_dir = module_path.parent()?;
-        let resolved_path = parent_dir.join(import_path);
+/// Determines whether a file is an ES module or a CommonJS module.
+///
+/// Some extensions force the module format regardless of the package type (e.g. `.mts` and
+/// `.d.cts`).
+fn get_module_format(
+    path: &Path,
+    known_package_types: &mut HashMap<PathBuf, PackageType>,
+) -> PackageType {
+    find_file_extension(path)
+        .and_then(|extension| extension.module_format)
+        .unwrap_or_else(|| find_package_type_with(path, known_package_types))
+}
 
-        if let Some(path) = normalise_file_path(&resolved_path) {
-            return Some(path);
-        }
+fn get_imported_module_paths(
+    module: &Module,
+    package_type: PackageType,
+    file_kinds: &FileKinds,
+) -> Vec<PathBuf> {
+    let mut collector = ImportedModuleCollector {
+        package_type,
+        file_kinds,
+        dependencies: vec![],
+    };
+    walk_module(module, &mut collector);
+    collector.dependencies
+}
 
-        if let Some(path) = normalise_file_path(&resolved_path.with_extension("d.ts")) {
-            return Some(path);
-        }
+/// Collects the modules that the top-level imports and re-exports of a module refer to.
+struct ImportedModuleCollector<'a> {
+    package_type: PackageType,
+    file_kinds: &'a FileKinds,
+    dependencies: Vec<PathBuf>,
+}
 
-        if let Some(path) = normalise_file_path(&resolved_path.with_extension("ts")) {
-            return Some(path);
+impl ImportedModuleCollector<'_> {
+    fn add(&mut self, context: &VisitContext, source_module: &str) {
+        if context.depth > 0 {
+            return;
         }
+        if let Some(resolved_path) = resolve_local_import(
+            context.module_path,
+            source_module,
+            self.package_type,
+            self.file_kinds,
+        ) {
+            self.dependencies.push(resolved_path);
+        }
+    }
+}
 
-        if resolved_path.is_dir() {
-            let with_index_dts = resolved_path.join("index.d.ts");
-            if let Some(path) = normalise_file_path(&with_index_dts) {
-                return Some(path);
-            }
+impl SymbolVisitor for ImportedModuleCollector<'_> {
+    fn visit_import(
+        &mut self,
+        context: &VisitContext,
+        source_module: &str,
+        _target: &ImportTarget,
+    ) {
+        self.add(context, source_module);
+    }
 
-            let with_index_ts = resolved_path.join("index.ts");
-            if let Some(path) = normalise_file_path(&with_index_ts) {
-                return Some(path);
-            }
+    fn visit_export(
+        &mut self,
+        context: &VisitContext,
+        source_module: Option<&str>,
+        _target: &ExportTarget,
+    ) {
+        if let Some(source_module) = source_module {
+            self.add(context, source_module);
         }
+    }
+}
+
+/// Resolves an import of a file within the package, which is either relative (e.g. `./foo.js`)
+/// or a subpath import declared in the manifest (e.g. `#internal/foo.js`).
+///
+/// Files whose kinds aren't followed are skipped, and None is returned if the import only refers
+/// to such files.
+fn resolve_local_import(
+    module_path: &Path,
+    import_path: &str,
+    package_type: PackageType,
+    file_kinds: &FileKinds,
+) -> Option<PathBuf> {
+    let resolved_path = if import_path.starts_with("./") || import_path.starts_with("../") {
+        module_path.parent()?.join(import_path)
+    } else if import_path.starts_with('#') {
+        resolve_subpath_import(module_path, import_path)?
+    } else {
+        return None;
+    };
+
+    // JavaScript files are only used when they have no declarations, as in TypeScript.
+    let mut candidates = vec![];
+    if !is_javascript_file(&resolved_path) {
+        candidates.push(resolved_path.clone());
+    }
+    candidates.extend(get_declaration_candidates(&resolved_path, package_type));
+    if resolved_path.is_dir() {
+        candidates.extend(get_declaration_candidates(
+            &resolved_path.join("index"),
+            package_type,
+        ));
+    }
+    if resolved_path.is_file() {
+        candidates.push(resolved_path.clone());
+    }
 
-        // The path doesn't exist but it isn't our responsibility to error out due to that
-        return Some(resolved_path);
+    let existing_paths = candidates
+        .iter()
+        .filter_map(|candidate| normalise_file_path(candidate))
+        .collect::<Vec<_>>();
+    if !existing_paths.is_empty() {
+        return existing_paths
+            .into_iter()
+            .find(|path| file_kinds.is_followed(path));
     }
 
-    None
+    // The path doesn't exist but it isn't our responsibility to error out due to that
+    Some(resolved_path)
+}
+
+fn is_javascript_file(path: &Path) -> bool {
+    find_file_extension(path).is_some_and(|extension| extension.kind.is_javascript())
+}
+
+/// Lists the files that may declare the types of an import, in order of preference.
+///
+/// Explicit `.mjs`/`.cjs` imports only match their `.d.mts`/`.d.cts` declarations or, failing
+/// that, their `.mts`/`.cts` sources. Otherwise, `.d.ts` files win, followed by `.ts` and `.tsx`
+/// sources, and the module format of the importer decides whether `.mts` or `.cts` files are used
+/// when there are none of those.
+fn get_declaration_candidates(path: &Path, package_type: PackageType) -> Vec<PathBuf> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("mjs") | Some("mts") => vec![path.with_extension("d.mts"), path.with_extension("mts")],
+        Some("cjs") | Some("cts") => vec![path.with_extension("d.cts"), path.with_extension("cts")],
+        _ => {
+            let variant_extension = match package_type {
+                PackageType::Module => "mts",
+                PackageType::CommonJs => "cts",
+            };
+            vec![
+                path.with_extension("d.ts"),
+                path.with_extension("ts"),
+                path.with_extension("tsx"),
+                path.with_extension(format!("d.{variant_extension}")),
+                path.with_extension(variant_extension),
+            ]
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::file_kinds::FileKind;
     use crate::api::module::{ExportTarget, ImportTarget};
-    use crate::api::test_helpers::make_parser;
-    use crate::metadata::TSEntryPoint;
+    use crate::api::test_helpers::{make_parser, make_specifier};
+    use crate::metadata::{PublishedFiles, TSEntryPoint};
     use assertables::{assert_contains, assert_matches};
     use daipendency_extractor::Symbol;
     use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
 
     struct ModuleFixture {
         entrypoint: Option<&'static str>,
@@ -197,7 +756,7 @@ mod tests {
         }
 
         fn generate_entry_points(&self) -> TSEntryPointSet {
-            let mut entrypoints = HashSet::new();
+            let mut entrypoints = TSEntryPointSet::default();
 
             for module in &self.modules {
                 self.temp_dir
@@ -208,6 +767,7 @@ mod tests {
                     entrypoints.insert(TSEntryPoint {
                         external_path: name.to_string(),
                         internal_path: self.make_path(module.path),
+                        conditions: BTreeMap::new(),
                     });
                 }
             }
@@ -220,151 +780,1276 @@ mod tests {
         use super::*;
 
         #[test]
-        fn returns_module_when_found() {
-            let path = PathBuf::from("/test/path.ts");
-            let module = Module {
-                path: path.clone(),
-                jsdoc: None,
-                symbols: vec![],
-                default_export_name: None,
+        fn returns_module_when_found() {
+            let path = PathBuf::from("/test/path.ts");
+            let module = Module {
+                path: path.clone(),
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+            let module_set = ModuleSet {
+                modules: HashSet::from([module.clone()]),
+                package_types: HashMap::new(),
+                diagnostics: vec![],
+                external_references: vec![],
+                metrics: vec![],
+                browser_remappings: BTreeMap::new(),
+                file_kinds: FileKinds::default(),
+                asset_references: vec![],
+            };
+
+            let module_retrieved = module_set.get(path.as_path()).unwrap();
+
+            assert_eq!(module_retrieved, &module);
+        }
+
+        #[test]
+        fn returns_none_when_not_found() {
+            let path = PathBuf::from("/test/path.ts");
+            let module = Module {
+                path,
+                jsdoc: None,
+                symbols: vec![],
+                default_export_name: None,
+                lib_references: vec![],
+                no_default_lib: false,
+                has_module_marker: false,
+                has_truncated_namespaces: false,
+                generator: None,
+                augmentations: vec![],
+                globals: vec![],
+                directives: vec![],
+                pragmas: vec![],
+            };
+            let module_set = ModuleSet {
+                modules: HashSet::from([module.clone()]),
+                package_types: HashMap::new(),
+                diagnostics: vec![],
+                external_references: vec![],
+                metrics: vec![],
+                browser_remappings: BTreeMap::new(),
+                file_kinds: FileKinds::default(),
+                asset_references: vec![],
+            };
+            let non_existent_path = PathBuf::from("/test/non_existent.ts");
+
+            let module_retrieved = module_set.get(non_existent_path.as_path());
+
+            assert_eq!(module_retrieved, None);
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn modules_are_sorted_by_path() {
+            let module_set = ModuleSet {
+                modules: HashSet::from([
+                    Module {
+                        path: PathBuf::from("/test/b.d.ts"),
+                        jsdoc: None,
+                        symbols: vec![],
+                        default_export_name: None,
+                        lib_references: vec![],
+                        no_default_lib: false,
+                        has_module_marker: false,
+                        has_truncated_namespaces: false,
+                        generator: None,
+                        augmentations: vec![],
+                        globals: vec![],
+                        directives: vec![],
+                        pragmas: vec![],
+                    },
+                    Module {
+                        path: PathBuf::from("/test/a.d.ts"),
+                        jsdoc: None,
+                        symbols: vec![],
+                        default_export_name: Some("foo".to_string()),
+                        lib_references: vec![],
+                        no_default_lib: false,
+                        has_module_marker: false,
+                        has_truncated_namespaces: false,
+                        generator: None,
+                        augmentations: vec![],
+                        globals: vec![],
+                        directives: vec![],
+                        pragmas: vec![],
+                    },
+                ]),
+                package_types: HashMap::new(),
+                diagnostics: vec![],
+                external_references: vec![],
+                metrics: vec![],
+                browser_remappings: BTreeMap::new(),
+                file_kinds: FileKinds::default(),
+                asset_references: vec![],
+            };
+
+            let output = module_set.to_string();
+
+            assert_eq!(output, "/test/a.d.ts\n  export default foo\n\n/test/b.d.ts");
+        }
+    }
+
+    mod resolve_import {
+        use super::*;
+
+        #[test]
+        fn relative_import() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.ts"));
+        }
+
+        #[test]
+        fn non_relative_import() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.d.ts",
+                content: "export * from 'foo';",
+            }]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "foo");
+
+            assert_eq!(imported_module, None);
+        }
+
+        #[test]
+        fn subpath_import_pattern() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: r##"{"imports": {"#internal/*": {"types": "./dist/internal/*.d.ts", "default": "./dist/internal/*.js"}}}"##,
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "dist/index.d.ts",
+                    content: "export { foo } from '#internal/foo';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "dist/internal/foo.d.ts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("dist/index.d.ts")).unwrap();
+
+            let imported_module = modules
+                .resolve_import(index_module, "#internal/foo")
+                .unwrap();
+
+            assert_eq!(
+                imported_module.path,
+                fixture.make_path("dist/internal/foo.d.ts")
+            );
+        }
+
+        #[test]
+        fn browser_remapping() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './fs';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "fs.d.ts",
+                    content: "export declare function readFile(): string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "fs-shim.d.ts",
+                    content: "export declare function readFile(): Promise<string>;",
+                },
+            ]);
+            let mut entrypoints = fixture.generate_entry_points();
+            let canonicalise = |path: &str| fixture.make_path(path).canonicalize().unwrap();
+            entrypoints.browser_remappings =
+                BTreeMap::from([(canonicalise("fs.d.ts"), canonicalise("fs-shim.d.ts"))]);
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./fs").unwrap();
+
+            assert_eq!(imported_module.path, canonicalise("fs-shim.d.ts"));
+            assert_eq!(modules.len(), 2);
+        }
+
+        #[test]
+        fn subpath_import_of_javascript_file() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: r##"{"imports": {"#utils": "./dist/utils.js"}}"##,
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "dist/index.d.ts",
+                    content: "export * from '#utils';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "dist/utils.d.ts",
+                    content: "export declare const util: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("dist/index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "#utils").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("dist/utils.d.ts"));
+        }
+
+        #[test]
+        fn undeclared_subpath_import() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: r##"{"imports": {"#utils": "./dist/utils.js"}}"##,
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "dist/index.d.ts",
+                    content: "export * from '#other';",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("dist/index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "#other");
+
+            assert_eq!(imported_module, None);
+        }
+
+        #[test]
+        fn explicit_module_extension() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.mts",
+                    content: "export * from './foo.mjs';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export declare const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.mts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.mts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.mjs").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.mts"));
+        }
+
+        #[test]
+        fn explicit_commonjs_extension() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.cts",
+                    content: "export * from './foo.cjs';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.cts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.cts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.cjs").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.cts"));
+        }
+
+        #[test]
+        fn tsx_source() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.ts",
+                    content: "export * from './Button.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "Button.tsx",
+                    content: "export const Button = () => <button />;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./Button.js").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("Button.tsx"));
+        }
+
+        #[test]
+        fn unfollowed_file_kind() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.ts",
+                    content: "export const foo = 'foo';",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let options = ExtractionOptions {
+                file_kinds: FileKinds::only([FileKind::Declaration]),
+                ..Default::default()
+            };
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser, &options).unwrap();
+
+            assert_eq!(modules.len(), 1);
+        }
+
+        #[test]
+        fn plain_declaration_wins_over_variants() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: r#"{"type": "module"}"#,
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export declare const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.mts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.js").unwrap();
+
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.ts"));
+        }
+
+        #[test]
+        fn module_package_prefers_module_variant() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: r#"{"type": "module"}"#,
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.mts",
+                    content: "export declare const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.cts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.js").unwrap();
+
+            assert_eq!(modules.package_type(index_module), PackageType::Module);
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.mts"));
+        }
+
+        #[test]
+        fn package_type_from_metadata() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.mts",
+                    content: "export declare const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.cts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let mut entrypoints = fixture.generate_entry_points();
+            entrypoints.package_type = PackageType::Module;
+            entrypoints.package_path = fixture.temp_dir.path.canonicalize().unwrap();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.js").unwrap();
+
+            assert_eq!(modules.package_type(index_module), PackageType::Module);
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.mts"));
+        }
+
+        #[test]
+        fn commonjs_package_prefers_commonjs_variant() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: "{}",
+                },
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.mts",
+                    content: "export declare const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.cts",
+                    content: "export declare const foo: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+
+            let imported_module = modules.resolve_import(index_module, "./foo.js").unwrap();
+
+            assert_eq!(modules.package_type(index_module), PackageType::CommonJs);
+            assert_eq!(imported_module.path, fixture.make_path("foo.d.cts"));
+        }
+    }
+
+    mod implementation_sources {
+        use super::*;
+
+        fn resolve(files: &[(&'static str, &'static str)], specifier: &str) -> Option<PathBuf> {
+            let fixture =
+                EntrypointFixture::new(files.iter().map(|(path, content)| ModuleFixture {
+                    entrypoint: path.starts_with("index.").then_some("main"),
+                    path,
+                    content,
+                }));
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let entry_point = entrypoints.iter().next().unwrap();
+            let index_module = modules.get(&entry_point.internal_path).unwrap();
+
+            modules
+                .resolve_import(index_module, specifier)
+                .map(|module| {
+                    module
+                        .path
+                        .strip_prefix(&fixture.temp_dir.path)
+                        .unwrap()
+                        .to_path_buf()
+                })
+        }
+
+        #[test]
+        fn javascript_file_with_declarations() {
+            let path = resolve(
+                &[
+                    ("index.d.ts", "export * from './foo.js';"),
+                    ("foo.js", "exports.foo = 1;"),
+                    ("foo.d.ts", "export const foo: number;"),
+                ],
+                "./foo.js",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.d.ts")));
+        }
+
+        #[test]
+        fn explicit_module_extension() {
+            let path = resolve(
+                &[
+                    ("index.mts", "export * from './foo.mjs';"),
+                    ("foo.mts", "export const foo = 1;"),
+                ],
+                "./foo.mjs",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.mts")));
+        }
+
+        #[test]
+        fn explicit_commonjs_extension() {
+            let path = resolve(
+                &[
+                    ("index.cts", "export * from './foo.cjs';"),
+                    ("foo.cts", "export const foo = 1;"),
+                ],
+                "./foo.cjs",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.cts")));
+        }
+
+        #[test]
+        fn declarations_preferred() {
+            let path = resolve(
+                &[
+                    ("index.mts", "export * from './foo.mjs';"),
+                    ("foo.mts", "export const foo = 1;"),
+                    ("foo.d.mts", "export declare const foo: number;"),
+                ],
+                "./foo.mjs",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.d.mts")));
+        }
+
+        #[test]
+        fn commonjs_file_in_module_package() {
+            let path = resolve(
+                &[
+                    ("package.json", r#"{"type": "module"}"#),
+                    ("index.cts", "export * from './foo.js';"),
+                    ("foo.mts", "export const foo = 1;"),
+                    ("foo.cts", "export const foo = 1;"),
+                ],
+                "./foo.js",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.cts")));
+        }
+
+        #[test]
+        fn module_file_in_commonjs_package() {
+            let path = resolve(
+                &[
+                    ("index.mts", "export * from './foo.js';"),
+                    ("foo.mts", "export const foo = 1;"),
+                    ("foo.cts", "export const foo = 1;"),
+                ],
+                "./foo.js",
+            );
+
+            assert_eq!(path, Some(PathBuf::from("foo.mts")));
+        }
+    }
+
+    mod from_entrypoints {
+        use super::*;
+
+        #[test]
+        fn empty_metadata() {
+            let fixture = EntrypointFixture::new([]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            assert_eq!(modules.len(), 0);
+        }
+
+        #[test]
+        fn single_entry_point() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.d.ts",
+                content: "export const foo: string;",
+            }]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let path = fixture.make_path("index.d.ts");
+            let module = modules.get(&path).unwrap();
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::Symbol {
+                    symbol: Symbol { name, source_code },
+                    is_exported: true
+                } if name == "foo" && source_code.contains("foo: string")
+            );
+        }
+
+        #[test]
+        fn multiple_entry_points() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: Some("other"),
+                    path: "other.d.ts",
+                    content: "export const bar: number;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+            let index_path = fixture.make_path("index.d.ts");
+            let other_path = fixture.make_path("other.d.ts");
+
+            let index_module = modules.get(&index_path).unwrap();
+            assert_eq!(index_module.symbols.len(), 1);
+            assert_matches!(
+                &index_module.symbols[0],
+                TypeScriptSymbol::Symbol {
+                    symbol: Symbol { name, source_code },
+                    is_exported: true
+                } if name == "foo" && source_code.contains("foo: string")
+            );
+
+            let other_module = modules.get(&other_path).unwrap();
+            assert_eq!(other_module.symbols.len(), 1);
+            assert_matches!(
+                &other_module.symbols[0],
+                TypeScriptSymbol::Symbol {
+                    symbol: Symbol { name, source_code },
+                    is_exported: true
+                } if name == "bar" && source_code.contains("bar: number")
+            );
+        }
+
+        #[test]
+        fn non_existing_entry_point() {
+            let path = PathBuf::from("./non-existing-file.d.ts");
+            let entrypoints = TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: "main".to_string(),
+                internal_path: path.clone(),
+                conditions: BTreeMap::new(),
+            }]);
+            let mut parser = make_parser();
+
+            let result = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            );
+
+            assert_matches!(result, Err(ExtractionError::Io(_)));
+            assert_contains!(
+                result.unwrap_err().to_string(),
+                &path.to_string_lossy().to_string()
+            );
+        }
+
+        #[test]
+        fn same_module_via_different_specifiers() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo';\nexport * from './foo.js';\nexport * from './lib/../foo.d.ts';\nexport * from './lib';\nexport * from './lib/index.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.js",
+                    content: "exports.foo = 'foo';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "lib/index.d.ts",
+                    content: "export const bar: string;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let mut paths = modules
+                .iter()
+                .map(|module| module.path.strip_prefix(&fixture.temp_dir.path).unwrap())
+                .collect::<Vec<_>>();
+            paths.sort();
+            assert_eq!(
+                paths,
+                vec![
+                    Path::new("foo.d.ts"),
+                    Path::new("index.d.ts"),
+                    Path::new("lib/index.d.ts")
+                ]
+            );
+        }
+
+        #[test]
+        fn deno_types() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "// @deno-types=\"./types/foo.d.ts\"\nexport * from './foo.js';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "types/foo.d.ts",
+                    content: "export const foo: number;",
+                },
+            ]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let mut paths = modules
+                .iter()
+                .map(|module| module.path.strip_prefix(&fixture.temp_dir.path).unwrap())
+                .collect::<Vec<_>>();
+            paths.sort();
+            assert_eq!(
+                paths,
+                vec![Path::new("index.d.ts"), Path::new("types/foo.d.ts")]
+            );
+        }
+
+        #[test]
+        fn excluded_files() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './foo';\nexport * from './foo.test.js';\nexport * from './examples/demo';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.d.ts",
+                    content: "export const foo: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "foo.test.d.ts",
+                    content: "export const fooTest: string;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "examples/demo.d.ts",
+                    content: "export const demo: string;",
+                },
+            ]);
+            let mut entrypoints = fixture.generate_entry_points();
+            let package_path = fixture.temp_dir.path.canonicalize().unwrap();
+            entrypoints.package_path = package_path.clone();
+            let options = ExtractionOptions {
+                exclude_test_files: true,
+                ..ExtractionOptions::default()
+            };
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser, &options).unwrap();
+
+            let mut paths = modules
+                .iter()
+                .map(|module| module.path.strip_prefix(&package_path).unwrap())
+                .collect::<Vec<_>>();
+            paths.sort();
+            assert_eq!(paths, vec![Path::new("foo.d.ts"), Path::new("index.d.ts")]);
+            assert_eq!(
+                modules.diagnostics(),
+                [Diagnostic::ExcludedFiles {
+                    paths: vec![
+                        package_path.join("examples/demo.d.ts"),
+                        package_path.join("foo.test.d.ts"),
+                    ]
+                }]
+            );
+        }
+
+        #[test]
+        fn excluded_entry_point() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "examples/index.d.ts",
+                content: "export const foo: string;",
+            }]);
+            let mut entrypoints = fixture.generate_entry_points();
+            entrypoints.package_path = fixture.temp_dir.path.canonicalize().unwrap();
+            let options = ExtractionOptions {
+                exclude_test_files: true,
+                ..ExtractionOptions::default()
             };
-            let module_set = ModuleSet(HashSet::from([module.clone()]));
+            let mut parser = make_parser();
 
-            let module_retrieved = module_set.get(path.as_path()).unwrap();
+            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser, &options).unwrap();
 
-            assert_eq!(module_retrieved, &module);
+            assert_eq!(modules.iter().count(), 1);
+            assert!(modules.diagnostics().is_empty());
         }
 
-        #[test]
-        fn returns_none_when_not_found() {
-            let path = PathBuf::from("/test/path.ts");
-            let module = Module {
-                path,
-                jsdoc: None,
-                symbols: vec![],
-                default_export_name: None,
+        fn load_with_published_files(policy: UnpublishedFiles) -> (PathBuf, ModuleSet) {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "dist/index.d.ts",
+                    content: "export * from '../src/foo';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "src/foo.d.ts",
+                    content: "export const foo: string;",
+                },
+            ]);
+            let mut entrypoints = fixture.generate_entry_points();
+            let package_path = fixture.temp_dir.path.canonicalize().unwrap();
+            entrypoints.published_files = PublishedFiles {
+                package_path: package_path.clone(),
+                patterns: Some(vec!["dist".to_string()]),
             };
-            let module_set = ModuleSet(HashSet::from([module.clone()]));
-            let non_existent_path = PathBuf::from("/test/non_existent.ts");
+            let options = ExtractionOptions {
+                unpublished_files: policy,
+                ..Default::default()
+            };
+            let mut parser = make_parser();
 
-            let module_retrieved = module_set.get(non_existent_path.as_path());
+            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser, &options).unwrap();
 
-            assert_eq!(module_retrieved, None);
+            (package_path, modules)
         }
-    }
 
-    mod from_entrypoints {
-        use super::*;
-        use std::collections::HashSet;
+        #[test]
+        fn unpublished_files_warning() {
+            let (package_path, modules) = load_with_published_files(UnpublishedFiles::Warn);
+
+            assert_eq!(modules.len(), 2);
+            assert_eq!(
+                modules.diagnostics(),
+                [Diagnostic::UnpublishedFiles {
+                    paths: vec![package_path.join("src/foo.d.ts")],
+                    is_skipped: false,
+                }]
+            );
+        }
 
         #[test]
-        fn empty_metadata() {
-            let fixture = EntrypointFixture::new([]);
-            let entrypoints = fixture.generate_entry_points();
+        fn unpublished_files_skipped() {
+            let (package_path, modules) = load_with_published_files(UnpublishedFiles::Skip);
+
+            assert_eq!(modules.len(), 1);
+            assert_eq!(
+                modules.diagnostics(),
+                [Diagnostic::UnpublishedFiles {
+                    paths: vec![package_path.join("src/foo.d.ts")],
+                    is_skipped: true,
+                }]
+            );
+        }
+
+        #[test]
+        fn non_canonical_entry_point() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "index.d.ts",
+                    content: "export * from './lib/other';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "lib/other.d.ts",
+                    content: "export * from '../index';",
+                },
+            ]);
+            fixture.generate_entry_points();
+            let internal_path = fixture.make_path("lib/../index.d.ts");
+            let entrypoints = TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: internal_path.clone(),
+                conditions: BTreeMap::new(),
+            }]);
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
 
-            assert_eq!(modules.len(), 0);
+            assert_eq!(modules.len(), 2);
+            let module = modules.get(&internal_path).unwrap();
+            assert_eq!(module.path, fixture.make_path("index.d.ts"));
         }
 
         #[test]
-        fn single_entry_point() {
+        fn parsing_error() {
             let fixture = EntrypointFixture::new([ModuleFixture {
                 entrypoint: Some("main"),
                 path: "index.d.ts",
-                content: "export const foo: string;",
+                content: "export const foo: @invalid-type;",
             }]);
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
-
-            let path = fixture.make_path("index.d.ts");
-            let module = modules.get(&path).unwrap();
-            assert_eq!(module.symbols.len(), 1);
-            assert_matches!(
-                &module.symbols[0],
-                TypeScriptSymbol::Symbol {
-                    symbol: Symbol { name, source_code },
-                    is_exported: true
-                } if name == "foo" && source_code.contains("foo: string")
+            let result = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
             );
+
+            assert_matches!(result, Err(ExtractionError::Malformed(_)));
         }
 
         #[test]
-        fn multiple_entry_points() {
+        fn metrics() {
             let fixture = EntrypointFixture::new([
                 ModuleFixture {
                     entrypoint: Some("main"),
                     path: "index.d.ts",
-                    content: "export const foo: string;",
+                    content: "export * from './a';",
                 },
                 ModuleFixture {
-                    entrypoint: Some("other"),
-                    path: "other.d.ts",
-                    content: "export const bar: number;",
+                    entrypoint: None,
+                    path: "a.d.ts",
+                    content: "export interface A {}\nexport interface B {}",
                 },
             ]);
             let entrypoints = fixture.generate_entry_points();
-            let mut parser = make_parser();
-
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
-            let index_path = fixture.make_path("index.d.ts");
-            let other_path = fixture.make_path("other.d.ts");
 
-            let index_module = modules.get(&index_path).unwrap();
-            assert_eq!(index_module.symbols.len(), 1);
-            assert_matches!(
-                &index_module.symbols[0],
-                TypeScriptSymbol::Symbol {
-                    symbol: Symbol { name, source_code },
-                    is_exported: true
-                } if name == "foo" && source_code.contains("foo: string")
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut make_parser(),
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let metrics = modules
+                .metrics()
+                .iter()
+                .map(|metrics| {
+                    (
+                        metrics.path.clone(),
+                        metrics.byte_count,
+                        metrics.symbol_count,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                metrics,
+                vec![
+                    (fixture.make_path("index.d.ts"), 20, 1),
+                    (fixture.make_path("a.d.ts"), 43, 2),
+                ]
             );
+        }
+    }
 
-            let other_module = modules.get(&other_path).unwrap();
-            assert_eq!(other_module.symbols.len(), 1);
-            assert_matches!(
-                &other_module.symbols[0],
-                TypeScriptSymbol::Symbol {
-                    symbol: Symbol { name, source_code },
-                    is_exported: true
-                } if name == "bar" && source_code.contains("bar: number")
+    mod queries {
+        use super::*;
+        use crate::api::resolution::ExportedItem;
+
+        fn make_fixture() -> EntrypointFixture {
+            EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "import { Options } from './options';\nexport * from './options';\nexport declare function run(options: Options): void;",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "options.d.ts",
+                    content: "export interface Options {}\nexport namespace Defaults {\n  function run(): void;\n}",
+                },
+            ])
+        }
+
+        fn load(fixture: &EntrypointFixture) -> ModuleSet {
+            ModuleSet::from_entrypoints(
+                &fixture.generate_entry_points(),
+                &mut make_parser(),
+                &ExtractionOptions::default(),
+            )
+            .unwrap()
+        }
+
+        fn get_file_names<'a>(modules: impl Iterator<Item = &'a Module>) -> Vec<String> {
+            modules
+                .map(|module| {
+                    module
+                        .path
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn find_symbol() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
+
+            let symbols = modules.find_symbol("run").collect::<Vec<_>>();
+
+            assert_eq!(
+                get_file_names(symbols.iter().map(|(module, _)| *module)),
+                vec!["index.d.ts", "options.d.ts"]
             );
+            assert!(symbols.iter().all(|(_, symbol)| symbol.name == "run"));
         }
 
         #[test]
-        fn non_existing_entry_point() {
-            let path = PathBuf::from("./non-existing-file.d.ts");
-            let entrypoints: TSEntryPointSet = HashSet::from([TSEntryPoint {
-                external_path: "main".to_string(),
-                internal_path: path.clone(),
-            }]);
-            let mut parser = make_parser();
+        fn find_unknown_symbol() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
 
-            let result = ModuleSet::from_entrypoints(&entrypoints, &mut parser);
+            assert_eq!(modules.find_symbol("missing").count(), 0);
+        }
 
-            assert_matches!(result, Err(ExtractionError::Io(_)));
-            assert_contains!(
-                result.unwrap_err().to_string(),
-                &path.to_string_lossy().to_string()
+        #[test]
+        fn symbols_of_kind() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
+
+            let names = modules
+                .symbols_of_kind(SymbolKind::Interface)
+                .map(|(_, symbol)| symbol.name.clone())
+                .collect::<Vec<_>>();
+
+            assert_eq!(names, vec!["Options"]);
+        }
+
+        #[test]
+        fn exports_of() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
+
+            let exports = modules
+                .exports_of(&fixture.make_path("index.d.ts"))
+                .map(|export| {
+                    (
+                        export.name,
+                        matches!(export.item, ExportedItem::Namespace { .. }),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                exports,
+                vec![
+                    ("run".to_string(), false),
+                    ("Options".to_string(), false),
+                    ("Defaults".to_string(), true),
+                ]
             );
         }
 
         #[test]
-        fn parsing_error() {
-            let fixture = EntrypointFixture::new([ModuleFixture {
-                entrypoint: Some("main"),
-                path: "index.d.ts",
-                content: "export const foo: @invalid-type;",
-            }]);
-            let entrypoints = fixture.generate_entry_points();
-            let mut parser = make_parser();
+        fn exports_of_unknown_module() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
+
+            assert_eq!(
+                modules
+                    .exports_of(&fixture.make_path("missing.d.ts"))
+                    .count(),
+                0
+            );
+        }
 
-            let result = ModuleSet::from_entrypoints(&entrypoints, &mut parser);
+        #[test]
+        fn modules_importing() {
+            let fixture = make_fixture();
+            let modules = load(&fixture);
 
-            assert_matches!(result, Err(ExtractionError::Malformed(_)));
+            assert_eq!(
+                get_file_names(modules.modules_importing(&fixture.make_path("options.d.ts"))),
+                vec!["index.d.ts"]
+            );
+            assert_eq!(
+                get_file_names(modules.modules_importing(&fixture.make_path("index.d.ts"))),
+                Vec::<String>::new()
+            );
         }
     }
 
@@ -388,7 +2073,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("index.d.ts");
             let index_module = modules.get(&index_path).unwrap();
             assert_eq!(index_module.symbols.len(), 2);
@@ -396,8 +2086,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, aliases }
-                } if source_module == "./bar" && names.len() == 1 && names[0] == "Bar" && aliases.is_empty()
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./bar" && *specifiers == vec![make_specifier("Bar", None)]
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -441,7 +2132,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("index.d.ts");
             let index_module = modules.get(&index_path).unwrap();
             assert_eq!(index_module.symbols.len(), 2);
@@ -449,8 +2145,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./bar" && names.contains(&"Bar".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./bar" && specifiers.iter().any(|specifier| specifier.name == "Bar")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -467,8 +2164,9 @@ mod tests {
                 &bar_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./baz" && names.contains(&"Baz".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./baz" && specifiers.iter().any(|specifier| specifier.name == "Baz")
             );
             assert_matches!(
                 &bar_module.symbols[1],
@@ -507,7 +2205,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let a_path = fixture.make_path("a.d.ts");
             let b_path = fixture.make_path("b.d.ts");
 
@@ -517,8 +2220,9 @@ mod tests {
                 &a_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./b" && names.contains(&"B".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./b" && specifiers.iter().any(|specifier| specifier.name == "B")
             );
             assert_matches!(
                 &a_module.symbols[1],
@@ -534,8 +2238,9 @@ mod tests {
                 &b_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./a" && names.contains(&"A".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./a" && specifiers.iter().any(|specifier| specifier.name == "A")
             );
             assert_matches!(
                 &b_module.symbols[1],
@@ -563,7 +2268,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("index.d.ts");
             let other_path = fixture.make_path("other-module.d.ts");
 
@@ -573,8 +2283,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleExport {
                     source_module: Some(source_module),
-                    target: ExportTarget::Named { names, .. }
-                } if source_module == "./other-module" && names.contains(&"Something".to_string())
+                    target: ExportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./other-module" && specifiers.iter().any(|specifier| specifier.name == "Something")
             );
 
             let other_module = modules.get(&other_path).unwrap();
@@ -589,6 +2300,146 @@ mod tests {
         }
     }
 
+    mod unresolved_externals {
+        use super::*;
+
+        fn load(fixture: &EntrypointFixture) -> ModuleSet {
+            let entrypoints = fixture.generate_entry_points();
+            ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut make_parser(),
+                &ExtractionOptions::default(),
+            )
+            .unwrap()
+        }
+
+        fn is_unresolved_external(symbol: &TypeScriptSymbol) -> bool {
+            matches!(
+                symbol,
+                TypeScriptSymbol::ModuleImport {
+                    is_unresolved_external: true,
+                    ..
+                }
+            )
+        }
+
+        #[test]
+        fn missing_package() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.d.ts",
+                content: "import { A } from 'optional-dep';\nimport { B } from 'optional-dep/sub';\nimport { C } from 'optional-dep';",
+            }]);
+
+            let modules = load(&fixture);
+
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+            assert!(index_module.symbols.iter().all(is_unresolved_external));
+            assert_eq!(
+                modules.diagnostics(),
+                [
+                    Diagnostic::UnresolvedExternal {
+                        specifier: "optional-dep".to_string(),
+                        importer: fixture.make_path("index.d.ts"),
+                    },
+                    Diagnostic::UnresolvedExternal {
+                        specifier: "optional-dep/sub".to_string(),
+                        importer: fixture.make_path("index.d.ts"),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn installed_package() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "import { A } from 'installed-dep';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "package.json",
+                    content: "{}",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "node_modules/installed-dep/index.d.ts",
+                    content: "export interface A {}",
+                },
+            ]);
+
+            let modules = load(&fixture);
+
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+            assert!(!is_unresolved_external(&index_module.symbols[0]));
+            assert!(modules.diagnostics().is_empty());
+        }
+
+        #[test]
+        fn reexport_from_missing_package() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.d.ts",
+                content: "export * from 'optional-dep';\nexport declare const a: number;",
+            }]);
+
+            let modules = load(&fixture);
+
+            assert_eq!(modules.len(), 1);
+            assert_eq!(
+                modules.diagnostics(),
+                [Diagnostic::UnresolvedExternal {
+                    specifier: "optional-dep".to_string(),
+                    importer: fixture.make_path("index.d.ts"),
+                }]
+            );
+        }
+
+        #[test]
+        fn builtin_modules() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.d.ts",
+                content: "import { Readable } from 'node:stream';\nimport { join } from 'path';",
+            }]);
+
+            let modules = load(&fixture);
+
+            let index_module = modules.get(&fixture.make_path("index.d.ts")).unwrap();
+            assert!(index_module.symbols.iter().all(|symbol| matches!(
+                symbol,
+                TypeScriptSymbol::ModuleImport {
+                    is_builtin: true,
+                    is_unresolved_external: false,
+                    ..
+                }
+            )));
+            assert!(modules.diagnostics().is_empty());
+        }
+
+        #[test]
+        fn relative_imports() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "import { A } from './a';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "a.d.ts",
+                    content: "export interface A {}",
+                },
+            ]);
+
+            let modules = load(&fixture);
+
+            assert!(modules.diagnostics().is_empty());
+        }
+    }
+
     mod path_resolution {
         use super::*;
 
@@ -609,7 +2460,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("src/index.d.ts");
             let foo_path = fixture.make_path("src/foo.d.ts");
 
@@ -619,8 +2475,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./foo" && names.contains(&"Foo".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./foo" && specifiers.iter().any(|specifier| specifier.name == "Foo")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -658,7 +2515,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let parent_path = fixture.make_path("src/parent-module.d.ts");
             let child_path = fixture.make_path("src/nested/child-module.d.ts");
 
@@ -678,8 +2540,9 @@ mod tests {
                 &child_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "../parent-module" && names.contains(&"ParentExport".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "../parent-module" && specifiers.iter().any(|specifier| specifier.name == "ParentExport")
             );
             assert_matches!(
                 &child_module.symbols[1],
@@ -707,7 +2570,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("src/index.d.ts");
             let utils_path = fixture.make_path("src/utils/index.d.ts");
 
@@ -717,8 +2585,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./utils" && names.contains(&"Foo".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./utils" && specifiers.iter().any(|specifier| specifier.name == "Foo")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -756,7 +2625,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("src/index.d.ts");
             let utils_path = fixture.make_path("src/utils/index.ts");
 
@@ -766,8 +2640,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./utils" && names.contains(&"Foo".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./utils" && specifiers.iter().any(|specifier| specifier.name == "Foo")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -805,7 +2680,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("src/index.d.ts");
             let foo_path = fixture.make_path("src/foo.ts");
 
@@ -815,8 +2695,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./foo" && names.contains(&"Foo".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./foo" && specifiers.iter().any(|specifier| specifier.name == "Foo")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -848,7 +2729,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("index.d.ts");
 
             let index_module = modules.get(&index_path).unwrap();
@@ -857,8 +2743,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "external-module" && names.contains(&"Something".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "external-module" && specifiers.iter().any(|specifier| specifier.name == "Something")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -869,6 +2756,71 @@ mod tests {
             );
         }
 
+        #[test]
+        fn asset_references() {
+            let fixture = EntrypointFixture::new([
+                ModuleFixture {
+                    entrypoint: Some("main"),
+                    path: "index.d.ts",
+                    content: "export * from './data.json';\nexport * from './native';",
+                },
+                ModuleFixture {
+                    entrypoint: None,
+                    path: "data.json",
+                    content: "{}",
+                },
+            ]);
+            std::fs::write(fixture.make_path("native"), b"\x7fELF\x02\x01\x01\0").unwrap();
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            let package_path = fixture.temp_dir.path.canonicalize().unwrap();
+            let asset_paths = vec![package_path.join("data.json"), package_path.join("native")];
+            assert_eq!(modules.len(), 1);
+            assert_eq!(
+                modules.asset_references(),
+                asset_paths
+                    .iter()
+                    .map(|path| AssetReference {
+                        path: path.clone(),
+                        referencing_files: vec![package_path.join("index.d.ts")],
+                    })
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                modules.diagnostics(),
+                [Diagnostic::AssetReferences { paths: asset_paths }]
+            );
+        }
+
+        #[test]
+        fn asset_entry_point() {
+            let fixture = EntrypointFixture::new([ModuleFixture {
+                entrypoint: Some("main"),
+                path: "index.json",
+                content: "{}",
+            }]);
+            let entrypoints = fixture.generate_entry_points();
+            let mut parser = make_parser();
+
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
+
+            assert!(modules.get(&fixture.make_path("index.json")).is_some());
+            assert!(modules.asset_references().is_empty());
+        }
+
         #[test]
         fn direct_file_resolution() {
             let fixture = EntrypointFixture::new([
@@ -886,7 +2838,12 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let modules = ModuleSet::from_entrypoints(&entrypoints, &mut parser).unwrap();
+            let modules = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            )
+            .unwrap();
             let index_path = fixture.make_path("src/index.d.ts");
             let exact_file_path = fixture.make_path("src/exact-file");
 
@@ -896,8 +2853,9 @@ mod tests {
                 &index_module.symbols[0],
                 TypeScriptSymbol::ModuleImport {
                     source_module,
-                    target: ImportTarget::Named { names, .. }
-                } if source_module == "./exact-file" && names.contains(&"Foo".to_string())
+                    target: ImportTarget::Named { specifiers },
+                    ..
+                } if source_module == "./exact-file" && specifiers.iter().any(|specifier| specifier.name == "Foo")
             );
             assert_matches!(
                 &index_module.symbols[1],
@@ -928,7 +2886,11 @@ mod tests {
             let entrypoints = fixture.generate_entry_points();
             let mut parser = make_parser();
 
-            let result = ModuleSet::from_entrypoints(&entrypoints, &mut parser);
+            let result = ModuleSet::from_entrypoints(
+                &entrypoints,
+                &mut parser,
+                &ExtractionOptions::default(),
+            );
 
             assert_matches!(result, Err(ExtractionError::Io(_)));
             assert_contains!(result.unwrap_err().to_string(), "non-existing.ts");
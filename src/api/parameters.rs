@@ -0,0 +1,412 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::declaration::find_declaration;
+use crate::api::members::{get_member_accessibility, Accessibility};
+use crate::api::queries::make_parser;
+use crate::signature::normalize_node;
+
+/// A parameter of a function or method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    /// The name of the parameter, or its destructuring pattern (e.g. `{ a, b }`).
+    pub name: String,
+    /// The declared type, if any (e.g. `string[]`).
+    pub type_annotation: Option<String>,
+    /// Whether the parameter can be omitted, because it's marked with `?` or has a default value.
+    pub is_optional: bool,
+    /// Whether it's a rest parameter (e.g. `...args`).
+    pub is_rest: bool,
+    /// The default value, if any (e.g. `1` in `retries = 1`).
+    pub default_value: Option<String>,
+}
+
+/// A function, method, constructor or call signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSignature {
+    /// The name of the function or method, `new` for construct signatures, or `None` for call
+    /// signatures (e.g. `(a: string): void` in an interface).
+    pub name: Option<String>,
+    pub parameters: Vec<Parameter>,
+    /// The declared return type, if any.
+    pub return_type: Option<String>,
+}
+
+/// Extracts the call signatures of a symbol: the signature of a function (or a variable whose
+/// value or type is a function), or the signatures of the public constructors and methods of a
+/// class or interface.
+///
+/// `this` parameters are omitted, since callers don't pass them.
+pub fn extract_call_signatures(symbol: &Symbol) -> Vec<CallSignature> {
+    let mut parser = make_parser();
+    let Ok(parsed_file) = ParsedFile::parse(&symbol.source_code, &mut parser) else {
+        return vec![];
+    };
+    let Some(declaration) = find_declaration(parsed_file.root_node()) else {
+        return vec![];
+    };
+    let source_code = symbol.source_code.as_str();
+
+    match declaration.kind() {
+        "function_signature" | "function_declaration" => {
+            vec![make_signature(
+                Some(symbol.name.clone()),
+                declaration,
+                source_code,
+            )]
+        }
+        "class_declaration" | "abstract_class_declaration" | "interface_declaration" => declaration
+            .child_by_field_name("body")
+            .map(|body| extract_member_signatures(body, source_code))
+            .unwrap_or_default(),
+        "lexical_declaration" | "variable_declaration" => {
+            extract_variable_signature(declaration, &symbol.name, source_code)
+                .into_iter()
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn extract_member_signatures(body: Node, source_code: &str) -> Vec<CallSignature> {
+    let mut signatures = vec![];
+    let mut cursor = body.walk();
+    for member in body.named_children(&mut cursor) {
+        let name = match member.kind() {
+            "method_signature" | "method_definition" | "abstract_method_signature" => {
+                let Some(name_node) = member.child_by_field_name("name") else {
+                    continue;
+                };
+                if get_member_accessibility(member, source_code) == Accessibility::Private {
+                    continue;
+                }
+                Some(source_code[name_node.byte_range()].to_string())
+            }
+            "construct_signature" => Some("new".to_string()),
+            "call_signature" => None,
+            _ => continue,
+        };
+        signatures.push(make_signature(name, member, source_code));
+    }
+    signatures
+}
+
+fn extract_variable_signature(
+    declaration: Node,
+    name: &str,
+    source_code: &str,
+) -> Option<CallSignature> {
+    let mut cursor = declaration.walk();
+    let declarator = declaration.named_children(&mut cursor).find(|child| {
+        child.kind() == "variable_declarator"
+            && child
+                .child_by_field_name("name")
+                .is_some_and(|name_node| &source_code[name_node.byte_range()] == name)
+    })?;
+
+    let function_type = declarator
+        .child_by_field_name("type")
+        .and_then(|type_annotation| type_annotation.named_child(0))
+        .filter(|type_node| type_node.kind() == "function_type");
+    let function_value = declarator
+        .child_by_field_name("value")
+        .filter(|value| matches!(value.kind(), "arrow_function" | "function_expression"));
+    let function = function_type.or(function_value)?;
+    Some(make_signature(
+        Some(name.to_string()),
+        function,
+        source_code,
+    ))
+}
+
+fn make_signature(name: Option<String>, node: Node, source_code: &str) -> CallSignature {
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| extract_parameters(parameters, source_code))
+        .unwrap_or_default();
+    // Construct signatures and function types name their return type differently
+    let return_type = node
+        .child_by_field_name("return_type")
+        .or_else(|| node.child_by_field_name("type"))
+        .map(|return_type| render_type(return_type, source_code));
+    CallSignature {
+        name,
+        parameters,
+        return_type,
+    }
+}
+
+fn extract_parameters(parameters: Node, source_code: &str) -> Vec<Parameter> {
+    let mut cursor = parameters.walk();
+    let parameters = parameters
+        .named_children(&mut cursor)
+        .filter(|parameter| {
+            matches!(
+                parameter.kind(),
+                "required_parameter" | "optional_parameter"
+            )
+        })
+        .filter_map(|parameter| {
+            let pattern = parameter.child_by_field_name("pattern")?;
+            if pattern.kind() == "this" {
+                return None;
+            }
+            let is_rest = pattern.kind() == "rest_pattern";
+            let name_node = match is_rest {
+                true => pattern.named_child(0)?,
+                false => pattern,
+            };
+            let default_value = parameter
+                .child_by_field_name("value")
+                .map(|value| normalize_node(value, source_code));
+            Some(Parameter {
+                name: normalize_node(name_node, source_code),
+                type_annotation: parameter
+                    .child_by_field_name("type")
+                    .map(|type_annotation| render_type(type_annotation, source_code)),
+                is_optional: parameter.kind() == "optional_parameter" || default_value.is_some(),
+                is_rest,
+                default_value,
+            })
+        })
+        .collect();
+    parameters
+}
+
+/// Renders a type, without the leading colon of type annotations.
+fn render_type(node: Node, source_code: &str) -> String {
+    let type_node = match node.kind() {
+        "type_annotation" | "type_predicate_annotation" | "asserts_annotation" => {
+            node.named_child(0).unwrap_or(node)
+        }
+        _ => node,
+    };
+    normalize_node(type_node, source_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(name: &str, source_code: &str) -> Vec<CallSignature> {
+        extract_call_signatures(&Symbol {
+            name: name.to_string(),
+            source_code: source_code.to_string(),
+        })
+    }
+
+    fn make_parameter(name: &str, type_annotation: Option<&str>) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            type_annotation: type_annotation.map(str::to_string),
+            is_optional: false,
+            is_rest: false,
+            default_value: None,
+        }
+    }
+
+    mod functions {
+        use super::*;
+
+        #[test]
+        fn no_parameters() {
+            let signatures = extract("f", "export declare function f(): void;");
+
+            assert_eq!(
+                signatures,
+                vec![CallSignature {
+                    name: Some("f".to_string()),
+                    parameters: vec![],
+                    return_type: Some("void".to_string()),
+                }]
+            );
+        }
+
+        #[test]
+        fn required_parameter() {
+            let signatures = extract("f", "export declare function f(a: string): void;");
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![make_parameter("a", Some("string"))]
+            );
+        }
+
+        #[test]
+        fn optional_parameter() {
+            let signatures = extract("f", "export declare function f(a?: string): void;");
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![Parameter {
+                    is_optional: true,
+                    ..make_parameter("a", Some("string"))
+                }]
+            );
+        }
+
+        #[test]
+        fn default_value() {
+            let signatures = extract("f", "export function f(retries = 1) {}");
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![Parameter {
+                    is_optional: true,
+                    default_value: Some("1".to_string()),
+                    ..make_parameter("retries", None)
+                }]
+            );
+            assert_eq!(signatures[0].return_type, None);
+        }
+
+        #[test]
+        fn rest_parameter() {
+            let signatures = extract("f", "export declare function f(...args: string[]): void;");
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![Parameter {
+                    is_rest: true,
+                    ..make_parameter("args", Some("string[]"))
+                }]
+            );
+        }
+
+        #[test]
+        fn destructured_parameter() {
+            let signatures = extract(
+                "f",
+                "export declare function f({ a,\n  b }: Options): void;",
+            );
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![make_parameter("{ a, b }", Some("Options"))]
+            );
+        }
+
+        #[test]
+        fn this_parameter() {
+            let signatures = extract(
+                "f",
+                "export declare function f(this: Foo, a: string): void;",
+            );
+
+            assert_eq!(
+                signatures[0].parameters,
+                vec![make_parameter("a", Some("string"))]
+            );
+        }
+
+        #[test]
+        fn type_predicate() {
+            let signatures = extract(
+                "isString",
+                "export declare function isString(value: unknown): value is string;",
+            );
+
+            assert_eq!(
+                signatures[0].return_type,
+                Some("value is string".to_string())
+            );
+        }
+    }
+
+    mod classes {
+        use super::*;
+
+        #[test]
+        fn constructor_and_methods() {
+            let signatures = extract(
+                "Foo",
+                "export declare class Foo {\n  constructor(private x: string);\n  get y(): number;\n  m<T>(value: T): void;\n  private hidden(): void;\n  #secret(): void;\n  bar: string;\n}",
+            );
+
+            assert_eq!(
+                signatures,
+                vec![
+                    CallSignature {
+                        name: Some("constructor".to_string()),
+                        parameters: vec![make_parameter("x", Some("string"))],
+                        return_type: None,
+                    },
+                    CallSignature {
+                        name: Some("y".to_string()),
+                        parameters: vec![],
+                        return_type: Some("number".to_string()),
+                    },
+                    CallSignature {
+                        name: Some("m".to_string()),
+                        parameters: vec![make_parameter("value", Some("T"))],
+                        return_type: Some("void".to_string()),
+                    },
+                ]
+            );
+        }
+    }
+
+    mod interfaces {
+        use super::*;
+
+        #[test]
+        fn call_construct_and_method_signatures() {
+            let signatures = extract(
+                "I",
+                "export interface I {\n  (a: string): void;\n  new (b: number): I;\n  m?(): void;\n}",
+            );
+
+            let names_and_returns = signatures
+                .iter()
+                .map(|signature| (signature.name.as_deref(), signature.return_type.as_deref()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                names_and_returns,
+                vec![
+                    (None, Some("void")),
+                    (Some("new"), Some("I")),
+                    (Some("m"), Some("void")),
+                ]
+            );
+        }
+    }
+
+    mod variables {
+        use super::*;
+
+        #[test]
+        fn function_type() {
+            let signatures = extract("f", "export declare const f: (a: string) => void;");
+
+            assert_eq!(
+                signatures,
+                vec![CallSignature {
+                    name: Some("f".to_string()),
+                    parameters: vec![make_parameter("a", Some("string"))],
+                    return_type: Some("void".to_string()),
+                }]
+            );
+        }
+
+        #[test]
+        fn arrow_function() {
+            let signatures = extract("f", "export const f = (a: string): number => 1;");
+
+            assert_eq!(signatures[0].return_type, Some("number".to_string()));
+        }
+
+        #[test]
+        fn non_function() {
+            let signatures = extract("a", "export declare const a: string;");
+
+            assert_eq!(signatures, vec![]);
+        }
+    }
+
+    #[test]
+    fn type_alias() {
+        let signatures = extract("T", "export type T = string;");
+
+        assert_eq!(signatures, vec![]);
+    }
+}
@@ -1,10 +1,36 @@
 use daipendency_extractor::{ExtractionError, ParsedFile, Symbol};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Node, Parser, QueryCursor};
-
-use crate::api::module::{ExportTarget, ImportTarget, Module, TypeScriptSymbol};
+use tree_sitter::{Node, Parser, Query, QueryCursor, QueryMatch};
+
+use crate::api::declaration::{
+    is_modifier_wrapper, unwrap_declaration, wrap_declaration, WrappedDeclaration,
+};
+use crate::api::extensions::QueryExtension;
+use crate::api::generated::detect_generator;
+use crate::api::module::{
+    Augmentation, ExportTarget, ImportTarget, Module, Pragma, Specifier, TypeScriptSymbol,
+};
+use crate::api::queries::{compile_query, get_query};
+
+/// How deep namespaces can be nested before the inner ones are left out, so that pathological
+/// files can't exhaust the stack (as the symbols in namespaces are processed recursively).
+const MAX_NAMESPACE_DEPTH: usize = 64;
+
+/// The pragmas recognised in comments, which are otherwise indistinguishable from JSDoc tags.
+const PRAGMA_NAMES: [&str; 7] = [
+    "ts-check",
+    "ts-nocheck",
+    "jsx",
+    "jsxFrag",
+    "jsxImportSource",
+    "jsxRuntime",
+    "deno-types",
+];
 
 const DEFAULT_EXPORT_QUERY: &str = r#"
 ; Default export
@@ -42,6 +68,10 @@ const SYMBOLS_QUERY: &str = r#"
     name: (identifier) @name
     ) @declaration
 
+(function_declaration
+    name: (identifier) @name
+    ) @declaration
+
 (type_alias_declaration
     name: (type_identifier) @name
     ) @declaration
@@ -66,6 +96,20 @@ const IMPORT_QUERY: &str = r#"
     )
 "#;
 
+const IMPORT_ALIAS_QUERY: &str = r#"
+(import_alias
+    (identifier) @name
+    [(identifier) (nested_identifier)] @target
+    ) @alias
+"#;
+
+const NAMESPACES_QUERY: &str = r#"
+(internal_module
+    name: (identifier) @name
+    body: (statement_block) @body
+    )
+"#;
+
 const EXPORTS_QUERY: &str = r#"
 ; Named exports, with or without source
 (export_statement
@@ -104,71 +148,496 @@ const EXPORTS_QUERY: &str = r#"
   ) @barrel_export
 "#;
 
+/// How deep the declarations in a container (a module or the body of a namespace) can be nested
+/// in its statements (e.g. the class in `export declare class Foo {}` is in an ambient declaration
+/// in an export statement).
+const MAX_STATEMENT_DEPTH: u32 = 3;
+
+/// The queries run on every container, compiled once and shared by every file.
+struct ContainerQueries {
+    imports: Query,
+    import_aliases: Query,
+    symbols: Query,
+    namespaces: Query,
+    exports: Query,
+}
+
+impl ContainerQueries {
+    fn get() -> &'static Self {
+        static QUERIES: OnceLock<ContainerQueries> = OnceLock::new();
+        QUERIES.get_or_init(|| Self {
+            imports: compile_query(IMPORT_QUERY),
+            import_aliases: compile_query(IMPORT_ALIAS_QUERY),
+            symbols: compile_query(SYMBOLS_QUERY),
+            namespaces: compile_query(NAMESPACES_QUERY),
+            exports: compile_query(EXPORTS_QUERY),
+        })
+    }
+}
+
+fn get_default_export_query() -> &'static Query {
+    static QUERY: OnceLock<Query> = OnceLock::new();
+    get_query(&QUERY, DEFAULT_EXPORT_QUERY)
+}
+
+/// Compiles the queries used to parse modules, if they haven't been compiled yet.
+pub(crate) fn warm_up_queries() {
+    ContainerQueries::get();
+    get_default_export_query();
+}
+
+/// How deep the namespace being extracted is nested.
+#[derive(Default)]
+struct NamespaceNesting {
+    depth: usize,
+    /// Whether namespaces nested deeper than [MAX_NAMESPACE_DEPTH] were left out.
+    is_truncated: bool,
+}
+
 pub fn parse_typescript_file(
     content: &str,
     parser: &mut Parser,
     file_path: PathBuf,
+    extensions: &[QueryExtension],
 ) -> Result<Module, ExtractionError> {
-    let parsed_file = ParsedFile::parse(content, parser)?;
+    let (parseable_content, type_only_star_exports) = mask_type_only_star_exports(content, parser);
+    let parsed_file = ParsedFile::parse(&parseable_content, parser)?;
     let root_node = parsed_file.root_node();
 
-    let jsdoc = get_jsdoc(root_node.child(0), &parsed_file).filter(|s| is_module_jsdoc(s.as_str()));
-    let symbols = get_module_symbols(root_node, &parsed_file)?;
+    let jsdoc = get_module_jsdoc(root_node, &parsed_file);
+    let queries = ContainerQueries::get();
+    let mut nesting = NamespaceNesting::default();
+    let mut symbols = get_module_symbols(
+        root_node,
+        &parsed_file,
+        queries,
+        &type_only_star_exports,
+        &mut nesting,
+    )?;
+    for extension in extensions {
+        symbols.extend(extension.run(root_node, content).into_iter().map(|symbol| {
+            TypeScriptSymbol::Custom {
+                kind: symbol.kind,
+                name: symbol.name,
+                source: symbol.source_code,
+            }
+        }));
+    }
     let default_export_name = extract_default_export_name(root_node, &parsed_file)?;
 
+    let mut lib_references = vec![];
+    let mut no_default_lib = false;
+    for (name, value) in get_reference_directives(root_node, &parsed_file) {
+        match name.as_str() {
+            "lib" => lib_references.push(value),
+            "no-default-lib" => no_default_lib = value == "true",
+            _ => {}
+        }
+    }
+
     Ok(Module {
         path: file_path,
         jsdoc,
         symbols,
         default_export_name,
+        lib_references,
+        no_default_lib,
+        has_module_marker: has_module_marker(root_node),
+        has_truncated_namespaces: nesting.is_truncated,
+        generator: detect_generator(&get_leading_comments(root_node, &parsed_file)),
+        augmentations: extract_augmentations(root_node, &parsed_file),
+        globals: extract_globals(root_node, &parsed_file),
+        directives: get_directives(root_node, &parsed_file),
+        pragmas: get_pragmas(root_node, &parsed_file),
     })
 }
 
+/// Blanks the `type` keyword of type-only star exports (e.g. `export type * from './types';`),
+/// which the grammar doesn't support, leaving everything else where it was.
+///
+/// Returns the content to parse and the start of every type-only star export statement.
+fn mask_type_only_star_exports<'a>(
+    content: &'a str,
+    parser: &mut Parser,
+) -> (Cow<'a, str>, HashSet<usize>) {
+    let candidates = find_type_only_star_export_candidates(content);
+    if candidates.is_empty() {
+        return (Cow::Borrowed(content), HashSet::new());
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return (Cow::Borrowed(content), HashSet::new());
+    };
+
+    let mut masked_content = content.to_string();
+    let mut statement_starts = HashSet::new();
+    for (statement_start, keyword_range) in candidates {
+        let keyword_node = tree
+            .root_node()
+            .descendant_for_byte_range(keyword_range.start, keyword_range.end);
+        let is_unsupported = keyword_node.is_some_and(|node| {
+            node.is_error() || node.parent().is_some_and(|parent| parent.is_error())
+        });
+        if is_unsupported {
+            masked_content.replace_range(keyword_range, "    ");
+            statement_starts.insert(statement_start);
+        }
+    }
+    (Cow::Owned(masked_content), statement_starts)
+}
+
+/// Finds the text that looks like a type-only star export (`export type *`), returning the
+/// start of the `export` keyword and the range of the `type` keyword.
+fn find_type_only_star_export_candidates(content: &str) -> Vec<(usize, Range<usize>)> {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    content
+        .match_indices("export")
+        .filter_map(|(start, keyword)| {
+            if content[..start].ends_with(is_identifier_char) {
+                return None;
+            }
+            let after_export = &content[start + keyword.len()..];
+            let after_whitespace = after_export.trim_start();
+            if after_whitespace.len() == after_export.len() {
+                return None;
+            }
+            let after_type = after_whitespace.strip_prefix("type")?;
+            if !after_type.starts_with(char::is_whitespace)
+                || !after_type.trim_start().starts_with('*')
+            {
+                return None;
+            }
+            let type_start = content.len() - after_whitespace.len();
+            Some((start, type_start..type_start + "type".len()))
+        })
+        .collect()
+}
+
+/// Checks whether an export statement only exports types (e.g. `export type { A } from './a';`).
+fn is_type_only_export(statement: Node, type_only_star_exports: &HashSet<usize>) -> bool {
+    type_only_star_exports.contains(&statement.start_byte())
+        || statement
+            .children(&mut statement.walk())
+            .any(|child| child.kind() == "type")
+}
+
+/// Checks whether the file has an export statement without specifiers (e.g. `export {};`).
+fn has_module_marker(root: Node) -> bool {
+    let mut cursor = root.walk();
+    let has_marker = root.children(&mut cursor).any(|statement| {
+        statement.kind() == "export_statement"
+            && statement
+                .named_children(&mut statement.walk())
+                .any(|child| {
+                    child.kind() == "export_clause"
+                        && !child
+                            .named_children(&mut child.walk())
+                            .any(|specifier| specifier.kind() == "export_specifier")
+                })
+    });
+    has_marker
+}
+
+/// Gets the attribute of each triple-slash reference directive at the top of the file (e.g.
+/// `("lib", "dom")` for `/// <reference lib="dom" />`).
+///
+/// Like in TypeScript, directives after the first statement are ordinary comments.
+fn get_reference_directives(root: Node, parsed_file: &ParsedFile) -> Vec<(String, String)> {
+    let mut cursor = root.walk();
+    let directives = root
+        .children(&mut cursor)
+        .take_while(|node| node.kind() == "comment")
+        .filter_map(|node| {
+            let comment = parsed_file.render_node(node).ok()?;
+            let directive = comment.strip_prefix("///")?.trim();
+            let attribute = directive
+                .strip_prefix("<reference")?
+                .trim_end_matches("/>")
+                .trim();
+            let (name, value) = attribute.split_once('=')?;
+            let value = value.trim().trim_matches(['"', '\'']);
+            Some((name.trim().to_string(), value.to_string()))
+        })
+        .collect();
+    directives
+}
+
+/// Gets the directive prologue: the string literal statements at the top of the file (e.g.
+/// `"use strict";`).
+fn get_directives(root: Node, parsed_file: &ParsedFile) -> Vec<String> {
+    let mut cursor = root.walk();
+    let directives = root
+        .named_children(&mut cursor)
+        .filter(|node| node.kind() != "comment")
+        .map_while(|statement| {
+            let literal = statement
+                .named_child(0)
+                .filter(|_| statement.kind() == "expression_statement")
+                .filter(|literal| literal.kind() == "string")?;
+            let content = parsed_file.render_node(literal).ok()?;
+            Some(content[1..content.len() - 1].to_string())
+        })
+        .collect();
+    directives
+}
+
+/// Gets the pragmas in the top-level comments of the file (e.g. `// @ts-nocheck`).
+fn get_pragmas(root: Node, parsed_file: &ParsedFile) -> Vec<Pragma> {
+    let mut cursor = root.walk();
+    let pragmas = root
+        .children(&mut cursor)
+        .filter(|node| node.kind() == "comment")
+        .filter_map(|node| parsed_file.render_node(node).ok())
+        .flat_map(|comment| parse_pragmas(&comment))
+        .collect();
+    pragmas
+}
+
+/// Parses the pragmas in a comment, whose arguments follow their names after a space or `=`
+/// (e.g. `@jsxImportSource preact` or `@deno-types="./foo.d.ts"`).
+fn parse_pragmas(comment: &str) -> Vec<Pragma> {
+    comment
+        .split('@')
+        .skip(1)
+        .filter_map(|text| {
+            let name_length = text
+                .find(|c: char| !(c.is_alphanumeric() || c == '-'))
+                .unwrap_or(text.len());
+            let name = &text[..name_length];
+            if !PRAGMA_NAMES.contains(&name) {
+                return None;
+            }
+            let rest = &text[name_length..];
+            let value = rest
+                .strip_prefix('=')
+                .or_else(|| rest.strip_prefix([' ', '\t']))
+                .map(|value| value.trim_start())
+                .and_then(|value| match value.chars().next()? {
+                    quote @ ('"' | '\'') => value[1..].split(quote).next(),
+                    _ => value.split(|c: char| c.is_whitespace() || c == '*').next(),
+                })
+                .filter(|value| !value.is_empty())
+                .map(str::to_string);
+            Some(Pragma {
+                name: name.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Gets the declaration file that a `@deno-types` comment right before an import or export
+/// statement substitutes for its source (e.g. `./foo.d.ts` for
+/// `// @deno-types="./foo.d.ts"` before `import { foo } from './foo.js';`).
+fn get_deno_types(statement: Node, parsed_file: &ParsedFile) -> Option<String> {
+    let comment = statement
+        .prev_sibling()
+        .filter(|node| node.kind() == "comment")?;
+    parse_pragmas(&parsed_file.render_node(comment).ok()?)
+        .into_iter()
+        .find(|pragma| pragma.name == "deno-types")?
+        .value
+}
+
+/// Gets the source of an import or export statement from the fragment of its string literal,
+/// honouring any `@deno-types` comment.
+fn get_statement_source(source_node: Node, parsed_file: &ParsedFile) -> Option<String> {
+    let statement = source_node.parent()?.parent()?;
+    get_deno_types(statement, parsed_file).or_else(|| parsed_file.render_node(source_node).ok())
+}
+
+/// Gets the text of the comments before the first statement of a file.
+fn get_leading_comments(root: Node, parsed_file: &ParsedFile) -> String {
+    let mut cursor = root.walk();
+    let comments = root
+        .children(&mut cursor)
+        .take_while(|node| node.kind() == "comment")
+        .filter_map(|node| parsed_file.render_node(node).ok())
+        .collect::<Vec<_>>();
+    comments.join("\n")
+}
+
+/// Extracts the top-level `declare module '...' { ... }` blocks.
+///
+/// Shorthand declarations without a body (e.g. `declare module 'foo';`) are skipped, as they
+/// don't declare anything.
+fn extract_augmentations(root: Node, parsed_file: &ParsedFile) -> Vec<Augmentation> {
+    let mut cursor = root.walk();
+    let augmentations = root
+        .children(&mut cursor)
+        .filter(|statement| statement.kind() == "ambient_declaration")
+        .filter_map(|statement| {
+            let module_node = statement
+                .named_children(&mut statement.walk())
+                .find(|child| child.kind() == "module")?;
+            let name_node = module_node
+                .child_by_field_name("name")
+                .filter(|name_node| name_node.kind() == "string")?;
+            let body_node = module_node.child_by_field_name("body")?;
+            let specifier = parsed_file.render_node(name_node.named_child(0)?).ok()?;
+            Some(Augmentation {
+                specifier,
+                symbols: get_block_declarations(body_node, parsed_file),
+            })
+        })
+        .collect();
+    augmentations
+}
+
+/// Extracts the declarations in the top-level `declare global { ... }` blocks.
+fn extract_globals(root: Node, parsed_file: &ParsedFile) -> Vec<Symbol> {
+    let mut cursor = root.walk();
+    let globals = root
+        .children(&mut cursor)
+        .filter(|statement| statement.kind() == "ambient_declaration")
+        .filter_map(|statement| {
+            statement
+                .named_children(&mut statement.walk())
+                .find(|child| child.kind() == "statement_block")
+        })
+        .flat_map(|body_node| get_block_declarations(body_node, parsed_file))
+        .collect();
+    globals
+}
+
+/// Gets the named declarations in a block, along with their JSDoc.
+fn get_block_declarations(block: Node, parsed_file: &ParsedFile) -> Vec<Symbol> {
+    let mut cursor = block.walk();
+    let declarations = block
+        .named_children(&mut cursor)
+        .filter_map(|statement| {
+            let declaration = unwrap_declaration(statement)?;
+            let name_node = match declaration.kind() {
+                "lexical_declaration" | "variable_declaration" => declaration
+                    .named_child(0)
+                    .and_then(|declarator| declarator.child_by_field_name("name")),
+                _ => declaration.child_by_field_name("name"),
+            }?;
+            let name = parsed_file.render_node(name_node).ok()?;
+
+            let start_byte = get_jsdoc(statement.prev_sibling(), parsed_file)
+                .and(statement.prev_sibling())
+                .map_or(statement.start_byte(), |jsdoc| jsdoc.start_byte());
+            let source_code = parsed_file.render(start_byte..statement.end_byte());
+            Some(Symbol { name, source_code })
+        })
+        .collect();
+    declarations
+}
+
 fn get_jsdoc<'a>(node: Option<Node<'a>>, parsed_file: &'a ParsedFile) -> Option<String> {
     node.filter(|n| n.kind() == "comment")
         .and_then(|n| parsed_file.render_node(n).ok())
         .filter(|comment| comment.starts_with("/**"))
 }
 
+/// Finds the module's JSDoc amongst the comments leading the file.
+///
+/// API Extractor's `@packageDocumentation` comment is often preceded by a licence header, so we
+/// look past any comments before it.
+fn get_module_jsdoc(root: Node, parsed_file: &ParsedFile) -> Option<String> {
+    let mut cursor = root.walk();
+    let module_jsdoc = root
+        .children(&mut cursor)
+        .take_while(|n| n.kind() == "comment")
+        .filter_map(|n| get_jsdoc(Some(n), parsed_file))
+        .find(|comment| is_module_jsdoc(comment));
+    module_jsdoc
+}
+
 fn is_module_jsdoc(comment: &str) -> bool {
-    comment.contains("@file") || comment.contains("@fileoverview") || comment.contains("@module")
+    comment.contains("@file")
+        || comment.contains("@fileoverview")
+        || comment.contains("@module")
+        || comment.contains("@packageDocumentation")
 }
 
-/// Extracts all symbols from the module.
+/// Extracts all symbols from a container: the module or the body of a namespace.
+///
+/// Only the statements of the container itself are considered, so the symbols of nested
+/// namespaces are extracted once, when their own bodies are.
 ///
 /// # Arguments
 ///
-/// * `node` - The root node of the TypeScript AST
+/// * `container` - The root node of the TypeScript AST or the body of a namespace
 /// * `parsed_file` - The parsed file containing the source code
 ///
 /// # Returns
 ///
-/// A vector of all symbols found in the module
+/// A vector of all symbols found in the container
 fn get_module_symbols<'a>(
-    node: Node<'a>,
+    container: Node<'a>,
     parsed_file: &'a ParsedFile,
+    queries: &ContainerQueries,
+    type_only_star_exports: &HashSet<usize>,
+    nesting: &mut NamespaceNesting,
 ) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
     let mut symbols = vec![];
 
-    symbols.extend(extract_imports(node, parsed_file)?);
-    symbols.extend(extract_symbols(node, parsed_file)?);
-    symbols.extend(extract_namespaces(node, parsed_file)?);
-    symbols.extend(extract_exports(node, parsed_file)?);
+    symbols.extend(extract_imports(container, parsed_file, &queries.imports)?);
+    symbols.extend(extract_import_aliases(
+        container,
+        parsed_file,
+        &queries.import_aliases,
+    )?);
+    symbols.extend(extract_symbols(container, parsed_file, &queries.symbols)?);
+    symbols.extend(extract_namespaces(
+        container,
+        parsed_file,
+        queries,
+        type_only_star_exports,
+        nesting,
+    )?);
+    symbols.extend(extract_exports(
+        container,
+        parsed_file,
+        &queries.exports,
+        type_only_star_exports,
+    )?);
 
     Ok(symbols)
 }
 
+/// Makes a cursor for running a query on a container, which only matches nodes up to `depth`
+/// levels down (e.g. `1` for the statements themselves).
+fn make_container_cursor(depth: u32) -> QueryCursor {
+    let mut cursor = QueryCursor::new();
+    cursor.set_max_start_depth(Some(depth));
+    cursor
+}
+
+/// Checks whether a declaration is a statement of a container, at most wrapped in `export`,
+/// `declare` or an expression statement (as namespaces are), rather than being nested in another
+/// declaration (e.g. in a function body).
+fn is_container_statement(node: Node, container: Node) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if parent.id() == container.id() {
+            return true;
+        }
+        if !is_modifier_wrapper(parent) {
+            return false;
+        }
+        ancestor = parent.parent();
+    }
+    false
+}
+
+/// Gets the name of the declaration exported by default from a scope: the root of a module or
+/// the body of an ambient module.
+///
+/// Only the statements directly in the scope are considered, as `export default` is illegal in
+/// namespaces but malformed declarations may still contain it.
 fn extract_default_export_name<'a>(
-    root: Node<'a>,
+    scope: Node<'a>,
     parsed_file: &'a ParsedFile,
 ) -> Result<Option<String>, ExtractionError> {
-    let query = parsed_file.make_query(DEFAULT_EXPORT_QUERY)?;
+    let query = get_default_export_query();
 
     let name_index = query
         .capture_index_for_name("name")
         .expect("Name capture not found");
-    let mut cursor = QueryCursor::new();
-    let mut matches = parsed_file.exec_query(&query, root, &mut cursor);
+    let mut cursor = make_container_cursor(1);
+    let mut matches = parsed_file.exec_query(query, scope, &mut cursor);
 
     Ok(matches.next().and_then(|match_| {
         match_
@@ -179,11 +648,11 @@ fn extract_default_export_name<'a>(
 }
 
 fn extract_symbols<'a>(
-    root: Node<'a>,
+    container: Node<'a>,
     parsed_file: &'a ParsedFile,
+    query: &Query,
 ) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
     let mut symbols = vec![];
-    let query = parsed_file.make_query(SYMBOLS_QUERY)?;
 
     let name_index = query
         .capture_index_for_name("name")
@@ -192,41 +661,27 @@ fn extract_symbols<'a>(
         .capture_index_for_name("declaration")
         .expect("Declaration capture not found");
 
-    let mut cursor = QueryCursor::new();
-    let mut matches = parsed_file.exec_query(&query, root, &mut cursor);
+    let mut cursor = make_container_cursor(MAX_STATEMENT_DEPTH);
+    let mut matches = parsed_file.exec_query(query, container, &mut cursor);
 
     while let Some(match_) = matches.next() {
-        let name_node = match_
-            .nodes_for_capture_index(name_index)
-            .next()
-            .expect("Missing name node in symbol declaration");
-        let mut definition_node = match_
-            .nodes_for_capture_index(definition_index)
-            .next()
-            .expect("Missing declaration node in symbol declaration");
-
-        // Skip symbols that are inside a namespace
-        if has_namespace_ancestor(definition_node, root) {
+        let name_node = get_capture(match_, name_index, "name node in symbol declaration")?;
+        let definition_node = get_capture(
+            match_,
+            definition_index,
+            "declaration node in symbol declaration",
+        )?;
+
+        if !is_container_statement(definition_node, container) {
             continue;
         }
 
         let name = parsed_file.render_node(name_node)?;
 
-        let parent = definition_node
-            .parent()
-            .expect("Symbol declaration has no parent");
-        if parent.kind() == "ambient_declaration" {
-            definition_node = parent;
-        }
-
-        let mut is_exported = false;
-        let parent = definition_node
-            .parent()
-            .expect("Symbol declaration has no parent");
-        if parent.kind() == "export_statement" {
-            definition_node = parent;
-            is_exported = true;
-        }
+        let WrappedDeclaration {
+            statement: definition_node,
+            is_exported,
+        } = wrap_declaration(definition_node);
 
         // Get the full source code including any preceding JSDoc comment.
         let mut start_byte = definition_node.start_byte();
@@ -252,23 +707,30 @@ fn extract_symbols<'a>(
     Ok(symbols)
 }
 
-fn has_namespace_ancestor(node: Node, root: Node) -> bool {
-    let parent = node.parent().expect("Node has no parent");
-    if parent.id() == root.id() {
-        false
-    } else if parent.kind() == "internal_module" {
-        true
-    } else {
-        has_namespace_ancestor(parent, root)
-    }
+/// Returns the first node captured under `index`, or a `Malformed` error naming the missing
+/// `description` rather than panicking on grammar shapes the queries didn't anticipate.
+fn get_capture<'tree>(
+    match_: &QueryMatch<'_, 'tree>,
+    index: u32,
+    description: &str,
+) -> Result<Node<'tree>, ExtractionError> {
+    match_
+        .nodes_for_capture_index(index)
+        .next()
+        .ok_or_else(|| ExtractionError::Malformed(format!("Missing {description}")))
+}
+
+fn get_parent<'tree>(node: Node<'tree>, description: &str) -> Result<Node<'tree>, ExtractionError> {
+    node.parent()
+        .ok_or_else(|| ExtractionError::Malformed(format!("The {description} has no parent")))
 }
 
 fn extract_imports<'a>(
-    root: Node<'a>,
+    container: Node<'a>,
     parsed_file: &'a ParsedFile,
+    query: &Query,
 ) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
     let mut imports = vec![];
-    let query = parsed_file.make_query(IMPORT_QUERY)?;
 
     let target_index = query
         .capture_index_for_name("target")
@@ -277,79 +739,136 @@ fn extract_imports<'a>(
         .capture_index_for_name("source")
         .expect("Source capture not found");
 
-    let mut cursor = QueryCursor::new();
-    let mut matches = parsed_file.exec_query(&query, root, &mut cursor);
+    let mut cursor = make_container_cursor(1);
+    let mut matches = parsed_file.exec_query(query, container, &mut cursor);
 
     while let Some(match_) = matches.next() {
-        let source_node = match_
-            .nodes_for_capture_index(source_index)
-            .next()
-            .expect("Missing source node in import");
-        let source_module = parsed_file.render_node(source_node)?;
+        let source_node = get_capture(match_, source_index, "source node in import")?;
+        let source_module = match get_statement_source(source_node, parsed_file) {
+            Some(source_module) => source_module,
+            None => parsed_file.render_node(source_node)?,
+        };
 
-        let target_node = match_
-            .nodes_for_capture_index(target_index)
-            .next()
-            .expect("Missing target node in import");
+        let target_node = get_capture(match_, target_index, "target node in import")?;
         let mut target_cursor = target_node.walk();
-        let subtarget_nodes = target_node.children(&mut target_cursor);
 
-        let targets = subtarget_nodes.filter_map(|child| match child.kind() {
-            "identifier" => Some(TypeScriptSymbol::ModuleImport {
-                source_module: source_module.clone(),
-                target: ImportTarget::Default {
-                    name: extract_identifier_text(child, parsed_file)
-                        .expect("Failed to get import identifier"),
+        for child in target_node.children(&mut target_cursor) {
+            let target = match child.kind() {
+                "identifier" => ImportTarget::Default {
+                    name: parsed_file.render_node(child)?,
                 },
-            }),
-            "namespace_import" => {
-                let mut namespace_cursor = child.walk();
-                let name = child
-                    .children(&mut namespace_cursor)
-                    .find_map(|n| extract_identifier_text(n, parsed_file))
-                    .expect("Failed to get import identifier");
-                Some(TypeScriptSymbol::ModuleImport {
-                    source_module: source_module.clone(),
-                    target: ImportTarget::Namespace { name },
-                })
-            }
-            "named_imports" => {
-                let mut names = Vec::new();
-                let mut aliases = HashMap::new();
-                let mut named_cursor = child.walk();
-
-                for import_specifier in child
-                    .children(&mut named_cursor)
-                    .filter(|n| n.kind() == "import_specifier")
-                {
-                    let mut specifier_cursor = import_specifier.walk();
-                    let mut children = import_specifier.children(&mut specifier_cursor);
-
-                    let name = children
-                        .next()
-                        .and_then(|n| extract_identifier_text(n, parsed_file))
-                        .expect("Failed to get import identifier");
-                    names.push(name.clone());
-
-                    if let Some(alias) =
-                        children.find_map(|n| extract_identifier_text(n, parsed_file))
+                "namespace_import" => {
+                    let mut namespace_cursor = child.walk();
+                    let name = child
+                        .children(&mut namespace_cursor)
+                        .find_map(|n| extract_identifier_text(n, parsed_file))
+                        .ok_or_else(|| {
+                            ExtractionError::Malformed(
+                                "Missing identifier in namespace import".to_string(),
+                            )
+                        })?;
+                    ImportTarget::Namespace { name }
+                }
+                "named_imports" => {
+                    let mut specifiers = Vec::new();
+                    let mut named_cursor = child.walk();
+
+                    for import_specifier in child
+                        .children(&mut named_cursor)
+                        .filter(|n| n.kind() == "import_specifier")
                     {
-                        aliases.insert(name, alias);
+                        let name_node =
+                            import_specifier
+                                .child_by_field_name("name")
+                                .ok_or_else(|| {
+                                    ExtractionError::Malformed(
+                                        "Missing name in import specifier".to_string(),
+                                    )
+                                })?;
+                        let alias = import_specifier
+                            .child_by_field_name("alias")
+                            .map(|alias_node| parsed_file.render_node(alias_node))
+                            .transpose()?;
+                        specifiers.push(Specifier {
+                            name: extract_module_export_name(name_node, parsed_file)?,
+                            alias,
+                            is_type_only: has_type_modifier(import_specifier),
+                        });
                     }
+
+                    ImportTarget::Named { specifiers }
                 }
+                _ => continue,
+            };
 
-                Some(TypeScriptSymbol::ModuleImport {
-                    source_module: source_module.clone(),
-                    target: ImportTarget::Named { names, aliases },
-                })
+            imports.push(TypeScriptSymbol::ModuleImport {
+                source_module: source_module.clone(),
+                target,
+                is_unresolved_external: false,
+                is_builtin: false,
+            });
+        }
+    }
+
+    Ok(imports)
+}
+
+fn extract_import_aliases<'a>(
+    container: Node<'a>,
+    parsed_file: &'a ParsedFile,
+    query: &Query,
+) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
+    let mut aliases = vec![];
+
+    let name_index = query
+        .capture_index_for_name("name")
+        .expect("Name capture not found");
+    let target_index = query
+        .capture_index_for_name("target")
+        .expect("Target capture not found");
+    let alias_index = query
+        .capture_index_for_name("alias")
+        .expect("Alias capture not found");
+
+    let mut cursor = make_container_cursor(MAX_STATEMENT_DEPTH);
+    let mut matches = parsed_file.exec_query(query, container, &mut cursor);
+
+    while let Some(match_) = matches.next() {
+        let alias_node = get_capture(match_, alias_index, "import alias")?;
+        if !is_container_statement(alias_node, container) {
+            continue;
+        }
+        let name =
+            parsed_file.render_node(get_capture(match_, name_index, "name in import alias")?)?;
+        let target = parsed_file
+            .render_node(get_capture(match_, target_index, "target in import alias")?)?
+            .split('.')
+            .map(|segment| segment.trim().to_string())
+            .collect();
+
+        let WrappedDeclaration {
+            statement: alias_node,
+            is_exported,
+        } = wrap_declaration(alias_node);
+
+        let mut start_byte = alias_node.start_byte();
+        if let Some(previous_node) = alias_node.prev_sibling() {
+            if let Some(jsdoc) = get_jsdoc(Some(previous_node), parsed_file) {
+                if !is_module_jsdoc(&jsdoc) {
+                    start_byte = previous_node.start_byte();
+                }
             }
-            _ => None,
-        });
+        }
+        let source_code = parsed_file.render(start_byte..alias_node.end_byte());
 
-        imports.extend(targets);
+        aliases.push(TypeScriptSymbol::ImportAlias {
+            symbol: Symbol { name, source_code },
+            target,
+            is_exported,
+        });
     }
 
-    Ok(imports)
+    Ok(aliases)
 }
 
 fn extract_identifier_text(node: Node, parsed_file: &ParsedFile) -> Option<String> {
@@ -360,18 +879,35 @@ fn extract_identifier_text(node: Node, parsed_file: &ParsedFile) -> Option<Strin
     }
 }
 
+/// Renders the name of an import specifier, which may be an identifier or, since ES2022, a
+/// string literal such as `'a-b'`.
+fn extract_module_export_name(
+    node: Node,
+    parsed_file: &ParsedFile,
+) -> Result<String, ExtractionError> {
+    if node.kind() == "string" {
+        let mut cursor = node.walk();
+        let fragment = node
+            .children(&mut cursor)
+            .find(|n| n.kind() == "string_fragment");
+        match fragment {
+            Some(fragment) => parsed_file.render_node(fragment),
+            None => Ok(String::new()),
+        }
+    } else {
+        parsed_file.render_node(node)
+    }
+}
+
 fn extract_namespaces<'a>(
-    root: Node<'a>,
+    container: Node<'a>,
     parsed_file: &'a ParsedFile,
+    queries: &ContainerQueries,
+    type_only_star_exports: &HashSet<usize>,
+    nesting: &mut NamespaceNesting,
 ) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
     let mut namespaces = vec![];
-    let query = parsed_file.make_query(
-        r#"
-        (internal_module
-            name: (identifier) @name
-            body: (statement_block) @body)
-    "#,
-    )?;
+    let query = &queries.namespaces;
 
     let name_index = query
         .capture_index_for_name("name")
@@ -380,37 +916,40 @@ fn extract_namespaces<'a>(
         .capture_index_for_name("body")
         .expect("Body capture not found");
 
-    let mut cursor = QueryCursor::new();
-    let mut matches = parsed_file.exec_query(&query, root, &mut cursor);
+    let mut cursor = make_container_cursor(MAX_STATEMENT_DEPTH);
+    let mut matches = parsed_file.exec_query(query, container, &mut cursor);
 
     while let Some(match_) = matches.next() {
-        let name_node = match_
-            .nodes_for_capture_index(name_index)
-            .next()
-            .expect("Missing name node in namespace");
-        let namespace_node = name_node.parent().expect("Namespace node has no parent");
+        let name_node = get_capture(match_, name_index, "name node in namespace")?;
+        let namespace_node = get_parent(name_node, "namespace name")?;
 
-        if has_namespace_ancestor(namespace_node, root) {
+        if !is_container_statement(namespace_node, container) {
             continue;
         }
 
         let name = parsed_file.render_node(name_node)?;
-        let body_node = match_
-            .nodes_for_capture_index(body_index)
-            .next()
-            .expect("Missing body node in namespace");
+        let body_node = get_capture(match_, body_index, "body node in namespace")?;
 
-        let inner_content = get_module_symbols(body_node, parsed_file)?;
-        let mut is_exported = false;
-        let mut current_node = namespace_node;
-        let parent = current_node.parent().expect("Namespace node has no parent");
-        if parent.kind() == "export_statement" {
-            is_exported = true;
-            current_node = parent;
+        if nesting.depth == MAX_NAMESPACE_DEPTH {
+            nesting.is_truncated = true;
+            continue;
         }
+        nesting.depth += 1;
+        let inner_content = get_module_symbols(
+            body_node,
+            parsed_file,
+            queries,
+            type_only_star_exports,
+            nesting,
+        );
+        nesting.depth -= 1;
+        let inner_content = inner_content?;
+        let WrappedDeclaration {
+            statement: statement_node,
+            is_exported,
+        } = wrap_declaration(namespace_node);
 
-        let expression_statement = current_node.parent().expect("Namespace node has no parent");
-        let jsdoc = get_jsdoc(expression_statement.prev_sibling(), parsed_file);
+        let jsdoc = get_jsdoc(statement_node.prev_sibling(), parsed_file);
 
         namespaces.push(TypeScriptSymbol::Namespace {
             name,
@@ -424,11 +963,12 @@ fn extract_namespaces<'a>(
 }
 
 fn extract_exports<'a>(
-    root: Node<'a>,
+    container: Node<'a>,
     parsed_file: &'a ParsedFile,
+    query: &Query,
+    type_only_star_exports: &HashSet<usize>,
 ) -> Result<Vec<TypeScriptSymbol>, ExtractionError> {
     let mut exports = vec![];
-    let query = parsed_file.make_query(EXPORTS_QUERY)?;
 
     let name_index = query
         .capture_index_for_name("name")
@@ -437,42 +977,38 @@ fn extract_exports<'a>(
     let source_index = query.capture_index_for_name("source").unwrap();
     let barrel_export_index = query.capture_index_for_name("barrel_export").unwrap();
 
-    let mut cursor = QueryCursor::new();
-    let mut matches = parsed_file.exec_query(&query, root, &mut cursor);
+    let mut cursor = make_container_cursor(1);
+    let mut matches = parsed_file.exec_query(query, container, &mut cursor);
 
-    let mut current_names = vec![];
-    let mut current_aliases = HashMap::new();
+    let mut current_specifiers = vec![];
     let mut current_source = None;
+    let mut current_is_type_only = false;
 
     while let Some(match_) = matches.next() {
         let source_module = match_
             .nodes_for_capture_index(source_index)
             .next()
-            .and_then(|n| parsed_file.render_node(n).ok());
+            .and_then(|n| get_statement_source(n, parsed_file));
 
-        if match_
-            .nodes_for_capture_index(barrel_export_index)
-            .next()
-            .is_some()
-        {
+        if let Some(statement_node) = match_.nodes_for_capture_index(barrel_export_index).next() {
             exports.push(TypeScriptSymbol::ModuleExport {
                 source_module,
                 target: ExportTarget::Barrel,
+                is_type_only: is_type_only_export(statement_node, type_only_star_exports),
             });
             continue;
         }
 
-        let name_node = match_
-            .nodes_for_capture_index(name_index)
-            .next()
-            .expect("Missing name node in export");
+        let name_node = get_capture(match_, name_index, "name node in export")?;
         let name = parsed_file.render_node(name_node)?;
-        let export_node = name_node.parent().expect("Export node has no parent");
+        let export_node = get_parent(name_node, "export name")?;
 
         if export_node.kind() == "namespace_export" {
+            let statement_node = get_parent(export_node, "namespace export")?;
             exports.push(TypeScriptSymbol::ModuleExport {
                 source_module,
                 target: ExportTarget::Namespace { name },
+                is_type_only: is_type_only_export(statement_node, type_only_star_exports),
             });
             continue;
         }
@@ -481,28 +1017,42 @@ fn extract_exports<'a>(
         if source_module != current_source {
             emit_accumulated_exports(
                 &mut exports,
-                &mut current_names,
-                &mut current_aliases,
+                &mut current_specifiers,
                 &current_source,
+                current_is_type_only,
             );
             current_source = source_module;
         }
+        current_is_type_only = match export_node.kind() {
+            "export_statement" => false,
+            _ => export_node
+                .parent()
+                .and_then(|clause_node| clause_node.parent())
+                .is_some_and(|statement_node| {
+                    is_type_only_export(statement_node, type_only_star_exports)
+                }),
+        };
 
         // Accumulate the current export
-        current_names.push(name.clone());
-
-        if let Some(alias_node) = match_.nodes_for_capture_index(alias_index).next() {
-            let alias = parsed_file.render_node(alias_node)?;
-            current_aliases.insert(name.clone(), alias.clone());
-        }
+        let alias = match_
+            .nodes_for_capture_index(alias_index)
+            .next()
+            .map(|alias_node| parsed_file.render_node(alias_node))
+            .transpose()?;
+        current_specifiers.push(Specifier {
+            name,
+            alias,
+            is_type_only: export_node.kind() == "export_specifier"
+                && has_type_modifier(export_node),
+        });
 
         // Handle CommonJS exports (export = myFunction)
         if export_node.kind() == "export_statement" {
             emit_accumulated_exports(
                 &mut exports,
-                &mut current_names,
-                &mut current_aliases,
+                &mut current_specifiers,
                 &current_source,
+                current_is_type_only,
             );
             current_source = None;
             continue;
@@ -513,68 +1063,540 @@ fn extract_exports<'a>(
         if is_last_specifier {
             emit_accumulated_exports(
                 &mut exports,
-                &mut current_names,
-                &mut current_aliases,
+                &mut current_specifiers,
                 &current_source,
+                current_is_type_only,
             );
             current_source = None;
         }
-    }
+    }
+
+    Ok(exports)
+}
+
+/// Determines whether an import or export specifier has the `type` modifier (e.g. `type Foo` in
+/// `import { type Foo } from './foo';`).
+fn has_type_modifier(specifier_node: Node) -> bool {
+    specifier_node
+        .child(0)
+        .is_some_and(|child| child.kind() == "type")
+}
+
+fn emit_accumulated_exports(
+    exports: &mut Vec<TypeScriptSymbol>,
+    current_specifiers: &mut Vec<Specifier>,
+    current_source: &Option<String>,
+    is_type_only: bool,
+) {
+    if !current_specifiers.is_empty() {
+        exports.push(TypeScriptSymbol::ModuleExport {
+            source_module: current_source.clone(),
+            target: ExportTarget::Named {
+                specifiers: std::mem::take(current_specifiers),
+            },
+            is_type_only,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::extensions::CustomSymbol;
+    use crate::api::test_helpers::{make_parser, make_specifier};
+    use assertables::assert_matches;
+    use daipendency_extractor::ExtractionError;
+
+    #[test]
+    fn empty_file() {
+        let mut parser = make_parser();
+        let path = PathBuf::from("/test/empty.ts");
+
+        let result = parse_typescript_file("", &mut parser, path.clone(), &[]);
+
+        assert_matches!(result, Ok(Module { path: p, jsdoc: None, symbols: s, default_export_name: None, lib_references: l, no_default_lib: false, has_module_marker: false, has_truncated_namespaces: false, generator: None, augmentations: a, globals: gl, directives: d, pragmas: g }) if p == path && s.is_empty() && l.is_empty() && a.is_empty() && gl.is_empty() && d.is_empty() && g.is_empty());
+    }
+
+    #[test]
+    fn malformed_file() {
+        let mut parser = make_parser();
+
+        let result = parse_typescript_file("class {", &mut parser, PathBuf::new(), &[]);
+
+        assert_matches!(result, Err(ExtractionError::Malformed(msg)) if msg == "Failed to parse source file");
+    }
+
+    #[test]
+    fn file_path_is_preserved() {
+        let mut parser = make_parser();
+        let test_path = PathBuf::from("/test/file/path.ts");
+
+        let result = parse_typescript_file("const foo = 42;", &mut parser, test_path.clone(), &[]);
+
+        assert_matches!(result, Ok(Module { path, .. }) if path == test_path);
+    }
+
+    #[test]
+    fn query_extensions() {
+        let mut parser = make_parser();
+        let extension = QueryExtension::new("(decorator (identifier) @decorator)")
+            .unwrap()
+            .on_capture("decorator", |capture| {
+                Some(CustomSymbol {
+                    kind: "decorator".to_string(),
+                    name: capture.text().to_string(),
+                    source_code: capture.text().to_string(),
+                })
+            });
+
+        let module = parse_typescript_file(
+            "export declare class Foo { @tracked bar: string; }",
+            &mut parser,
+            PathBuf::new(),
+            &[extension],
+        )
+        .unwrap();
+
+        assert_eq!(module.symbols.len(), 2);
+        assert_matches!(
+            &module.symbols[1],
+            TypeScriptSymbol::Custom { kind, name, source } if kind == "decorator" && name == "tracked" && source == "tracked"
+        );
+    }
+
+    mod module_marker {
+        use super::*;
+        use crate::api::test_helpers::deconstruct_module_export;
+
+        fn parse(content: &str) -> Module {
+            parse_typescript_file(content, &mut make_parser(), PathBuf::new(), &[]).unwrap()
+        }
+
+        #[test]
+        fn empty_export() {
+            let module = parse("export declare const a: number;\nexport {};");
+
+            assert!(module.has_module_marker);
+            assert_eq!(module.symbols.len(), 1);
+        }
+
+        #[test]
+        fn empty_export_with_source() {
+            let module = parse("export {} from './side-effects';");
+
+            assert!(module.has_module_marker);
+            assert!(module.symbols.is_empty());
+        }
+
+        #[test]
+        fn empty_export_with_comment() {
+            let module = parse("export { /* nothing */ };");
+
+            assert!(module.has_module_marker);
+        }
+
+        #[test]
+        fn named_exports() {
+            let module = parse("declare const a: number;\nexport { a };");
+
+            assert!(!module.has_module_marker);
+        }
+
+        #[test]
+        fn exports_around_marker() {
+            let module = parse(
+                "declare const a: number;\nexport { a };\nexport {};\nexport { b as c } from './b';",
+            );
+
+            assert_eq!(module.symbols.len(), 3);
+            let (source_module, target) = deconstruct_module_export(&module.symbols[1]);
+            assert_eq!(source_module, None);
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("a", None)]);
+            let (source_module, target) = deconstruct_module_export(&module.symbols[2]);
+            assert_eq!(source_module, Some("./b".to_string()));
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("b", Some("c"))]);
+        }
+    }
+
+    mod generated_files {
+        use super::*;
+        use crate::api::generated::Generator;
+
+        #[test]
+        fn generated_file() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "// @generated by protoc-gen-es v1.10.0\n// @generated from file user.proto\nexport declare class User {}",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(module.generator, Some(Generator::Protobuf));
+        }
+
+        #[test]
+        fn marker_after_statement() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "export declare class User {}\n// DO NOT EDIT",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(module.generator, None);
+        }
+    }
+
+    mod augmentations {
+        use super::*;
+
+        #[test]
+        fn declarations() {
+            let mut parser = make_parser();
+            let content = "import 'fastify';\ndeclare module 'fastify' {\n  /** The instance. */\n  interface FastifyInstance { foo(): void; }\n  export function bar(): void;\n  const baz: string;\n}\nexport {};";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(
+                module.augmentations,
+                vec![Augmentation {
+                    specifier: "fastify".to_string(),
+                    symbols: vec![
+                        Symbol {
+                            name: "FastifyInstance".to_string(),
+                            source_code:
+                                "/** The instance. */\n  interface FastifyInstance { foo(): void; }"
+                                    .to_string(),
+                        },
+                        Symbol {
+                            name: "bar".to_string(),
+                            source_code: "export function bar(): void;".to_string(),
+                        },
+                        Symbol {
+                            name: "baz".to_string(),
+                            source_code: "const baz: string;".to_string(),
+                        },
+                    ],
+                }]
+            );
+        }
+
+        #[test]
+        fn declarations_not_in_module() {
+            let mut parser = make_parser();
+            let content = "declare module 'fastify' {\n  export function bar(): void;\n}\nexport declare function own(): void;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(&module.symbols[0], TypeScriptSymbol::Symbol { symbol, .. } if symbol.name == "own");
+        }
+
+        #[test]
+        fn shorthand_declaration() {
+            let mut parser = make_parser();
+
+            let module =
+                parse_typescript_file("declare module 'foo';", &mut parser, PathBuf::new(), &[])
+                    .unwrap();
+
+            assert!(module.augmentations.is_empty());
+        }
+
+        #[test]
+        fn global_augmentation() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "declare global { interface Window { foo: string } }",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert!(module.augmentations.is_empty());
+        }
+    }
+
+    mod globals {
+        use super::*;
+
+        #[test]
+        fn global_block() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "export {};\ndeclare global {\n  /** Polyfilled. */\n  interface Array<T> { findLast(): T | undefined; }\n  var VERSION: string;\n}",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(
+                module
+                    .globals
+                    .iter()
+                    .map(|symbol| symbol.name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["Array", "VERSION"]
+            );
+            assert!(module.globals[0]
+                .source_code
+                .starts_with("/** Polyfilled. */"));
+        }
+
+        #[test]
+        fn nested_modifiers() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "export {};\ndeclare global {\n  export declare const enum Mode { A }\n  namespace Polyfills { export declare abstract class Shim {} }\n}",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(
+                module
+                    .globals
+                    .iter()
+                    .map(|symbol| symbol.name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["Mode", "Polyfills"]
+            );
+            assert!(module.globals[1]
+                .source_code
+                .starts_with("namespace Polyfills {"));
+        }
+
+        #[test]
+        fn module_augmentation() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "declare module 'foo' { interface Foo {} }",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert!(module.globals.is_empty());
+        }
+    }
+
+    mod pragmas {
+        use super::*;
+
+        fn make_pragma(name: &str, value: Option<&str>) -> Pragma {
+            Pragma {
+                name: name.to_string(),
+                value: value.map(str::to_string),
+            }
+        }
+
+        #[test]
+        fn pragmas_without_values() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "// @ts-nocheck\nexport declare const foo: string;",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(module.pragmas, vec![make_pragma("ts-nocheck", None)]);
+        }
+
+        #[test]
+        fn pragmas_with_values() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "/** @jsxImportSource preact */\n/* @jsx h @jsxFrag Fragment */\nexport {};",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(
+                module.pragmas,
+                vec![
+                    make_pragma("jsxImportSource", Some("preact")),
+                    make_pragma("jsx", Some("h")),
+                    make_pragma("jsxFrag", Some("Fragment")),
+                ]
+            );
+        }
+
+        #[test]
+        fn jsdoc_tags() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "/** @param foo The foo. */\nexport declare function bar(foo: string): void;",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert!(module.pragmas.is_empty());
+        }
+
+        #[test]
+        fn directives() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "// Licence\n\"use strict\";\n'use client';\nexport declare const foo: string;\n\"not a directive\";",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(module.directives, vec!["use strict", "use client"]);
+        }
+
+        #[test]
+        fn deno_types_import() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "// @deno-types=\"./types/foo.d.ts\"\nimport { foo } from './foo.js';",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(
+                module.pragmas,
+                vec![make_pragma("deno-types", Some("./types/foo.d.ts"))]
+            );
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::ModuleImport { source_module, .. } if source_module == "./types/foo.d.ts"
+            );
+        }
+
+        #[test]
+        fn deno_types_export() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "// @deno-types='./types/foo.d.ts'\nexport * from './foo.js';\nexport * from './bar.js';",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            let sources = module
+                .symbols
+                .iter()
+                .filter_map(|symbol| match symbol {
+                    TypeScriptSymbol::ModuleExport { source_module, .. } => {
+                        source_module.as_deref()
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(sources, vec!["./types/foo.d.ts", "./bar.js"]);
+        }
+    }
+
+    mod reference_directives {
+        use super::*;
+
+        #[test]
+        fn lib_references() {
+            let mut parser = make_parser();
+
+            let module = parse_typescript_file(
+                "/// <reference lib=\"dom\" />\n/// <reference lib='es2020.promise'/>\nexport declare const a: Window;",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(module.lib_references, vec!["dom", "es2020.promise"]);
+            assert!(!module.no_default_lib);
+        }
 
-    Ok(exports)
-}
+        #[test]
+        fn no_default_lib() {
+            let mut parser = make_parser();
 
-fn emit_accumulated_exports(
-    exports: &mut Vec<TypeScriptSymbol>,
-    current_names: &mut Vec<String>,
-    current_aliases: &mut HashMap<String, String>,
-    current_source: &Option<String>,
-) {
-    if !current_names.is_empty() {
-        exports.push(TypeScriptSymbol::ModuleExport {
-            source_module: current_source.clone(),
-            target: ExportTarget::Named {
-                names: std::mem::take(current_names),
-                aliases: std::mem::take(current_aliases),
-            },
-        });
-    }
-}
+            let module = parse_typescript_file(
+                "/// <reference no-default-lib=\"true\"/>\ninterface Array<T> {}",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api::test_helpers::make_parser;
-    use assertables::assert_matches;
-    use daipendency_extractor::ExtractionError;
+            assert!(module.no_default_lib);
+        }
 
-    #[test]
-    fn empty_file() {
-        let mut parser = make_parser();
-        let path = PathBuf::from("/test/empty.ts");
+        #[test]
+        fn other_directives() {
+            let mut parser = make_parser();
 
-        let result = parse_typescript_file("", &mut parser, path.clone());
+            let module = parse_typescript_file(
+                "/// <reference types=\"node\" />\n/// <reference path=\"globals.d.ts\" />",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
 
-        assert_matches!(result, Ok(Module { path: p, jsdoc: None, symbols: s, default_export_name: None }) if p == path && s.is_empty());
-    }
+            assert!(module.lib_references.is_empty());
+        }
 
-    #[test]
-    fn malformed_file() {
-        let mut parser = make_parser();
+        #[test]
+        fn directive_after_statement() {
+            let mut parser = make_parser();
 
-        let result = parse_typescript_file("class {", &mut parser, PathBuf::new());
+            let module = parse_typescript_file(
+                "export declare const a: string;\n/// <reference lib=\"dom\" />",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
 
-        assert_matches!(result, Err(ExtractionError::Malformed(msg)) if msg == "Failed to parse source file");
-    }
+            assert!(module.lib_references.is_empty());
+        }
 
-    #[test]
-    fn file_path_is_preserved() {
-        let mut parser = make_parser();
-        let test_path = PathBuf::from("/test/file/path.ts");
+        #[test]
+        fn regular_comments() {
+            let mut parser = make_parser();
 
-        let result = parse_typescript_file("const foo = 42;", &mut parser, test_path.clone());
+            let module = parse_typescript_file(
+                "// <reference lib=\"dom\" />\n/* Licence */",
+                &mut parser,
+                PathBuf::new(),
+                &[],
+            )
+            .unwrap();
 
-        assert_matches!(result, Ok(Module { path, .. }) if path == test_path);
+            assert!(module.lib_references.is_empty());
+        }
     }
 
     mod module_jsdoc {
@@ -587,7 +1609,7 @@ mod tests {
             let mut parser = make_parser();
             let content = format!("/** @file {FILE_DESCRIPTION} */\ndeclare const foo = 42;");
 
-            let result = parse_typescript_file(&content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: Some(j), .. }) if j == format!("/** @file {FILE_DESCRIPTION} */"));
         }
@@ -598,7 +1620,7 @@ mod tests {
             let content =
                 format!("/** @fileoverview {FILE_DESCRIPTION} */\ndeclare const foo = 42;");
 
-            let result = parse_typescript_file(&content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: Some(j), .. }) if j == format!("/** @fileoverview {FILE_DESCRIPTION} */"));
         }
@@ -608,17 +1630,51 @@ mod tests {
             let mut parser = make_parser();
             let content = format!("/** @module {FILE_DESCRIPTION} */\ndeclare const foo = 42;");
 
-            let result = parse_typescript_file(&content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: Some(j), .. }) if j == format!("/** @module {FILE_DESCRIPTION} */"));
         }
 
+        #[test]
+        fn package_documentation_tag() {
+            let mut parser = make_parser();
+            let content =
+                format!("/**\n * {FILE_DESCRIPTION}\n * @packageDocumentation\n */\ndeclare const foo = 42;");
+
+            let result = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
+
+            assert_matches!(result, Ok(Module { jsdoc: Some(j), .. }) if j == format!("/**\n * {FILE_DESCRIPTION}\n * @packageDocumentation\n */"));
+        }
+
+        #[test]
+        fn tag_after_licence_header() {
+            let mut parser = make_parser();
+            let content = format!(
+                "// Copyright (c) Someone. Licensed under the MIT licence.\n/** {FILE_DESCRIPTION} @packageDocumentation */\ndeclare const foo = 42;"
+            );
+
+            let result = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
+
+            assert_matches!(result, Ok(Module { jsdoc: Some(j), .. }) if j == format!("/** {FILE_DESCRIPTION} @packageDocumentation */"));
+        }
+
+        #[test]
+        fn package_documentation_is_not_symbol_jsdoc() {
+            let mut parser = make_parser();
+            let content = "/** @packageDocumentation */
+export declare const foo = 42;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(&module.symbols[0], TypeScriptSymbol::Symbol { symbol, .. } if symbol.source_code == "export declare const foo = 42;");
+        }
+
         #[test]
         fn no_tag() {
             let mut parser = make_parser();
             let content = "/** Just a comment */\ndeclare const foo = 42;";
 
-            let result = parse_typescript_file(content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: None, .. }));
         }
@@ -628,7 +1684,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "/* @module Just a comment */\ndeclare const foo = 42;";
 
-            let result = parse_typescript_file(content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: None, .. }));
         }
@@ -638,7 +1694,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "// @module Just a comment\ndeclare const foo = 42;";
 
-            let result = parse_typescript_file(content, &mut parser, PathBuf::new());
+            let result = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]);
 
             assert_matches!(result, Ok(Module { jsdoc: None, .. }));
         }
@@ -652,7 +1708,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare class Foo { bar(): void; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -664,7 +1720,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare abstract class Foo { bar(): void; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -676,7 +1732,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "type Bar = string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -688,7 +1744,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "interface Baz { qux: number; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -700,7 +1756,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "enum Status { Active, Inactive }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -712,7 +1768,19 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare function greet(name: string): void;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
+            let symbol = &module.symbols[0];
+            assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: false } if symbol.name == "greet" && symbol.source_code == content);
+        }
+
+        #[test]
+        fn function_implementation() {
+            let mut parser = make_parser();
+            let content = "function greet(name: string): void {}";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -724,7 +1792,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare const VERSION: string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -736,7 +1804,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare let counter: number;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -748,7 +1816,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "/** The version number */\ndeclare const VERSION: string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -760,7 +1828,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "declare const VERSION: string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -772,11 +1840,11 @@ mod tests {
             let mut parser = make_parser();
             let content = "/** @module The module description */\ndeclare const VERSION: string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
-            assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: false } if symbol.source_code == "declare const VERSION: string;".to_string());
+            assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: false } if symbol.source_code == "declare const VERSION: string;");
         }
 
         #[test]
@@ -784,11 +1852,11 @@ mod tests {
             let mut parser = make_parser();
             let content = "// The comment\ndeclare const VERSION: string;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
-            assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: false } if symbol.source_code == "declare const VERSION: string;".to_string());
+            assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: false } if symbol.source_code == "declare const VERSION: string;");
         }
 
         #[test]
@@ -796,7 +1864,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "export declare function greet(name: string): void;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
             let symbol = &module.symbols[0];
@@ -808,12 +1876,23 @@ mod tests {
             let mut parser = make_parser();
             let content = "export default declare function greet(name: string): void;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, default_export_name: Some(n), .. } if symbols.len() == 1 && n == "greet");
             let symbol = &module.symbols[0];
             assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: true } if symbol.name == "greet" && symbol.source_code == content);
         }
+
+        #[test]
+        fn declarations_in_function_body() {
+            let mut parser = make_parser();
+            let content = "export function greet(): void {\n  const greeting = 'Hello';\n}";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(module, Module { ref symbols, .. } if symbols.len() == 1);
+            assert_matches!(&module.symbols[0], TypeScriptSymbol::Symbol { symbol, .. } if symbol.name == "greet");
+        }
     }
 
     mod namespaces {
@@ -826,7 +1905,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "namespace Foo {}";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -839,7 +1918,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "namespace Foo { declare const VERSION: string; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -852,7 +1931,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "export namespace Foo { declare const VERSION: string; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -866,13 +1945,30 @@ mod tests {
             );
         }
 
+        #[test]
+        fn exported_ambient_namespace() {
+            let mut parser = make_parser();
+            let content = "export declare namespace Foo { const VERSION: string; }";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::Namespace {
+                    is_exported: true,
+                    ..
+                }
+            );
+        }
+
         #[test]
         fn namespace_with_multiple_symbols() {
             let mut parser = make_parser();
             let content =
                 "namespace Foo { declare const VERSION: string; declare function greet(): void; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -886,7 +1982,7 @@ mod tests {
             let content =
                 "namespace Foo { namespace Bar { export declare const VERSION: string; } }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let (outer_name, outer_content, outer_exported, outer_jsdoc) =
@@ -907,13 +2003,35 @@ mod tests {
             assert_matches!(symbol, TypeScriptSymbol::Symbol { symbol, is_exported: true } if symbol.name == "VERSION");
         }
 
+        #[test]
+        fn namespace_with_imports_and_exports() {
+            let mut parser = make_parser();
+            let content = "declare namespace Foo {\n  import Bar = Baz.Bar;\n  const VERSION: string;\n  export { VERSION, Bar };\n}";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            let (_, content, _, _) = deconstruct_namespace(&module.symbols[0]);
+            assert_eq!(content.len(), 3);
+        }
+
+        #[test]
+        fn imports_in_ambient_module() {
+            let mut parser = make_parser();
+            let content = "declare module 'foo' {\n  import { Bar } from './bar';\n  export { Bar };\n}\nexport {};";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert!(module.symbols.is_empty());
+        }
+
         #[test]
         fn namespace_with_jsdoc() {
             let mut parser = make_parser();
             let content =
                 "/** Utility functions */\nnamespace Foo { declare const VERSION: string; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -921,12 +2039,24 @@ mod tests {
             assert_matches!(namespace, TypeScriptSymbol::Namespace { jsdoc: Some(j), .. } if j == "/** Utility functions */");
         }
 
+        #[test]
+        fn exported_namespace_with_jsdoc() {
+            let mut parser = make_parser();
+            let content =
+                "/** Utility functions */\nexport namespace Foo { declare const VERSION: string; }";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(&module.symbols[0], TypeScriptSymbol::Namespace { jsdoc: Some(j), .. } if j == "/** Utility functions */");
+        }
+
         #[test]
         fn namespace_without_jsdoc() {
             let mut parser = make_parser();
             let content = "namespace Foo { declare const VERSION: string; }";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_eq!(module.symbols.len(), 1);
             let namespace = &module.symbols[0];
@@ -934,6 +2064,69 @@ mod tests {
         }
     }
 
+    mod import_aliases {
+        use super::*;
+
+        #[test]
+        fn exported_alias() {
+            let mut parser = make_parser();
+            let content = "export import EventEmitter = NodeJS.EventEmitter;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::ImportAlias { symbol, target, is_exported: true }
+                    if symbol.name == "EventEmitter"
+                        && symbol.source_code == content
+                        && target == &vec!["NodeJS".to_string(), "EventEmitter".to_string()]
+            );
+        }
+
+        #[test]
+        fn non_exported_alias() {
+            let mut parser = make_parser();
+            let content = "import Foo = Bar;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::ImportAlias { target, is_exported: false, .. }
+                    if target == &vec!["Bar".to_string()]
+            );
+        }
+
+        #[test]
+        fn alias_with_jsdoc() {
+            let mut parser = make_parser();
+            let content = "/** The emitter. */\nexport import EventEmitter = events.EventEmitter;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::ImportAlias { symbol, .. } if symbol.source_code == content
+            );
+        }
+
+        #[test]
+        fn alias_in_namespace() {
+            let mut parser = make_parser();
+            let content = "declare namespace NodeJS {\n  export import EventEmitter = events.EventEmitter;\n}";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(
+                &module.symbols[0],
+                TypeScriptSymbol::Namespace { content, .. }
+                    if matches!(&content[..], [TypeScriptSymbol::ImportAlias { is_exported: true, .. }])
+            );
+        }
+    }
+
     mod imports {
         use super::*;
         use crate::api::test_helpers::deconstruct_module_import;
@@ -943,7 +2136,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "import foo from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_import(&module.symbols[0]);
@@ -956,7 +2149,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "import * as foo from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_import(&module.symbols[0]);
@@ -969,12 +2162,12 @@ mod tests {
             let mut parser = make_parser();
             let content = "import { foo } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_import(&module.symbols[0]);
             assert_eq!(source_module, "./foo.js");
-            assert_matches!(target, ImportTarget::Named { names, aliases } if names == vec!["foo".to_string()] && aliases.is_empty());
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None)]);
         }
 
         #[test]
@@ -982,12 +2175,46 @@ mod tests {
             let mut parser = make_parser();
             let content = "import { foo as bar } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_import(&module.symbols[0]);
             assert_eq!(source_module, "./foo.js");
-            assert_matches!(target, ImportTarget::Named { names, aliases } if names == vec!["foo".to_string()] && aliases == HashMap::from([("foo".to_string(), "bar".to_string())]));
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", Some("bar"))]);
+        }
+
+        #[test]
+        fn type_only_import_specifier() {
+            let mut parser = make_parser();
+            let content = "import { type Foo as Bar, baz } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            let (_, target) = deconstruct_module_import(&module.symbols[0]);
+            assert_eq!(
+                target,
+                ImportTarget::Named {
+                    specifiers: vec![
+                        Specifier {
+                            name: "Foo".to_string(),
+                            alias: Some("Bar".to_string()),
+                            is_type_only: true,
+                        },
+                        make_specifier("baz", None),
+                    ],
+                }
+            );
+        }
+
+        #[test]
+        fn import_named_type() {
+            let mut parser = make_parser();
+            let content = "import { type } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            let (_, target) = deconstruct_module_import(&module.symbols[0]);
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("type", None)]);
         }
 
         #[test]
@@ -995,7 +2222,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "import foo, { bar } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 2);
 
@@ -1005,7 +2232,7 @@ mod tests {
 
             let (source_module, target) = deconstruct_module_import(&module.symbols[1]);
             assert_eq!(source_module, "./foo.js");
-            assert_matches!(target, ImportTarget::Named { names, aliases } if names == vec!["bar".to_string()] && aliases.is_empty());
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("bar", None)]);
         }
 
         #[test]
@@ -1013,12 +2240,36 @@ mod tests {
             let mut parser = make_parser();
             let content = "import { foo, bar as baz } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_import(&module.symbols[0]);
             assert_eq!(source_module, "./foo.js");
-            assert_matches!(target, ImportTarget::Named { names, aliases } if names == vec!["foo".to_string(), "bar".to_string()] && aliases == HashMap::from([("bar".to_string(), "baz".to_string())]));
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None), make_specifier("bar", Some("baz"))]);
+        }
+
+        #[test]
+        fn inline_type_import() {
+            let mut parser = make_parser();
+            let content = "import { type Foo, bar } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
+            let (_, target) = deconstruct_module_import(&module.symbols[0]);
+            assert_matches!(target, ImportTarget::Named { specifiers } if specifiers[0].is_type_only && !specifiers[1].is_type_only);
+        }
+
+        #[test]
+        fn string_literal_import_name() {
+            let mut parser = make_parser();
+            let content = "import { 'a-b' as ab } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
+            let (_, target) = deconstruct_module_import(&module.symbols[0]);
+            assert_matches!(target, ImportTarget::Named { specifiers } if *specifiers == vec![make_specifier("a-b", Some("ab"))]);
         }
     }
 
@@ -1031,7 +2282,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "export * as foo from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
@@ -1044,12 +2295,12 @@ mod tests {
             let mut parser = make_parser();
             let content = "export { foo, bar } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, Some("./foo.js".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["foo".to_string(), "bar".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None), make_specifier("bar", None)]);
         }
 
         #[test]
@@ -1057,12 +2308,46 @@ mod tests {
             let mut parser = make_parser();
             let content = "export { foo as bar } from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, Some("./foo.js".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["foo".to_string()] && aliases == HashMap::from([("foo".to_string(), "bar".to_string())]));
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", Some("bar"))]);
+        }
+
+        #[test]
+        fn type_only_export_specifier() {
+            let mut parser = make_parser();
+            let content = "export { foo, type Bar } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            let (_, target) = deconstruct_module_export(&module.symbols[0]);
+            assert_eq!(
+                target,
+                ExportTarget::Named {
+                    specifiers: vec![
+                        make_specifier("foo", None),
+                        Specifier {
+                            name: "Bar".to_string(),
+                            alias: None,
+                            is_type_only: true,
+                        },
+                    ],
+                }
+            );
+        }
+
+        #[test]
+        fn duplicate_export_specifiers() {
+            let mut parser = make_parser();
+            let content = "export { foo, foo as bar } from './foo.js';";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            let (_, target) = deconstruct_module_export(&module.symbols[0]);
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None), make_specifier("foo", Some("bar"))]);
         }
 
         #[test]
@@ -1070,7 +2355,7 @@ mod tests {
             let mut parser = make_parser();
             let content = "export * from './foo.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
@@ -1083,12 +2368,12 @@ mod tests {
             let mut parser = make_parser();
             let content = "\nexport { VERSION };";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, None);
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["VERSION".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("VERSION", None)]);
         }
 
         #[test]
@@ -1096,12 +2381,12 @@ mod tests {
             let mut parser = make_parser();
             let content = "export = myFunction;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, None);
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["myFunction".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("myFunction", None)]);
         }
 
         #[test]
@@ -1109,22 +2394,43 @@ mod tests {
             let mut parser = make_parser();
             let content = "export default VERSION;";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_matches!(&module, Module { default_export_name: Some(n), .. } if n == "VERSION");
+        }
+
+        #[test]
+        fn default_export_in_namespace() {
+            let mut parser = make_parser();
+            let content =
+                "declare namespace Utils {\n  export default helper;\n}\nexport default VERSION;";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { default_export_name: Some(n), .. } if n == "VERSION");
         }
 
+        #[test]
+        fn default_export_in_ambient_module() {
+            let mut parser = make_parser();
+            let content = "declare module 'foo' {\n  export default helper;\n}\nexport {};";
+
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+            assert_eq!(module.default_export_name, None);
+        }
+
         #[test]
         fn mixed_export_from_another_module() {
             let mut parser = make_parser();
             let content = "export { foo, bar as baz } from './module.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 1);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, Some("./module.js".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["foo".to_string(), "bar".to_string()] && aliases == HashMap::from([("bar".to_string(), "baz".to_string())]));
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None), make_specifier("bar", Some("baz"))]);
         }
 
         #[test]
@@ -1132,16 +2438,112 @@ mod tests {
             let mut parser = make_parser();
             let content = "export { foo } from './foo.js';\nexport { bar } from './bar.js';";
 
-            let module = parse_typescript_file(content, &mut parser, PathBuf::new()).unwrap();
+            let module = parse_typescript_file(content, &mut parser, PathBuf::new(), &[]).unwrap();
 
             assert_matches!(&module, Module { symbols, .. } if symbols.len() == 2);
             let (source_module, target) = deconstruct_module_export(&module.symbols[0]);
             assert_eq!(source_module, Some("./foo.js".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["foo".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("foo", None)]);
 
             let (source_module, target) = deconstruct_module_export(&module.symbols[1]);
             assert_eq!(source_module, Some("./bar.js".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if *names == vec!["bar".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("bar", None)]);
+        }
+    }
+
+    mod type_only_exports {
+        use super::*;
+
+        fn parse(content: &str) -> Module {
+            parse_typescript_file(content, &mut make_parser(), PathBuf::new(), &[]).unwrap()
+        }
+
+        fn deconstruct(symbol: &TypeScriptSymbol) -> (Option<&str>, &ExportTarget, bool) {
+            match symbol {
+                TypeScriptSymbol::ModuleExport {
+                    source_module,
+                    target,
+                    is_type_only,
+                } => (source_module.as_deref(), target, *is_type_only),
+                _ => panic!("Expected module export"),
+            }
+        }
+
+        #[test]
+        fn type_only_barrel_export() {
+            let module = parse("export type * from './types';");
+
+            assert_eq!(module.symbols.len(), 1);
+            let (source_module, target, is_type_only) = deconstruct(&module.symbols[0]);
+            assert_eq!(source_module, Some("./types"));
+            assert_matches!(target, ExportTarget::Barrel);
+            assert!(is_type_only);
+        }
+
+        #[test]
+        fn type_only_namespace_export() {
+            let module = parse("export type * as types from './types';");
+
+            assert_eq!(module.symbols.len(), 1);
+            let (source_module, target, is_type_only) = deconstruct(&module.symbols[0]);
+            assert_eq!(source_module, Some("./types"));
+            assert_matches!(target, ExportTarget::Namespace { name } if name == "types");
+            assert!(is_type_only);
+        }
+
+        #[test]
+        fn type_only_named_export() {
+            let module = parse("export type { Foo } from './types';");
+
+            assert_eq!(module.symbols.len(), 1);
+            let (source_module, target, is_type_only) = deconstruct(&module.symbols[0]);
+            assert_eq!(source_module, Some("./types"));
+            assert_matches!(target, ExportTarget::Named { specifiers } if *specifiers == vec![make_specifier("Foo", None)]);
+            assert!(is_type_only);
+        }
+
+        #[test]
+        fn value_barrel_export() {
+            let module = parse("export * from './values';");
+
+            let (_, _, is_type_only) = deconstruct(&module.symbols[0]);
+            assert!(!is_type_only);
+        }
+
+        #[test]
+        fn mixed_star_exports() {
+            let module = parse(
+                "export type * from './types';\nexport * from './values';\nexport type  *  as ns from './ns';",
+            );
+
+            assert_eq!(module.symbols.len(), 3);
+            let flags = module
+                .symbols
+                .iter()
+                .map(|symbol| deconstruct(symbol).2)
+                .collect::<Vec<_>>();
+            assert_eq!(flags, vec![true, false, true]);
+        }
+
+        #[test]
+        fn lookalike_in_comment_and_string() {
+            let content =
+                "// export type * from './types';\nexport declare const a: \"export type * from\";";
+
+            let module = parse(content);
+
+            assert_eq!(module.symbols.len(), 1);
+            assert_matches!(&module.symbols[0], TypeScriptSymbol::Symbol { symbol, .. } if symbol.source_code.contains("\"export type * from\""));
+        }
+
+        #[test]
+        fn candidates() {
+            let content =
+                "export type * from './a';\nreexport type * from './b';\nexport type Foo = string;";
+
+            let candidates = find_type_only_star_export_candidates(content);
+
+            assert_eq!(candidates, vec![(0, 7..11)]);
         }
     }
 }
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::api::module_set::ModuleSet;
+use crate::api::resolution::{ExportedItem, ResolvedExport};
+use crate::api::ResolvedEntryPoint;
+
+/// Finds the modules that contribute to the public API once the exports are resolved: the entry
+/// points, those declaring something that's exported, and those with global declarations or
+/// augmentations, which are part of the API without being exported.
+///
+/// Modules that are only traversed (e.g. barrels) or whose declarations are only used internally
+/// are left out.
+pub(super) fn find_reachable_modules(
+    module_set: &ModuleSet,
+    entry_points: &[ResolvedEntryPoint],
+) -> HashSet<PathBuf> {
+    let mut exported_source_codes = HashSet::new();
+    for entry_point in entry_points {
+        collect_exported_source_codes(&entry_point.exports, &mut exported_source_codes);
+    }
+
+    let mut reachable_paths = entry_points
+        .iter()
+        .filter_map(|entry_point| module_set.get(&entry_point.entry_point.internal_path))
+        .map(|module| module.path.clone())
+        .collect::<HashSet<_>>();
+    reachable_paths.extend(
+        module_set
+            .iter()
+            .filter(|module| !module.globals.is_empty() || !module.augmentations.is_empty())
+            .map(|module| module.path.clone()),
+    );
+    reachable_paths.extend(
+        module_set
+            .iter_declarations()
+            .filter(|(_, declaration)| {
+                exported_source_codes.contains(declaration.source_code.as_str())
+            })
+            .map(|(module, _)| module.path.clone()),
+    );
+    reachable_paths
+}
+
+/// Collects the source code of the exported declarations, which identifies them regardless of the
+/// names they're exported under.
+fn collect_exported_source_codes<'a>(
+    exports: &'a [ResolvedExport],
+    source_codes: &mut HashSet<&'a str>,
+) {
+    for export in exports {
+        match &export.item {
+            ExportedItem::Symbol(symbol) => {
+                source_codes.insert(symbol.source_code.as_str());
+            }
+            ExportedItem::Namespace { exports, .. } => {
+                collect_exported_source_codes(exports, source_codes)
+            }
+            ExportedItem::External(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use daipendency_testing::tempdir::TempDir;
+
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use crate::api::{extract_public_api_with_modules, resolve_entry_points};
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet, TSLibraryMetadata};
+    use crate::options::ExtractionOptions;
+
+    fn make_library(files: &[(&str, &str)]) -> (TempDir, TSLibraryMetadata) {
+        let temp_dir = TempDir::new();
+        for (path, content) in files {
+            temp_dir.create_file(path, content).unwrap();
+        }
+        let library_metadata = TSLibraryMetadata {
+            name: "pkg".to_string(),
+            version: None,
+            documentation: String::new(),
+            entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: temp_dir.path.join("index.d.ts"),
+                conditions: BTreeMap::new(),
+            }]),
+        };
+        (temp_dir, library_metadata)
+    }
+
+    fn get_file_names(paths: impl IntoIterator<Item = PathBuf>) -> Vec<String> {
+        let mut file_names = paths
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        file_names.sort();
+        file_names
+    }
+
+    const BARREL_FILES: [(&str, &str); 4] = [
+        ("index.d.ts", "export * from './barrel';"),
+        ("barrel.d.ts", "export { Public } from './public';"),
+        (
+            "public.d.ts",
+            "import { Internal } from './internal';\nexport declare class Public { internal: Internal; }",
+        ),
+        ("internal.d.ts", "export interface Internal {}"),
+    ];
+
+    #[test]
+    fn reachable_modules() {
+        let (_temp_dir, library_metadata) = make_library(&[
+            (
+                "index.d.ts",
+                "export * from './barrel';\nexport * from './polyfill';",
+            ),
+            BARREL_FILES[1],
+            BARREL_FILES[2],
+            BARREL_FILES[3],
+            (
+                "polyfill.d.ts",
+                "export {};\ndeclare global { interface Window { foo: string } }",
+            ),
+        ]);
+        let module_set = ModuleSet::from_entrypoints(
+            &library_metadata.entry_point,
+            &mut make_parser(),
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+        let entry_points = resolve_entry_points(&library_metadata, &module_set).unwrap();
+
+        let reachable_paths = find_reachable_modules(&module_set, &entry_points);
+
+        assert_eq!(module_set.len(), 5);
+        assert_eq!(
+            get_file_names(reachable_paths),
+            vec!["index.d.ts", "polyfill.d.ts", "public.d.ts"]
+        );
+    }
+
+    #[test]
+    fn pruned_module_reports() {
+        let (_temp_dir, library_metadata) = make_library(&BARREL_FILES);
+        let options = ExtractionOptions {
+            prune_unreachable_modules: true,
+            ..Default::default()
+        };
+
+        let extracted_api =
+            extract_public_api_with_modules(&library_metadata, &mut make_parser(), &options)
+                .unwrap();
+
+        assert_eq!(
+            get_file_names(extracted_api.modules.into_iter().map(|module| module.path)),
+            vec!["index.d.ts", "public.d.ts"]
+        );
+        assert_eq!(
+            get_file_names(
+                extracted_api
+                    .module_metrics
+                    .into_iter()
+                    .map(|metrics| metrics.path)
+            ),
+            vec!["index.d.ts", "public.d.ts"]
+        );
+        assert_eq!(extracted_api.namespaces[0].symbols[0].name, "Public");
+    }
+
+    #[test]
+    fn unpruned_module_reports() {
+        let (_temp_dir, library_metadata) = make_library(&BARREL_FILES);
+
+        let extracted_api = extract_public_api_with_modules(
+            &library_metadata,
+            &mut make_parser(),
+            &ExtractionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_file_names(extracted_api.modules.into_iter().map(|module| module.path)),
+            vec!["barrel.d.ts", "index.d.ts", "internal.d.ts", "public.d.ts"]
+        );
+    }
+}
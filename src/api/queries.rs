@@ -0,0 +1,80 @@
+use std::sync::OnceLock;
+
+use tree_sitter::{Language, Parser, Query};
+
+use crate::api::parsing::warm_up_queries;
+use crate::api::symbol_kind::get_symbol_kind_query;
+
+/// Gets the TypeScript grammar, which is loaded once and shared by every extraction.
+pub(crate) fn get_typescript_language() -> &'static Language {
+    static LANGUAGE: OnceLock<Language> = OnceLock::new();
+    LANGUAGE.get_or_init(|| tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+}
+
+/// Gets the TypeScript grammar with JSX, which is loaded once when the first JSX file is parsed.
+pub(crate) fn get_tsx_language() -> &'static Language {
+    static LANGUAGE: OnceLock<Language> = OnceLock::new();
+    LANGUAGE.get_or_init(|| tree_sitter_typescript::LANGUAGE_TSX.into())
+}
+
+/// Makes a parser for the TypeScript grammar.
+pub(crate) fn make_parser() -> Parser {
+    let mut parser = Parser::new();
+    parser
+        .set_language(get_typescript_language())
+        .expect("The TypeScript grammar should be compatible with tree-sitter");
+    parser
+}
+
+/// Loads the grammars and compiles every built-in query, so that the first extraction doesn't have
+/// to. This is idempotent and cheap once done.
+///
+/// The queries only exist for the TypeScript grammar, as TSX files are turned into TypeScript
+/// declarations before being queried.
+pub(crate) fn warm_up() {
+    get_typescript_language();
+    get_tsx_language();
+    warm_up_queries();
+    get_symbol_kind_query();
+}
+
+/// Gets a built-in query, compiling it against the TypeScript grammar the first time.
+///
+/// Each query must have its own cell, as the cell only ever holds the first query compiled in it.
+pub(crate) fn get_query(cell: &'static OnceLock<Query>, source: &str) -> &'static Query {
+    cell.get_or_init(|| compile_query(source))
+}
+
+/// Compiles a built-in query, which is meant to be cached (e.g. with [get_query]).
+pub(crate) fn compile_query(source: &str) -> Query {
+    Query::new(get_typescript_language(), source)
+        .expect("Built-in queries should be valid for the TypeScript grammar")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_compiled_once() {
+        static CELL: OnceLock<Query> = OnceLock::new();
+
+        let query = get_query(&CELL, "(identifier) @name");
+        let same_query = get_query(&CELL, "(identifier) @name");
+
+        assert!(std::ptr::eq(query, same_query));
+        assert_eq!(query.capture_names(), ["name"]);
+    }
+
+    #[test]
+    fn warm_up_is_idempotent() {
+        warm_up();
+        warm_up();
+
+        assert!(std::ptr::eq(
+            get_symbol_kind_query(),
+            get_symbol_kind_query()
+        ));
+        assert!(std::ptr::eq(get_tsx_language(), get_tsx_language()));
+    }
+}
@@ -0,0 +1,539 @@
+use daipendency_extractor::Namespace;
+use tree_sitter::{Node, Parser};
+
+use crate::api::queries::make_parser;
+use crate::metadata::TSLibraryMetadata;
+
+/// The languages of README code blocks whose imports we check.
+const SCRIPT_LANGUAGES: [&str; 10] = [
+    "ts",
+    "typescript",
+    "tsx",
+    "mts",
+    "cts",
+    "js",
+    "javascript",
+    "jsx",
+    "mjs",
+    "cjs",
+];
+
+/// A TypeScript or JavaScript code block in the README.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadmeSnippet {
+    /// The language of the code block, as set by its fence (e.g. `ts`).
+    pub language: String,
+    pub code: String,
+}
+
+/// An import in a README snippet that doesn't match the extracted public API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadmeMismatch {
+    /// The index of the snippet in [ReadmeReport::snippets].
+    pub snippet: usize,
+    /// The module the import is from (e.g. `pkg/utils`).
+    pub specifier: String,
+    /// The imported name, or `None` if the module itself isn't an entry point of the package.
+    pub name: Option<String>,
+}
+
+/// A README snippet that imports a symbol from the public API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadmeReference {
+    /// The index of the snippet in [ReadmeReport::snippets].
+    pub snippet: usize,
+    /// The name of the namespace containing the symbol.
+    pub namespace: String,
+    pub symbol: String,
+}
+
+/// The result of checking the README examples against the public API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadmeReport {
+    pub snippets: Vec<ReadmeSnippet>,
+    pub mismatches: Vec<ReadmeMismatch>,
+    pub references: Vec<ReadmeReference>,
+}
+
+/// An import of the package in a README snippet.
+#[derive(Debug, PartialEq, Eq)]
+enum PackageImport {
+    /// Named imports (e.g. `import { foo } from 'pkg'` or `const { foo } = require('pkg')`).
+    Named {
+        specifier: String,
+        names: Vec<String>,
+    },
+    /// Imports of the whole module (e.g. default, namespace and side-effect imports).
+    Module { specifier: String },
+}
+
+/// Checks that the names imported from the package in the README code blocks are part of the
+/// extracted public API.
+///
+/// Only imports from the package itself (e.g. `pkg` or `pkg/utils`) are checked, since the
+/// README may well import from other packages too.
+pub fn check_readme(metadata: &TSLibraryMetadata, namespaces: &[Namespace]) -> ReadmeReport {
+    let mut parser = make_parser();
+
+    let snippets = extract_snippets(&metadata.documentation);
+    let mut mismatches = vec![];
+    let mut references = vec![];
+
+    for (snippet_index, snippet) in snippets.iter().enumerate() {
+        for import in get_package_imports(&snippet.code, &metadata.name, &mut parser) {
+            let specifier = get_specifier(&import);
+            let Some(namespace) = namespaces.iter().find(|n| n.name == specifier) else {
+                mismatches.push(ReadmeMismatch {
+                    snippet: snippet_index,
+                    specifier: specifier.to_string(),
+                    name: None,
+                });
+                continue;
+            };
+
+            let PackageImport::Named { names, .. } = &import else {
+                continue;
+            };
+            for name in names {
+                if namespace.symbols.iter().any(|symbol| &symbol.name == name) {
+                    let reference = ReadmeReference {
+                        snippet: snippet_index,
+                        namespace: namespace.name.clone(),
+                        symbol: name.clone(),
+                    };
+                    if !references.contains(&reference) {
+                        references.push(reference);
+                    }
+                } else if !namespaces.iter().any(|n| &n.name == name) {
+                    mismatches.push(ReadmeMismatch {
+                        snippet: snippet_index,
+                        specifier: specifier.to_string(),
+                        name: Some(name.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    ReadmeReport {
+        snippets,
+        mismatches,
+        references,
+    }
+}
+
+/// Extracts the TypeScript and JavaScript fenced code blocks from Markdown.
+fn extract_snippets(markdown: &str) -> Vec<ReadmeSnippet> {
+    let mut snippets = vec![];
+    // The fence character and length, the language and the lines of the current code block
+    let mut current_block: Option<(char, usize, String, Vec<&str>)> = None;
+
+    for line in markdown.lines() {
+        let trimmed_line = line.trim_start();
+        let fence_character = trimmed_line
+            .chars()
+            .next()
+            .filter(|c| *c == '`' || *c == '~');
+        let fence_length = fence_character
+            .map(|c| trimmed_line.chars().take_while(|x| *x == c).count())
+            .unwrap_or(0);
+
+        match current_block.take() {
+            None => {
+                if let Some(fence_character) = fence_character.filter(|_| fence_length >= 3) {
+                    let language = trimmed_line[fence_length..]
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or("")
+                        .to_lowercase();
+                    current_block = Some((fence_character, fence_length, language, vec![]));
+                }
+            }
+            Some((open_character, open_length, language, mut lines)) => {
+                let is_closing_fence = fence_character == Some(open_character)
+                    && fence_length >= open_length
+                    && trimmed_line[fence_length..].trim().is_empty();
+                if !is_closing_fence {
+                    lines.push(line);
+                    current_block = Some((open_character, open_length, language, lines));
+                } else if SCRIPT_LANGUAGES.contains(&language.as_str()) {
+                    snippets.push(ReadmeSnippet {
+                        language,
+                        code: lines.join("\n"),
+                    });
+                }
+            }
+        }
+    }
+
+    snippets
+}
+
+/// Finds the imports of the package (or its subpaths) in the code, tolerating syntax errors.
+fn get_package_imports(code: &str, package_name: &str, parser: &mut Parser) -> Vec<PackageImport> {
+    let Some(tree) = parser.parse(code, None) else {
+        return vec![];
+    };
+
+    let mut imports = vec![];
+    let mut nodes = vec![tree.root_node()];
+    while let Some(node) = nodes.pop() {
+        let import = match node.kind() {
+            "import_statement" => get_import_statement(node, code),
+            "variable_declarator" => get_require_call(node, code),
+            _ => None,
+        };
+        match import {
+            Some(import) if is_package_specifier(get_specifier(&import), package_name) => {
+                imports.push(import)
+            }
+            Some(_) => {}
+            None => {
+                let mut cursor = node.walk();
+                let children = node.children(&mut cursor).collect::<Vec<_>>();
+                nodes.extend(children.into_iter().rev());
+            }
+        }
+    }
+    imports
+}
+
+fn get_specifier(import: &PackageImport) -> &str {
+    match import {
+        PackageImport::Named { specifier, .. } | PackageImport::Module { specifier } => specifier,
+    }
+}
+
+fn is_package_specifier(specifier: &str, package_name: &str) -> bool {
+    specifier == package_name
+        || specifier
+            .strip_prefix(package_name)
+            .is_some_and(|subpath| subpath.starts_with('/'))
+}
+
+fn get_import_statement(node: Node, code: &str) -> Option<PackageImport> {
+    let specifier = get_string_content(node.child_by_field_name("source")?, code)?;
+
+    let mut cursor = node.walk();
+    let Some(clause) = node
+        .children(&mut cursor)
+        .find(|n| n.kind() == "import_clause")
+    else {
+        return Some(PackageImport::Module { specifier });
+    };
+
+    let mut clause_cursor = clause.walk();
+    let named_imports = clause
+        .children(&mut clause_cursor)
+        .find(|n| n.kind() == "named_imports");
+    let Some(named_imports) = named_imports else {
+        return Some(PackageImport::Module { specifier });
+    };
+
+    let mut names_cursor = named_imports.walk();
+    let names = named_imports
+        .children(&mut names_cursor)
+        .filter(|n| n.kind() == "import_specifier")
+        .filter_map(|n| n.child_by_field_name("name"))
+        .filter_map(|n| match n.kind() {
+            "string" => get_string_content(n, code),
+            _ => n.utf8_text(code.as_bytes()).ok().map(str::to_string),
+        })
+        .collect();
+    Some(PackageImport::Named { specifier, names })
+}
+
+/// Gets the names destructured from a `require()` call (e.g. `const { foo } = require('pkg')`).
+fn get_require_call(node: Node, code: &str) -> Option<PackageImport> {
+    let value = node.child_by_field_name("value")?;
+    if value.kind() != "call_expression" {
+        return None;
+    }
+    let function = value.child_by_field_name("function")?;
+    if function.utf8_text(code.as_bytes()).ok()? != "require" {
+        return None;
+    }
+    let arguments = value.child_by_field_name("arguments")?;
+    let specifier = get_string_content(arguments.named_child(0)?, code)?;
+
+    let pattern = node.child_by_field_name("name")?;
+    if pattern.kind() != "object_pattern" {
+        return Some(PackageImport::Module { specifier });
+    }
+
+    let mut cursor = pattern.walk();
+    let names = pattern
+        .named_children(&mut cursor)
+        .filter_map(|n| match n.kind() {
+            "shorthand_property_identifier_pattern" => Some(n),
+            "pair_pattern" => n.child_by_field_name("key"),
+            _ => None,
+        })
+        .filter_map(|n| n.utf8_text(code.as_bytes()).ok().map(str::to_string))
+        .collect();
+    Some(PackageImport::Named { specifier, names })
+}
+
+fn get_string_content(node: Node, code: &str) -> Option<String> {
+    if node.kind() != "string" {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let fragment = node
+        .children(&mut cursor)
+        .find(|n| n.kind() == "string_fragment")?;
+    fragment.utf8_text(code.as_bytes()).ok().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_metadata;
+    use daipendency_extractor::Symbol;
+
+    fn make_namespace(name: &str, symbol_names: &[&str]) -> Namespace {
+        Namespace {
+            name: name.to_string(),
+            symbols: symbol_names
+                .iter()
+                .map(|symbol_name| Symbol {
+                    name: symbol_name.to_string(),
+                    source_code: format!("export declare const {symbol_name}: string;"),
+                })
+                .collect(),
+            doc_comment: None,
+        }
+    }
+
+    mod snippets {
+        use super::*;
+
+        #[test]
+        fn script_languages() {
+            let markdown =
+                "# Title\n\n```ts\nconst a = 1;\n```\n\n```javascript\nconst b = 2;\n```";
+
+            let snippets = extract_snippets(markdown);
+
+            assert_eq!(
+                snippets,
+                vec![
+                    ReadmeSnippet {
+                        language: "ts".to_string(),
+                        code: "const a = 1;".to_string(),
+                    },
+                    ReadmeSnippet {
+                        language: "javascript".to_string(),
+                        code: "const b = 2;".to_string(),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn other_languages() {
+            let markdown = "```sh\nnpm install pkg\n```\n\n```\nplain\n```";
+
+            let snippets = extract_snippets(markdown);
+
+            assert!(snippets.is_empty());
+        }
+
+        #[test]
+        fn nested_fences() {
+            let markdown = "````ts\nconst a = `\n```\n`;\n````";
+
+            let snippets = extract_snippets(markdown);
+
+            assert_eq!(snippets.len(), 1);
+            assert_eq!(snippets[0].code, "const a = `\n```\n`;");
+        }
+
+        #[test]
+        fn tilde_fences() {
+            let markdown = "~~~ts title=\"example.ts\"\nconst a = 1;\n~~~";
+
+            let snippets = extract_snippets(markdown);
+
+            assert_eq!(snippets.len(), 1);
+            assert_eq!(snippets[0].language, "ts");
+        }
+
+        #[test]
+        fn unterminated_fence() {
+            let markdown = "```ts\nconst a = 1;";
+
+            let snippets = extract_snippets(markdown);
+
+            assert!(snippets.is_empty());
+        }
+    }
+
+    mod imports {
+        use super::*;
+
+        fn get_imports(code: &str) -> Vec<PackageImport> {
+            let mut parser = make_parser();
+            get_package_imports(code, "pkg", &mut parser)
+        }
+
+        #[test]
+        fn named_imports() {
+            let imports = get_imports("import { foo, bar as baz, type Qux } from 'pkg';");
+
+            assert_eq!(
+                imports,
+                vec![PackageImport::Named {
+                    specifier: "pkg".to_string(),
+                    names: vec!["foo".to_string(), "bar".to_string(), "Qux".to_string()],
+                }]
+            );
+        }
+
+        #[test]
+        fn module_imports() {
+            let imports = get_imports(
+                "import pkg from 'pkg';\nimport * as utils from 'pkg/utils';\nimport 'pkg/register';",
+            );
+
+            assert_eq!(
+                imports,
+                vec![
+                    PackageImport::Module {
+                        specifier: "pkg".to_string(),
+                    },
+                    PackageImport::Module {
+                        specifier: "pkg/utils".to_string(),
+                    },
+                    PackageImport::Module {
+                        specifier: "pkg/register".to_string(),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn require_calls() {
+            let imports = get_imports(
+                "const { foo, bar: baz } = require('pkg');\nconst pkg = require('pkg/utils');",
+            );
+
+            assert_eq!(
+                imports,
+                vec![
+                    PackageImport::Named {
+                        specifier: "pkg".to_string(),
+                        names: vec!["foo".to_string(), "bar".to_string()],
+                    },
+                    PackageImport::Module {
+                        specifier: "pkg/utils".to_string(),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn other_packages() {
+            let imports =
+                get_imports("import { foo } from 'pkg-extra';\nimport { bar } from 'other';");
+
+            assert!(imports.is_empty());
+        }
+
+        #[test]
+        fn invalid_syntax() {
+            let imports = get_imports("import { foo } from 'pkg';\nfoo(...);\n// ...");
+
+            assert_eq!(imports.len(), 1);
+        }
+    }
+
+    #[test]
+    fn matching_imports() {
+        let metadata = make_metadata("```ts\nimport { foo } from 'pkg';\nfoo();\n```");
+        let namespaces = vec![make_namespace("pkg", &["foo", "bar"])];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert!(report.mismatches.is_empty());
+        assert_eq!(
+            report.references,
+            vec![ReadmeReference {
+                snippet: 0,
+                namespace: "pkg".to_string(),
+                symbol: "foo".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_symbol() {
+        let metadata = make_metadata("```ts\nimport { foo, removed } from 'pkg';\n```");
+        let namespaces = vec![make_namespace("pkg", &["foo"])];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert_eq!(
+            report.mismatches,
+            vec![ReadmeMismatch {
+                snippet: 0,
+                specifier: "pkg".to_string(),
+                name: Some("removed".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_subpath() {
+        let metadata = make_metadata("```js\nimport { foo } from 'pkg/missing';\n```");
+        let namespaces = vec![make_namespace("pkg", &["foo"])];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert_eq!(
+            report.mismatches,
+            vec![ReadmeMismatch {
+                snippet: 0,
+                specifier: "pkg/missing".to_string(),
+                name: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn exported_namespace() {
+        let metadata = make_metadata("```ts\nimport { Utils } from 'pkg';\n```");
+        let namespaces = vec![
+            make_namespace("pkg", &[]),
+            make_namespace("Utils", &["foo"]),
+        ];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert!(report.mismatches.is_empty());
+    }
+
+    #[test]
+    fn subpath_import() {
+        let metadata = make_metadata("```ts\nimport { helper } from 'pkg/utils';\n```");
+        let namespaces = vec![
+            make_namespace("pkg", &[]),
+            make_namespace("pkg/utils", &["helper"]),
+        ];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert!(report.mismatches.is_empty());
+        assert_eq!(report.references[0].namespace, "pkg/utils");
+    }
+
+    #[test]
+    fn no_readme() {
+        let metadata = make_metadata("");
+        let namespaces = vec![make_namespace("pkg", &["foo"])];
+
+        let report = check_readme(&metadata, &namespaces);
+
+        assert_eq!(report, ReadmeReport::default());
+    }
+}
@@ -0,0 +1,135 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::queries::make_parser;
+
+/// Presents a symbol under the name it's exported as (e.g. `Client` in
+/// `export { InternalClient as Client };`).
+///
+/// The references to the original name in the code are replaced (comments and strings are left
+/// alone), and a line comment noting the original name is added right before the declaration, so
+/// that any leading JSDoc stays at the start of the source code.
+pub(crate) fn rename_symbol(symbol: &Symbol, public_name: &str) -> Symbol {
+    let mut parser = make_parser();
+    let note = format!("// Declared as `{}`.\n", symbol.name);
+
+    let Ok(parsed_file) = ParsedFile::parse(&symbol.source_code, &mut parser) else {
+        return Symbol {
+            name: public_name.to_string(),
+            source_code: format!("{note}{}", symbol.source_code),
+        };
+    };
+    let root = parsed_file.root_node();
+
+    let mut ranges = vec![];
+    collect_references(root, &symbol.name, &symbol.source_code, &mut ranges);
+    let declaration_start = root
+        .named_children(&mut root.walk())
+        .find(|child| child.kind() != "comment")
+        .map_or(0, |statement| statement.start_byte());
+
+    let mut source_code = symbol.source_code.clone();
+    for range in ranges.into_iter().rev() {
+        source_code.replace_range(range, public_name);
+    }
+    source_code.insert_str(declaration_start, &note);
+
+    Symbol {
+        name: public_name.to_string(),
+        source_code,
+    }
+}
+
+/// Collects the byte ranges of the identifiers referring to `name`, in order.
+fn collect_references(
+    node: Node,
+    name: &str,
+    source_code: &str,
+    ranges: &mut Vec<std::ops::Range<usize>>,
+) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "identifier" | "type_identifier")
+            && &source_code[node.byte_range()] == name
+        {
+            ranges.push(node.byte_range());
+            continue;
+        }
+        let children = node.children(&mut node.walk()).collect::<Vec<_>>();
+        stack.extend(children.into_iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_symbol;
+
+    #[test]
+    fn class() {
+        let symbol = make_symbol(
+            "InternalClient",
+            "export declare class InternalClient { clone(): InternalClient; }",
+        );
+
+        let renamed = rename_symbol(&symbol, "Client");
+
+        assert_eq!(renamed.name, "Client");
+        assert_eq!(
+            renamed.source_code,
+            "// Declared as `InternalClient`.\nexport declare class Client { clone(): Client; }"
+        );
+    }
+
+    #[test]
+    fn function_overloads() {
+        let symbol = make_symbol(
+            "internalParse",
+            "declare function internalParse(input: string): number;\ndeclare function internalParse(input: Buffer): number;",
+        );
+
+        let renamed = rename_symbol(&symbol, "parse");
+
+        assert_eq!(
+            renamed.source_code,
+            "// Declared as `internalParse`.\ndeclare function parse(input: string): number;\ndeclare function parse(input: Buffer): number;"
+        );
+    }
+
+    #[test]
+    fn leading_jsdoc() {
+        let symbol = make_symbol(
+            "InternalOptions",
+            "/** The InternalOptions of the client. */\ninterface InternalOptions {}",
+        );
+
+        let renamed = rename_symbol(&symbol, "Options");
+
+        assert_eq!(
+            renamed.source_code,
+            "/** The InternalOptions of the client. */\n// Declared as `InternalOptions`.\ninterface Options {}"
+        );
+    }
+
+    #[test]
+    fn properties_and_strings() {
+        let symbol = make_symbol("kind", "declare const kind: { kind: \"kind\" };");
+
+        let renamed = rename_symbol(&symbol, "type");
+
+        assert_eq!(
+            renamed.source_code,
+            "// Declared as `kind`.\ndeclare const type: { kind: \"kind\" };"
+        );
+    }
+
+    #[test]
+    fn unparseable_source_code() {
+        let symbol = make_symbol("Foo", "class Foo {");
+
+        let renamed = rename_symbol(&symbol, "Bar");
+
+        assert_eq!(renamed.name, "Bar");
+        assert_eq!(renamed.source_code, "// Declared as `Foo`.\nclass Foo {");
+    }
+}
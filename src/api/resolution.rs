@@ -0,0 +1,1310 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use daipendency_extractor::Symbol;
+
+use crate::api::externals::ExternalExport;
+use crate::api::module::{ExportTarget, ImportTarget, Module, TypeScriptSymbol};
+use crate::api::module_set::ModuleSet;
+use crate::diagnostics::Diagnostic;
+
+/// A declaration made available by a module under a given name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedExport {
+    /// The name under which the declaration is exported.
+    pub name: String,
+    pub item: ExportedItem,
+    /// Whether the declaration can only be used as a type because it's re-exported with a
+    /// type-only export (e.g. `export type * from './types';`).
+    pub is_type_only: bool,
+}
+
+/// The declaration behind an export.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExportedItem {
+    /// A symbol declared in some module of the package.
+    Symbol(Symbol),
+    /// A TypeScript namespace, or a module exported as a namespace (e.g. `export * as foo from './foo';`).
+    Namespace {
+        jsdoc: Option<String>,
+        exports: Vec<ResolvedExport>,
+    },
+    /// A declaration of another package, which isn't followed.
+    External(ExternalExport),
+}
+
+/// Resolves the exports of the modules in a module set, following imports and re-exports across modules.
+pub struct ExportResolver<'a> {
+    module_set: &'a ModuleSet,
+    /// Modules whose exports are currently being resolved, to break circular re-exports.
+    modules_in_progress: HashSet<PathBuf>,
+    /// Import aliases currently being resolved, to break circular aliases.
+    aliases_in_progress: HashSet<(PathBuf, String)>,
+    /// Default exports currently being resolved, keyed by module and local name, to break
+    /// circular default re-exports.
+    defaults_in_progress: HashSet<(PathBuf, String)>,
+    resolved_modules: HashMap<PathBuf, Vec<ResolvedExport>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> ExportResolver<'a> {
+    pub fn new(module_set: &'a ModuleSet) -> Self {
+        Self {
+            module_set,
+            modules_in_progress: HashSet::new(),
+            aliases_in_progress: HashSet::new(),
+            defaults_in_progress: HashSet::new(),
+            resolved_modules: HashMap::new(),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Lists the problems encountered whilst resolving the exports, which didn't prevent it.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Resolves the named exports of a module, excluding its default export.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module whose exports should be resolved
+    ///
+    /// # Returns
+    ///
+    /// The exports in the order they appear in the module
+    pub fn resolve_exports(&mut self, module: &Module) -> Vec<ResolvedExport> {
+        if let Some(exports) = self.resolved_modules.get(&module.path) {
+            return exports.clone();
+        }
+        if !self.modules_in_progress.insert(module.path.clone()) {
+            return vec![];
+        }
+
+        let exports = self.resolve_scope_exports(module, &module.symbols, false);
+
+        self.modules_in_progress.remove(&module.path);
+        self.resolved_modules
+            .insert(module.path.clone(), exports.clone());
+        exports
+    }
+
+    /// Resolves the declaration(s) behind the default export of a module, if any.
+    pub fn resolve_default_export(&mut self, module: &Module) -> Vec<ExportedItem> {
+        let Some(name) = &module.default_export_name else {
+            return vec![];
+        };
+        let default_key = (module.path.clone(), name.clone());
+        if !self.defaults_in_progress.insert(default_key.clone()) {
+            let diagnostic = Diagnostic::CircularDefaultExport {
+                path: module.path.clone(),
+                name: name.clone(),
+            };
+            if !self.diagnostics.contains(&diagnostic) {
+                self.diagnostics.push(diagnostic);
+            }
+            return vec![];
+        }
+
+        let items = self.resolve_local_name(module, &module.symbols, name);
+
+        self.defaults_in_progress.remove(&default_key);
+        items
+    }
+
+    /// Resolves the exports of a scope (i.e. a module or a namespace body).
+    ///
+    /// Declarations in ambient namespaces are implicitly exported unless the namespace exports
+    /// something explicitly, so `implicit_exports` makes every declaration count as exported in
+    /// scopes without explicit exports.
+    fn resolve_scope_exports(
+        &mut self,
+        module: &Module,
+        scope: &[TypeScriptSymbol],
+        implicit_exports: bool,
+    ) -> Vec<ResolvedExport> {
+        let export_all = implicit_exports && !scope.iter().any(is_explicit_export);
+        let mut exports = vec![];
+
+        for symbol in scope {
+            match symbol {
+                TypeScriptSymbol::Symbol {
+                    symbol,
+                    is_exported,
+                } if *is_exported || export_all => {
+                    exports.push(ResolvedExport {
+                        name: symbol.name.clone(),
+                        item: ExportedItem::Symbol(symbol.clone()),
+                        is_type_only: false,
+                    });
+                }
+                TypeScriptSymbol::Namespace {
+                    name,
+                    is_exported,
+                    jsdoc,
+                    content,
+                } if *is_exported || export_all => {
+                    exports.push(ResolvedExport {
+                        name: name.clone(),
+                        item: ExportedItem::Namespace {
+                            jsdoc: jsdoc.clone(),
+                            exports: self.resolve_scope_exports(module, content, true),
+                        },
+                        is_type_only: false,
+                    });
+                }
+                TypeScriptSymbol::Custom { name, source, .. } => {
+                    exports.push(ResolvedExport {
+                        name: name.clone(),
+                        item: ExportedItem::Symbol(Symbol {
+                            name: name.clone(),
+                            source_code: source.clone(),
+                        }),
+                        is_type_only: false,
+                    });
+                }
+                TypeScriptSymbol::ImportAlias {
+                    symbol,
+                    target,
+                    is_exported: true,
+                } => {
+                    for item in self.resolve_alias(module, scope, symbol, target) {
+                        exports.push(ResolvedExport {
+                            name: symbol.name.clone(),
+                            item,
+                            is_type_only: false,
+                        });
+                    }
+                }
+                TypeScriptSymbol::ModuleExport {
+                    source_module: None,
+                    target: ExportTarget::Named { specifiers },
+                    is_type_only,
+                } => {
+                    for specifier in specifiers {
+                        for item in self.resolve_local_name(module, scope, &specifier.name) {
+                            exports.push(ResolvedExport {
+                                name: specifier.alias_or_name().to_string(),
+                                item,
+                                is_type_only: *is_type_only || specifier.is_type_only,
+                            });
+                        }
+                    }
+                }
+                TypeScriptSymbol::ModuleExport {
+                    source_module: Some(source_module),
+                    target,
+                    is_type_only,
+                } => {
+                    let Some(source) = self.module_set.resolve_import(module, source_module) else {
+                        exports.extend(self.resolve_external_exports(
+                            module,
+                            source_module,
+                            target,
+                            *is_type_only,
+                        ));
+                        continue;
+                    };
+                    let source_exports = self.resolve_exports(source);
+                    match target {
+                        ExportTarget::Named { specifiers } => {
+                            for specifier in specifiers {
+                                exports.extend(
+                                    source_exports
+                                        .iter()
+                                        .filter(|export| export.name == specifier.name)
+                                        .map(|export| ResolvedExport {
+                                            name: specifier.alias_or_name().to_string(),
+                                            item: export.item.clone(),
+                                            is_type_only: *is_type_only
+                                                || specifier.is_type_only
+                                                || export.is_type_only,
+                                        }),
+                                );
+                            }
+                        }
+                        ExportTarget::Namespace { name } => exports.push(ResolvedExport {
+                            name: name.clone(),
+                            item: ExportedItem::Namespace {
+                                jsdoc: source.jsdoc.clone(),
+                                exports: source_exports,
+                            },
+                            is_type_only: *is_type_only,
+                        }),
+                        ExportTarget::Barrel => {
+                            exports.extend(source_exports.into_iter().map(|export| {
+                                ResolvedExport {
+                                    is_type_only: *is_type_only || export.is_type_only,
+                                    ..export
+                                }
+                            }))
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        canonicalize_exports(exports)
+    }
+
+    /// Resolves a name declared or imported in a scope, regardless of whether it's exported.
+    fn resolve_local_name(
+        &mut self,
+        module: &Module,
+        scope: &[TypeScriptSymbol],
+        name: &str,
+    ) -> Vec<ExportedItem> {
+        let mut items = vec![];
+
+        for symbol in scope {
+            match symbol {
+                TypeScriptSymbol::Symbol { symbol, .. } if symbol.name == name => {
+                    items.push(ExportedItem::Symbol(symbol.clone()));
+                }
+                TypeScriptSymbol::Namespace {
+                    name: namespace_name,
+                    jsdoc,
+                    content,
+                    ..
+                } if namespace_name == name => {
+                    items.push(ExportedItem::Namespace {
+                        jsdoc: jsdoc.clone(),
+                        exports: self.resolve_scope_exports(module, content, true),
+                    });
+                }
+                TypeScriptSymbol::ImportAlias { symbol, target, .. } if symbol.name == name => {
+                    items.extend(self.resolve_alias(module, scope, symbol, target));
+                }
+                TypeScriptSymbol::Custom {
+                    name: custom_name,
+                    source,
+                    ..
+                } if custom_name == name => {
+                    items.push(ExportedItem::Symbol(Symbol {
+                        name: custom_name.clone(),
+                        source_code: source.clone(),
+                    }));
+                }
+                TypeScriptSymbol::ModuleImport {
+                    source_module,
+                    target,
+                    ..
+                } => {
+                    let Some(imported_name) = get_imported_name(target, name) else {
+                        continue;
+                    };
+                    let Some(source) = self.module_set.resolve_import(module, source_module) else {
+                        let external_name = match &imported_name {
+                            ImportedName::Default => Some("default"),
+                            ImportedName::Namespace => None,
+                            ImportedName::Named(original_name) => Some(original_name.as_str()),
+                        };
+                        items.extend(
+                            self.module_set
+                                .find_external_reference(module, source_module)
+                                .map(|reference| {
+                                    ExportedItem::External(ExternalExport::new(
+                                        reference,
+                                        external_name,
+                                    ))
+                                }),
+                        );
+                        continue;
+                    };
+                    match imported_name {
+                        ImportedName::Default => {
+                            items.extend(self.resolve_default_export(source));
+                        }
+                        ImportedName::Namespace => {
+                            items.push(ExportedItem::Namespace {
+                                jsdoc: source.jsdoc.clone(),
+                                exports: self.resolve_exports(source),
+                            });
+                        }
+                        ImportedName::Named(original_name) => {
+                            items.extend(
+                                self.resolve_exports(source)
+                                    .into_iter()
+                                    .filter(|export| export.name == original_name)
+                                    .map(|export| export.item),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        items
+    }
+}
+
+impl ExportResolver<'_> {
+    /// Resolves the re-exports of another package (e.g. `export { z } from 'zod';`), which are
+    /// attributed to that package. Its barrel re-exports (e.g. `export * from 'zod';`) are
+    /// skipped, as the names they export are unknown.
+    fn resolve_external_exports(
+        &self,
+        module: &Module,
+        specifier: &str,
+        target: &ExportTarget,
+        is_type_only: bool,
+    ) -> Vec<ResolvedExport> {
+        let Some(reference) = self.module_set.find_external_reference(module, specifier) else {
+            return vec![];
+        };
+        match target {
+            ExportTarget::Named { specifiers } => specifiers
+                .iter()
+                .map(|specifier| ResolvedExport {
+                    name: specifier.alias_or_name().to_string(),
+                    item: ExportedItem::External(ExternalExport::new(
+                        reference,
+                        Some(&specifier.name),
+                    )),
+                    is_type_only: is_type_only || specifier.is_type_only,
+                })
+                .collect(),
+            ExportTarget::Namespace { name } => vec![ResolvedExport {
+                name: name.clone(),
+                item: ExportedItem::External(ExternalExport::new(reference, None)),
+                is_type_only,
+            }],
+            ExportTarget::Barrel => vec![],
+        }
+    }
+
+    /// Resolves the target of an import alias (e.g. `NodeJS.EventEmitter`), looking up its first
+    /// segment in the scope of the alias and then at the top level of the module.
+    ///
+    /// Aliases whose target can't be resolved (e.g. because it's a global from another package)
+    /// stand for themselves, so their source code still shows up in the public API.
+    fn resolve_alias(
+        &mut self,
+        module: &Module,
+        scope: &[TypeScriptSymbol],
+        alias: &Symbol,
+        target: &[String],
+    ) -> Vec<ExportedItem> {
+        let fallback = vec![ExportedItem::Symbol(alias.clone())];
+        let Some((first_segment, other_segments)) = target.split_first() else {
+            return fallback;
+        };
+        let alias_key = (module.path.clone(), alias.source_code.clone());
+        if !self.aliases_in_progress.insert(alias_key.clone()) {
+            return fallback;
+        }
+
+        let mut items = self.resolve_local_name(module, scope, first_segment);
+        if items.is_empty() {
+            items = self.resolve_local_name(module, &module.symbols, first_segment);
+        }
+        for segment in other_segments {
+            items = items
+                .into_iter()
+                .flat_map(|item| match item {
+                    ExportedItem::Namespace { exports, .. } => exports
+                        .into_iter()
+                        .filter(|export| &export.name == segment)
+                        .map(|export| export.item)
+                        .collect(),
+                    ExportedItem::Symbol(_) | ExportedItem::External(_) => vec![],
+                })
+                .collect();
+        }
+
+        self.aliases_in_progress.remove(&alias_key);
+        if items.is_empty() {
+            fallback
+        } else {
+            items
+        }
+    }
+}
+
+/// Removes the members of a default-exported namespace that are also named exports of the module
+/// (e.g. `export default utils; export * from './utils';`), so they're only listed once.
+///
+/// The JSDoc of the namespace lists the removed members, so that both ways of accessing them are
+/// still documented.
+pub fn factor_out_named_exports(
+    default_export: ExportedItem,
+    named_exports: &[ResolvedExport],
+) -> ExportedItem {
+    let ExportedItem::Namespace { jsdoc, exports } = default_export else {
+        return default_export;
+    };
+    let (shared_exports, own_exports): (Vec<_>, Vec<_>) = exports
+        .into_iter()
+        .partition(|export| named_exports.contains(export));
+    if shared_exports.is_empty() {
+        return ExportedItem::Namespace {
+            jsdoc,
+            exports: own_exports,
+        };
+    }
+
+    let shared_names = shared_exports
+        .iter()
+        .map(|export| format!("`{}`", export.name))
+        .collect::<Vec<_>>();
+    let note = format!(
+        "/** Also provides the named exports {} as members of the default export. */",
+        shared_names.join(", ")
+    );
+    ExportedItem::Namespace {
+        jsdoc: merge_jsdoc(jsdoc, Some(note)),
+        exports: own_exports,
+    }
+}
+
+/// What an import binds a local name to.
+enum ImportedName {
+    Default,
+    Namespace,
+    Named(String),
+}
+
+/// Determines what `local_name` refers to in the source module of an import, if the import binds it.
+fn get_imported_name(target: &ImportTarget, local_name: &str) -> Option<ImportedName> {
+    match target {
+        ImportTarget::Default { name } if name == local_name => Some(ImportedName::Default),
+        ImportTarget::Namespace { name } if name == local_name => Some(ImportedName::Namespace),
+        ImportTarget::Named { specifiers } => specifiers
+            .iter()
+            .find(|specifier| specifier.alias_or_name() == local_name)
+            .map(|specifier| ImportedName::Named(specifier.name.clone())),
+        _ => None,
+    }
+}
+
+/// Removes the exports that repeat an earlier export of the same declaration under the same name
+/// (e.g. `export interface A {}` followed by `export { A };`), keeping the first occurrence. The
+/// declaration is only type-only if every occurrence is.
+///
+/// Namespaces exported under the same name are merged into the first one, as TypeScript does when
+/// a namespace is declared several times (e.g. across the files of an `@types` package).
+fn canonicalize_exports(exports: Vec<ResolvedExport>) -> Vec<ResolvedExport> {
+    let mut canonical_exports: Vec<ResolvedExport> = Vec::with_capacity(exports.len());
+    // The position of each canonical export that isn't a namespace, keyed by name and declaration
+    let mut declaration_indices: HashMap<(String, ExportedItem), usize> = HashMap::new();
+    // The position of the namespace exported under each name, which later ones are merged into
+    let mut namespace_indices: HashMap<String, usize> = HashMap::new();
+    for export in exports {
+        let ExportedItem::Namespace { jsdoc, exports } = export.item else {
+            let key = (export.name.clone(), export.item.clone());
+            match declaration_indices.get(&key) {
+                Some(&index) => canonical_exports[index].is_type_only &= export.is_type_only,
+                None => {
+                    declaration_indices.insert(key, canonical_exports.len());
+                    canonical_exports.push(export);
+                }
+            }
+            continue;
+        };
+        let previous_namespace = namespace_indices
+            .get(&export.name)
+            .map(|&index| &mut canonical_exports[index]);
+        match previous_namespace {
+            Some(ResolvedExport {
+                item:
+                    ExportedItem::Namespace {
+                        jsdoc: previous_jsdoc,
+                        exports: previous_exports,
+                    },
+                is_type_only,
+                ..
+            }) => {
+                if *previous_jsdoc == jsdoc && *previous_exports == exports {
+                    *is_type_only &= export.is_type_only;
+                    continue;
+                }
+                *previous_jsdoc = merge_jsdoc(previous_jsdoc.take(), jsdoc);
+                previous_exports.extend(exports);
+                *previous_exports = canonicalize_exports(std::mem::take(previous_exports));
+            }
+            _ => {
+                namespace_indices.insert(export.name.clone(), canonical_exports.len());
+                canonical_exports.push(ResolvedExport {
+                    name: export.name,
+                    item: ExportedItem::Namespace { jsdoc, exports },
+                    is_type_only: export.is_type_only,
+                });
+            }
+        }
+    }
+    canonical_exports
+}
+
+/// Combines the JSDoc of two declarations of the same namespace, skipping repeated comments.
+fn merge_jsdoc(first: Option<String>, second: Option<String>) -> Option<String> {
+    match (first, second) {
+        (Some(first), Some(second)) if first != second => Some(format!("{first}\n{second}")),
+        (first, second) => first.or(second),
+    }
+}
+
+fn is_explicit_export(symbol: &TypeScriptSymbol) -> bool {
+    match symbol {
+        TypeScriptSymbol::Symbol { is_exported, .. }
+        | TypeScriptSymbol::Namespace { is_exported, .. }
+        | TypeScriptSymbol::ImportAlias { is_exported, .. } => *is_exported,
+        TypeScriptSymbol::ModuleExport { .. } | TypeScriptSymbol::Custom { .. } => true,
+        TypeScriptSymbol::ModuleImport { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use crate::options::ExtractionOptions;
+    use assertables::assert_matches;
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    /// Builds a module set from files in a temporary directory, with `index.d.ts` as the entry point.
+    fn make_module_set(files: &[(&str, &str)]) -> (TempDir, ModuleSet) {
+        let temp_dir = TempDir::new();
+        for (path, content) in files {
+            temp_dir.create_file(path, content).unwrap();
+        }
+        let entry_points = TSEntryPointSet::from_iter([TSEntryPoint {
+            external_path: ".".to_string(),
+            internal_path: temp_dir.path.join("index.d.ts"),
+            conditions: BTreeMap::new(),
+        }]);
+        let mut parser = make_parser();
+        let module_set =
+            ModuleSet::from_entrypoints(&entry_points, &mut parser, &ExtractionOptions::default())
+                .unwrap();
+        (temp_dir, module_set)
+    }
+
+    fn resolve_entry_point_exports(
+        temp_dir: &TempDir,
+        module_set: &ModuleSet,
+    ) -> Vec<ResolvedExport> {
+        let module = module_set.get(&temp_dir.path.join("index.d.ts")).unwrap();
+        ExportResolver::new(module_set).resolve_exports(module)
+    }
+
+    fn get_export_names(exports: &[ResolvedExport]) -> Vec<&str> {
+        exports.iter().map(|export| export.name.as_str()).collect()
+    }
+
+    #[test]
+    fn exported_declaration() {
+        let (temp_dir, module_set) =
+            make_module_set(&[("index.d.ts", "export declare const foo: string;")]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(exports.len(), 1);
+        assert_matches!(&exports[0], ResolvedExport { name, item: ExportedItem::Symbol(symbol), is_type_only: false } if name == "foo" && symbol.source_code == "export declare const foo: string;");
+    }
+
+    #[test]
+    fn non_exported_declaration() {
+        let (temp_dir, module_set) =
+            make_module_set(&[("index.d.ts", "declare const foo: string;")]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert!(exports.is_empty());
+    }
+
+    #[test]
+    fn local_export_list() {
+        let (temp_dir, module_set) = make_module_set(&[(
+            "index.d.ts",
+            "declare const foo: string;\ndeclare const bar: string;\nexport { foo, bar as baz };",
+        )]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["foo", "baz"]);
+        assert_matches!(&exports[1].item, ExportedItem::Symbol(symbol) if symbol.name == "bar");
+    }
+
+    mod duplicate_exports {
+        use super::*;
+
+        #[test]
+        fn declaration_and_export_list() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export interface A {}\nexport declare const b: string;\nexport { A, b };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["A", "b"]);
+        }
+
+        #[test]
+        fn declaration_and_aliased_export() {
+            let (temp_dir, module_set) =
+                make_module_set(&[("index.d.ts", "export interface A {}\nexport { A as B };")]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["A", "B"]);
+        }
+
+        #[test]
+        fn namespace_and_export_list() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export namespace N { const a: string; }\nexport { N };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["N"]);
+        }
+
+        #[test]
+        fn declaration_and_barrel_reexport() {
+            let (temp_dir, module_set) = make_module_set(&[
+                (
+                    "index.d.ts",
+                    "export * from './foo';\nexport { foo } from './foo';",
+                ),
+                ("foo.d.ts", "export declare const foo: string;"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["foo"]);
+        }
+
+        #[test]
+        fn overloads() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "declare function f(): void;\ndeclare function f(a: string): void;\nexport { f };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["f", "f"]);
+        }
+    }
+
+    #[test]
+    fn named_reexport() {
+        let (temp_dir, module_set) = make_module_set(&[
+            ("index.d.ts", "export { foo as bar } from './foo';"),
+            (
+                "foo.d.ts",
+                "export declare const foo: string;\nexport declare const other: string;",
+            ),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["bar"]);
+        assert_matches!(&exports[0].item, ExportedItem::Symbol(symbol) if symbol.name == "foo");
+    }
+
+    #[test]
+    fn barrel_reexport() {
+        let (temp_dir, module_set) = make_module_set(&[
+            ("index.d.ts", "export * from './foo';"),
+            (
+                "foo.d.ts",
+                "export declare const foo: string;\ndeclare const hidden: string;",
+            ),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["foo"]);
+    }
+
+    #[test]
+    fn namespace_reexport() {
+        let (temp_dir, module_set) = make_module_set(&[
+            ("index.d.ts", "export * as utils from './utils';"),
+            ("utils.d.ts", "export declare function helper(): void;"),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["utils"]);
+        assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["helper"]);
+    }
+
+    #[test]
+    fn imported_then_exported() {
+        let (temp_dir, module_set) = make_module_set(&[
+            (
+                "index.d.ts",
+                "import { foo as localFoo } from './foo';\nexport { localFoo };",
+            ),
+            ("foo.d.ts", "export declare const foo: string;"),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["localFoo"]);
+        assert_matches!(&exports[0].item, ExportedItem::Symbol(symbol) if symbol.name == "foo");
+    }
+
+    #[test]
+    fn namespace_import_then_exported() {
+        let (temp_dir, module_set) = make_module_set(&[
+            (
+                "index.d.ts",
+                "import * as foo from './foo';\nexport { foo };",
+            ),
+            ("foo.d.ts", "export declare const bar: string;"),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["foo"]);
+        assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["bar"]);
+    }
+
+    #[test]
+    fn default_import_then_exported() {
+        let (temp_dir, module_set) = make_module_set(&[
+            ("index.d.ts", "import foo from './foo';\nexport { foo };"),
+            (
+                "foo.d.ts",
+                "declare function bar(): void;\nexport default bar;",
+            ),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["foo"]);
+        assert_matches!(&exports[0].item, ExportedItem::Symbol(symbol) if symbol.name == "bar");
+    }
+
+    mod external_modules {
+        use super::*;
+
+        #[test]
+        fn missing_package() {
+            let (temp_dir, module_set) =
+                make_module_set(&[("index.d.ts", "export { foo } from 'external';")]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(
+                exports,
+                vec![ResolvedExport {
+                    name: "foo".to_string(),
+                    item: ExportedItem::External(ExternalExport {
+                        specifier: "external".to_string(),
+                        package_name: "external".to_string(),
+                        version: None,
+                        name: Some("foo".to_string()),
+                    }),
+                    is_type_only: false,
+                }]
+            );
+        }
+
+        #[test]
+        fn installed_package() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export { z as schema } from 'zod/v4';"),
+                ("package.json", "{}"),
+                (
+                    "node_modules/zod/package.json",
+                    r#"{"name": "zod", "version": "3.23.8"}"#,
+                ),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["schema"]);
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::External(external)
+                    if external.package_name == "zod"
+                        && external.version.as_deref() == Some("3.23.8")
+                        && external.name.as_deref() == Some("z")
+            );
+        }
+
+        #[test]
+        fn namespace_reexport() {
+            let (temp_dir, module_set) =
+                make_module_set(&[("index.d.ts", "export type * as z from 'zod';")]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["z"]);
+            assert!(exports[0].is_type_only);
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::External(external) if external.name.is_none()
+            );
+        }
+
+        #[test]
+        fn import_then_export() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "import React, { FC } from 'react';\nexport { React, FC };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            let names = exports
+                .iter()
+                .map(|export| match &export.item {
+                    ExportedItem::External(external) => external.name.as_deref(),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec![Some("default"), Some("FC")]);
+        }
+
+        #[test]
+        fn reexport_through_module() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export * from './schemas';"),
+                ("schemas.d.ts", "export { z } from 'zod';"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["z"]);
+            assert_matches!(&exports[0].item, ExportedItem::External(_));
+        }
+
+        #[test]
+        fn barrel_reexport() {
+            let (temp_dir, module_set) = make_module_set(&[("index.d.ts", "export * from 'zod';")]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert!(exports.is_empty());
+        }
+    }
+
+    #[test]
+    fn circular_reexports() {
+        let (temp_dir, module_set) = make_module_set(&[
+            (
+                "index.d.ts",
+                "export * from './other';\nexport declare const foo: string;",
+            ),
+            (
+                "other.d.ts",
+                "export * from './index';\nexport declare const bar: string;",
+            ),
+        ]);
+
+        let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+        assert_eq!(get_export_names(&exports), vec!["foo", "bar"]);
+    }
+
+    #[test]
+    fn default_export() {
+        let (temp_dir, module_set) = make_module_set(&[(
+            "index.d.ts",
+            "declare function foo(): void;\nexport default foo;",
+        )]);
+        let module = module_set.get(&temp_dir.path.join("index.d.ts")).unwrap();
+
+        let items = ExportResolver::new(&module_set).resolve_default_export(module);
+
+        assert_eq!(items.len(), 1);
+        assert_matches!(&items[0], ExportedItem::Symbol(symbol) if symbol.name == "foo");
+    }
+
+    #[test]
+    fn circular_default_export() {
+        let (temp_dir, module_set) = make_module_set(&[
+            ("index.d.ts", "import x from './other';\nexport default x;"),
+            ("other.d.ts", "import x from './index';\nexport default x;"),
+        ]);
+        let module = module_set.get(&temp_dir.path.join("index.d.ts")).unwrap();
+        let mut resolver = ExportResolver::new(&module_set);
+
+        let items = resolver.resolve_default_export(module);
+
+        assert!(items.is_empty());
+        assert_eq!(
+            resolver.diagnostics(),
+            [Diagnostic::CircularDefaultExport {
+                path: temp_dir.path.join("index.d.ts"),
+                name: "x".to_string(),
+            }]
+        );
+    }
+
+    mod type_only_exports {
+        use super::*;
+
+        fn get_type_only_flags(exports: &[ResolvedExport]) -> Vec<(&str, bool)> {
+            exports
+                .iter()
+                .map(|export| (export.name.as_str(), export.is_type_only))
+                .collect()
+        }
+
+        #[test]
+        fn type_only_barrel_export() {
+            let (temp_dir, module_set) = make_module_set(&[
+                (
+                    "index.d.ts",
+                    "export type * from './types';\nexport * from './values';",
+                ),
+                ("types.d.ts", "export interface Options {}"),
+                ("values.d.ts", "export declare const VERSION: string;"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(
+                get_type_only_flags(&exports),
+                vec![("Options", true), ("VERSION", false)]
+            );
+        }
+
+        #[test]
+        fn type_only_namespace_export() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export type * as types from './types';"),
+                ("types.d.ts", "export interface Options {}"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_type_only_flags(&exports), vec![("types", true)]);
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_type_only_flags(exports) == vec![("Options", false)]);
+        }
+
+        #[test]
+        fn propagation_through_barrels() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export * from './types';"),
+                ("types.d.ts", "export type * from './options';"),
+                ("options.d.ts", "export interface Options {}"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_type_only_flags(&exports), vec![("Options", true)]);
+        }
+
+        #[test]
+        fn type_only_named_reexport_of_barrel() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export { Options } from './types';"),
+                ("types.d.ts", "export type * from './options';"),
+                ("options.d.ts", "export interface Options {}"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_type_only_flags(&exports), vec![("Options", true)]);
+        }
+
+        #[test]
+        fn type_only_specifiers() {
+            let (temp_dir, module_set) = make_module_set(&[
+                (
+                    "index.d.ts",
+                    "export { type Options, VERSION } from './options';",
+                ),
+                (
+                    "options.d.ts",
+                    "export interface Options {}\nexport declare const VERSION: string;",
+                ),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(
+                get_type_only_flags(&exports),
+                vec![("Options", true), ("VERSION", false)]
+            );
+        }
+
+        #[test]
+        fn type_only_local_specifier() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "interface Options {}\nexport { type Options as Config };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_type_only_flags(&exports), vec![("Config", true)]);
+        }
+
+        #[test]
+        fn value_export_takes_precedence() {
+            let (temp_dir, module_set) = make_module_set(&[
+                (
+                    "index.d.ts",
+                    "export type * from './options';\nexport * from './options';",
+                ),
+                ("options.d.ts", "export declare class Options {}"),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_type_only_flags(&exports), vec![("Options", false)]);
+        }
+    }
+
+    mod namespaces {
+        use super::*;
+
+        #[test]
+        fn explicit_exports() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export namespace Foo { export const bar: string; const baz: string; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Foo"]);
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["bar"]);
+        }
+
+        #[test]
+        fn implicit_exports() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export declare namespace Foo { const bar: string; const baz: string; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["bar", "baz"]);
+        }
+    }
+
+    mod import_aliases {
+        use super::*;
+
+        #[test]
+        fn alias_to_namespace_member() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "declare namespace NodeJS { class EventEmitter {} }
+export declare namespace Events { export import EventEmitter = NodeJS.EventEmitter; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::Namespace { exports, .. }
+                    if matches!(&exports[..], [ResolvedExport { name, item: ExportedItem::Symbol(symbol), .. }]
+                        if name == "EventEmitter" && symbol.source_code == "class EventEmitter {}")
+            );
+        }
+
+        #[test]
+        fn alias_to_namespace() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "declare namespace Inner { const a: string; }
+export import Outer = Inner;",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Outer"]);
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["a"]);
+        }
+
+        #[test]
+        fn unresolved_alias() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export declare namespace Foo { export import Timer = NodeJS.Timer; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::Namespace { exports, .. }
+                    if matches!(&exports[..], [ResolvedExport { item: ExportedItem::Symbol(symbol), .. }]
+                        if symbol.source_code == "export import Timer = NodeJS.Timer;")
+            );
+        }
+
+        #[test]
+        fn non_exported_alias_in_export_list() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "declare namespace Inner { const a: string; }
+import Outer = Inner;
+export { Outer };",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Outer"]);
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { .. });
+        }
+
+        #[test]
+        fn circular_alias() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export declare namespace A { export import B = A.B; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_matches!(&exports[0].item, ExportedItem::Namespace { exports, .. } if get_export_names(exports) == vec!["B"]);
+        }
+    }
+
+    mod namespace_merging {
+        use super::*;
+
+        #[test]
+        fn namespace_across_files() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export * from './a';\nexport * from './b';"),
+                (
+                    "a.d.ts",
+                    "/** From a. */\nexport declare namespace Foo { const a: string; }",
+                ),
+                (
+                    "b.d.ts",
+                    "/** From b. */\nexport declare namespace Foo { const b: string; }",
+                ),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Foo"]);
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::Namespace { jsdoc: Some(jsdoc), exports }
+                    if jsdoc == "/** From a. */\n/** From b. */" && get_export_names(exports) == vec!["a", "b"]
+            );
+        }
+
+        #[test]
+        fn namespace_in_same_file() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "/** Foo. */\nexport declare namespace Foo { const a: string; }\n/** Foo. */\nexport declare namespace Foo { const b: string; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Foo"]);
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::Namespace { jsdoc: Some(jsdoc), exports }
+                    if jsdoc == "/** Foo. */" && get_export_names(exports) == vec!["a", "b"]
+            );
+        }
+
+        #[test]
+        fn nested_namespaces() {
+            let (temp_dir, module_set) = make_module_set(&[
+                ("index.d.ts", "export * from './a';\nexport * from './b';"),
+                (
+                    "a.d.ts",
+                    "export declare namespace Outer { namespace Inner { const a: string; } }",
+                ),
+                (
+                    "b.d.ts",
+                    "export declare namespace Outer { namespace Inner { const b: string; } }",
+                ),
+            ]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_matches!(
+                &exports[0].item,
+                ExportedItem::Namespace { exports, .. }
+                    if matches!(&exports[..], [ResolvedExport { item: ExportedItem::Namespace { exports, .. }, .. }]
+                        if get_export_names(exports) == vec!["a", "b"])
+            );
+        }
+
+        #[test]
+        fn different_names() {
+            let (temp_dir, module_set) = make_module_set(&[(
+                "index.d.ts",
+                "export declare namespace Foo { const a: string; }\nexport declare namespace Bar { const b: string; }",
+            )]);
+
+            let exports = resolve_entry_point_exports(&temp_dir, &module_set);
+
+            assert_eq!(get_export_names(&exports), vec!["Foo", "Bar"]);
+        }
+    }
+
+    mod factor_out_named_exports {
+        use super::*;
+
+        fn make_export(name: &str) -> ResolvedExport {
+            ResolvedExport {
+                name: name.to_string(),
+                item: ExportedItem::Symbol(Symbol {
+                    name: name.to_string(),
+                    source_code: format!("export declare const {name}: string;"),
+                }),
+                is_type_only: false,
+            }
+        }
+
+        #[test]
+        fn shared_members() {
+            let default_export = ExportedItem::Namespace {
+                jsdoc: None,
+                exports: vec![make_export("a"), make_export("b")],
+            };
+
+            let item = factor_out_named_exports(default_export, &[make_export("a")]);
+
+            assert_eq!(
+                item,
+                ExportedItem::Namespace {
+                    jsdoc: Some(
+                        "/** Also provides the named exports `a` as members of the default export. */"
+                            .to_string()
+                    ),
+                    exports: vec![make_export("b")],
+                }
+            );
+        }
+
+        #[test]
+        fn no_shared_members() {
+            let default_export = ExportedItem::Namespace {
+                jsdoc: Some("/** Utils. */".to_string()),
+                exports: vec![make_export("a")],
+            };
+
+            let item = factor_out_named_exports(default_export.clone(), &[make_export("b")]);
+
+            assert_eq!(item, default_export);
+        }
+
+        #[test]
+        fn symbol() {
+            let default_export = make_export("a").item;
+
+            let item = factor_out_named_exports(default_export.clone(), &[make_export("a")]);
+
+            assert_eq!(item, default_export);
+        }
+    }
+}
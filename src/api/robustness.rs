@@ -0,0 +1,213 @@
+#![cfg(test)]
+
+//! Property-based checks that the parser front-end never panics, whatever the input.
+
+use std::path::PathBuf;
+
+use daipendency_extractor::Symbol;
+use proptest::prelude::*;
+
+use super::get_type_alias_target;
+use super::parsing::parse_typescript_file;
+use super::renaming::rename_symbol;
+use super::test_helpers::make_parser;
+
+const TOKENS: &[&str] = &[
+    "export",
+    "import",
+    "declare",
+    "default",
+    "namespace",
+    "module",
+    "type",
+    "interface",
+    "class",
+    "abstract",
+    "function",
+    "enum",
+    "const",
+    "let",
+    "from",
+    "as",
+    "*",
+    "=",
+    "{",
+    "}",
+    "(",
+    ")",
+    "<",
+    ">",
+    ":",
+    ";",
+    ",",
+    "Foo",
+    "bar",
+    "string",
+    "'./foo'",
+    "'external'",
+    "'a-b'",
+    "/** @module The module */",
+    "/** Docs */",
+    "// Comment\n",
+    "\n",
+];
+
+const SNIPPETS: &[&str] = &[
+    "/** @file The module */\nexport declare const foo: string;",
+    "import foo, { bar as baz } from './foo.js';\nexport { baz };",
+    "import * as ns from './ns';\nexport default ns;",
+    "export * from './foo';\nexport * as bar from './bar';",
+    "export { foo, bar as baz } from './module.js';",
+    "export = myFunction;",
+    "export default declare function greet(name: string): void;",
+    "/** Utility functions */\nexport namespace Foo { namespace Bar { export declare const VERSION: string; } }",
+    "export declare namespace Foo { const bar: string; }",
+    "declare abstract class Foo<T> { bar(): T; }\nexport { Foo };",
+    "export enum Status { Active, Inactive }\ntype Alias = Status;",
+    "export function greet(name: string): string { return name; }",
+    "import { type Foo, 'a-b' as ab } from './foo';\nexport { Foo, ab };",
+    "import type { Foo } from './foo';\nexport type { Foo } from './foo';",
+];
+
+/// Generates sequences of TypeScript tokens, which are mostly (but not always) invalid.
+fn token_soup() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(TOKENS), 0..40).prop_map(|tokens| tokens.join(" "))
+}
+
+/// A change to a valid snippet.
+#[derive(Debug, Clone)]
+enum Mutation {
+    Delete {
+        start: usize,
+        length: usize,
+    },
+    Duplicate {
+        start: usize,
+        length: usize,
+    },
+    Insert {
+        position: usize,
+        token: &'static str,
+    },
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        (any::<usize>(), 0..20usize).prop_map(|(start, length)| Mutation::Delete { start, length }),
+        (any::<usize>(), 0..20usize)
+            .prop_map(|(start, length)| Mutation::Duplicate { start, length }),
+        (any::<usize>(), prop::sample::select(TOKENS))
+            .prop_map(|(position, token)| Mutation::Insert { position, token }),
+    ]
+}
+
+/// Applies the mutations to the characters of the snippet, wrapping positions around its length.
+fn mutate(snippet: &str, mutations: &[Mutation]) -> String {
+    let mut characters = snippet.chars().collect::<Vec<_>>();
+    for mutation in mutations {
+        let length = characters.len() + 1;
+        match *mutation {
+            Mutation::Delete {
+                start,
+                length: range_length,
+            } => {
+                let start = start % length;
+                let end = (start + range_length).min(characters.len());
+                characters.drain(start..end);
+            }
+            Mutation::Duplicate {
+                start,
+                length: range_length,
+            } => {
+                let start = start % length;
+                let end = (start + range_length).min(characters.len());
+                let duplicate = characters[start..end].to_vec();
+                characters.splice(end..end, duplicate);
+            }
+            Mutation::Insert { position, token } => {
+                let position = position % length;
+                let token = format!(" {token} ");
+                characters.splice(position..position, token.chars());
+            }
+        }
+    }
+    characters.into_iter().collect()
+}
+
+fn mutated_snippet() -> impl Strategy<Value = String> {
+    (
+        prop::sample::select(SNIPPETS),
+        prop::collection::vec(mutation(), 1..4),
+    )
+        .prop_map(|(snippet, mutations)| mutate(snippet, &mutations))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn token_soup_never_panics(content in token_soup()) {
+        let mut parser = make_parser();
+
+        let _ = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
+    }
+
+    #[test]
+    fn mutated_snippet_never_panics(content in mutated_snippet()) {
+        let mut parser = make_parser();
+
+        let _ = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
+    }
+
+    #[test]
+    fn arbitrary_text_never_panics(content in any::<String>()) {
+        let mut parser = make_parser();
+
+        let _ = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]);
+    }
+}
+
+#[test]
+fn snippets_are_valid() {
+    let mut parser = make_parser();
+
+    for snippet in SNIPPETS {
+        let result = parse_typescript_file(snippet, &mut parser, PathBuf::new(), &[]);
+
+        assert!(result.is_ok(), "Failed to parse {snippet:?}");
+    }
+}
+
+#[test]
+fn deeply_nested_namespaces() {
+    let depth = 100;
+    let content = format!(
+        "{}export declare const foo: string;{}",
+        "export namespace Foo { ".repeat(depth),
+        " }".repeat(depth)
+    );
+    let mut parser = make_parser();
+
+    let module = parse_typescript_file(&content, &mut parser, PathBuf::new(), &[]).unwrap();
+
+    assert!(module.has_truncated_namespaces);
+}
+
+#[test]
+fn deeply_nested_types() {
+    let depth = 20_000;
+    let symbol = Symbol {
+        name: "Deep".to_string(),
+        source_code: format!(
+            "export type Deep = {}string{};",
+            "Array<".repeat(depth),
+            ">".repeat(depth)
+        ),
+    };
+
+    let target = get_type_alias_target(&symbol).unwrap();
+    let renamed = rename_symbol(&symbol, "Nested");
+
+    assert_eq!(target.referenced_identifiers, vec!["Array"]);
+    assert!(renamed.source_code.contains("export type Nested = Array<"));
+}
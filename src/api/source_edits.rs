@@ -0,0 +1,60 @@
+use std::ops::Range;
+
+/// Replaces byte ranges of the source code, which may be in any order.
+///
+/// Overlapping ranges are merged, keeping the replacement of the one that starts first, since
+/// they're found by walking trees where a node's range may contain those of its descendants.
+pub(crate) fn replace_ranges(source_code: &str, mut edits: Vec<(Range<usize>, &str)>) -> String {
+    edits.sort_by_key(|(range, _)| range.start);
+
+    let mut edited_source_code = String::with_capacity(source_code.len());
+    let mut offset = 0;
+    for (range, replacement) in edits {
+        if range.start >= offset {
+            edited_source_code.push_str(&source_code[offset..range.start]);
+            edited_source_code.push_str(replacement);
+        }
+        offset = offset.max(range.end);
+    }
+    edited_source_code.push_str(&source_code[offset..]);
+    edited_source_code
+}
+
+/// Removes byte ranges from the source code, which may be in any order and overlap.
+pub(crate) fn remove_ranges(source_code: &str, ranges: Vec<Range<usize>>) -> String {
+    let edits = ranges.into_iter().map(|range| (range, "")).collect();
+    replace_ranges(source_code, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disjoint_ranges() {
+        let source_code = remove_ranges("abcdef", vec![0..1, 3..4]);
+
+        assert_eq!(source_code, "bcef");
+    }
+
+    #[test]
+    fn unsorted_ranges() {
+        let source_code = remove_ranges("abcdef", vec![3..4, 0..1]);
+
+        assert_eq!(source_code, "bcef");
+    }
+
+    #[test]
+    fn overlapping_ranges() {
+        let source_code = remove_ranges("abcdef", vec![1..3, 2..5, 3..4]);
+
+        assert_eq!(source_code, "af");
+    }
+
+    #[test]
+    fn replacements() {
+        let source_code = replace_ranges("f() { x }", vec![(4..9, ";"), (5..8, "y")]);
+
+        assert_eq!(source_code, "f() ;");
+    }
+}
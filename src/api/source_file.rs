@@ -0,0 +1,147 @@
+use std::io;
+use std::path::Path;
+
+/// Files at least this large are memory-mapped when the `mmap` feature is enabled.
+#[cfg(all(feature = "mmap", unix))]
+const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// The contents of a declaration file.
+///
+/// With the `mmap` feature, large files (e.g. `typescript/lib/typescript.d.ts`) are memory-mapped
+/// on Unix instead of being copied into memory, so parsing and rendering work on the mapped bytes.
+pub(crate) struct SourceFile {
+    contents: Contents,
+}
+
+enum Contents {
+    Owned(String),
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(memmap2::Mmap),
+}
+
+impl SourceFile {
+    /// Reads a file, failing if it isn't valid UTF-8 like [std::fs::read_to_string].
+    ///
+    /// # Safety
+    ///
+    /// The file mustn't be modified or truncated until the source file is dropped, as it may be
+    /// memory-mapped: its contents would change under the `&str` and reading past the new end
+    /// would crash the process with `SIGBUS`.
+    pub unsafe fn read(path: &Path) -> io::Result<Self> {
+        #[cfg(all(feature = "mmap", unix))]
+        {
+            let file = std::fs::File::open(path)?;
+            if file.metadata()?.len() >= MMAP_THRESHOLD {
+                // SAFETY: The caller guarantees that the file isn't modified whilst mapped.
+                let mapping = unsafe { memmap2::Mmap::map(&file)? };
+                if std::str::from_utf8(&mapping).is_err() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "stream did not contain valid UTF-8",
+                    ));
+                }
+                return Ok(Self {
+                    contents: Contents::Mapped(mapping),
+                });
+            }
+        }
+
+        Ok(Self {
+            contents: Contents::Owned(std::fs::read_to_string(path)?),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.contents {
+            Contents::Owned(contents) => contents,
+            // SAFETY: The contents were validated as UTF-8 when the file was mapped, and the
+            // caller of `read` guarantees that they haven't changed since.
+            #[cfg(all(feature = "mmap", unix))]
+            Contents::Mapped(mapping) => unsafe { std::str::from_utf8_unchecked(mapping) },
+        }
+    }
+
+    /// Whether the file is memory-mapped rather than copied into memory.
+    #[cfg(test)]
+    fn is_mapped(&self) -> bool {
+        !matches!(self.contents, Contents::Owned(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_testing::tempdir::TempDir;
+
+    fn read(path: &Path) -> io::Result<SourceFile> {
+        // SAFETY: The temporary files aren't modified once written.
+        unsafe { SourceFile::read(path) }
+    }
+
+    #[test]
+    fn small_file() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir
+            .create_file("index.d.ts", "export declare const a: number;")
+            .unwrap();
+
+        let source_file = read(&path).unwrap();
+
+        assert_eq!(source_file.as_str(), "export declare const a: number;");
+        assert!(!source_file.is_mapped());
+    }
+
+    #[test]
+    fn missing_file() {
+        let temp_dir = TempDir::new();
+
+        let result = read(&temp_dir.path.join("index.d.ts"));
+
+        assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let temp_dir = TempDir::new();
+        let path = temp_dir.path.join("index.d.ts");
+        std::fs::write(&path, [0xff, 0xfe]).unwrap();
+
+        let result = read(&path);
+
+        assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::InvalidData));
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    mod mmap {
+        use super::*;
+
+        fn make_large_contents() -> String {
+            "export declare const a: number;\n".repeat(MMAP_THRESHOLD as usize / 32 + 1)
+        }
+
+        #[test]
+        fn large_file() {
+            let temp_dir = TempDir::new();
+            let contents = make_large_contents();
+            let path = temp_dir.create_file("index.d.ts", &contents).unwrap();
+
+            let source_file = read(&path).unwrap();
+
+            assert!(source_file.is_mapped());
+            assert_eq!(source_file.as_str(), contents);
+        }
+
+        #[test]
+        fn large_file_with_invalid_utf8() {
+            let temp_dir = TempDir::new();
+            let path = temp_dir.path.join("index.d.ts");
+            let mut contents = make_large_contents().into_bytes();
+            contents.push(0xff);
+            std::fs::write(&path, contents).unwrap();
+
+            let result = read(&path);
+
+            assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::InvalidData));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use daipendency_extractor::Symbol;
+
+use crate::api::jsdoc::{get_leading_jsdoc, parse_tags};
+
+/// How stable an API is, as annotated in the style of the AWS CDK: with a `@stability` tag on a
+/// declaration (e.g. `@stability experimental`) or a `stability` field in `package.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stability {
+    /// The API is subject to the usual semantic versioning rules.
+    Stable,
+    /// The API may change or be removed in any release.
+    Experimental,
+    /// The API is still supported but will be removed.
+    Deprecated,
+    /// The API wraps another one, so it's as stable as the latter.
+    External,
+}
+
+impl Stability {
+    /// Parses a stability level, ignoring case (e.g. `experimental`).
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "stable" => Some(Self::Stable),
+            "experimental" => Some(Self::Experimental),
+            "deprecated" => Some(Self::Deprecated),
+            "external" => Some(Self::External),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Stability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stable => write!(f, "stable"),
+            Self::Experimental => write!(f, "experimental"),
+            Self::Deprecated => write!(f, "deprecated"),
+            Self::External => write!(f, "external"),
+        }
+    }
+}
+
+/// Gets the stability of a symbol from the `@stability` tag in its JSDoc, if any.
+///
+/// This doesn't account for the stability of the package: see
+/// [crate::ExtractionReport::get_stability] for that.
+pub fn get_stability(symbol: &Symbol) -> Option<Stability> {
+    let jsdoc = get_leading_jsdoc(&symbol.source_code)?;
+    parse_tags(jsdoc)
+        .into_iter()
+        .find(|tag| tag.name == "stability")
+        .and_then(|tag| {
+            tag.content
+                .split_whitespace()
+                .next()
+                .and_then(Stability::parse)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_symbol(source_code: &str) -> Symbol {
+        Symbol {
+            name: "Bucket".to_string(),
+            source_code: source_code.to_string(),
+        }
+    }
+
+    #[test]
+    fn stability_tag() {
+        let symbol = make_symbol(
+            "/**\n * A bucket.\n *\n * @stability experimental\n */\nexport declare class Bucket {}",
+        );
+
+        assert_eq!(get_stability(&symbol), Some(Stability::Experimental));
+    }
+
+    #[test]
+    fn case_insensitive_levels() {
+        let symbol = make_symbol("/** @stability Stable */\nexport declare class Bucket {}");
+
+        assert_eq!(get_stability(&symbol), Some(Stability::Stable));
+    }
+
+    #[test]
+    fn unknown_level() {
+        let symbol = make_symbol("/** @stability wobbly */\nexport declare class Bucket {}");
+
+        assert_eq!(get_stability(&symbol), None);
+    }
+
+    #[test]
+    fn no_tag() {
+        let symbol = make_symbol("/** A bucket. */\nexport declare class Bucket {}");
+
+        assert_eq!(get_stability(&symbol), None);
+    }
+
+    #[test]
+    fn no_jsdoc() {
+        let symbol = make_symbol("export declare class Bucket {}");
+
+        assert_eq!(get_stability(&symbol), None);
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+
+use daipendency_extractor::{ExtractionError, Namespace, Symbol};
+use tree_sitter::Parser;
+
+use crate::api::module_set::ModuleSet;
+use crate::api::resolution::ExportResolver;
+use crate::api::{
+    add_namespaces, apply_symbol_options, get_entry_point_namespace_name, resolve_entry_point,
+    GLOBAL_NAMESPACE_NAME,
+};
+use crate::metadata::{TSEntryPoint, TSEntryPointSet, TSLibraryMetadata};
+use crate::options::ExtractionOptions;
+
+/// Yields the namespaces of the public API one at a time, in the same order as
+/// [`crate::TypeScriptExtractor::extract_public_api`](daipendency_extractor::Extractor::extract_public_api).
+///
+/// Entry points are processed one by one: the modules reachable from an entry point are only
+/// parsed once the namespaces of the previous entry point have been consumed, and they're
+/// dropped as soon as its namespaces have been built. Modules shared by several entry points are
+/// therefore parsed once per entry point.
+pub struct PublicApiIter<'a> {
+    library_metadata: &'a TSLibraryMetadata,
+    parser: &'a mut Parser,
+    options: &'a ExtractionOptions,
+    /// The entry points yet to be processed, in order of external path.
+    entry_points: VecDeque<&'a TSEntryPoint>,
+    /// The namespaces of the current entry point that haven't been yielded yet.
+    namespaces: VecDeque<Namespace>,
+    /// An error to yield before anything else.
+    pending_error: Option<ExtractionError>,
+    /// Whether any entry point exported something, in which case globals are left out.
+    has_exports: bool,
+    /// The global declarations of the modules parsed so far, to yield if nothing is exported.
+    globals: Vec<Symbol>,
+    /// The modules whose global declarations have been collected.
+    global_modules: HashSet<PathBuf>,
+}
+
+impl<'a> PublicApiIter<'a> {
+    pub(crate) fn new(
+        library_metadata: &'a TSLibraryMetadata,
+        parser: &'a mut Parser,
+        options: &'a ExtractionOptions,
+    ) -> Self {
+        let mut entry_points = library_metadata.entry_point.iter().collect::<Vec<_>>();
+        entry_points.sort_by(|a, b| a.external_path.cmp(&b.external_path));
+        Self {
+            library_metadata,
+            parser,
+            options,
+            entry_points: entry_points.into(),
+            namespaces: VecDeque::new(),
+            pending_error: library_metadata
+                .entry_point
+                .is_empty()
+                .then(|| ExtractionError::Malformed("No types entry point specified".to_string())),
+            has_exports: false,
+            globals: vec![],
+            global_modules: HashSet::new(),
+        }
+    }
+
+    /// Parses the modules reachable from an entry point and builds its namespaces.
+    fn load_entry_point(&mut self, entry_point: &TSEntryPoint) -> Result<(), ExtractionError> {
+        let mut entry_points = TSEntryPointSet::from_iter([entry_point.clone()]);
+        entry_points.package_path = self.library_metadata.entry_point.package_path.clone();
+        entry_points.browser_remappings =
+            self.library_metadata.entry_point.browser_remappings.clone();
+        let module_set = ModuleSet::from_entrypoints(&entry_points, self.parser, self.options)?;
+        let mut resolver = ExportResolver::new(&module_set);
+        let resolved_entry_point = resolve_entry_point(&mut resolver, &module_set, entry_point)?;
+
+        let mut namespaces = vec![];
+        add_namespaces(
+            &mut namespaces,
+            get_entry_point_namespace_name(&self.library_metadata.name, entry_point),
+            resolved_entry_point.jsdoc,
+            &resolved_entry_point.exports,
+            None,
+        );
+        apply_symbol_options(&mut namespaces, self.options);
+        self.has_exports |= namespaces
+            .iter()
+            .any(|namespace| !namespace.symbols.is_empty());
+        self.namespaces.extend(namespaces);
+
+        let mut modules = module_set.iter().collect::<Vec<_>>();
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+        for module in modules {
+            if self.global_modules.insert(module.path.clone()) {
+                self.globals.extend(module.globals.iter().cloned());
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the namespace with the global declarations, if nothing was exported. This mirrors
+    /// the global namespace of [crate::api::extract_public_api], except that the declarations
+    /// are in the order their entry points were processed.
+    fn take_global_namespace(&mut self) -> Option<Namespace> {
+        let globals = std::mem::take(&mut self.globals);
+        if self.has_exports || globals.is_empty() {
+            return None;
+        }
+        let mut namespaces = vec![Namespace {
+            name: GLOBAL_NAMESPACE_NAME.to_string(),
+            symbols: globals,
+            doc_comment: None,
+        }];
+        apply_symbol_options(&mut namespaces, self.options);
+        namespaces.pop()
+    }
+}
+
+impl Iterator for PublicApiIter<'_> {
+    type Item = Result<Namespace, ExtractionError>;
+
+    /// Yields the next namespace, or the error that stopped the extraction. Nothing is yielded
+    /// after an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.pending_error.take() {
+            return Some(Err(error));
+        }
+        loop {
+            if let Some(namespace) = self.namespaces.pop_front() {
+                return Some(Ok(namespace));
+            }
+            let Some(entry_point) = self.entry_points.pop_front() else {
+                return self.take_global_namespace().map(Ok);
+            };
+            if let Err(error) = self.load_entry_point(entry_point) {
+                self.entry_points.clear();
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::extract_public_api;
+    use crate::api::test_helpers::{make_metadata, make_parser};
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    fn make_package_metadata(
+        temp_dir: &TempDir,
+        entry_points: &[(&str, &str)],
+    ) -> TSLibraryMetadata {
+        TSLibraryMetadata {
+            name: "test-pkg".to_string(),
+            entry_point: entry_points
+                .iter()
+                .map(|(external_path, internal_path)| TSEntryPoint {
+                    external_path: external_path.to_string(),
+                    internal_path: temp_dir.path.join(internal_path),
+                    conditions: BTreeMap::new(),
+                })
+                .collect(),
+            ..make_metadata("")
+        }
+    }
+
+    #[test]
+    fn same_namespaces_as_eager_extraction() {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "index.d.ts",
+                "export * from './shared';\nexport namespace Inner { export const a: number; }",
+            )
+            .unwrap();
+        temp_dir
+            .create_file("utils.d.ts", "export * from './shared';")
+            .unwrap();
+        temp_dir
+            .create_file("shared.d.ts", "export declare function shared(): void;")
+            .unwrap();
+        let metadata =
+            make_package_metadata(&temp_dir, &[("./utils", "utils.d.ts"), (".", "index.d.ts")]);
+        let mut parser = make_parser();
+
+        let namespaces = PublicApiIter::new(&metadata, &mut parser, &ExtractionOptions::default())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let expected_namespaces =
+            extract_public_api(&metadata, &mut make_parser(), &ExtractionOptions::default())
+                .unwrap();
+        assert_eq!(namespaces, expected_namespaces);
+        let names = namespaces
+            .iter()
+            .map(|namespace| namespace.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["test-pkg", "Inner", "test-pkg/utils"]);
+    }
+
+    #[test]
+    fn global_declarations() {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "index.d.ts",
+                "export {};\ndeclare global { interface Array<T> { findLast(): T; } }",
+            )
+            .unwrap();
+        let metadata = make_package_metadata(&temp_dir, &[(".", "index.d.ts")]);
+        let mut parser = make_parser();
+
+        let namespaces = PublicApiIter::new(&metadata, &mut parser, &ExtractionOptions::default())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let expected_namespaces =
+            extract_public_api(&metadata, &mut make_parser(), &ExtractionOptions::default())
+                .unwrap();
+        assert_eq!(namespaces, expected_namespaces);
+        assert_eq!(namespaces.last().unwrap().name, GLOBAL_NAMESPACE_NAME);
+    }
+
+    #[test]
+    fn entry_points_are_loaded_on_demand() {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file("index.d.ts", "export declare const a: number;")
+            .unwrap();
+        let metadata = make_package_metadata(
+            &temp_dir,
+            &[(".", "index.d.ts"), ("./missing", "missing.d.ts")],
+        );
+        let options = ExtractionOptions::default();
+        let mut parser = make_parser();
+        let mut iterator = PublicApiIter::new(&metadata, &mut parser, &options);
+
+        let first_namespace = iterator.next().unwrap().unwrap();
+
+        assert_eq!(first_namespace.name, "test-pkg");
+        assert!(matches!(iterator.next(), Some(Err(ExtractionError::Io(_)))));
+        assert!(iterator.next().is_none());
+    }
+
+    #[test]
+    fn no_entry_points() {
+        let metadata = TSLibraryMetadata {
+            name: "test-pkg".to_string(),
+            version: None,
+            documentation: String::new(),
+            entry_point: TSEntryPointSet::default(),
+        };
+        let options = ExtractionOptions::default();
+        let mut parser = make_parser();
+        let mut iterator = PublicApiIter::new(&metadata, &mut parser, &options);
+
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(ExtractionError::Malformed(message))) if message == "No types entry point specified"
+        ));
+        assert!(iterator.next().is_none());
+    }
+}
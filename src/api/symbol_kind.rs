@@ -0,0 +1,308 @@
+use std::sync::OnceLock;
+
+use daipendency_extractor::{ParsedFile, Symbol};
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor};
+
+use crate::api::declaration::is_modifier_wrapper;
+use crate::api::queries::get_query;
+use crate::api::queries::make_parser;
+
+const SYMBOL_KIND_QUERY: &str = r#"
+(class_declaration) @class
+
+(abstract_class_declaration) @abstract_class
+
+(interface_declaration) @interface
+
+(function_signature) @function
+
+(function_declaration) @function
+
+(type_alias_declaration) @type_alias
+
+(enum_declaration
+    "const"? @const
+    ) @enum
+
+(lexical_declaration
+    kind: "const"? @const
+    ) @variable
+"#;
+
+/// The kind of declaration behind a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Class {
+        is_abstract: bool,
+    },
+    Interface,
+    Function,
+    TypeAlias,
+    Enum {
+        /// Whether it's a `const enum`, whose members are inlined by the compiler.
+        is_const: bool,
+        /// Whether it's declared with `declare`, so it's implemented elsewhere.
+        is_ambient: bool,
+    },
+    Variable {
+        /// Whether it's declared with `const` rather than `let` or `var`.
+        is_const: bool,
+    },
+}
+
+impl SymbolKind {
+    /// Whether the symbol can be referenced as a value at runtime.
+    ///
+    /// Types don't exist at runtime, and neither do `const enum`s since their members are
+    /// inlined (unless the package is compiled with `preserveConstEnums`).
+    pub fn exists_at_runtime(&self) -> bool {
+        match self {
+            SymbolKind::Interface | SymbolKind::TypeAlias => false,
+            SymbolKind::Enum { is_const, .. } => !is_const,
+            SymbolKind::Class { .. } | SymbolKind::Function | SymbolKind::Variable { .. } => true,
+        }
+    }
+}
+
+/// Gets the query used to determine the kind of symbols, compiling it the first time.
+pub(crate) fn get_symbol_kind_query() -> &'static Query {
+    static QUERY: OnceLock<Query> = OnceLock::new();
+    get_query(&QUERY, SYMBOL_KIND_QUERY)
+}
+
+/// Determines the kind of declaration in the source code of a symbol, or `None` if the source
+/// code isn't a supported declaration (e.g. it doesn't parse).
+pub fn get_symbol_kind(symbol: &Symbol) -> Option<SymbolKind> {
+    let mut parser = make_parser();
+    let parsed_file = ParsedFile::parse(&symbol.source_code, &mut parser).ok()?;
+    get_parsed_symbol_kind(&parsed_file)
+}
+
+/// Like [get_symbol_kind], with the source code of the symbol already parsed.
+pub(crate) fn get_parsed_symbol_kind(parsed_file: &ParsedFile) -> Option<SymbolKind> {
+    let root = parsed_file.root_node();
+    let query = get_symbol_kind_query();
+    let const_index = query.capture_index_for_name("const");
+
+    let mut cursor = QueryCursor::new();
+    let mut matches = parsed_file.exec_query(query, root, &mut cursor);
+    while let Some(match_) = matches.next() {
+        let Some(declaration) = match_
+            .captures
+            .iter()
+            .find(|capture| Some(capture.index) != const_index)
+        else {
+            continue;
+        };
+        // Skip declarations nested in the top-level one (e.g. in a function body)
+        if !is_top_level(declaration.node) {
+            continue;
+        }
+
+        let is_const = match_
+            .captures
+            .iter()
+            .any(|capture| Some(capture.index) == const_index);
+        let capture_name = query.capture_names()[declaration.index as usize];
+        let kind = match capture_name {
+            "class" => SymbolKind::Class { is_abstract: false },
+            "abstract_class" => SymbolKind::Class { is_abstract: true },
+            "interface" => SymbolKind::Interface,
+            "function" => SymbolKind::Function,
+            "type_alias" => SymbolKind::TypeAlias,
+            "enum" => SymbolKind::Enum {
+                is_const,
+                is_ambient: is_ambient(declaration.node),
+            },
+            _ => SymbolKind::Variable { is_const },
+        };
+        return Some(kind);
+    }
+    None
+}
+
+/// Checks whether a declaration is only wrapped in modifiers (e.g. `export` or `declare`) at the
+/// top of the file.
+fn is_top_level(node: Node) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        match parent.kind() {
+            "program" => return true,
+            _ if is_modifier_wrapper(parent) => ancestor = parent.parent(),
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn is_ambient(node: Node) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if parent.kind() == "ambient_declaration" {
+            return true;
+        }
+        ancestor = parent.parent();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_kind(source_code: &str) -> Option<SymbolKind> {
+        get_symbol_kind(&Symbol {
+            name: "symbol".to_string(),
+            source_code: source_code.to_string(),
+        })
+    }
+
+    mod get_symbol_kind {
+        use super::*;
+
+        #[test]
+        fn class() {
+            assert_eq!(
+                get_kind("export declare class Foo {}"),
+                Some(SymbolKind::Class { is_abstract: false })
+            );
+        }
+
+        #[test]
+        fn abstract_class() {
+            assert_eq!(
+                get_kind("export declare abstract class Foo {}"),
+                Some(SymbolKind::Class { is_abstract: true })
+            );
+        }
+
+        #[test]
+        fn interface() {
+            assert_eq!(
+                get_kind("export interface Foo {}"),
+                Some(SymbolKind::Interface)
+            );
+        }
+
+        #[test]
+        fn function_signature() {
+            assert_eq!(
+                get_kind("export declare function foo(): void;"),
+                Some(SymbolKind::Function)
+            );
+        }
+
+        #[test]
+        fn function_implementation() {
+            assert_eq!(
+                get_kind("export function foo() { const bar = 1; }"),
+                Some(SymbolKind::Function)
+            );
+        }
+
+        #[test]
+        fn type_alias() {
+            assert_eq!(
+                get_kind("export type Foo = string;"),
+                Some(SymbolKind::TypeAlias)
+            );
+        }
+
+        #[test]
+        fn regular_enum() {
+            assert_eq!(
+                get_kind("export enum Foo { A }"),
+                Some(SymbolKind::Enum {
+                    is_const: false,
+                    is_ambient: false
+                })
+            );
+        }
+
+        #[test]
+        fn const_enum() {
+            assert_eq!(
+                get_kind("export const enum Foo { A }"),
+                Some(SymbolKind::Enum {
+                    is_const: true,
+                    is_ambient: false
+                })
+            );
+        }
+
+        #[test]
+        fn ambient_enum() {
+            assert_eq!(
+                get_kind("export declare enum Foo { A }"),
+                Some(SymbolKind::Enum {
+                    is_const: false,
+                    is_ambient: true
+                })
+            );
+        }
+
+        #[test]
+        fn ambient_const_enum() {
+            assert_eq!(
+                get_kind("/** Doc */\nexport declare const enum Foo { A }"),
+                Some(SymbolKind::Enum {
+                    is_const: true,
+                    is_ambient: true
+                })
+            );
+        }
+
+        #[test]
+        fn const_variable() {
+            assert_eq!(
+                get_kind("export declare const foo: string;"),
+                Some(SymbolKind::Variable { is_const: true })
+            );
+        }
+
+        #[test]
+        fn let_variable() {
+            assert_eq!(
+                get_kind("export declare let foo: string;"),
+                Some(SymbolKind::Variable { is_const: false })
+            );
+        }
+
+        #[test]
+        fn malformed_source_code() {
+            assert_eq!(get_kind("export enum {"), None);
+        }
+    }
+
+    mod exists_at_runtime {
+        use super::*;
+
+        #[test]
+        fn types() {
+            assert!(!SymbolKind::Interface.exists_at_runtime());
+            assert!(!SymbolKind::TypeAlias.exists_at_runtime());
+        }
+
+        #[test]
+        fn enums() {
+            let regular_enum = SymbolKind::Enum {
+                is_const: false,
+                is_ambient: true,
+            };
+            let const_enum = SymbolKind::Enum {
+                is_const: true,
+                is_ambient: true,
+            };
+
+            assert!(regular_enum.exists_at_runtime());
+            assert!(!const_enum.exists_at_runtime());
+        }
+
+        #[test]
+        fn values() {
+            assert!(SymbolKind::Function.exists_at_runtime());
+            assert!(SymbolKind::Variable { is_const: true }.exists_at_runtime());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use daipendency_extractor::Symbol;
+
+use crate::api::jsdoc::get_leading_jsdoc;
+
+/// The source code of a symbol split into its JSDoc comment and its declaration, for consumers
+/// that render signatures and fetch docs separately.
+///
+/// The ranges are byte offsets into the source code of the symbol, worked out once when the parts
+/// are created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolParts<'a> {
+    source_code: &'a str,
+    docs_range: Option<Range<usize>>,
+    signature_range: Range<usize>,
+}
+
+impl<'a> SymbolParts<'a> {
+    pub fn new(symbol: &'a Symbol) -> Self {
+        let source_code = symbol.source_code.as_str();
+        let docs_range = get_leading_jsdoc(source_code).map(|jsdoc| {
+            let start = source_code.len() - source_code.trim_start().len();
+            start..start + jsdoc.len()
+        });
+
+        let docs_end = docs_range.as_ref().map_or(0, |range| range.end);
+        let declaration = &source_code[docs_end..];
+        let start = docs_end + declaration.len() - declaration.trim_start().len();
+        let end = docs_end + declaration.trim_end().len();
+
+        Self {
+            source_code,
+            docs_range,
+            signature_range: start..end.max(start),
+        }
+    }
+
+    /// Gets the range of the JSDoc comment preceding the declaration, if any.
+    pub fn docs_range(&self) -> Option<Range<usize>> {
+        self.docs_range.clone()
+    }
+
+    /// Gets the range of the declaration, excluding the JSDoc comment and the whitespace around
+    /// it.
+    pub fn signature_range(&self) -> Range<usize> {
+        self.signature_range.clone()
+    }
+
+    /// Gets the JSDoc comment preceding the declaration, if any.
+    pub fn docs(&self) -> Option<&'a str> {
+        self.docs_range().map(|range| &self.source_code[range])
+    }
+
+    /// Gets the declaration without its JSDoc comment.
+    pub fn signature(&self) -> &'a str {
+        &self.source_code[self.signature_range()]
+    }
+
+    /// Gets the declaration along with its JSDoc comment.
+    pub fn full(&self) -> &'a str {
+        self.source_code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_symbol;
+
+    #[test]
+    fn jsdoc_and_declaration() {
+        let symbol = make_symbol("foo", "/** Docs */\nexport declare const foo: string;");
+        let parts = SymbolParts::new(&symbol);
+
+        assert_eq!(parts.docs(), Some("/** Docs */"));
+        assert_eq!(parts.docs_range(), Some(0..11));
+        assert_eq!(parts.signature(), "export declare const foo: string;");
+        assert_eq!(parts.signature_range(), 12..45);
+        assert_eq!(parts.full(), symbol.source_code);
+    }
+
+    #[test]
+    fn no_jsdoc() {
+        let symbol = make_symbol("foo", "export declare const foo: string;");
+        let parts = SymbolParts::new(&symbol);
+
+        assert_eq!(parts.docs(), None);
+        assert_eq!(parts.signature(), "export declare const foo: string;");
+    }
+
+    #[test]
+    fn block_comment() {
+        let symbol = make_symbol("foo", "/* Not docs */\nexport declare const foo: string;");
+        let parts = SymbolParts::new(&symbol);
+
+        assert_eq!(parts.docs(), None);
+        assert_eq!(parts.signature(), symbol.source_code);
+    }
+
+    #[test]
+    fn surrounding_whitespace() {
+        let symbol = make_symbol(
+            "foo",
+            "\n  /**\n   * Docs\n   */\n  declare const foo: string;\n",
+        );
+        let parts = SymbolParts::new(&symbol);
+
+        assert_eq!(parts.docs(), Some("/**\n   * Docs\n   */"));
+        assert_eq!(parts.signature(), "declare const foo: string;");
+    }
+
+    #[test]
+    fn jsdoc_only() {
+        let symbol = make_symbol("foo", "/** Docs */");
+        let parts = SymbolParts::new(&symbol);
+
+        assert_eq!(parts.docs(), Some("/** Docs */"));
+        assert_eq!(parts.signature(), "");
+    }
+}
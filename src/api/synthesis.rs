@@ -0,0 +1,197 @@
+use std::ops::Range;
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use crate::api::file_kinds::{find_file_extension, FileKind, Grammar};
+use crate::api::source_edits::replace_ranges;
+
+/// Checks whether a file is a TypeScript source file (e.g. `src/index.ts`) rather than a
+/// declaration file, as found in packages published without being built.
+pub(crate) fn is_source_file(path: &Path) -> bool {
+    find_file_extension(path)
+        .is_some_and(|extension| matches!(extension.kind, FileKind::TypeScript | FileKind::Tsx))
+}
+
+/// Turns a TypeScript or JavaScript implementation into declarations, so that its symbols show
+/// signatures rather than implementations.
+///
+/// The bodies of named functions and methods are replaced with `;`, turning them into signatures,
+/// whilst other function bodies and static blocks are emptied. JSX (in `.tsx` and `.jsx` files) is
+/// replaced with `null`, as the rest of the extraction only understands TypeScript.
+///
+/// The source code is returned unchanged if it doesn't parse.
+pub(crate) fn synthesise_declarations(source_code: &str, path: &Path) -> String {
+    let grammar =
+        find_file_extension(path).map_or(Grammar::TypeScript, |extension| extension.grammar);
+    let mut parser = Parser::new();
+    parser
+        .set_language(grammar.language())
+        .expect("The TypeScript grammar should be compatible with tree-sitter");
+    let Some(tree) = parser.parse(source_code, None) else {
+        return source_code.to_string();
+    };
+
+    let mut edits = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if let Some(edit) = get_edit(node) {
+            edits.push(edit);
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children = node.children(&mut cursor).collect::<Vec<_>>();
+        stack.extend(children.into_iter().rev());
+    }
+
+    replace_ranges(source_code, edits)
+}
+
+/// Gets the range of source code to replace in a node (if any), along with its replacement.
+///
+/// Nodes inside the replaced range aren't visited.
+fn get_edit(node: Node) -> Option<(Range<usize>, &'static str)> {
+    match node.kind() {
+        "function_declaration" | "generator_function_declaration" | "method_definition" => {
+            // The whitespace before the body goes too, as in `function foo(): void;`
+            let body = node.child_by_field_name("body")?;
+            let start = body
+                .prev_sibling()
+                .map_or(body.start_byte(), |sibling| sibling.end_byte());
+            Some((start..body.end_byte(), ";"))
+        }
+        "arrow_function" | "function_expression" | "generator_function" => {
+            let body = node.child_by_field_name("body")?;
+            (body.kind() == "statement_block").then(|| (body.byte_range(), "{}"))
+        }
+        "class_static_block" => Some((node.byte_range(), "")),
+        "jsx_element" | "jsx_self_closing_element" | "jsx_fragment" => {
+            Some((node.byte_range(), "null"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synthesise(source_code: &str) -> String {
+        synthesise_declarations(source_code, Path::new("index.ts"))
+    }
+
+    mod is_source_file {
+        use super::*;
+
+        #[test]
+        fn source_files() {
+            for path in ["src/index.ts", "src/App.tsx", "index.mts", "index.cts"] {
+                assert!(is_source_file(Path::new(path)), "{path}");
+            }
+        }
+
+        #[test]
+        fn declaration_files() {
+            for path in ["index.d.ts", "index.d.mts", "index.d.cts"] {
+                assert!(!is_source_file(Path::new(path)), "{path}");
+            }
+        }
+
+        #[test]
+        fn javascript_files() {
+            assert!(!is_source_file(Path::new("index.js")));
+        }
+    }
+
+    mod synthesise_declarations {
+        use super::*;
+
+        #[test]
+        fn function_body() {
+            let declarations = synthesise(
+                "export function add(a: number, b: number): number {\n  return a + b;\n}",
+            );
+
+            assert_eq!(
+                declarations,
+                "export function add(a: number, b: number): number;"
+            );
+        }
+
+        #[test]
+        fn method_bodies() {
+            let declarations = synthesise(
+                "export class Client {\n  constructor(private url: string) { connect(); }\n  get(): string { return this.url; }\n}",
+            );
+
+            assert_eq!(
+                declarations,
+                "export class Client {\n  constructor(private url: string);\n  get(): string;\n}"
+            );
+        }
+
+        #[test]
+        fn arrow_function_body() {
+            let declarations = synthesise(
+                "export const add = (a: number, b: number): number => {\n  return a + b;\n};",
+            );
+
+            assert_eq!(
+                declarations,
+                "export const add = (a: number, b: number): number => {};"
+            );
+        }
+
+        #[test]
+        fn arrow_function_expression() {
+            let source_code = "export const double = (a: number) => a * 2;";
+
+            assert_eq!(synthesise(source_code), source_code);
+        }
+
+        #[test]
+        fn anonymous_default_function() {
+            let declarations = synthesise("export default function () { return 1; }");
+
+            assert_eq!(declarations, "export default function () {}");
+        }
+
+        #[test]
+        fn static_block() {
+            let declarations = synthesise("export class Registry {\n  static { init(); }\n}");
+
+            assert_eq!(declarations, "export class Registry {\n  \n}");
+        }
+
+        #[test]
+        fn declarations_unchanged() {
+            let source_code =
+                "export interface Options {\n  url: string;\n}\nexport type Id = string;";
+
+            assert_eq!(synthesise(source_code), source_code);
+        }
+
+        #[test]
+        fn jsx() {
+            let declarations = synthesise_declarations(
+                "export const Button = (props: Props) => <button>{props.label}</button>;",
+                Path::new("Button.tsx"),
+            );
+
+            assert_eq!(
+                declarations,
+                "export const Button = (props: Props) => null;"
+            );
+        }
+
+        #[test]
+        fn javascript_jsx() {
+            let declarations = synthesise_declarations(
+                "export const Button = (props) => <button>{props.label}</button>;",
+                Path::new("Button.jsx"),
+            );
+
+            assert_eq!(declarations, "export const Button = (props) => null;");
+        }
+    }
+}
@@ -1,18 +1,38 @@
 #![cfg(test)]
 
-use crate::TypeScriptExtractor;
 use assertables::assert_matches;
-use daipendency_extractor::Extractor;
-use std::collections::HashMap;
-use tree_sitter::Parser;
+use daipendency_extractor::Symbol;
 
-use super::module::{ExportTarget, ImportTarget, TypeScriptSymbol};
+use super::module::{ExportTarget, ImportTarget, Specifier, TypeScriptSymbol};
+use crate::metadata::{TSEntryPointSet, TSLibraryMetadata};
 
-pub fn make_parser() -> Parser {
-    let mut parser = Parser::new();
-    let language = TypeScriptExtractor.get_parser_language();
-    parser.set_language(&language).unwrap();
-    parser
+pub(crate) use crate::api::queries::make_parser;
+
+/// Makes a symbol from its name and source code.
+pub fn make_symbol(name: &str, source_code: &str) -> Symbol {
+    Symbol {
+        name: name.to_string(),
+        source_code: source_code.to_string(),
+    }
+}
+
+/// Makes the metadata of a package named `pkg` without entry points.
+pub fn make_metadata(documentation: &str) -> TSLibraryMetadata {
+    TSLibraryMetadata {
+        name: "pkg".to_string(),
+        version: Some("1.0.0".to_string()),
+        documentation: documentation.to_string(),
+        entry_point: TSEntryPointSet::default(),
+    }
+}
+
+/// Makes a specifier without the `type` modifier.
+pub fn make_specifier(name: &str, alias: Option<&str>) -> Specifier {
+    Specifier {
+        name: name.to_string(),
+        alias: alias.map(str::to_string),
+        is_type_only: false,
+    }
 }
 
 /// Deconstructs a `TypeScriptSymbol::ModuleImport` into its source module and target.
@@ -21,6 +41,7 @@ pub fn deconstruct_module_import(symbol: &TypeScriptSymbol) -> (String, ImportTa
         TypeScriptSymbol::ModuleImport {
             source_module,
             target,
+            ..
         } => (source_module.clone(), target.clone()),
         _ => panic!("Expected module import"),
     }
@@ -47,6 +68,7 @@ pub fn deconstruct_module_export(symbol: &TypeScriptSymbol) -> (Option<String>,
         TypeScriptSymbol::ModuleExport {
             source_module,
             target,
+            ..
         } => (source_module.clone(), target.clone()),
         _ => panic!("Expected module export"),
     }
@@ -65,6 +87,8 @@ mod tests {
                 target: ImportTarget::Default {
                     name: "lodash".to_string(),
                 },
+                is_unresolved_external: false,
+                is_builtin: false,
             };
 
             let (module, target) = deconstruct_module_import(&symbol);
@@ -142,15 +166,15 @@ mod tests {
             let symbol = TypeScriptSymbol::ModuleExport {
                 source_module: Some("lodash".to_string()),
                 target: ExportTarget::Named {
-                    names: vec!["map".to_string()],
-                    aliases: HashMap::new(),
+                    specifiers: vec![make_specifier("map", None)],
                 },
+                is_type_only: false,
             };
 
             let (source_module, target) = deconstruct_module_export(&symbol);
 
             assert_eq!(source_module, Some("lodash".to_string()));
-            assert_matches!(target, ExportTarget::Named { names, aliases } if names == vec!["map".to_string()] && aliases.is_empty());
+            assert_matches!(target, ExportTarget::Named { specifiers } if specifiers == vec![make_specifier("map", None)]);
         }
 
         #[test]
@@ -160,6 +184,7 @@ mod tests {
                 target: ExportTarget::Namespace {
                     name: "utils".to_string(),
                 },
+                is_type_only: false,
             };
 
             let (source_module, target) = deconstruct_module_export(&symbol);
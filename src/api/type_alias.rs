@@ -0,0 +1,209 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::declaration::find_declaration;
+use crate::api::queries::make_parser;
+use crate::signature::normalize_node;
+
+/// The type behind a type alias (e.g. `Brand<string, 'user'>` in
+/// `export type UserId = Brand<string, 'user'>;`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeAliasTarget {
+    /// The aliased type, on a single line.
+    pub type_text: String,
+    /// The types and values the aliased type refers to, in order of first appearance (e.g.
+    /// `Brand`, `NodeJS.Timer` or `config` in `typeof config`).
+    ///
+    /// Built-in types, the type parameters of the alias and the alias itself are omitted.
+    pub referenced_identifiers: Vec<String>,
+}
+
+/// Gets the type behind a type alias, or `None` if the symbol isn't a type alias.
+///
+/// Only the aliased type itself is analysed: aliases of aliases aren't followed.
+pub fn get_type_alias_target(symbol: &Symbol) -> Option<TypeAliasTarget> {
+    let mut parser = make_parser();
+    let parsed_file = ParsedFile::parse(&symbol.source_code, &mut parser).ok()?;
+    let declaration = find_declaration(parsed_file.root_node())?;
+    if declaration.kind() != "type_alias_declaration" {
+        return None;
+    }
+    let value = declaration.child_by_field_name("value")?;
+    let source_code = symbol.source_code.as_str();
+
+    // Constraints and defaults of type parameters count as references too
+    let type_parameters = declaration.child_by_field_name("type_parameters");
+    let mut local_names = vec![symbol.name.clone()];
+    let mut referenced_identifiers = vec![];
+    for node in type_parameters.iter().chain([&value]) {
+        collect_local_names(*node, source_code, &mut local_names);
+        collect_references(*node, source_code, &mut referenced_identifiers);
+    }
+    referenced_identifiers.retain(|identifier| !local_names.contains(identifier));
+
+    Some(TypeAliasTarget {
+        type_text: normalize_node(value, source_code),
+        referenced_identifiers,
+    })
+}
+
+/// Collects the names of type variables declared in a type (e.g. `T` in `<T>`, `U` in
+/// `infer U` or `K` in `{ [K in keyof T]: ... }`).
+fn collect_local_names(node: Node, source_code: &str, local_names: &mut Vec<String>) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        let declared_name = match node.kind() {
+            "type_parameter" | "mapped_type_clause" => node.child_by_field_name("name"),
+            "infer_type" => node.named_child(0),
+            _ => None,
+        };
+        if let Some(declared_name) = declared_name {
+            local_names.push(source_code[declared_name.byte_range()].to_string());
+        }
+        push_named_children(node, &mut stack);
+    }
+}
+
+fn collect_references(node: Node, source_code: &str, references: &mut Vec<String>) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        let reference = match node.kind() {
+            "type_identifier" | "nested_type_identifier" => Some(node),
+            "type_query" => node.named_child(0),
+            _ => None,
+        };
+        if let Some(reference) = reference {
+            let reference = normalize_node(reference, source_code);
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+            continue;
+        }
+        push_named_children(node, &mut stack);
+    }
+}
+
+/// Pushes the named children of a node onto a stack so that they're popped in order.
+fn push_named_children<'a>(node: Node<'a>, stack: &mut Vec<Node<'a>>) {
+    let mut cursor = node.walk();
+    let children = node.named_children(&mut cursor).collect::<Vec<_>>();
+    stack.extend(children.into_iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_target(name: &str, source_code: &str) -> Option<TypeAliasTarget> {
+        get_type_alias_target(&Symbol {
+            name: name.to_string(),
+            source_code: source_code.to_string(),
+        })
+    }
+
+    fn get_references(name: &str, source_code: &str) -> Vec<String> {
+        get_target(name, source_code)
+            .map(|target| target.referenced_identifiers)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn generic_type() {
+        let target = get_target("UserId", "export type UserId = Brand<string, 'user'>;");
+
+        assert_eq!(
+            target,
+            Some(TypeAliasTarget {
+                type_text: "Brand<string, 'user'>".to_string(),
+                referenced_identifiers: vec!["Brand".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn builtin_type() {
+        let target = get_target("Name", "/** The name. */\nexport type Name = string;");
+
+        assert_eq!(
+            target,
+            Some(TypeAliasTarget {
+                type_text: "string".to_string(),
+                referenced_identifiers: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn multiline_type() {
+        let target = get_target(
+            "Pair",
+            "export type Pair = {\n  left: Left;\n  right: Right;\n};",
+        );
+
+        assert_eq!(
+            target,
+            Some(TypeAliasTarget {
+                type_text: "{ left: Left; right: Right }".to_string(),
+                referenced_identifiers: vec!["Left".to_string(), "Right".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn repeated_references() {
+        let references = get_references("Either", "export type Either = Left | Right | Left[];");
+
+        assert_eq!(references, vec!["Left", "Right"]);
+    }
+
+    #[test]
+    fn qualified_names() {
+        let references = get_references("Timer", "export type Timer = NodeJS.Timer;");
+
+        assert_eq!(references, vec!["NodeJS.Timer"]);
+    }
+
+    #[test]
+    fn type_queries() {
+        let references = get_references(
+            "Config",
+            "export type Config = typeof config & typeof foo.bar;",
+        );
+
+        assert_eq!(references, vec!["config", "foo.bar"]);
+    }
+
+    #[test]
+    fn type_parameters() {
+        let references = get_references("Box", "export type Box<T extends Base> = { value: T };");
+
+        assert_eq!(references, vec!["Base"]);
+    }
+
+    #[test]
+    fn local_type_variables() {
+        let references = get_references(
+            "Mapped",
+            "export type Mapped<T> = { [K in keyof T]: T[K] extends Array<infer U> ? U : Other };",
+        );
+
+        assert_eq!(references, vec!["Array", "Other"]);
+    }
+
+    #[test]
+    fn recursive_type() {
+        let references = get_references(
+            "Tree",
+            "export type Tree = { children: Tree[]; leaf: Leaf };",
+        );
+
+        assert_eq!(references, vec!["Leaf"]);
+    }
+
+    #[test]
+    fn non_type_alias() {
+        let target = get_target("Foo", "export interface Foo {}");
+
+        assert_eq!(target, None);
+    }
+}
@@ -0,0 +1,255 @@
+use daipendency_extractor::{ParsedFile, Symbol};
+use tree_sitter::Node;
+
+use crate::api::declaration::find_declaration;
+use crate::api::queries::make_parser;
+use crate::signature::normalize_node;
+
+/// The maximum number of characters of a literal, beyond which it's truncated.
+const MAX_LITERAL_LENGTH: usize = 80;
+
+/// The shape of a variable initialised with a literal that pins down its type (e.g.
+/// `export const routes = { home: '/' } satisfies RouteMap;`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueShape {
+    /// The literal on a single line, truncated with `…` if it's longer than 80 characters.
+    pub literal: String,
+    pub is_truncated: bool,
+    /// The type the literal must satisfy (e.g. `RouteMap`), if any.
+    pub satisfies: Option<String>,
+    /// Whether the literal is asserted with `as const`, which makes its type as narrow as possible.
+    pub is_const_assertion: bool,
+}
+
+impl ValueShape {
+    /// Renders the declaration of the variable with the (truncated) literal, e.g.
+    /// `const routes = { home: '/' } satisfies RouteMap;`.
+    pub fn render_declaration(&self, keyword: &str, name: &str) -> String {
+        let mut declaration = format!("{keyword} {name} = {}", self.literal);
+        if self.is_const_assertion {
+            declaration.push_str(" as const");
+        }
+        if let Some(satisfies) = &self.satisfies {
+            declaration.push_str(&format!(" satisfies {satisfies}"));
+        }
+        declaration.push(';');
+        declaration
+    }
+}
+
+/// Gets the shape of a variable initialised with a `satisfies` or `as const` expression, or
+/// `None` if the symbol isn't such a variable.
+pub fn get_value_shape(symbol: &Symbol) -> Option<ValueShape> {
+    let mut parser = make_parser();
+    let parsed_file = ParsedFile::parse(&symbol.source_code, &mut parser).ok()?;
+    get_parsed_value_shape(symbol, &parsed_file)
+}
+
+/// Like [get_value_shape], with the source code of the symbol already parsed.
+pub(crate) fn get_parsed_value_shape(
+    symbol: &Symbol,
+    parsed_file: &ParsedFile,
+) -> Option<ValueShape> {
+    let declarator = find_declarator(parsed_file.root_node(), &symbol.name, &symbol.source_code)?;
+    let mut value = declarator.child_by_field_name("value")?;
+
+    let mut satisfies = None;
+    if value.kind() == "satisfies_expression" {
+        let type_node = value.named_child(1)?;
+        satisfies = Some(normalize_node(type_node, &symbol.source_code));
+        value = value.named_child(0)?;
+    }
+    let is_const_assertion = value.kind() == "as_expression" && is_const_assertion(value);
+    if is_const_assertion {
+        value = value.named_child(0)?;
+    }
+    if satisfies.is_none() && !is_const_assertion {
+        return None;
+    }
+
+    let literal = normalize_node(value, &symbol.source_code);
+    let is_truncated = literal.chars().count() > MAX_LITERAL_LENGTH;
+    let literal = if is_truncated {
+        let truncated_literal = literal.chars().take(MAX_LITERAL_LENGTH).collect::<String>();
+        format!("{}…", truncated_literal.trim_end())
+    } else {
+        literal
+    };
+    Some(ValueShape {
+        literal,
+        is_truncated,
+        satisfies,
+        is_const_assertion,
+    })
+}
+
+/// Finds the top-level variable declarator with the given name.
+pub(crate) fn find_declarator<'a>(
+    root: Node<'a>,
+    name: &str,
+    source_code: &str,
+) -> Option<Node<'a>> {
+    let declaration = find_declaration(root)?;
+    if !matches!(
+        declaration.kind(),
+        "lexical_declaration" | "variable_declaration"
+    ) {
+        return None;
+    }
+
+    let mut cursor = declaration.walk();
+    let declarator = declaration.named_children(&mut cursor).find(|child| {
+        child.kind() == "variable_declarator"
+            && child
+                .child_by_field_name("name")
+                .is_some_and(|name_node| &source_code[name_node.byte_range()] == name)
+    });
+    declarator
+}
+
+fn is_const_assertion(as_expression: Node) -> bool {
+    let mut cursor = as_expression.walk();
+    let is_const_assertion = as_expression
+        .children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == "const");
+    is_const_assertion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_shape(name: &str, source_code: &str) -> Option<ValueShape> {
+        get_value_shape(&Symbol {
+            name: name.to_string(),
+            source_code: source_code.to_string(),
+        })
+    }
+
+    mod get_value_shape {
+        use super::*;
+
+        #[test]
+        fn satisfies_expression() {
+            let shape = get_shape(
+                "routes",
+                "export const routes = {\n  home: '/',\n  about: '/about',\n} satisfies RouteMap;",
+            );
+
+            assert_eq!(
+                shape,
+                Some(ValueShape {
+                    literal: "{ home: '/', about: '/about' }".to_string(),
+                    is_truncated: false,
+                    satisfies: Some("RouteMap".to_string()),
+                    is_const_assertion: false,
+                })
+            );
+        }
+
+        #[test]
+        fn const_assertion() {
+            let shape = get_shape(
+                "COLOURS",
+                "export const COLOURS = ['red', 'green'] as const;",
+            );
+
+            assert_eq!(
+                shape,
+                Some(ValueShape {
+                    literal: "['red', 'green']".to_string(),
+                    is_truncated: false,
+                    satisfies: None,
+                    is_const_assertion: true,
+                })
+            );
+        }
+
+        #[test]
+        fn const_assertion_and_satisfies_expression() {
+            let shape = get_shape(
+                "sizes",
+                "export const sizes = [1, 2] as const satisfies readonly number[];",
+            );
+
+            assert_eq!(
+                shape,
+                Some(ValueShape {
+                    literal: "[1, 2]".to_string(),
+                    is_truncated: false,
+                    satisfies: Some("readonly number[]".to_string()),
+                    is_const_assertion: true,
+                })
+            );
+        }
+
+        #[test]
+        fn long_literal() {
+            let properties = (0..20)
+                .map(|index| format!("key{index}: {index}"))
+                .collect::<Vec<_>>();
+            let source_code = format!(
+                "export const config = {{ {} }} as const;",
+                properties.join(", ")
+            );
+
+            let shape = get_shape("config", &source_code).unwrap();
+
+            assert!(shape.is_truncated);
+            assert!(shape.literal.ends_with('…'));
+            assert!(shape.literal.chars().count() <= MAX_LITERAL_LENGTH + 1);
+        }
+
+        #[test]
+        fn multiple_declarators() {
+            let shape = get_shape("b", "export const a = 1, b = { x: 1 } as const;");
+
+            assert_eq!(
+                shape.map(|shape| shape.literal),
+                Some("{ x: 1 }".to_string())
+            );
+        }
+
+        #[test]
+        fn type_assertion() {
+            let shape = get_shape("a", "export const a = {} as Foo;");
+
+            assert_eq!(shape, None);
+        }
+
+        #[test]
+        fn plain_initialiser() {
+            let shape = get_shape("a", "export const a = { x: 1 };");
+
+            assert_eq!(shape, None);
+        }
+
+        #[test]
+        fn non_variable() {
+            let shape = get_shape("Foo", "export interface Foo {}");
+
+            assert_eq!(shape, None);
+        }
+    }
+
+    mod render_declaration {
+        use super::*;
+
+        #[test]
+        fn satisfies_expression() {
+            let shape = ValueShape {
+                literal: "{ home: '/' }".to_string(),
+                is_truncated: false,
+                satisfies: Some("RouteMap".to_string()),
+                is_const_assertion: true,
+            };
+
+            let declaration = shape.render_declaration("const", "routes");
+
+            assert_eq!(
+                declaration,
+                "const routes = { home: '/' } as const satisfies RouteMap;"
+            );
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use daipendency_extractor::{ExtractionError, Symbol};
+use tree_sitter::Parser;
+
+use crate::api::generated::Generator;
+use crate::api::load_module_set;
+use crate::api::module::{ExportTarget, ImportTarget, Module, TypeScriptSymbol};
+use crate::api::module_set::ModuleSet;
+use crate::metadata::TSLibraryMetadata;
+use crate::options::ExtractionOptions;
+
+/// Where a symbol was found whilst walking the modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisitContext<'a> {
+    /// The path of the module the symbol is in.
+    pub module_path: &'a Path,
+    /// How many namespaces the symbol is nested in, where top-level symbols are at depth 0.
+    pub depth: usize,
+    /// The tool that generated the module, if it looks machine-generated.
+    pub generator: Option<Generator>,
+}
+
+/// Receives the symbols of each module, so the module graph can be analysed without matching on
+/// every kind of symbol.
+///
+/// Every method does nothing by default, so visitors only need to implement the ones they care
+/// about. Namespaces are visited before their content, which is visited one level deeper.
+pub trait SymbolVisitor {
+    /// Visits a declaration (e.g. class, interface, function, constant, type alias).
+    fn visit_symbol(&mut self, _context: &VisitContext, _symbol: &Symbol, _is_exported: bool) {}
+
+    /// Visits an alias for an entity in scope (e.g. `export import EventEmitter = NodeJS.EventEmitter;`),
+    /// which is treated as a declaration unless overridden.
+    fn visit_import_alias(
+        &mut self,
+        context: &VisitContext,
+        symbol: &Symbol,
+        _target: &[String],
+        is_exported: bool,
+    ) {
+        self.visit_symbol(context, symbol, is_exported);
+    }
+
+    /// Visits a TypeScript namespace.
+    fn visit_namespace(
+        &mut self,
+        _context: &VisitContext,
+        _name: &str,
+        _jsdoc: Option<&str>,
+        _is_exported: bool,
+    ) {
+    }
+
+    /// Visits an import from another module (e.g. `import Foo from './foo.js';`).
+    fn visit_import(
+        &mut self,
+        _context: &VisitContext,
+        _source_module: &str,
+        _target: &ImportTarget,
+    ) {
+    }
+
+    /// Visits an export, where the source module is `None` if the exported symbols are declared
+    /// or imported in the current module.
+    fn visit_export(
+        &mut self,
+        _context: &VisitContext,
+        _source_module: Option<&str>,
+        _target: &ExportTarget,
+    ) {
+    }
+
+    /// Visits a symbol produced by a query extension.
+    fn visit_custom_symbol(
+        &mut self,
+        _context: &VisitContext,
+        _kind: &str,
+        _name: &str,
+        _source_code: &str,
+    ) {
+    }
+}
+
+/// Walks the modules reachable from the entry points, in order of path.
+pub fn visit_modules(
+    library_metadata: &TSLibraryMetadata,
+    parser: &mut Parser,
+    options: &ExtractionOptions,
+    visitor: &mut impl SymbolVisitor,
+) -> Result<(), ExtractionError> {
+    let module_set = load_module_set(library_metadata, parser, options)?;
+    walk_module_set(&module_set, visitor);
+    Ok(())
+}
+
+/// Walks every module in the set, in order of path.
+pub(crate) fn walk_module_set(module_set: &ModuleSet, visitor: &mut impl SymbolVisitor) {
+    let mut modules = module_set.iter().collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+    for module in modules {
+        walk_module(module, visitor);
+    }
+}
+
+/// Walks the symbols of a module in the order they're declared.
+pub(crate) fn walk_module(module: &Module, visitor: &mut impl SymbolVisitor) {
+    let context = VisitContext {
+        module_path: &module.path,
+        depth: 0,
+        generator: module.generator,
+    };
+    walk_symbols(&context, &module.symbols, visitor);
+}
+
+fn walk_symbols(
+    context: &VisitContext,
+    symbols: &[TypeScriptSymbol],
+    visitor: &mut impl SymbolVisitor,
+) {
+    for symbol in symbols {
+        match symbol {
+            TypeScriptSymbol::Symbol {
+                symbol,
+                is_exported,
+            } => visitor.visit_symbol(context, symbol, *is_exported),
+            TypeScriptSymbol::ImportAlias {
+                symbol,
+                target,
+                is_exported,
+            } => visitor.visit_import_alias(context, symbol, target, *is_exported),
+            TypeScriptSymbol::Namespace {
+                name,
+                jsdoc,
+                content,
+                is_exported,
+            } => {
+                visitor.visit_namespace(context, name, jsdoc.as_deref(), *is_exported);
+                let content_context = VisitContext {
+                    depth: context.depth + 1,
+                    ..*context
+                };
+                walk_symbols(&content_context, content, visitor);
+            }
+            TypeScriptSymbol::ModuleImport {
+                source_module,
+                target,
+                ..
+            } => visitor.visit_import(context, source_module, target),
+            TypeScriptSymbol::ModuleExport {
+                source_module,
+                target,
+                ..
+            } => visitor.visit_export(context, source_module.as_deref(), target),
+            TypeScriptSymbol::Custom { kind, name, source } => {
+                visitor.visit_custom_symbol(context, kind, name, source)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_parser;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use daipendency_testing::tempdir::TempDir;
+    use std::collections::BTreeMap;
+
+    /// Records what was visited as `<depth> <kind> <name> (<module>)`.
+    #[derive(Default)]
+    struct RecordingVisitor {
+        visits: Vec<String>,
+    }
+
+    impl RecordingVisitor {
+        fn record(&mut self, context: &VisitContext, description: String) {
+            let module_name = context.module_path.file_name().unwrap().to_string_lossy();
+            self.visits
+                .push(format!("{} {description} ({module_name})", context.depth));
+        }
+    }
+
+    impl SymbolVisitor for RecordingVisitor {
+        fn visit_symbol(&mut self, context: &VisitContext, symbol: &Symbol, is_exported: bool) {
+            let prefix = if is_exported { "export " } else { "" };
+            self.record(context, format!("{prefix}symbol {}", symbol.name));
+        }
+
+        fn visit_namespace(
+            &mut self,
+            context: &VisitContext,
+            name: &str,
+            _jsdoc: Option<&str>,
+            is_exported: bool,
+        ) {
+            let prefix = if is_exported { "export " } else { "" };
+            self.record(context, format!("{prefix}namespace {name}"));
+        }
+
+        fn visit_import(
+            &mut self,
+            context: &VisitContext,
+            source_module: &str,
+            _target: &ImportTarget,
+        ) {
+            self.record(context, format!("import {source_module}"));
+        }
+
+        fn visit_export(
+            &mut self,
+            context: &VisitContext,
+            source_module: Option<&str>,
+            _target: &ExportTarget,
+        ) {
+            self.record(
+                context,
+                format!("export from {}", source_module.unwrap_or("self")),
+            );
+        }
+    }
+
+    fn visit(files: &[(&str, &str)]) -> Vec<String> {
+        let temp_dir = TempDir::new();
+        for (path, content) in files {
+            temp_dir.create_file(path, content).unwrap();
+        }
+        let library_metadata = TSLibraryMetadata {
+            name: "pkg".to_string(),
+            version: None,
+            documentation: String::new(),
+            entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: temp_dir.path.join("index.d.ts"),
+                conditions: BTreeMap::new(),
+            }]),
+        };
+
+        let mut visitor = RecordingVisitor::default();
+        visit_modules(
+            &library_metadata,
+            &mut make_parser(),
+            &ExtractionOptions::default(),
+            &mut visitor,
+        )
+        .unwrap();
+        visitor.visits
+    }
+
+    #[test]
+    fn symbols_in_declaration_order() {
+        let visits = visit(&[(
+            "index.d.ts",
+            "declare const a: string;\nexport declare function b(): void;",
+        )]);
+
+        assert_eq!(
+            visits,
+            vec!["0 symbol a (index.d.ts)", "0 export symbol b (index.d.ts)"]
+        );
+    }
+
+    #[test]
+    fn namespace_content_is_nested() {
+        let visits = visit(&[(
+            "index.d.ts",
+            "export namespace Outer {\n  namespace Inner {\n    const a: string;\n  }\n}",
+        )]);
+
+        assert_eq!(
+            visits,
+            vec![
+                "0 export namespace Outer (index.d.ts)",
+                "1 namespace Inner (index.d.ts)",
+                "2 symbol a (index.d.ts)",
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_and_exports() {
+        let visits = visit(&[
+            (
+                "index.d.ts",
+                "import { A } from './a';\nexport { A };\nexport * from './a';",
+            ),
+            ("a.d.ts", "export interface A {}"),
+        ]);
+
+        assert_eq!(
+            visits,
+            vec![
+                "0 export symbol A (a.d.ts)",
+                "0 import ./a (index.d.ts)",
+                "0 export from self (index.d.ts)",
+                "0 export from ./a (index.d.ts)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use daipendency_extractor::Namespace;
+
+use crate::api::jsdoc::{get_leading_jsdoc, get_summary};
+use crate::api::{get_symbol_kind, SymbolKind};
+use crate::report::EntryPointReport;
+
+/// The number of symbols highlighted for each entry point.
+const MAX_HIGHLIGHTS: usize = 5;
+
+/// An overview of what a package offers, meant to introduce it before its full API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilitySummary {
+    /// The headings of the README, in order, excluding those in code blocks.
+    pub headings: Vec<String>,
+    /// The entry points, in the same order as in the report.
+    pub entry_points: Vec<EntryPointCapabilities>,
+}
+
+/// The main classes and functions exported by an entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPointCapabilities {
+    pub external_path: String,
+    /// The name of the namespace in the public API (e.g. `pkg/utils`).
+    pub namespace: String,
+    /// The highest-ranked classes and functions, from the highest to the lowest.
+    pub highlights: Vec<Capability>,
+}
+
+/// A class or function highlighted in the summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capability {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The first line of its JSDoc description, if any.
+    pub summary: Option<String>,
+}
+
+/// Summarises the capabilities of a package from its README and the exports of its entry points.
+///
+/// Only classes and functions are highlighted. Those mentioned in a README heading come first,
+/// followed by those with a JSDoc description, and classes come before functions; otherwise, the
+/// order of the exports is kept.
+pub(crate) fn summarise_capabilities(
+    documentation: &str,
+    entry_points: &[EntryPointReport],
+    public_api: &[Namespace],
+) -> CapabilitySummary {
+    let headings = extract_headings(documentation);
+    let entry_points = entry_points
+        .iter()
+        .map(|report| {
+            let highlights = public_api
+                .iter()
+                .find(|namespace| namespace.name == report.namespace)
+                .map(|namespace| get_highlights(namespace, &headings))
+                .unwrap_or_default();
+            EntryPointCapabilities {
+                external_path: report.entry_point.external_path.clone(),
+                namespace: report.namespace.clone(),
+                highlights,
+            }
+        })
+        .collect();
+    CapabilitySummary {
+        headings,
+        entry_points,
+    }
+}
+
+fn get_highlights(namespace: &Namespace, headings: &[String]) -> Vec<Capability> {
+    let mut capabilities: Vec<Capability> = vec![];
+    for symbol in &namespace.symbols {
+        let Some(kind) = get_symbol_kind(symbol).filter(is_highlightable) else {
+            continue;
+        };
+        let summary = get_leading_jsdoc(&symbol.source_code).and_then(get_summary);
+        // Overloads are separate symbols with the same name, and only some may be documented
+        if let Some(existing) = capabilities
+            .iter_mut()
+            .find(|capability| capability.name == symbol.name)
+        {
+            existing.summary = existing.summary.take().or(summary);
+            continue;
+        }
+        capabilities.push(Capability {
+            name: symbol.name.clone(),
+            kind,
+            summary,
+        });
+    }
+
+    capabilities.sort_by_key(|capability| {
+        (
+            !is_in_headings(&capability.name, headings),
+            capability.summary.is_none(),
+            !matches!(capability.kind, SymbolKind::Class { .. }),
+        )
+    });
+    capabilities.truncate(MAX_HIGHLIGHTS);
+    capabilities
+}
+
+fn is_highlightable(kind: &SymbolKind) -> bool {
+    matches!(kind, SymbolKind::Class { .. } | SymbolKind::Function)
+}
+
+/// Checks whether a heading mentions the name as a whole word (e.g. `` `createClient()` ``).
+fn is_in_headings(name: &str, headings: &[String]) -> bool {
+    headings.iter().any(|heading| {
+        heading
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .any(|word| word == name)
+    })
+}
+
+/// Extracts the text of the ATX headings (e.g. `## Usage`) in Markdown, skipping code blocks.
+fn extract_headings(markdown: &str) -> Vec<String> {
+    let mut headings = vec![];
+    let mut fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed_line = line.trim_start();
+        if let Some(open_fence) = fence {
+            if trimmed_line.starts_with(open_fence) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed_line.starts_with(marker))
+        {
+            fence = Some(marker);
+            continue;
+        }
+
+        let level = trimmed_line.chars().take_while(|c| *c == '#').count();
+        let text = &trimmed_line[level..];
+        if !(1..=6).contains(&level) || !(text.is_empty() || text.starts_with(char::is_whitespace))
+        {
+            continue;
+        }
+        let text = text.trim().trim_end_matches('#').trim_end();
+        if !text.is_empty() {
+            headings.push(text.to_string());
+        }
+    }
+    headings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_symbol;
+    use crate::metadata::TSEntryPoint;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn make_entry_point(external_path: &str, namespace: &str) -> EntryPointReport {
+        EntryPointReport {
+            entry_point: TSEntryPoint {
+                external_path: external_path.to_string(),
+                internal_path: PathBuf::from("index.d.ts"),
+                conditions: BTreeMap::new(),
+            },
+            namespace: namespace.to_string(),
+            import_hints: vec![],
+            exported_namespaces: vec![],
+        }
+    }
+
+    fn get_names(capabilities: &[Capability]) -> Vec<&str> {
+        capabilities
+            .iter()
+            .map(|capability| capability.name.as_str())
+            .collect()
+    }
+
+    mod headings {
+        use super::*;
+
+        #[test]
+        fn levels() {
+            let markdown = "# pkg\n\nIntro.\n\n## Usage ##\n###### Deep";
+
+            assert_eq!(extract_headings(markdown), vec!["pkg", "Usage", "Deep"]);
+        }
+
+        #[test]
+        fn code_blocks() {
+            let markdown = "```sh\n# install\nnpm i pkg\n```\n## API";
+
+            assert_eq!(extract_headings(markdown), vec!["API"]);
+        }
+
+        #[test]
+        fn not_headings() {
+            let markdown = "#hashtag\n####### Too deep\n#";
+
+            assert_eq!(extract_headings(markdown), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn only_classes_and_functions() {
+        let namespace = Namespace {
+            name: "pkg".to_string(),
+            symbols: vec![
+                make_symbol("Options", "export interface Options {}"),
+                make_symbol("VERSION", "export declare const VERSION: string;"),
+                make_symbol("run", "export declare function run(): void;"),
+            ],
+            doc_comment: None,
+        };
+
+        let highlights = get_highlights(&namespace, &[]);
+
+        assert_eq!(
+            highlights,
+            vec![Capability {
+                name: "run".to_string(),
+                kind: SymbolKind::Function,
+                summary: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn ranking() {
+        let namespace = Namespace {
+            name: "pkg".to_string(),
+            symbols: vec![
+                make_symbol("helper", "export declare function helper(): void;"),
+                make_symbol(
+                    "format",
+                    "/** Formats a value. */\nexport declare function format(): string;",
+                ),
+                make_symbol("Client", "/** A client. */\nexport declare class Client {}"),
+                make_symbol("connect", "export declare function connect(): void;"),
+            ],
+            doc_comment: None,
+        };
+        let headings = vec!["`connect()`".to_string()];
+
+        let highlights = get_highlights(&namespace, &headings);
+
+        assert_eq!(
+            get_names(&highlights),
+            vec!["connect", "Client", "format", "helper"]
+        );
+    }
+
+    #[test]
+    fn overloads() {
+        let namespace = Namespace {
+            name: "pkg".to_string(),
+            symbols: vec![
+                make_symbol("parse", "export declare function parse(a: string): void;"),
+                make_symbol(
+                    "parse",
+                    "/**\n * Parses a value.\n *\n * @param a The value.\n */\nexport declare function parse(a: number): void;",
+                ),
+            ],
+            doc_comment: None,
+        };
+
+        let highlights = get_highlights(&namespace, &[]);
+
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].summary, Some("Parses a value.".to_string()));
+    }
+
+    #[test]
+    fn limit() {
+        let symbols = (0..MAX_HIGHLIGHTS + 2)
+            .map(|index| {
+                let name = format!("f{index}");
+                let source_code = format!("export declare function {name}(): void;");
+                make_symbol(&name, &source_code)
+            })
+            .collect();
+        let namespace = Namespace {
+            name: "pkg".to_string(),
+            symbols,
+            doc_comment: None,
+        };
+
+        let highlights = get_highlights(&namespace, &[]);
+
+        assert_eq!(highlights.len(), MAX_HIGHLIGHTS);
+        assert_eq!(highlights[0].name, "f0");
+    }
+
+    #[test]
+    fn entry_points() {
+        let entry_points = vec![
+            make_entry_point(".", "pkg"),
+            make_entry_point("./empty", "pkg/empty"),
+        ];
+        let public_api = vec![Namespace {
+            name: "pkg".to_string(),
+            symbols: vec![make_symbol("run", "export declare function run(): void;")],
+            doc_comment: None,
+        }];
+
+        let summary = summarise_capabilities("# pkg", &entry_points, &public_api);
+
+        assert_eq!(summary.headings, vec!["pkg"]);
+        assert_eq!(summary.entry_points.len(), 2);
+        assert_eq!(summary.entry_points[0].external_path, ".");
+        assert_eq!(get_names(&summary.entry_points[0].highlights), vec!["run"]);
+        assert_eq!(summary.entry_points[1].namespace, "pkg/empty");
+        assert!(summary.entry_points[1].highlights.is_empty());
+    }
+}
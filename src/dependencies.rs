@@ -1,33 +1,236 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use daipendency_extractor::DependencyResolutionError;
+use serde::Deserialize;
 
-pub fn resolve_dependency_path(
-    name: &str,
-    dependant_path: &Path,
-) -> Result<PathBuf, DependencyResolutionError> {
-    if let Some(path) = recursive_resolve_dependency_path(name, dependant_path) {
-        Ok(path)
-    } else {
-        Err(DependencyResolutionError::MissingDependency(
-            name.to_string(),
-        ))
-    }
+use crate::diagnostics::Diagnostic;
+use crate::jsonc::parse_jsonc;
+use crate::options::{DependencyFallback, ExtractionOptions};
+
+/// Resolves dependencies like Node.js, looking for `node_modules` in the dependant package and
+/// then in the packages containing it, followed by the fallback directories if any. Packages
+/// located by the caller take precedence over all of them.
+///
+/// Every lookup is cached so that resolving the dependencies of the packages of a project doesn't
+/// check the same directories over and over. The cache is meant to last for one extraction
+/// session, so changes to the filesystem after a directory has been checked aren't noticed.
+#[derive(Debug, Default)]
+pub struct DependencyResolver {
+    /// The directories of the packages located by the caller, keyed by package name.
+    workspace_packages: BTreeMap<String, PathBuf>,
+    /// The directories to search when a dependency isn't in `node_modules`, in order, along with
+    /// the fallback each comes from.
+    fallback_directories: Vec<(DependencyFallback, PathBuf)>,
+    /// Whether each directory checked has a `package.json`.
+    manifest_directories: Mutex<HashMap<PathBuf, bool>>,
+    /// The peer dependencies of each dependant package.
+    peer_dependencies: Mutex<HashMap<PathBuf, HashSet<String>>>,
+    /// The location of each dependency looked up from a directory, if it was found.
+    locations: Mutex<HashMap<(PathBuf, String), Option<PathBuf>>>,
+    /// The location of each dependency looked up in the fallback directories, if it was found.
+    fallback_locations: Mutex<HashMap<String, Option<PathBuf>>>,
+    /// The dependencies that were found in fallback directories.
+    diagnostics: Mutex<Vec<Diagnostic>>,
 }
 
-fn recursive_resolve_dependency_path(name: &str, dependant_path: &Path) -> Option<PathBuf> {
-    if !dependant_path.join("package.json").exists() {
-        return None;
+impl DependencyResolver {
+    /// Creates a resolver using the workspace packages in the options and the fallbacks as
+    /// configured in the environment of the process.
+    pub fn new(options: &ExtractionOptions) -> Self {
+        Self {
+            workspace_packages: options.workspace_packages.clone(),
+            fallback_directories: get_fallback_directories(&options.dependency_fallbacks, |name| {
+                std::env::var_os(name)
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Resolves a dependency of the package at `dependant_path`.
+    ///
+    /// Packages located by the caller are used as is, without looking in `node_modules`.
+    ///
+    /// If the dependency is a peer dependency and there's a `host_root` (the root of the project
+    /// the dependant is installed in), it's resolved from the host project first, as the host is
+    /// what provides peer dependencies.
+    pub fn resolve(
+        &self,
+        name: &str,
+        dependant_path: &Path,
+        host_root: Option<&Path>,
+    ) -> Result<PathBuf, DependencyResolutionError> {
+        self.resolve_from_workspace(name)
+            .or_else(|| {
+                host_root
+                    .filter(|_| self.is_peer_dependency(name, dependant_path))
+                    .and_then(|host_root| self.resolve_from(name, host_root))
+            })
+            .or_else(|| self.resolve_from(name, dependant_path))
+            .or_else(|| self.resolve_from_fallbacks(name))
+            .ok_or_else(|| DependencyResolutionError::MissingDependency(name.to_string()))
+    }
+
+    /// Lists the dependencies that were only found thanks to a fallback.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.lock().unwrap().clone()
+    }
+
+    fn resolve_from_workspace(&self, name: &str) -> Option<PathBuf> {
+        self.workspace_packages
+            .get(name)
+            .filter(|directory| directory.is_dir())
+            .and_then(|directory| std::path::absolute(directory).ok())
+    }
+
+    fn resolve_from(&self, name: &str, directory: &Path) -> Option<PathBuf> {
+        let key = (directory.to_path_buf(), name.to_string());
+        if let Some(location) = self.locations.lock().unwrap().get(&key) {
+            return location.clone();
+        }
+
+        let location = if self.has_manifest(directory) {
+            let node_modules_path = directory.join("node_modules").join(name);
+            if node_modules_path.exists() {
+                Some(node_modules_path)
+            } else {
+                directory
+                    .parent()
+                    .and_then(|parent| self.resolve_from(name, parent))
+            }
+        } else if is_node_modules_directory(directory) {
+            // Installed packages find their hoisted dependencies in the project containing them
+            directory
+                .parent()
+                .and_then(|parent| self.resolve_from(name, parent))
+        } else {
+            None
+        };
+
+        self.locations.lock().unwrap().insert(key, location.clone());
+        location
+    }
+
+    fn resolve_from_fallbacks(&self, name: &str) -> Option<PathBuf> {
+        if let Some(location) = self.fallback_locations.lock().unwrap().get(name) {
+            return location.clone();
+        }
+
+        let found = self
+            .fallback_directories
+            .iter()
+            .map(|(fallback, directory)| (fallback, directory.join(name)))
+            .find(|(_, path)| path.exists());
+        if let Some((fallback, path)) = &found {
+            self.diagnostics
+                .lock()
+                .unwrap()
+                .push(Diagnostic::DependencyFallback {
+                    name: name.to_string(),
+                    fallback: **fallback,
+                    path: path.clone(),
+                });
+        }
+
+        let location = found.map(|(_, path)| path);
+        self.fallback_locations
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), location.clone());
+        location
+    }
+
+    fn is_peer_dependency(&self, name: &str, dependant_path: &Path) -> bool {
+        self.peer_dependencies
+            .lock()
+            .unwrap()
+            .entry(dependant_path.to_path_buf())
+            .or_insert_with(|| read_peer_dependencies(dependant_path))
+            .contains(name)
     }
 
-    let node_modules_path = dependant_path.join("node_modules").join(name);
-    if node_modules_path.exists() {
-        return Some(node_modules_path);
+    fn has_manifest(&self, directory: &Path) -> bool {
+        *self
+            .manifest_directories
+            .lock()
+            .unwrap()
+            .entry(directory.to_path_buf())
+            .or_insert_with(|| directory.join("package.json").exists())
     }
+}
+
+/// Checks whether a directory is a `node_modules` directory or a scope in one (e.g.
+/// `node_modules/@types`), neither of which has a manifest.
+fn is_node_modules_directory(directory: &Path) -> bool {
+    let is_node_modules = |directory: &Path| directory.file_name() == Some("node_modules".as_ref());
+    let is_scope = directory
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('@'));
+    is_node_modules(directory) || (is_scope && directory.parent().is_some_and(is_node_modules))
+}
 
-    dependant_path
-        .parent()
-        .and_then(|parent| recursive_resolve_dependency_path(name, parent))
+/// The subset of a `package.json` needed to tell peer dependencies apart.
+#[derive(Debug, Deserialize)]
+struct PeerDependencyManifest {
+    #[serde(default, rename = "peerDependencies")]
+    peer_dependencies: HashMap<String, String>,
+}
+
+/// Reads the names of the peer dependencies of a package, ignoring unreadable manifests.
+fn read_peer_dependencies(package_path: &Path) -> HashSet<String> {
+    std::fs::read_to_string(package_path.join("package.json"))
+        .ok()
+        .and_then(|content| parse_jsonc::<PeerDependencyManifest>(&content).ok())
+        .map(|manifest| manifest.peer_dependencies.into_keys().collect())
+        .unwrap_or_default()
+}
+
+/// Lists the directories of each fallback, in order, given a way to read environment variables.
+fn get_fallback_directories(
+    fallbacks: &[DependencyFallback],
+    get_env: impl Fn(&str) -> Option<OsString>,
+) -> Vec<(DependencyFallback, PathBuf)> {
+    let mut directories = vec![];
+    for fallback in fallbacks {
+        let fallback_directories = match fallback {
+            DependencyFallback::NodePath => get_env("NODE_PATH")
+                .map(|node_path| {
+                    std::env::split_paths(&node_path)
+                        .filter(|path| !path.as_os_str().is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            DependencyFallback::GlobalInstallation => {
+                let prefix = get_env("NPM_CONFIG_PREFIX").or_else(|| get_env("npm_config_prefix"));
+                let home = get_env("HOME").or_else(|| get_env("USERPROFILE"));
+                let mut global_directories = vec![];
+                if let Some(prefix) = prefix {
+                    let prefix = PathBuf::from(prefix);
+                    // npm installs global packages in the prefix itself on Windows.
+                    global_directories.push(if cfg!(windows) {
+                        prefix.join("node_modules")
+                    } else {
+                        prefix.join("lib").join("node_modules")
+                    });
+                }
+                if let Some(home) = home {
+                    let home = PathBuf::from(home);
+                    global_directories.push(home.join(".node_modules"));
+                    global_directories.push(home.join(".node_libraries"));
+                }
+                global_directories
+            }
+        };
+        directories.extend(
+            fallback_directories
+                .into_iter()
+                .map(|directory| (*fallback, directory)),
+        );
+    }
+    directories
 }
 
 #[cfg(test)]
@@ -37,6 +240,13 @@ mod tests {
     use daipendency_testing::tempdir::TempDir;
     use std::fs;
 
+    fn resolve_dependency_path(
+        name: &str,
+        dependant_path: &Path,
+    ) -> Result<PathBuf, DependencyResolutionError> {
+        DependencyResolver::default().resolve(name, dependant_path, None)
+    }
+
     #[test]
     fn missing_manifest() {
         let temp_dir = TempDir::new();
@@ -76,7 +286,7 @@ mod tests {
         let child_manifest_path = temp_dir.create_file("child/package.json", "{}").unwrap();
         let child_directory = child_manifest_path.parent().unwrap();
 
-        let result = resolve_dependency_path("some-dep", &child_directory);
+        let result = resolve_dependency_path("some-dep", child_directory);
 
         assert_eq!(result.unwrap(), parent_path.join("node_modules/some-dep"));
     }
@@ -95,11 +305,394 @@ mod tests {
             .unwrap();
         let child_directory = child_manifest_path.parent().unwrap();
 
-        let result = resolve_dependency_path("some-dep", &child_directory);
+        let result = resolve_dependency_path("some-dep", child_directory);
 
         assert_eq!(
             result.unwrap(),
             grandparent_path.join("node_modules/some-dep")
         );
     }
+
+    #[test]
+    fn nested_copies() {
+        let temp_dir = TempDir::new();
+        temp_dir.create_file("package.json", "{}").unwrap();
+        fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+        let plugin_manifest_path = temp_dir
+            .create_file("node_modules/plugin/package.json", "{}")
+            .unwrap();
+        let plugin_path = plugin_manifest_path.parent().unwrap();
+        fs::create_dir_all(plugin_path.join("node_modules/some-dep")).unwrap();
+        let resolver = DependencyResolver::default();
+
+        let plugin_result = resolver.resolve("some-dep", plugin_path, None);
+        let root_result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+        assert_eq!(
+            plugin_result.unwrap(),
+            plugin_path.join("node_modules/some-dep")
+        );
+        assert_eq!(
+            root_result.unwrap(),
+            temp_dir.path.join("node_modules/some-dep")
+        );
+    }
+
+    #[test]
+    fn hoisted_dependency() {
+        let temp_dir = TempDir::new();
+        temp_dir.create_file("package.json", "{}").unwrap();
+        fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+        let plugin_manifest_path = temp_dir
+            .create_file("node_modules/@scope/plugin/package.json", "{}")
+            .unwrap();
+
+        let result = resolve_dependency_path("some-dep", plugin_manifest_path.parent().unwrap());
+
+        assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/some-dep"));
+    }
+
+    mod cache {
+        use super::*;
+
+        #[test]
+        fn resolution_is_cached() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+            let resolver = DependencyResolver::default();
+            resolver.resolve("some-dep", &temp_dir.path, None).unwrap();
+            fs::remove_dir_all(temp_dir.path.join("node_modules")).unwrap();
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/some-dep"));
+        }
+
+        #[test]
+        fn missing_dependency_is_cached() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            let resolver = DependencyResolver::default();
+            resolver
+                .resolve("some-dep", &temp_dir.path, None)
+                .unwrap_err();
+            fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_matches!(
+                result,
+                Err(DependencyResolutionError::MissingDependency(msg)) if msg == "some-dep"
+            );
+        }
+
+        #[test]
+        fn ancestors_are_shared() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/some-dep/package.json", "{}")
+                .unwrap();
+            let first_manifest = temp_dir.create_file("first/package.json", "{}").unwrap();
+            let second_manifest = temp_dir.create_file("second/package.json", "{}").unwrap();
+            let resolver = DependencyResolver::default();
+            resolver
+                .resolve("some-dep", first_manifest.parent().unwrap(), None)
+                .unwrap();
+            fs::remove_file(temp_dir.path.join("package.json")).unwrap();
+
+            let result = resolver.resolve("some-dep", second_manifest.parent().unwrap(), None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/some-dep"));
+        }
+    }
+
+    mod fallbacks {
+        use super::*;
+
+        fn make_resolver(
+            fallback_directories: &[(DependencyFallback, &Path)],
+        ) -> DependencyResolver {
+            DependencyResolver {
+                fallback_directories: fallback_directories
+                    .iter()
+                    .map(|(fallback, directory)| (*fallback, directory.to_path_buf()))
+                    .collect(),
+                ..DependencyResolver::default()
+            }
+        }
+
+        #[test]
+        fn node_modules_take_precedence() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+            fs::create_dir_all(temp_dir.path.join("global/some-dep")).unwrap();
+            let resolver =
+                make_resolver(&[(DependencyFallback::NodePath, &temp_dir.path.join("global"))]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/some-dep"));
+            assert!(resolver.diagnostics().is_empty());
+        }
+
+        #[test]
+        fn dependency_in_fallback_directory() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("project/package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("global/some-dep")).unwrap();
+            let resolver = make_resolver(&[
+                (DependencyFallback::NodePath, &temp_dir.path.join("missing")),
+                (
+                    DependencyFallback::GlobalInstallation,
+                    &temp_dir.path.join("global"),
+                ),
+            ]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path.join("project"), None);
+            resolver
+                .resolve("some-dep", &temp_dir.path.join("project"), None)
+                .unwrap();
+
+            let path = temp_dir.path.join("global/some-dep");
+            assert_eq!(result.unwrap(), path);
+            assert_eq!(
+                resolver.diagnostics(),
+                vec![Diagnostic::DependencyFallback {
+                    name: "some-dep".to_string(),
+                    fallback: DependencyFallback::GlobalInstallation,
+                    path,
+                }]
+            );
+        }
+
+        #[test]
+        fn dependency_missing_from_fallbacks() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            let resolver =
+                make_resolver(&[(DependencyFallback::NodePath, &temp_dir.path.join("global"))]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_matches!(
+                result,
+                Err(DependencyResolutionError::MissingDependency(msg)) if msg == "some-dep"
+            );
+            assert!(resolver.diagnostics().is_empty());
+        }
+
+        #[test]
+        fn node_path_directories() {
+            let node_path = std::env::join_paths(["/first", "/second"]).unwrap();
+
+            let directories = get_fallback_directories(&[DependencyFallback::NodePath], |name| {
+                (name == "NODE_PATH").then(|| node_path.clone())
+            });
+
+            assert_eq!(
+                directories,
+                vec![
+                    (DependencyFallback::NodePath, PathBuf::from("/first")),
+                    (DependencyFallback::NodePath, PathBuf::from("/second")),
+                ]
+            );
+        }
+
+        #[test]
+        fn unset_environment_variables() {
+            let directories = get_fallback_directories(
+                &[
+                    DependencyFallback::NodePath,
+                    DependencyFallback::GlobalInstallation,
+                ],
+                |_| None,
+            );
+
+            assert!(directories.is_empty());
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn global_installation_directories() {
+            let directories =
+                get_fallback_directories(&[DependencyFallback::GlobalInstallation], |name| {
+                    match name {
+                        "NPM_CONFIG_PREFIX" => Some("/usr/local".into()),
+                        "HOME" => Some("/home/user".into()),
+                        _ => None,
+                    }
+                });
+
+            assert_eq!(
+                directories
+                    .into_iter()
+                    .map(|(_, directory)| directory)
+                    .collect::<Vec<_>>(),
+                vec![
+                    PathBuf::from("/usr/local/lib/node_modules"),
+                    PathBuf::from("/home/user/.node_modules"),
+                    PathBuf::from("/home/user/.node_libraries"),
+                ]
+            );
+        }
+    }
+
+    mod workspace_packages {
+        use super::*;
+
+        fn make_resolver(workspace_packages: &[(&str, PathBuf)]) -> DependencyResolver {
+            DependencyResolver {
+                workspace_packages: workspace_packages
+                    .iter()
+                    .map(|(name, directory)| (name.to_string(), directory.clone()))
+                    .collect(),
+                ..DependencyResolver::default()
+            }
+        }
+
+        #[test]
+        fn workspace_package_takes_precedence() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+            fs::create_dir_all(temp_dir.path.join("packages/some-dep")).unwrap();
+            let resolver = make_resolver(&[("some-dep", temp_dir.path.join("packages/some-dep"))]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("packages/some-dep"));
+        }
+
+        #[test]
+        fn uninstalled_workspace_package() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("packages/some-dep")).unwrap();
+            let resolver = make_resolver(&[("some-dep", temp_dir.path.join("packages/some-dep"))]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("packages/some-dep"));
+        }
+
+        #[test]
+        fn missing_workspace_package() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("node_modules/some-dep")).unwrap();
+            let resolver = make_resolver(&[("some-dep", temp_dir.path.join("packages/some-dep"))]);
+
+            let result = resolver.resolve("some-dep", &temp_dir.path, None);
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/some-dep"));
+        }
+
+        #[test]
+        fn relative_directory() {
+            let resolver = make_resolver(&[("some-dep", PathBuf::from("src"))]);
+
+            let result = resolver.resolve("some-dep", Path::new("/nonexistent"), None);
+
+            assert_eq!(
+                result.unwrap(),
+                std::env::current_dir().unwrap().join("src")
+            );
+        }
+
+        #[test]
+        fn other_dependencies() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            fs::create_dir_all(temp_dir.path.join("node_modules/other-dep")).unwrap();
+            let resolver = make_resolver(&[("some-dep", temp_dir.path.join("packages/some-dep"))]);
+
+            let result = resolver.resolve("other-dep", &temp_dir.path, None);
+
+            assert_eq!(
+                result.unwrap(),
+                temp_dir.path.join("node_modules/other-dep")
+            );
+        }
+    }
+
+    mod peer_dependencies {
+        use super::*;
+
+        /// Creates a host project with a plugin installed in it, which has its own copy of `vite`
+        /// besides the host's.
+        fn make_host_project() -> (TempDir, PathBuf) {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", "{}")
+                .unwrap();
+            temp_dir
+                .create_file("node_modules/typescript/package.json", "{}")
+                .unwrap();
+            let plugin_manifest = temp_dir
+                .create_file(
+                    "node_modules/vite-plugin/package.json",
+                    r#"{"peerDependencies": {"vite": "^5.0.0"}}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "node_modules/vite-plugin/node_modules/vite/package.json",
+                    "{}",
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "node_modules/vite-plugin/node_modules/typescript/package.json",
+                    "{}",
+                )
+                .unwrap();
+            let plugin_path = plugin_manifest.parent().unwrap().to_path_buf();
+            (temp_dir, plugin_path)
+        }
+
+        #[test]
+        fn peer_dependency_from_host() {
+            let (temp_dir, plugin_path) = make_host_project();
+            let resolver = DependencyResolver::default();
+
+            let result = resolver.resolve("vite", &plugin_path, Some(&temp_dir.path));
+
+            assert_eq!(result.unwrap(), temp_dir.path.join("node_modules/vite"));
+        }
+
+        #[test]
+        fn regular_dependency_from_dependant() {
+            let (temp_dir, plugin_path) = make_host_project();
+            let resolver = DependencyResolver::default();
+
+            let result = resolver.resolve("typescript", &plugin_path, Some(&temp_dir.path));
+
+            assert_eq!(result.unwrap(), plugin_path.join("node_modules/typescript"));
+        }
+
+        #[test]
+        fn peer_dependency_without_host() {
+            let (_temp_dir, plugin_path) = make_host_project();
+            let resolver = DependencyResolver::default();
+
+            let result = resolver.resolve("vite", &plugin_path, None);
+
+            assert_eq!(result.unwrap(), plugin_path.join("node_modules/vite"));
+        }
+
+        #[test]
+        fn peer_dependency_missing_from_host() {
+            let (temp_dir, plugin_path) = make_host_project();
+            std::fs::remove_dir_all(temp_dir.path.join("node_modules/vite")).unwrap();
+            let resolver = DependencyResolver::default();
+
+            let result = resolver.resolve("vite", &plugin_path, Some(&temp_dir.path));
+
+            assert_eq!(result.unwrap(), plugin_path.join("node_modules/vite"));
+        }
+    }
 }
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::options::DependencyFallback;
+use crate::paths::relativise_path;
+
+/// A problem or assumption encountered during extraction that doesn't prevent it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// The manifest doesn't specify the declarations, so they were found in a conventional location.
+    InferredEntryPoint {
+        external_path: String,
+        internal_path: PathBuf,
+    },
+    /// No condition of an export provides declarations directly, so the declarations were taken
+    /// from a nested condition (e.g. `browser.types`).
+    FallbackCondition {
+        external_path: String,
+        /// The conditions leading to the declarations, outermost first (e.g. `["browser"]`).
+        conditions: Vec<String>,
+    },
+    /// No condition of an export provides declarations, so those next to the JavaScript file of a
+    /// runtime condition were used instead (e.g. `./index.d.mts` for `"import": "./index.mjs"`).
+    SiblingDeclarations {
+        external_path: String,
+        /// The conditions leading to the JavaScript file, outermost first (e.g. `["import"]`).
+        conditions: Vec<String>,
+        /// The path of the declarations, as it would be written in the manifest.
+        declarations: String,
+    },
+    /// The declarations of an export point to a directory (e.g. `"types": "./dist/types"`), so
+    /// the index file in the directory was used instead.
+    DirectoryTarget {
+        external_path: String,
+        directory: PathBuf,
+        internal_path: PathBuf,
+    },
+    /// The types of an export point to a TypeScript source file (e.g. `src/index.ts`) rather than
+    /// declarations, so the declarations were synthesised from the source code.
+    SourceEntryPoint {
+        external_path: String,
+        internal_path: PathBuf,
+    },
+    /// A module imports a package that couldn't be found (e.g. an optional dependency that isn't
+    /// installed), so the types it refers to are missing.
+    UnresolvedExternal {
+        specifier: String,
+        importer: PathBuf,
+    },
+    /// A dependency isn't in any `node_modules` directory, so it was found using a fallback.
+    DependencyFallback {
+        name: String,
+        fallback: DependencyFallback,
+        path: PathBuf,
+    },
+    /// Declaration files reachable from the entry points were left out, as they look like tests
+    /// or demos or match [crate::ExtractionOptions::excluded_files].
+    ExcludedFiles {
+        /// The files left out, sorted.
+        paths: Vec<PathBuf>,
+    },
+    /// A module has namespaces nested so deeply that the innermost ones were left out.
+    NamespacesTooDeep { path: PathBuf },
+    /// The default export of a module leads back to itself through default imports (e.g. two
+    /// modules that each do `import x from './other'; export default x;`), so it was left out.
+    CircularDefaultExport {
+        path: PathBuf,
+        /// The local name exported as the default.
+        name: String,
+    },
+    /// Files reachable from the entry points aren't TypeScript (e.g. `.wasm` or `.json` assets),
+    /// so they were left out rather than parsed.
+    AssetReferences {
+        /// The files left out, sorted.
+        paths: Vec<PathBuf>,
+    },
+    /// Entry points or declaration files reachable from them aren't covered by the `files` field
+    /// of the manifest, so they wouldn't be published, as checked with
+    /// [crate::ExtractionOptions::unpublished_files].
+    UnpublishedFiles {
+        /// The unpublished files, sorted.
+        paths: Vec<PathBuf>,
+        /// Whether the files were left out rather than used.
+        is_skipped: bool,
+    },
+}
+
+impl Diagnostic {
+    /// Makes the paths in the diagnostic relative to the root of the package (see
+    /// [crate::ExtractionReport::relativise_path]), so that its message doesn't depend on where
+    /// the package is on disk.
+    pub fn relativise_paths(&self, root: &Path) -> Self {
+        let relativise = |path: &PathBuf| relativise_path(path, root);
+        match self {
+            Diagnostic::InferredEntryPoint {
+                external_path,
+                internal_path,
+            } => Diagnostic::InferredEntryPoint {
+                external_path: external_path.clone(),
+                internal_path: relativise(internal_path),
+            },
+            Diagnostic::FallbackCondition { .. } | Diagnostic::SiblingDeclarations { .. } => {
+                self.clone()
+            }
+            Diagnostic::DirectoryTarget {
+                external_path,
+                directory,
+                internal_path,
+            } => Diagnostic::DirectoryTarget {
+                external_path: external_path.clone(),
+                directory: relativise(directory),
+                internal_path: relativise(internal_path),
+            },
+            Diagnostic::SourceEntryPoint {
+                external_path,
+                internal_path,
+            } => Diagnostic::SourceEntryPoint {
+                external_path: external_path.clone(),
+                internal_path: relativise(internal_path),
+            },
+            Diagnostic::UnresolvedExternal {
+                specifier,
+                importer,
+            } => Diagnostic::UnresolvedExternal {
+                specifier: specifier.clone(),
+                importer: relativise(importer),
+            },
+            Diagnostic::DependencyFallback {
+                name,
+                fallback,
+                path,
+            } => Diagnostic::DependencyFallback {
+                name: name.clone(),
+                fallback: *fallback,
+                path: relativise(path),
+            },
+            Diagnostic::ExcludedFiles { paths } => Diagnostic::ExcludedFiles {
+                paths: paths.iter().map(relativise).collect(),
+            },
+            Diagnostic::NamespacesTooDeep { path } => Diagnostic::NamespacesTooDeep {
+                path: relativise(path),
+            },
+            Diagnostic::CircularDefaultExport { path, name } => Diagnostic::CircularDefaultExport {
+                path: relativise(path),
+                name: name.clone(),
+            },
+            Diagnostic::AssetReferences { paths } => Diagnostic::AssetReferences {
+                paths: paths.iter().map(relativise).collect(),
+            },
+            Diagnostic::UnpublishedFiles { paths, is_skipped } => Diagnostic::UnpublishedFiles {
+                paths: paths.iter().map(relativise).collect(),
+                is_skipped: *is_skipped,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::InferredEntryPoint {
+                external_path,
+                internal_path,
+            } => write!(
+                f,
+                "Inferred entry point '{external_path}' from '{}' as the manifest doesn't specify any types",
+                internal_path.display()
+            ),
+            Diagnostic::FallbackCondition {
+                external_path,
+                conditions,
+            } => write!(
+                f,
+                "Used the types of the '{}' condition for export '{external_path}' as it has no top-level types",
+                conditions.join(".")
+            ),
+            Diagnostic::SiblingDeclarations {
+                external_path,
+                conditions,
+                declarations,
+            } => write!(
+                f,
+                "Used '{declarations}' next to the target of the '{}' condition for export '{external_path}' as it has no types",
+                conditions.join(".")
+            ),
+            Diagnostic::DirectoryTarget {
+                external_path,
+                directory,
+                internal_path,
+            } => write!(
+                f,
+                "Used '{}' for export '{external_path}' as its types point to the directory '{}'",
+                internal_path.display(),
+                directory.display()
+            ),
+            Diagnostic::SourceEntryPoint {
+                external_path,
+                internal_path,
+            } => write!(
+                f,
+                "Synthesised declarations for export '{external_path}' from the source file '{}'",
+                internal_path.display()
+            ),
+            Diagnostic::UnresolvedExternal {
+                specifier,
+                importer,
+            } => write!(
+                f,
+                "Couldn't find the package of '{specifier}', imported by '{}'",
+                importer.display()
+            ),
+            Diagnostic::DependencyFallback {
+                name,
+                fallback,
+                path,
+            } => write!(
+                f,
+                "Resolved dependency '{name}' to '{}' using {fallback} as it isn't in node_modules",
+                path.display()
+            ),
+            Diagnostic::ExcludedFiles { paths } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| format!("'{}'", path.display()))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "Left out {} excluded declaration file(s): {}",
+                    paths.len(),
+                    paths.join(", ")
+                )
+            }
+            Diagnostic::NamespacesTooDeep { path } => write!(
+                f,
+                "Left out the namespaces nested too deeply in '{}'",
+                path.display()
+            ),
+            Diagnostic::CircularDefaultExport { path, name } => write!(
+                f,
+                "Left out the default export '{name}' of '{}' as it leads back to itself",
+                path.display()
+            ),
+            Diagnostic::AssetReferences { paths } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| format!("'{}'", path.display()))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "Left out {} referenced file(s) that aren't TypeScript: {}",
+                    paths.len(),
+                    paths.join(", ")
+                )
+            }
+            Diagnostic::UnpublishedFiles { paths, is_skipped } => {
+                let paths = paths
+                    .iter()
+                    .map(|path| format!("'{}'", path.display()))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "{} {} file(s) that the manifest doesn't publish: {}",
+                    if *is_skipped { "Left out" } else { "Used" },
+                    paths.len(),
+                    paths.join(", ")
+                )
+            }
+        }
+    }
+}
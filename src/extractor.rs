@@ -1,23 +1,94 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use crate::{
-    api, dependencies,
+    api::{
+        self,
+        queries::{self, get_typescript_language},
+        PublicApiIter,
+    },
+    dependencies::DependencyResolver,
+    diagnostics::Diagnostic,
     metadata::{extract_metadata, TSEntryPointSet, TSLibraryMetadata},
+    options::ExtractionOptions,
+    report::ExtractionReport,
 };
 use daipendency_extractor::{
-    DependencyResolutionError, ExtractionError, Extractor, LibraryMetadataError, Namespace,
+    get_parser, DependencyResolutionError, ExtractionError, Extractor, LibraryMetadataError,
+    Namespace,
 };
 use tree_sitter::{Language, Parser};
 
-pub struct TypeScriptExtractor;
+/// Extracts the public API of TypeScript packages from their declaration files.
+///
+/// Use [TypeScriptExtractor::default] for the default [ExtractionOptions].
+///
+/// Dependency lookups are cached for the lifetime of the extractor (and shared with its clones),
+/// so a new extractor should be used once the `node_modules` directories may have changed.
+#[derive(Debug, Clone, Default)]
+pub struct TypeScriptExtractor {
+    options: ExtractionOptions,
+    dependency_resolver: Arc<DependencyResolver>,
+}
+
+impl TypeScriptExtractor {
+    pub fn new(options: ExtractionOptions) -> Self {
+        let dependency_resolver = DependencyResolver::new(&options);
+        Self {
+            options,
+            dependency_resolver: Arc::new(dependency_resolver),
+        }
+    }
+
+    /// Loads the TypeScript and TSX grammars and compiles the built-in queries ahead of the first
+    /// extraction, so that latency-sensitive hosts can pay that cost at startup instead.
+    ///
+    /// The grammars and queries are shared by every extractor, so this only needs calling once.
+    pub fn warm_up() {
+        queries::warm_up();
+    }
+
+    /// Lists the dependencies resolved so far that were only found thanks to one of the
+    /// [ExtractionOptions::dependency_fallbacks].
+    pub fn dependency_diagnostics(&self) -> Vec<Diagnostic> {
+        self.dependency_resolver.diagnostics()
+    }
+
+    /// Extracts the metadata and public API of the package at `path` in one go.
+    pub fn extract(&self, path: &Path) -> Result<ExtractionReport, ExtractionError> {
+        let metadata = self
+            .get_library_metadata(path)
+            .map_err(|error| match error {
+                LibraryMetadataError::MissingManifest(error) => ExtractionError::Io(error),
+                LibraryMetadataError::MalformedManifest(message) => {
+                    ExtractionError::Malformed(message)
+                }
+            })?;
+        let mut parser = get_parser(&self.get_parser_language())
+            .map_err(|error| ExtractionError::Malformed(error.to_string()))?;
+        ExtractionReport::build(metadata, path, &mut parser, &self.options)
+    }
+
+    /// Extracts the public API lazily, parsing the modules of one entry point at a time.
+    ///
+    /// This yields the same namespaces as [Extractor::extract_public_api] but only keeps the
+    /// modules of the current entry point in memory.
+    pub fn iter_public_api<'a>(
+        &'a self,
+        library_metadata: &'a TSLibraryMetadata,
+        parser: &'a mut Parser,
+    ) -> PublicApiIter<'a> {
+        PublicApiIter::new(library_metadata, parser, &self.options)
+    }
+}
 
 impl Extractor<TSEntryPointSet> for TypeScriptExtractor {
     fn get_parser_language(&self) -> Language {
-        tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+        get_typescript_language().clone()
     }
 
     fn get_library_metadata(&self, path: &Path) -> Result<TSLibraryMetadata, LibraryMetadataError> {
-        extract_metadata(path)
+        extract_metadata(path, &self.options)
     }
 
     fn extract_public_api(
@@ -25,7 +96,7 @@ impl Extractor<TSEntryPointSet> for TypeScriptExtractor {
         library_metadata: &TSLibraryMetadata,
         parser: &mut Parser,
     ) -> Result<Vec<Namespace>, ExtractionError> {
-        api::extract_public_api(library_metadata, parser)
+        api::extract_public_api(library_metadata, parser, &self.options)
     }
 
     fn resolve_dependency_path(
@@ -33,6 +104,7 @@ impl Extractor<TSEntryPointSet> for TypeScriptExtractor {
         name: &str,
         dependant_path: &Path,
     ) -> Result<PathBuf, DependencyResolutionError> {
-        dependencies::resolve_dependency_path(name, dependant_path)
+        self.dependency_resolver
+            .resolve(name, dependant_path, self.options.host_root.as_deref())
     }
 }
@@ -0,0 +1,150 @@
+mod compact;
+mod formatter;
+mod summary;
+mod tokens;
+mod typedoc;
+
+use daipendency_extractor::Namespace;
+
+use crate::api::queries::make_parser;
+use crate::metadata::TSLibraryMetadata;
+
+pub use compact::render_compact;
+pub use formatter::{
+    CompactFormatter, FormatterRegistry, JsonFormatter, MarkdownFormatter, OutputFormatter,
+};
+pub use summary::{render_summary, summarise_api, NamespaceSummary, SummaryOptions, SymbolGroup};
+pub use tokens::{
+    estimate_api_tokens, estimate_tokens, NamespaceTokens, SymbolTokens, TokenReport,
+};
+pub use typedoc::render_typedoc_json;
+
+/// The formats the public API can be rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// The outline produced by [`crate::pretty_print`].
+    #[default]
+    Outline,
+    /// TypeDoc's JSON reflection schema.
+    TypeDocJson,
+    /// A dense text format meant to be included in LLM prompts.
+    Compact,
+    /// Counts and samples of the symbols grouped by name affix, for packages with many exports.
+    Summary,
+}
+
+/// Renders the public API in the given format.
+pub fn render(
+    format: OutputFormat,
+    metadata: &TSLibraryMetadata,
+    namespaces: &[Namespace],
+) -> String {
+    match format {
+        OutputFormat::Outline => crate::api::pretty_print(namespaces),
+        OutputFormat::TypeDocJson => {
+            let json = render_typedoc_json(metadata, namespaces);
+            serde_json::to_string_pretty(&json).expect("JSON values should be serialisable")
+        }
+        OutputFormat::Compact => render_compact(metadata, namespaces),
+        OutputFormat::Summary => {
+            render_summary(&summarise_api(namespaces, &SummaryOptions::default()))
+        }
+    }
+}
+
+/// Checks whether a namespace corresponds to an entry point rather than an exported namespace.
+///
+/// Entry point namespaces are named after the package (e.g. `pkg` or `pkg/utils`), and the
+/// namespaces they export follow them, named after their path (e.g. `Outer.Inner`).
+fn is_entry_point_namespace(package_name: &str, namespace_name: &str) -> bool {
+    namespace_name == package_name
+        || namespace_name
+            .strip_prefix(package_name)
+            .is_some_and(|subpath| subpath.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_metadata;
+    use daipendency_extractor::Symbol;
+
+    fn make_namespaces() -> Vec<Namespace> {
+        vec![Namespace {
+            name: "pkg".to_string(),
+            symbols: vec![Symbol {
+                name: "VERSION".to_string(),
+                source_code: "export declare const VERSION: string;".to_string(),
+            }],
+            doc_comment: None,
+        }]
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn outline() {
+            let output = render(
+                OutputFormat::Outline,
+                &make_metadata(""),
+                &make_namespaces(),
+            );
+
+            assert_eq!(output, "pkg\n  VERSION");
+        }
+
+        #[test]
+        fn typedoc_json() {
+            let output = render(
+                OutputFormat::TypeDocJson,
+                &make_metadata(""),
+                &make_namespaces(),
+            );
+
+            let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+            assert_eq!(json["children"][0]["name"], "VERSION");
+        }
+
+        #[test]
+        fn compact() {
+            let output = render(
+                OutputFormat::Compact,
+                &make_metadata(""),
+                &make_namespaces(),
+            );
+
+            assert_eq!(output, "# pkg\nconst VERSION: string;");
+        }
+
+        #[test]
+        fn summary() {
+            let output = render(
+                OutputFormat::Summary,
+                &make_metadata(""),
+                &make_namespaces(),
+            );
+
+            assert_eq!(output, "# pkg (1 symbol)\nOther: `VERSION`");
+        }
+    }
+
+    mod is_entry_point_namespace {
+        use super::*;
+
+        #[test]
+        fn package_name() {
+            assert!(is_entry_point_namespace("pkg", "pkg"));
+        }
+
+        #[test]
+        fn subpath() {
+            assert!(is_entry_point_namespace("pkg", "pkg/utils"));
+        }
+
+        #[test]
+        fn namespace_with_package_prefix() {
+            assert!(!is_entry_point_namespace("pkg", "pkgUtils"));
+        }
+    }
+}
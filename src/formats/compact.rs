@@ -0,0 +1,302 @@
+use daipendency_extractor::{Namespace, ParsedFile, Symbol};
+
+use crate::api::jsdoc::{get_leading_jsdoc, get_summary};
+use crate::api::symbol_kind::get_parsed_symbol_kind;
+use crate::api::value_shape::get_parsed_value_shape;
+use crate::api::{SymbolKind, SymbolParts};
+use crate::metadata::TSLibraryMetadata;
+use crate::signature::normalize_node;
+
+use super::{is_entry_point_namespace, make_parser};
+
+/// Renders the public API in a dense text format meant to be included in LLM prompts.
+///
+/// Each entry point is a block starting with `# <entry point>`, and the namespaces it exports
+/// are sections starting with `## <path>`. Each symbol is rendered as its signature on a single
+/// line, without comments or the `export` and `declare` keywords, preceded by the first line of
+/// its JSDoc description if any. Identical signatures in a block are only rendered once.
+///
+/// The headings of entry points in type-only packages point out that they need `import type`.
+pub fn render_compact(metadata: &TSLibraryMetadata, namespaces: &[Namespace]) -> String {
+    let mut parser = make_parser();
+    let mut blocks: Vec<Vec<String>> = vec![];
+    let mut block_signatures: Vec<String> = vec![];
+
+    for namespace in namespaces {
+        let is_entry_point = is_entry_point_namespace(&metadata.name, &namespace.name);
+        let lines = match blocks.last_mut() {
+            Some(lines) if !is_entry_point => {
+                lines.push(format!("## {}", namespace.name));
+                lines
+            }
+            _ => {
+                block_signatures.clear();
+                let heading = match metadata.entry_point.is_type_only {
+                    true => format!("# {} (types only: use `import type`)", namespace.name),
+                    false => format!("# {}", namespace.name),
+                };
+                blocks.push(vec![heading]);
+                blocks.last_mut().expect("Block was just added")
+            }
+        };
+        if let Some(summary) = namespace.doc_comment.as_deref().and_then(get_summary) {
+            lines.push(format!("// {summary}"));
+        }
+
+        for symbol in &namespace.symbols {
+            // Symbols reduced to their names have no signature to render
+            if symbol.source_code.is_empty() {
+                lines.push(symbol.name.clone());
+                continue;
+            }
+            let parsed_file = ParsedFile::parse(&symbol.source_code, &mut parser).ok();
+            let normalized_signature = match &parsed_file {
+                Some(parsed_file) => normalize_node(parsed_file.root_node(), &symbol.source_code),
+                None => collapse_whitespace(&symbol.source_code),
+            };
+            if block_signatures.contains(&normalized_signature) {
+                continue;
+            }
+            if let Some(summary) = get_leading_jsdoc(&symbol.source_code).and_then(get_summary) {
+                lines.push(format!("// {summary}"));
+            }
+            lines.push(render_signature(
+                symbol,
+                parsed_file.as_ref(),
+                &normalized_signature,
+            ));
+            block_signatures.push(normalized_signature);
+        }
+    }
+
+    blocks
+        .into_iter()
+        .map(|lines| lines.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Renders the source code of a symbol on a single line, given its source code parsed (unless
+/// it doesn't parse) and normalised like [crate::normalize_signature], without leading
+/// `export`/`declare` keywords.
+///
+/// Variables initialised with `satisfies` or `as const` expressions have their literal truncated.
+fn render_signature(
+    symbol: &Symbol,
+    parsed_file: Option<&ParsedFile>,
+    normalized_signature: &str,
+) -> String {
+    let Some(parsed_file) = parsed_file else {
+        return collapse_whitespace(SymbolParts::new(symbol).signature());
+    };
+
+    if let Some(shape) = get_parsed_value_shape(symbol, parsed_file) {
+        let keyword = match get_parsed_symbol_kind(parsed_file) {
+            Some(SymbolKind::Variable { is_const: false }) => "let",
+            _ => "const",
+        };
+        return shape.render_declaration(keyword, &symbol.name);
+    }
+
+    let mut signature = normalized_signature;
+    while let Some(rest) = signature
+        .strip_prefix("export ")
+        .or_else(|| signature.strip_prefix("declare "))
+    {
+        signature = rest;
+    }
+    signature.to_string()
+}
+
+fn collapse_whitespace(source_code: &str) -> String {
+    source_code.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_metadata;
+
+    fn make_namespace(name: &str, source_codes: &[&str]) -> Namespace {
+        Namespace {
+            name: name.to_string(),
+            symbols: source_codes
+                .iter()
+                .map(|source_code| Symbol {
+                    name: "symbol".to_string(),
+                    source_code: source_code.to_string(),
+                })
+                .collect(),
+            doc_comment: None,
+        }
+    }
+
+    #[test]
+    fn no_namespaces() {
+        let output = render_compact(&make_metadata(""), &[]);
+
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn names_only() {
+        let namespaces = vec![make_namespace("pkg", &["", ""])];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(output, "# pkg\nsymbol\nsymbol");
+    }
+
+    #[test]
+    fn entry_points() {
+        let namespaces = vec![
+            make_namespace("pkg", &["export declare const a: string;"]),
+            make_namespace("pkg/utils", &["export declare const b: string;"]),
+        ];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(
+            output,
+            "# pkg\nconst a: string;\n\n# pkg/utils\nconst b: string;"
+        );
+    }
+
+    #[test]
+    fn type_only_package() {
+        let mut metadata = make_metadata("");
+        metadata.entry_point.is_type_only = true;
+        let namespaces = vec![
+            make_namespace("pkg", &["export interface A {}"]),
+            make_namespace("Outer", &["export interface B {}"]),
+        ];
+
+        let output = render_compact(&metadata, &namespaces);
+
+        assert_eq!(
+            output,
+            "# pkg (types only: use `import type`)\ninterface A {}\n## Outer\ninterface B {}"
+        );
+    }
+
+    #[test]
+    fn exported_namespaces() {
+        let namespaces = vec![
+            make_namespace("pkg", &[]),
+            make_namespace("Outer", &["export declare const a: string;"]),
+            make_namespace("Outer.Inner", &["export declare const b: string;"]),
+        ];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(
+            output,
+            "# pkg\n## Outer\nconst a: string;\n## Outer.Inner\nconst b: string;"
+        );
+    }
+
+    #[test]
+    fn collapsed_whitespace() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &["export interface User {\n    id: string;\n\n    name:   string;\n}"],
+        )];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(output, "# pkg\ninterface User { id: string; name: string }");
+    }
+
+    #[test]
+    fn string_whitespace() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &["export type Padding = 'a  b' | `c  ${string}`;"],
+        )];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(output, "# pkg\ntype Padding = 'a  b' | `c  ${string}`;");
+    }
+
+    #[test]
+    fn member_comments() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &["export declare class Foo {\n  /** The bar. */\n  bar: string; // Trailing\n}"],
+        )];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(output, "# pkg\nclass Foo { bar: string }");
+    }
+
+    #[test]
+    fn summaries() {
+        let mut namespace = make_namespace(
+            "pkg",
+            &["/**\n * Greets someone.\n * Politely.\n * @param name The name\n */\nexport declare function greet(name: string): void;"],
+        );
+        namespace.doc_comment =
+            Some("/**\n * The package.\n * @packageDocumentation\n */".to_string());
+
+        let output = render_compact(&make_metadata(""), &[namespace]);
+
+        assert_eq!(
+            output,
+            "# pkg\n// The package.\n// Greets someone.\nfunction greet(name: string): void;"
+        );
+    }
+
+    #[test]
+    fn duplicate_signatures() {
+        let namespaces = vec![
+            make_namespace(
+                "pkg",
+                &[
+                    "export declare function f(): void;",
+                    "/** Doc */\nexport declare function f(\n): void;",
+                    "export declare function f(a: string): void;",
+                ],
+            ),
+            make_namespace("pkg/utils", &["export declare function f(): void;"]),
+        ];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(
+            output,
+            "# pkg\nfunction f(): void;\nfunction f(a: string): void;\n\n# pkg/utils\nfunction f(): void;"
+        );
+    }
+
+    #[test]
+    fn satisfies_expression() {
+        let properties = (0..30)
+            .map(|index| format!("  route{index}: '/{index}',"))
+            .collect::<Vec<_>>();
+        let source_code = format!(
+            "export const routes = {{\n{}\n}} satisfies RouteMap;",
+            properties.join("\n")
+        );
+        let mut namespace = make_namespace("pkg", &[&source_code]);
+        namespace.symbols[0].name = "routes".to_string();
+
+        let output = render_compact(&make_metadata(""), &[namespace]);
+
+        assert!(output.starts_with("# pkg\nconst routes = { route0: '/0', "));
+        assert!(output.ends_with("… satisfies RouteMap;"));
+    }
+
+    #[test]
+    fn unparsable_source_code() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &["/** Doc */\nexport const = ;\n  oops"],
+        )];
+
+        let output = render_compact(&make_metadata(""), &namespaces);
+
+        assert_eq!(output, "# pkg\n// Doc\nexport const = ; oops");
+    }
+}
@@ -0,0 +1,261 @@
+use crate::report::ExtractionReport;
+
+use super::render_compact;
+
+/// Renders an [ExtractionReport] in a given format, so that formats can be added by other crates
+/// and registered in a [FormatterRegistry].
+pub trait OutputFormatter: Send + Sync {
+    /// The name the formatter is registered under (e.g. `json`).
+    fn name(&self) -> &str;
+
+    fn format(&self, report: &ExtractionReport) -> String;
+}
+
+/// Renders the report as the JSON of [ExtractionReport::to_json], pretty-printed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormatter;
+
+impl OutputFormatter for JsonFormatter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn format(&self, report: &ExtractionReport) -> String {
+        serde_json::to_string_pretty(&report.to_json()).expect("JSON values should be serialisable")
+    }
+}
+
+/// Renders the public API as Markdown, with a section per namespace containing its doc comment
+/// and the source code of its symbols, or a list of their names if their source code was left
+/// out (e.g. with [crate::DetailLevel::NamesOnly]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownFormatter;
+
+impl OutputFormatter for MarkdownFormatter {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn format(&self, report: &ExtractionReport) -> String {
+        let metadata = &report.metadata;
+        let mut sections = vec![match &metadata.version {
+            Some(version) => format!("# {}@{}", metadata.name, version),
+            None => format!("# {}", metadata.name),
+        }];
+        for namespace in &report.public_api {
+            let mut section = format!("## {}", namespace.name);
+            if let Some(doc_comment) = &namespace.doc_comment {
+                section.push_str("\n\n");
+                section.push_str(doc_comment);
+            }
+            let (name_only_symbols, symbols): (Vec<_>, Vec<_>) = namespace
+                .symbols
+                .iter()
+                .partition(|symbol| symbol.source_code.is_empty());
+            if !symbols.is_empty() {
+                let source_code = symbols
+                    .iter()
+                    .map(|symbol| symbol.source_code.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                section.push_str(&format!("\n\n```ts\n{source_code}\n```"));
+            }
+            if !name_only_symbols.is_empty() {
+                let names = name_only_symbols
+                    .iter()
+                    .map(|symbol| format!("- `{}`", symbol.name))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                section.push_str(&format!("\n\n{names}"));
+            }
+            sections.push(section);
+        }
+        sections.join("\n\n")
+    }
+}
+
+/// Renders the public API with [render_compact].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompactFormatter;
+
+impl OutputFormatter for CompactFormatter {
+    fn name(&self) -> &str {
+        "compact"
+    }
+
+    fn format(&self, report: &ExtractionReport) -> String {
+        render_compact(&report.metadata, &report.public_api)
+    }
+}
+
+/// The formatters available by name, starting with the built-in ones.
+pub struct FormatterRegistry {
+    formatters: Vec<Box<dyn OutputFormatter>>,
+}
+
+impl FormatterRegistry {
+    /// Creates a registry without any formatters, not even the built-in ones.
+    pub fn empty() -> Self {
+        Self { formatters: vec![] }
+    }
+
+    /// Adds a formatter, replacing any registered under the same name.
+    pub fn register(&mut self, formatter: impl OutputFormatter + 'static) {
+        let formatter: Box<dyn OutputFormatter> = Box::new(formatter);
+        match self
+            .formatters
+            .iter_mut()
+            .find(|existing| existing.name() == formatter.name())
+        {
+            Some(existing) => *existing = formatter,
+            None => self.formatters.push(formatter),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn OutputFormatter> {
+        self.formatters
+            .iter()
+            .find(|formatter| formatter.name() == name)
+            .map(|formatter| formatter.as_ref())
+    }
+
+    /// Lists the names of the formatters, in the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.formatters
+            .iter()
+            .map(|formatter| formatter.name())
+            .collect()
+    }
+
+    /// Renders the report with the formatter registered under `name`, if any.
+    pub fn format(&self, name: &str, report: &ExtractionReport) -> Option<String> {
+        self.get(name).map(|formatter| formatter.format(report))
+    }
+}
+
+impl Default for FormatterRegistry {
+    /// Creates a registry with the JSON, Markdown and compact formatters.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(JsonFormatter);
+        registry.register(MarkdownFormatter);
+        registry.register(CompactFormatter);
+        registry
+    }
+}
+
+impl std::fmt::Debug for FormatterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FormatterRegistry")
+            .field("formatters", &self.names())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeScriptExtractor;
+    use daipendency_testing::tempdir::TempDir;
+
+    fn make_report() -> ExtractionReport {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "package.json",
+                r#"{"name": "pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+            )
+            .unwrap();
+        temp_dir
+            .create_file("index.d.ts", "export declare const VERSION: string;")
+            .unwrap();
+        TypeScriptExtractor::default()
+            .extract(&temp_dir.path)
+            .unwrap()
+    }
+
+    struct NameFormatter(&'static str);
+
+    impl OutputFormatter for NameFormatter {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn format(&self, report: &ExtractionReport) -> String {
+            format!("{}: {}", self.0, report.metadata.name)
+        }
+    }
+
+    #[test]
+    fn json() {
+        let output = JsonFormatter.format(&make_report());
+
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["metadata"]["name"], "pkg");
+    }
+
+    #[test]
+    fn markdown() {
+        let output = MarkdownFormatter.format(&make_report());
+
+        assert_eq!(
+            output,
+            "# pkg@1.0.0\n\n## pkg\n\n```ts\nexport declare const VERSION: string;\n```"
+        );
+    }
+
+    #[test]
+    fn markdown_names_only() {
+        let mut report = make_report();
+        report.public_api[0].symbols[0].source_code = String::new();
+
+        let output = MarkdownFormatter.format(&report);
+
+        assert_eq!(output, "# pkg@1.0.0\n\n## pkg\n\n- `VERSION`");
+    }
+
+    #[test]
+    fn compact() {
+        let output = CompactFormatter.format(&make_report());
+
+        assert_eq!(
+            output,
+            "# pkg (types only: use `import type`)\nconst VERSION: string;"
+        );
+    }
+
+    #[test]
+    fn built_in_formatters() {
+        let registry = FormatterRegistry::default();
+
+        assert_eq!(registry.names(), vec!["json", "markdown", "compact"]);
+    }
+
+    #[test]
+    fn custom_formatter() {
+        let mut registry = FormatterRegistry::default();
+        registry.register(NameFormatter("name"));
+
+        let output = registry.format("name", &make_report());
+
+        assert_eq!(output.as_deref(), Some("name: pkg"));
+    }
+
+    #[test]
+    fn replaced_formatter() {
+        let mut registry = FormatterRegistry::default();
+        registry.register(NameFormatter("json"));
+
+        let output = registry.format("json", &make_report());
+
+        assert_eq!(output.as_deref(), Some("json: pkg"));
+        assert_eq!(registry.names(), vec!["json", "markdown", "compact"]);
+    }
+
+    #[test]
+    fn unknown_formatter() {
+        let registry = FormatterRegistry::empty();
+
+        assert_eq!(registry.format("json", &make_report()), None);
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::{BTreeMap, HashSet};
+
+use daipendency_extractor::{Namespace, Symbol};
+use tree_sitter::Parser;
+
+use crate::api::declaration::find_declaration;
+
+use super::make_parser;
+
+/// Settings for summarising namespaces with many symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryOptions {
+    /// The smallest number of symbols sharing a name affix and kind that are grouped together.
+    pub min_group_size: usize,
+    /// The number of names listed as examples of each group.
+    pub samples: usize,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self {
+            min_group_size: 5,
+            samples: 2,
+        }
+    }
+}
+
+/// Symbols of the same kind whose names share a prefix or suffix (e.g. `*Command` classes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolGroup {
+    /// The shared part of the names, with `*` standing for the rest (e.g. `*Command` or `get*`).
+    pub pattern: String,
+    /// The kind of the symbols, in the plural (e.g. `classes`).
+    pub kind: &'static str,
+    pub count: usize,
+    /// The names of the first few symbols in the group.
+    pub samples: Vec<String>,
+}
+
+/// The summary of the symbols in a namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceSummary {
+    pub name: String,
+    /// The number of distinct symbol names in the namespace.
+    pub symbol_count: usize,
+    /// The groups, from the largest to the smallest.
+    pub groups: Vec<SymbolGroup>,
+    /// The names of the symbols that don't belong to any group.
+    pub ungrouped: Vec<String>,
+}
+
+/// Summarises each namespace by grouping its symbols by kind and name affix.
+///
+/// Groups are picked greedily, largest first, so each symbol is in at most one group. This is
+/// meant for packages with thousands of exports (e.g. generated SDK clients), where listing
+/// every symbol would be too large to be useful.
+pub fn summarise_api(namespaces: &[Namespace], options: &SummaryOptions) -> Vec<NamespaceSummary> {
+    let mut parser = make_parser();
+    namespaces
+        .iter()
+        .map(|namespace| summarise_namespace(namespace, options, &mut parser))
+        .collect()
+}
+
+/// Renders the summaries as text, with one line per group (e.g.
+/// ``214 `*Command` classes, e.g. `PutObjectCommand` ``).
+pub fn render_summary(summaries: &[NamespaceSummary]) -> String {
+    let blocks = summaries
+        .iter()
+        .map(|summary| {
+            let noun = if summary.symbol_count == 1 {
+                "symbol"
+            } else {
+                "symbols"
+            };
+            let mut lines = vec![format!(
+                "# {} ({} {noun})",
+                summary.name, summary.symbol_count
+            )];
+            for group in &summary.groups {
+                let samples = group
+                    .samples
+                    .iter()
+                    .map(|sample| format!("`{sample}`"))
+                    .collect::<Vec<_>>();
+                lines.push(format!(
+                    "{} `{}` {}, e.g. {}",
+                    group.count,
+                    group.pattern,
+                    group.kind,
+                    samples.join(", ")
+                ));
+            }
+            if !summary.ungrouped.is_empty() {
+                let names = summary
+                    .ungrouped
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>();
+                lines.push(format!("Other: {}", names.join(", ")));
+            }
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>();
+    blocks.join("\n\n")
+}
+
+fn summarise_namespace(
+    namespace: &Namespace,
+    options: &SummaryOptions,
+    parser: &mut Parser,
+) -> NamespaceSummary {
+    let mut symbols: Vec<(&str, &'static str)> = vec![];
+    let mut seen_names = HashSet::new();
+    for symbol in &namespace.symbols {
+        // Overloads are separate symbols with the same name
+        if seen_names.insert(symbol.name.as_str()) {
+            symbols.push((&symbol.name, get_kind(symbol, parser)));
+        }
+    }
+    let symbol_count = symbols.len();
+
+    let mut groups = vec![];
+    while let Some((pattern, kind, members)) = find_largest_group(&symbols) {
+        if members.len() < options.min_group_size.max(2) {
+            break;
+        }
+        groups.push(SymbolGroup {
+            pattern,
+            kind,
+            count: members.len(),
+            samples: members
+                .iter()
+                .take(options.samples)
+                .map(|name| name.to_string())
+                .collect(),
+        });
+        let grouped_names = members.into_iter().collect::<HashSet<_>>();
+        symbols.retain(|(name, _)| !grouped_names.contains(name));
+    }
+
+    NamespaceSummary {
+        name: namespace.name.clone(),
+        symbol_count,
+        groups,
+        ungrouped: symbols.iter().map(|(name, _)| name.to_string()).collect(),
+    }
+}
+
+/// Whether the pattern is a prefix (rather than a suffix), the pattern and the kind of symbols.
+type GroupKey = (bool, String, &'static str);
+
+/// Finds the largest set of symbols of the same kind sharing a name prefix or suffix.
+///
+/// Suffixes win ties, since they usually denote the role of a symbol (e.g. `Command`).
+fn find_largest_group<'a>(
+    symbols: &[(&'a str, &'static str)],
+) -> Option<(String, &'static str, Vec<&'a str>)> {
+    let mut candidates: BTreeMap<GroupKey, Vec<&'a str>> = BTreeMap::new();
+    for (name, kind) in symbols {
+        let words = split_words(name);
+        let (Some(first_word), Some(last_word)) = (words.first(), words.last()) else {
+            continue;
+        };
+        if words.len() < 2 {
+            continue;
+        }
+        let prefix = format!("{}*", &name[..first_word.end]);
+        let suffix = format!("*{}", &name[last_word.start..]);
+        candidates
+            .entry((false, suffix, kind))
+            .or_default()
+            .push(name);
+        candidates
+            .entry((true, prefix, kind))
+            .or_default()
+            .push(name);
+    }
+
+    let mut largest: Option<(GroupKey, Vec<&'a str>)> = None;
+    for (key, members) in candidates {
+        if largest
+            .as_ref()
+            .is_none_or(|(_, largest_members)| members.len() > largest_members.len())
+        {
+            largest = Some((key, members));
+        }
+    }
+    largest.map(|((_, pattern, kind), members)| (pattern, kind, members))
+}
+
+/// Splits an identifier into its words, by case changes and underscores (e.g. `XMLHttpRequest`
+/// into `XML`, `Http` and `Request`).
+fn split_words(name: &str) -> Vec<std::ops::Range<usize>> {
+    let characters = name.char_indices().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut word_start = None;
+
+    for (index, (offset, character)) in characters.iter().enumerate() {
+        if *character == '_' || *character == '$' {
+            if let Some(start) = word_start.take() {
+                words.push(start..*offset);
+            }
+            continue;
+        }
+        let previous = index
+            .checked_sub(1)
+            .map(|previous_index| characters[previous_index].1);
+        let next = characters.get(index + 1).map(|(_, next)| *next);
+        let is_boundary = character.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next.is_some_and(char::is_lowercase))
+            });
+        match word_start {
+            Some(start) if is_boundary => {
+                words.push(start..*offset);
+                word_start = Some(*offset);
+            }
+            None => word_start = Some(*offset),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(start..name.len());
+    }
+    words
+}
+
+fn get_kind(symbol: &Symbol, parser: &mut Parser) -> &'static str {
+    let tree = parser.parse(&symbol.source_code, None);
+    let declaration_kind = tree
+        .as_ref()
+        .and_then(|tree| find_declaration(tree.root_node()))
+        .map(|declaration| declaration.kind());
+    match declaration_kind {
+        Some("class_declaration" | "abstract_class_declaration" | "class") => "classes",
+        Some("interface_declaration") => "interfaces",
+        Some("function_signature" | "function_declaration") => "functions",
+        Some("type_alias_declaration") => "type aliases",
+        Some("enum_declaration") => "enums",
+        Some("internal_module" | "module") => "namespaces",
+        _ => "variables",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_namespace(source_codes: &[(&str, &str)]) -> Namespace {
+        Namespace {
+            name: "pkg".to_string(),
+            symbols: source_codes
+                .iter()
+                .map(|(name, source_code)| Symbol {
+                    name: name.to_string(),
+                    source_code: source_code.to_string(),
+                })
+                .collect(),
+            doc_comment: None,
+        }
+    }
+
+    fn make_classes(names: &[&str]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    format!("export declare class {name} {{}}"),
+                )
+            })
+            .collect()
+    }
+
+    fn as_pairs(symbols: &[(String, String)]) -> Vec<(&str, &str)> {
+        symbols
+            .iter()
+            .map(|(name, source_code)| (name.as_str(), source_code.as_str()))
+            .collect()
+    }
+
+    const OPTIONS: SummaryOptions = SummaryOptions {
+        min_group_size: 2,
+        samples: 1,
+    };
+
+    mod summarise_api {
+        use super::*;
+
+        #[test]
+        fn suffix_group() {
+            let symbols = make_classes(&["PutObjectCommand", "GetObjectCommand", "S3Client"]);
+            let namespace = make_namespace(&as_pairs(&symbols));
+
+            let summaries = summarise_api(&[namespace], &OPTIONS);
+
+            assert_eq!(
+                summaries,
+                vec![NamespaceSummary {
+                    name: "pkg".to_string(),
+                    symbol_count: 3,
+                    groups: vec![SymbolGroup {
+                        pattern: "*Command".to_string(),
+                        kind: "classes",
+                        count: 2,
+                        samples: vec!["PutObjectCommand".to_string()],
+                    }],
+                    ungrouped: vec!["S3Client".to_string()],
+                }]
+            );
+        }
+
+        #[test]
+        fn prefix_group() {
+            let namespace = make_namespace(&[
+                ("getUser", "export declare function getUser(): void;"),
+                ("getGroup", "export declare function getGroup(): void;"),
+                ("setRole", "export declare function setRole(): void;"),
+            ]);
+
+            let summaries = summarise_api(&[namespace], &OPTIONS);
+
+            let group = &summaries[0].groups[0];
+            assert_eq!(group.pattern, "get*");
+            assert_eq!(group.kind, "functions");
+            assert_eq!(group.count, 2);
+            assert_eq!(summaries[0].ungrouped, vec!["setRole".to_string()]);
+        }
+
+        #[test]
+        fn different_kinds() {
+            let namespace = make_namespace(&[
+                ("PutObjectInput", "export interface PutObjectInput {}"),
+                ("GetObjectInput", "export declare class GetObjectInput {}"),
+            ]);
+
+            let summaries = summarise_api(&[namespace], &OPTIONS);
+
+            assert_eq!(summaries[0].groups, vec![]);
+            assert_eq!(summaries[0].ungrouped.len(), 2);
+        }
+
+        #[test]
+        fn small_groups() {
+            let symbols = make_classes(&["PutObjectCommand", "GetObjectCommand"]);
+            let namespace = make_namespace(&as_pairs(&symbols));
+            let options = SummaryOptions {
+                min_group_size: 3,
+                ..OPTIONS
+            };
+
+            let summaries = summarise_api(&[namespace], &options);
+
+            assert_eq!(summaries[0].groups, vec![]);
+        }
+
+        #[test]
+        fn symbols_in_single_group() {
+            let symbols = make_classes(&[
+                "PutObjectCommand",
+                "GetObjectCommand",
+                "ListObjectsCommand",
+                "PutBucketPolicy",
+            ]);
+            let namespace = make_namespace(&as_pairs(&symbols));
+
+            let summaries = summarise_api(&[namespace], &OPTIONS);
+
+            let patterns = summaries[0]
+                .groups
+                .iter()
+                .map(|group| (group.pattern.as_str(), group.count))
+                .collect::<Vec<_>>();
+            assert_eq!(patterns, vec![("*Command", 3)]);
+            assert_eq!(summaries[0].ungrouped, vec!["PutBucketPolicy".to_string()]);
+        }
+
+        #[test]
+        fn overloads() {
+            let namespace = make_namespace(&[
+                ("getUser", "export declare function getUser(): void;"),
+                (
+                    "getUser",
+                    "export declare function getUser(id: string): void;",
+                ),
+            ]);
+
+            let summaries = summarise_api(&[namespace], &OPTIONS);
+
+            assert_eq!(summaries[0].symbol_count, 1);
+            assert_eq!(summaries[0].groups, vec![]);
+        }
+    }
+
+    mod render_summary {
+        use super::*;
+
+        #[test]
+        fn groups_and_ungrouped_symbols() {
+            let summaries = vec![NamespaceSummary {
+                name: "pkg".to_string(),
+                symbol_count: 215,
+                groups: vec![SymbolGroup {
+                    pattern: "*Command".to_string(),
+                    kind: "classes",
+                    count: 214,
+                    samples: vec![
+                        "PutObjectCommand".to_string(),
+                        "GetObjectCommand".to_string(),
+                    ],
+                }],
+                ungrouped: vec!["S3Client".to_string()],
+            }];
+
+            let output = render_summary(&summaries);
+
+            assert_eq!(
+                output,
+                "# pkg (215 symbols)\n214 `*Command` classes, e.g. `PutObjectCommand`, `GetObjectCommand`\nOther: `S3Client`"
+            );
+        }
+
+        #[test]
+        fn single_symbol() {
+            let summaries = vec![NamespaceSummary {
+                name: "pkg".to_string(),
+                symbol_count: 1,
+                groups: vec![],
+                ungrouped: vec!["S3Client".to_string()],
+            }];
+
+            let output = render_summary(&summaries);
+
+            assert_eq!(output, "# pkg (1 symbol)\nOther: `S3Client`");
+        }
+    }
+
+    mod split_words {
+        use super::*;
+
+        fn get_words(name: &str) -> Vec<&str> {
+            split_words(name)
+                .into_iter()
+                .map(|range| &name[range])
+                .collect()
+        }
+
+        #[test]
+        fn pascal_case() {
+            assert_eq!(
+                get_words("PutObjectCommand"),
+                vec!["Put", "Object", "Command"]
+            );
+        }
+
+        #[test]
+        fn camel_case() {
+            assert_eq!(get_words("getSignedUrl"), vec!["get", "Signed", "Url"]);
+        }
+
+        #[test]
+        fn acronyms() {
+            assert_eq!(get_words("XMLHttpRequest"), vec!["XML", "Http", "Request"]);
+        }
+
+        #[test]
+        fn snake_case() {
+            assert_eq!(get_words("MAX_RETRY_COUNT"), vec!["MAX", "RETRY", "COUNT"]);
+        }
+
+        #[test]
+        fn digits() {
+            assert_eq!(get_words("S3Client"), vec!["S3", "Client"]);
+        }
+    }
+}
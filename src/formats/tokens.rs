@@ -0,0 +1,192 @@
+use daipendency_extractor::Namespace;
+
+/// The approximate number of characters per token in identifiers and other words.
+const CHARACTERS_PER_TOKEN: usize = 4;
+
+/// The estimated number of tokens of a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolTokens {
+    pub name: String,
+    pub tokens: usize,
+}
+
+/// The estimated number of tokens of a namespace, including its documentation and symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceTokens {
+    pub name: String,
+    pub tokens: usize,
+    pub symbols: Vec<SymbolTokens>,
+}
+
+/// The estimated number of tokens of the public API, broken down by namespace and symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenReport {
+    pub namespaces: Vec<NamespaceTokens>,
+    /// The sum of the tokens of all the namespaces.
+    pub total: usize,
+}
+
+/// Estimates the number of tokens an LLM tokeniser would produce for the text.
+///
+/// This mimics byte-pair encoders without shipping a vocabulary: runs of word characters count
+/// as one token per four characters, other symbols count as one token each, and whitespace is
+/// free unless it breaks a line. The estimate is meant for budgeting rather than billing.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word_length: usize = 0;
+    let mut is_in_line_break = false;
+
+    for character in text.chars() {
+        if character.is_alphanumeric() || character == '_' || character == '$' {
+            word_length += 1;
+            is_in_line_break = false;
+            continue;
+        }
+        tokens += word_length.div_ceil(CHARACTERS_PER_TOKEN);
+        word_length = 0;
+
+        if character == '\n' {
+            if !is_in_line_break {
+                tokens += 1;
+            }
+            is_in_line_break = true;
+        } else if !character.is_whitespace() {
+            tokens += 1;
+            is_in_line_break = false;
+        }
+    }
+    tokens + word_length.div_ceil(CHARACTERS_PER_TOKEN)
+}
+
+/// Estimates the number of tokens of each namespace and symbol as rendered by daipendency,
+/// which is the name and documentation of each namespace followed by the source code of its
+/// symbols.
+pub fn estimate_api_tokens(namespaces: &[Namespace]) -> TokenReport {
+    let namespaces = namespaces
+        .iter()
+        .map(|namespace| {
+            let symbols = namespace
+                .symbols
+                .iter()
+                .map(|symbol| SymbolTokens {
+                    name: symbol.name.clone(),
+                    tokens: estimate_tokens(&symbol.source_code),
+                })
+                .collect::<Vec<_>>();
+            let tokens = estimate_tokens(&namespace.name)
+                + namespace
+                    .doc_comment
+                    .as_deref()
+                    .map(estimate_tokens)
+                    .unwrap_or_default()
+                + symbols.iter().map(|symbol| symbol.tokens).sum::<usize>();
+            NamespaceTokens {
+                name: namespace.name.clone(),
+                tokens,
+                symbols,
+            }
+        })
+        .collect::<Vec<_>>();
+    let total = namespaces.iter().map(|namespace| namespace.tokens).sum();
+    TokenReport { namespaces, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_extractor::Symbol;
+
+    mod estimate_tokens {
+        use super::*;
+
+        #[test]
+        fn empty_text() {
+            assert_eq!(estimate_tokens(""), 0);
+        }
+
+        #[test]
+        fn short_words() {
+            assert_eq!(estimate_tokens("let a"), 2);
+        }
+
+        #[test]
+        fn long_words() {
+            assert_eq!(estimate_tokens("PutObjectCommand"), 4);
+        }
+
+        #[test]
+        fn punctuation() {
+            assert_eq!(estimate_tokens("f(a, b);"), 7);
+        }
+
+        #[test]
+        fn line_breaks() {
+            assert_eq!(estimate_tokens("a\n\n    b"), 3);
+        }
+
+        #[test]
+        fn non_ascii_words() {
+            assert_eq!(estimate_tokens("données"), 2);
+        }
+    }
+
+    mod estimate_api_tokens {
+        use super::*;
+
+        #[test]
+        fn no_namespaces() {
+            let report = estimate_api_tokens(&[]);
+
+            assert_eq!(
+                report,
+                TokenReport {
+                    namespaces: vec![],
+                    total: 0
+                }
+            );
+        }
+
+        #[test]
+        fn namespaces_and_symbols() {
+            let namespaces = vec![
+                Namespace {
+                    name: "pkg".to_string(),
+                    symbols: vec![Symbol {
+                        name: "a".to_string(),
+                        source_code: "const a: string;".to_string(),
+                    }],
+                    doc_comment: Some("/** Doc */".to_string()),
+                },
+                Namespace {
+                    name: "Utils".to_string(),
+                    symbols: vec![],
+                    doc_comment: None,
+                },
+            ];
+
+            let report = estimate_api_tokens(&namespaces);
+
+            assert_eq!(
+                report,
+                TokenReport {
+                    namespaces: vec![
+                        NamespaceTokens {
+                            name: "pkg".to_string(),
+                            tokens: 1 + 6 + 7,
+                            symbols: vec![SymbolTokens {
+                                name: "a".to_string(),
+                                tokens: 7,
+                            }],
+                        },
+                        NamespaceTokens {
+                            name: "Utils".to_string(),
+                            tokens: 2,
+                            symbols: vec![],
+                        },
+                    ],
+                    total: 16,
+                }
+            );
+        }
+    }
+}
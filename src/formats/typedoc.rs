@@ -0,0 +1,763 @@
+use daipendency_extractor::{Namespace, Symbol};
+use serde_json::{json, Map, Value};
+use tree_sitter::{Node, Parser};
+
+use crate::api::declaration::find_declaration;
+use crate::api::jsdoc::{get_description, get_leading_jsdoc, parse_tags};
+use crate::metadata::TSLibraryMetadata;
+
+use super::{is_entry_point_namespace, make_parser};
+
+/// The version of the TypeDoc JSON schema we emit.
+const SCHEMA_VERSION: &str = "2.0";
+
+/// TSDoc tags without content, which TypeDoc lists as modifiers rather than block tags.
+const MODIFIER_TAGS: [&str; 11] = [
+    "alpha",
+    "beta",
+    "eventProperty",
+    "experimental",
+    "hidden",
+    "internal",
+    "override",
+    "packageDocumentation",
+    "public",
+    "readonly",
+    "sealed",
+];
+
+/// The kinds of reflections in TypeDoc, with the same values as its `ReflectionKind` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReflectionKind {
+    Project = 1,
+    Module = 2,
+    Namespace = 4,
+    Enum = 8,
+    EnumMember = 16,
+    Variable = 32,
+    Function = 64,
+    Class = 128,
+    Interface = 256,
+    Constructor = 512,
+    Property = 1024,
+    Method = 2048,
+    TypeAlias = 2097152,
+}
+
+impl ReflectionKind {
+    /// The title of the group of reflections of this kind, as used by TypeDoc.
+    fn group_title(self) -> &'static str {
+        match self {
+            ReflectionKind::Project => "Projects",
+            ReflectionKind::Module => "Modules",
+            ReflectionKind::Namespace => "Namespaces",
+            ReflectionKind::Enum => "Enumerations",
+            ReflectionKind::EnumMember => "Enumeration Members",
+            ReflectionKind::Variable => "Variables",
+            ReflectionKind::Function => "Functions",
+            ReflectionKind::Class => "Classes",
+            ReflectionKind::Interface => "Interfaces",
+            ReflectionKind::Constructor => "Constructors",
+            ReflectionKind::Property => "Properties",
+            ReflectionKind::Method => "Methods",
+            ReflectionKind::TypeAlias => "Type Aliases",
+        }
+    }
+}
+
+/// The order in which TypeDoc lists groups.
+const GROUP_ORDER: [ReflectionKind; 12] = [
+    ReflectionKind::Module,
+    ReflectionKind::Namespace,
+    ReflectionKind::Enum,
+    ReflectionKind::EnumMember,
+    ReflectionKind::Class,
+    ReflectionKind::Interface,
+    ReflectionKind::TypeAlias,
+    ReflectionKind::Constructor,
+    ReflectionKind::Property,
+    ReflectionKind::Method,
+    ReflectionKind::Variable,
+    ReflectionKind::Function,
+];
+
+/// A declaration reflection, before it's converted to JSON.
+struct Reflection {
+    name: String,
+    kind: ReflectionKind,
+    flags: Map<String, Value>,
+    comment: Option<Value>,
+    children: Vec<Reflection>,
+}
+
+impl Reflection {
+    fn new(name: String, kind: ReflectionKind, comment: Option<Value>) -> Self {
+        Self {
+            name,
+            kind,
+            flags: Map::new(),
+            comment,
+            children: vec![],
+        }
+    }
+
+    fn find_namespace_mut(&mut self, name: &str) -> Option<&mut Reflection> {
+        self.children
+            .iter_mut()
+            .find(|child| child.kind == ReflectionKind::Namespace && child.name == name)
+    }
+}
+
+/// Converts the public API to TypeDoc's JSON reflection schema.
+///
+/// Like TypeDoc, the declarations of packages with a single entry point are children of the
+/// project, whereas each entry point becomes a module when there are several. Members are only
+/// listed for classes, interfaces and enums, and types aren't resolved since there's no compiler.
+pub fn render_typedoc_json(metadata: &TSLibraryMetadata, namespaces: &[Namespace]) -> Value {
+    let mut parser = make_parser();
+    let mut modules = build_modules(&metadata.name, namespaces, &mut parser);
+    let (children, project_comment) = match modules.len() {
+        1 => {
+            let module = modules.remove(0);
+            (module.children, module.comment)
+        }
+        _ => (modules, None),
+    };
+
+    let mut next_id = 1;
+    let children = children
+        .into_iter()
+        .map(|child| reflection_to_json(child, &mut next_id))
+        .collect::<Vec<_>>();
+
+    let mut project = json!({
+        "id": 0,
+        "name": metadata.name,
+        "variant": "project",
+        "kind": ReflectionKind::Project as u32,
+        "flags": {},
+    });
+    if let Some(comment) = project_comment {
+        project["comment"] = comment;
+    }
+    if !children.is_empty() {
+        project["groups"] = get_groups(&children);
+        project["children"] = Value::Array(children);
+    }
+    if let Some(version) = &metadata.version {
+        project["packageVersion"] = json!(version);
+    }
+    if !metadata.documentation.is_empty() {
+        project["readme"] = json!([{ "kind": "text", "text": metadata.documentation }]);
+    }
+    project["schemaVersion"] = json!(SCHEMA_VERSION);
+    project
+}
+
+/// Rebuilds the hierarchy of entry points and namespaces from the flat list of namespaces.
+fn build_modules(
+    package_name: &str,
+    namespaces: &[Namespace],
+    parser: &mut Parser,
+) -> Vec<Reflection> {
+    let mut modules: Vec<Reflection> = vec![];
+    for namespace in namespaces {
+        let is_entry_point = is_entry_point_namespace(package_name, &namespace.name);
+        let comment = namespace.doc_comment.as_deref().and_then(convert_comment);
+
+        let parent = match modules.last_mut() {
+            Some(module) if !is_entry_point => {
+                let segments = namespace.name.split('.').collect::<Vec<_>>();
+                let (name, parent_path) = segments.split_last().expect("Split yields a segment");
+                let mut parent = module;
+                for segment in parent_path {
+                    if parent.find_namespace_mut(segment).is_none() {
+                        parent.children.push(Reflection::new(
+                            segment.to_string(),
+                            ReflectionKind::Namespace,
+                            None,
+                        ));
+                    }
+                    parent = parent
+                        .find_namespace_mut(segment)
+                        .expect("Namespace was just added");
+                }
+                parent.children.push(Reflection::new(
+                    name.to_string(),
+                    ReflectionKind::Namespace,
+                    comment,
+                ));
+                parent
+                    .children
+                    .last_mut()
+                    .expect("Namespace was just added")
+            }
+            _ => {
+                modules.push(Reflection::new(
+                    namespace.name.clone(),
+                    ReflectionKind::Module,
+                    comment,
+                ));
+                modules.last_mut().expect("Module was just added")
+            }
+        };
+
+        for symbol in &namespace.symbols {
+            let reflection = convert_symbol(symbol, parser);
+            // Overloads are separate symbols but a single reflection in TypeDoc
+            let is_duplicate = parent
+                .children
+                .iter()
+                .any(|child| child.name == reflection.name && child.kind == reflection.kind);
+            if !is_duplicate {
+                parent.children.push(reflection);
+            }
+        }
+    }
+    modules
+}
+
+fn convert_symbol(symbol: &Symbol, parser: &mut Parser) -> Reflection {
+    let comment = get_leading_jsdoc(&symbol.source_code).and_then(convert_comment);
+    let tree = parser.parse(&symbol.source_code, None);
+    let declaration = tree
+        .as_ref()
+        .and_then(|tree| find_declaration(tree.root_node()));
+
+    let Some(declaration) = declaration else {
+        return Reflection::new(symbol.name.clone(), ReflectionKind::Variable, comment);
+    };
+
+    let source_code = symbol.source_code.as_bytes();
+    let kind = match declaration.kind() {
+        "class_declaration" | "abstract_class_declaration" | "class" => ReflectionKind::Class,
+        "interface_declaration" => ReflectionKind::Interface,
+        "function_signature" | "function_declaration" => ReflectionKind::Function,
+        "type_alias_declaration" => ReflectionKind::TypeAlias,
+        "enum_declaration" => ReflectionKind::Enum,
+        "internal_module" | "module" => ReflectionKind::Namespace,
+        _ => ReflectionKind::Variable,
+    };
+    let mut reflection = Reflection::new(symbol.name.clone(), kind, comment);
+
+    if declaration.kind() == "abstract_class_declaration" {
+        reflection
+            .flags
+            .insert("isAbstract".to_string(), json!(true));
+    }
+    if has_keyword(declaration, "const") {
+        reflection.flags.insert("isConst".to_string(), json!(true));
+    }
+    if let Some(body) = declaration.child_by_field_name("body") {
+        reflection.children = convert_members(body, source_code);
+    }
+    reflection
+}
+
+fn has_keyword(node: Node, keyword: &str) -> bool {
+    let mut cursor = node.walk();
+    let has_keyword = node
+        .children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == keyword);
+    has_keyword
+}
+
+/// Converts the public members of a class, interface or enum body.
+fn convert_members(body: Node, source_code: &[u8]) -> Vec<Reflection> {
+    let mut members: Vec<Reflection> = vec![];
+    let mut cursor = body.walk();
+    let mut previous_comment = None;
+
+    for member in body.named_children(&mut cursor) {
+        if member.kind() == "comment" {
+            previous_comment = member.utf8_text(source_code).ok();
+            continue;
+        }
+        let comment = previous_comment
+            .take()
+            .filter(|comment| comment.starts_with("/**"))
+            .and_then(convert_comment);
+
+        let (name_node, kind) = match member.kind() {
+            "property_identifier" | "string" => (Some(member), ReflectionKind::EnumMember),
+            "enum_assignment" => (
+                member.child_by_field_name("name"),
+                ReflectionKind::EnumMember,
+            ),
+            "public_field_definition" | "property_signature" => {
+                (member.child_by_field_name("name"), ReflectionKind::Property)
+            }
+            "method_definition" | "method_signature" | "abstract_method_signature" => {
+                (member.child_by_field_name("name"), ReflectionKind::Method)
+            }
+            _ => (None, ReflectionKind::Property),
+        };
+        let Some(name_node) = name_node else {
+            continue;
+        };
+        if name_node.kind() == "private_property_identifier"
+            || is_private_member(member, source_code)
+        {
+            continue;
+        }
+        let Ok(name) = name_node.utf8_text(source_code) else {
+            continue;
+        };
+        let name = name.trim_matches(['"', '\'']).to_string();
+
+        let kind = match (kind, name.as_str()) {
+            (ReflectionKind::Method, "constructor") => ReflectionKind::Constructor,
+            (kind, _) => kind,
+        };
+        if members
+            .iter()
+            .any(|existing| existing.name == name && existing.kind == kind)
+        {
+            continue;
+        }
+
+        let mut reflection = Reflection::new(name, kind, comment);
+        for (keyword, flag) in [
+            ("static", "isStatic"),
+            ("readonly", "isReadonly"),
+            ("abstract", "isAbstract"),
+            ("?", "isOptional"),
+        ] {
+            if has_keyword(member, keyword) {
+                reflection.flags.insert(flag.to_string(), json!(true));
+            }
+        }
+        if let Some(accessibility) = get_accessibility(member, source_code) {
+            if accessibility == "protected" {
+                reflection
+                    .flags
+                    .insert("isProtected".to_string(), json!(true));
+            }
+        }
+        members.push(reflection);
+    }
+    members
+}
+
+fn get_accessibility<'a>(member: Node, source_code: &'a [u8]) -> Option<&'a str> {
+    let mut cursor = member.walk();
+    let modifier = member
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "accessibility_modifier")?;
+    modifier.utf8_text(source_code).ok()
+}
+
+fn is_private_member(member: Node, source_code: &[u8]) -> bool {
+    get_accessibility(member, source_code) == Some("private")
+}
+
+/// Converts a JSDoc comment to a TypeDoc comment, or `None` if it's empty.
+fn convert_comment(jsdoc: &str) -> Option<Value> {
+    let description = get_description(jsdoc);
+    let mut block_tags = vec![];
+    let mut modifier_tags = vec![];
+
+    for tag in parse_tags(jsdoc) {
+        if MODIFIER_TAGS.contains(&tag.name.as_str()) && tag.content.is_empty() {
+            modifier_tags.push(json!(format!("@{}", tag.name)));
+            continue;
+        }
+        let content = match tag.name.as_str() {
+            "example" if !tag.content.contains("```") => {
+                format!("```ts\n{}\n```", tag.content.trim())
+            }
+            _ => tag.content.trim().to_string(),
+        };
+        let content_kind = if tag.name == "example" {
+            "code"
+        } else {
+            "text"
+        };
+        let mut block_tag = json!({
+            "tag": format!("@{}", tag.name),
+            "content": [{ "kind": content_kind, "text": content }],
+        });
+        if matches!(tag.name.as_str(), "param" | "typeParam" | "template") {
+            if let Some((name, text)) = split_parameter_tag(&tag.content) {
+                block_tag["name"] = json!(name);
+                block_tag["content"] = json!([{ "kind": "text", "text": text }]);
+            }
+        }
+        block_tags.push(block_tag);
+    }
+
+    if description.is_empty() && block_tags.is_empty() && modifier_tags.is_empty() {
+        return None;
+    }
+    let mut comment = json!({ "summary": [] });
+    if !description.is_empty() {
+        comment["summary"] = json!([{ "kind": "text", "text": description }]);
+    }
+    if !block_tags.is_empty() {
+        comment["blockTags"] = Value::Array(block_tags);
+    }
+    if !modifier_tags.is_empty() {
+        comment["modifierTags"] = Value::Array(modifier_tags);
+    }
+    Some(comment)
+}
+
+/// Splits the content of a `@param` tag into the parameter name and its description, dropping
+/// any JSDoc type (e.g. `{string} name - The name`).
+fn split_parameter_tag(content: &str) -> Option<(&str, &str)> {
+    let content = content.trim_start();
+    let content = match content.strip_prefix('{') {
+        Some(rest) => rest.split_once('}')?.1.trim_start(),
+        None => content,
+    };
+    let (name, description) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+    let description = description.trim_start();
+    let description = description.strip_prefix("- ").unwrap_or(description);
+    Some((name.trim_matches(['[', ']']), description.trim()))
+}
+
+fn reflection_to_json(reflection: Reflection, next_id: &mut u32) -> Value {
+    let id = *next_id;
+    *next_id += 1;
+
+    let mut value = json!({
+        "id": id,
+        "name": reflection.name,
+        "variant": "declaration",
+        "kind": reflection.kind as u32,
+        "flags": reflection.flags,
+    });
+    if let Some(comment) = reflection.comment {
+        value["comment"] = comment;
+    }
+    if !reflection.children.is_empty() {
+        let children = reflection
+            .children
+            .into_iter()
+            .map(|child| reflection_to_json(child, next_id))
+            .collect::<Vec<_>>();
+        value["groups"] = get_groups(&children);
+        value["children"] = Value::Array(children);
+    }
+    value
+}
+
+/// Groups the children by kind, as TypeDoc does.
+fn get_groups(children: &[Value]) -> Value {
+    let groups = GROUP_ORDER
+        .iter()
+        .filter_map(|kind| {
+            let ids = children
+                .iter()
+                .filter(|child| child["kind"] == *kind as u32)
+                .map(|child| child["id"].clone())
+                .collect::<Vec<_>>();
+            (!ids.is_empty()).then(|| json!({ "title": kind.group_title(), "children": ids }))
+        })
+        .collect();
+    Value::Array(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_helpers::make_metadata;
+
+    fn make_namespace(name: &str, symbols: &[(&str, &str)]) -> Namespace {
+        Namespace {
+            name: name.to_string(),
+            symbols: symbols
+                .iter()
+                .map(|(name, source_code)| Symbol {
+                    name: name.to_string(),
+                    source_code: source_code.to_string(),
+                })
+                .collect(),
+            doc_comment: None,
+        }
+    }
+
+    fn get_child<'a>(value: &'a Value, name: &str) -> &'a Value {
+        value["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|child| child["name"] == name)
+            .unwrap_or_else(|| panic!("Missing child {name} in {value}"))
+    }
+
+    #[test]
+    fn project() {
+        let metadata = make_metadata("# pkg");
+
+        let output = render_typedoc_json(&metadata, &[make_namespace("pkg", &[])]);
+
+        assert_eq!(
+            output,
+            json!({
+                "id": 0,
+                "name": "pkg",
+                "variant": "project",
+                "kind": 1,
+                "flags": {},
+                "packageVersion": "1.0.0",
+                "readme": [{ "kind": "text", "text": "# pkg" }],
+                "schemaVersion": SCHEMA_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn single_entry_point() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[
+                ("Foo", "export declare class Foo {}"),
+                ("greet", "export declare function greet(): void;"),
+            ],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        assert_eq!(
+            output["children"],
+            json!([
+                { "id": 1, "name": "Foo", "variant": "declaration", "kind": 128, "flags": {} },
+                { "id": 2, "name": "greet", "variant": "declaration", "kind": 64, "flags": {} },
+            ])
+        );
+        assert_eq!(
+            output["groups"],
+            json!([
+                { "title": "Classes", "children": [1] },
+                { "title": "Functions", "children": [2] },
+            ])
+        );
+    }
+
+    #[test]
+    fn multiple_entry_points() {
+        let namespaces = vec![
+            make_namespace(
+                "pkg",
+                &[("VERSION", "export declare const VERSION: string;")],
+            ),
+            make_namespace(
+                "pkg/utils",
+                &[("helper", "export declare function helper(): void;")],
+            ),
+        ];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let root_module = get_child(&output, "pkg");
+        assert_eq!(root_module["kind"], 2);
+        assert_eq!(get_child(root_module, "VERSION")["kind"], 32);
+        let utils_module = get_child(&output, "pkg/utils");
+        assert_eq!(utils_module["kind"], 2);
+        assert_eq!(get_child(utils_module, "helper")["kind"], 64);
+    }
+
+    #[test]
+    fn symbol_kinds() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[
+                ("A", "export declare abstract class A {}"),
+                ("I", "export interface I {}"),
+                ("T", "export type T = string;"),
+                ("E", "export declare const enum E { X }"),
+                ("v", "export declare let v: string;"),
+                ("c", "export declare const c: string;"),
+            ],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let kinds_and_flags = ["A", "I", "T", "E", "v", "c"]
+            .iter()
+            .map(|name| {
+                let child = get_child(&output, name);
+                (child["kind"].clone(), child["flags"].clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds_and_flags,
+            vec![
+                (json!(128), json!({ "isAbstract": true })),
+                (json!(256), json!({})),
+                (json!(2097152), json!({})),
+                (json!(8), json!({ "isConst": true })),
+                (json!(32), json!({})),
+                (json!(32), json!({ "isConst": true })),
+            ]
+        );
+    }
+
+    #[test]
+    fn overloads() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[
+                ("greet", "export declare function greet(): void;"),
+                (
+                    "greet",
+                    "export declare function greet(name: string): void;",
+                ),
+            ],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        assert_eq!(output["children"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn nested_namespaces() {
+        let namespaces = vec![
+            make_namespace("pkg", &[]),
+            make_namespace("Outer", &[("a", "export declare const a: string;")]),
+            make_namespace("Outer.Inner", &[("b", "export declare const b: string;")]),
+        ];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let outer = get_child(&output, "Outer");
+        assert_eq!(outer["kind"], 4);
+        let inner = get_child(outer, "Inner");
+        assert_eq!(inner["kind"], 4);
+        assert_eq!(get_child(inner, "b")["kind"], 32);
+    }
+
+    #[test]
+    fn class_members() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[(
+                "Foo",
+                "export declare class Foo {\n  /** The bar */\n  static readonly bar: string;\n  private secret;\n  #hidden;\n  protected baz?: number;\n  constructor();\n  qux(): void;\n}",
+            )],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let class = get_child(&output, "Foo");
+        let members = class["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|member| {
+                (
+                    member["name"].as_str().unwrap(),
+                    member["kind"].clone(),
+                    member["flags"].clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![
+                (
+                    "bar",
+                    json!(1024),
+                    json!({ "isStatic": true, "isReadonly": true })
+                ),
+                (
+                    "baz",
+                    json!(1024),
+                    json!({ "isOptional": true, "isProtected": true })
+                ),
+                ("constructor", json!(512), json!({})),
+                ("qux", json!(2048), json!({})),
+            ]
+        );
+        assert_eq!(
+            get_child(class, "bar")["comment"],
+            json!({ "summary": [{ "kind": "text", "text": "The bar" }] })
+        );
+    }
+
+    #[test]
+    fn interface_members() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[(
+                "I",
+                "export interface I { a: string; m(): void; (): void; }",
+            )],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let interface = get_child(&output, "I");
+        assert_eq!(get_child(interface, "a")["kind"], 1024);
+        assert_eq!(get_child(interface, "m")["kind"], 2048);
+        assert_eq!(interface["children"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn enum_members() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[("E", "export enum E { A, B = 2, 'C' }")],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        let members = output["children"][0]["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|member| (member["name"].as_str().unwrap(), member["kind"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![("A", json!(16)), ("B", json!(16)), ("C", json!(16))]
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let namespaces = vec![make_namespace(
+            "pkg",
+            &[(
+                "greet",
+                "/**\n * Greets someone.\n * @param {string} name - The name\n * @returns Nothing\n * @example\n * greet('Alice');\n * @beta\n */\nexport declare function greet(name: string): void;",
+            )],
+        )];
+
+        let output = render_typedoc_json(&make_metadata(""), &namespaces);
+
+        assert_eq!(
+            output["children"][0]["comment"],
+            json!({
+                "summary": [{ "kind": "text", "text": "Greets someone." }],
+                "blockTags": [
+                    { "tag": "@param", "name": "name", "content": [{ "kind": "text", "text": "The name" }] },
+                    { "tag": "@returns", "content": [{ "kind": "text", "text": "Nothing" }] },
+                    { "tag": "@example", "content": [{ "kind": "code", "text": "```ts\ngreet('Alice');\n```" }] },
+                ],
+                "modifierTags": ["@beta"],
+            })
+        );
+    }
+
+    #[test]
+    fn entry_point_comment() {
+        let mut namespace = make_namespace("pkg", &[]);
+        namespace.doc_comment =
+            Some("/**\n * The package.\n * @packageDocumentation\n */".to_string());
+
+        let output = render_typedoc_json(&make_metadata(""), &[namespace]);
+
+        assert_eq!(
+            output["comment"],
+            json!({
+                "summary": [{ "kind": "text", "text": "The package." }],
+                "modifierTags": ["@packageDocumentation"],
+            })
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use daipendency_extractor::Namespace;
+
+use crate::api::{get_heritage, get_symbol_kind, SymbolKind};
+
+/// How the classes in a [HeritageGroup] relate to its base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeritageRelation {
+    /// The classes extend the base class.
+    Extends,
+    /// The classes implement the base interface.
+    Implements,
+}
+
+/// The exported classes built upon the same exported class or interface, so that plugin-style
+/// APIs can be navigated from the type they revolve around (e.g. the implementations of
+/// `Transport`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeritageGroup {
+    /// The name of the base class or interface, as exported.
+    pub base: String,
+    pub relation: HeritageRelation,
+    /// The namespace and name of each class, in the order of the public API.
+    pub classes: Vec<(String, String)>,
+}
+
+impl fmt::Display for HeritageGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.relation {
+            HeritageRelation::Extends => write!(f, "subclasses of `{}`", self.base),
+            HeritageRelation::Implements => write!(f, "implementations of `{}`", self.base),
+        }
+    }
+}
+
+/// Groups the exported classes by the exported classes they extend and the exported interfaces
+/// they implement, sorted by base and then relation.
+///
+/// Bases are matched by name, ignoring any namespace qualifier (e.g. `transports.Transport`
+/// matches `Transport`), so those from other packages are left out unless this package exports
+/// a symbol with the same name. A class exported from several entry points is only listed once.
+pub(crate) fn group_by_heritage(public_api: &[Namespace]) -> Vec<HeritageGroup> {
+    let exported_names = public_api
+        .iter()
+        .flat_map(|namespace| &namespace.symbols)
+        .map(|symbol| symbol.name.as_str())
+        .collect::<Vec<_>>();
+
+    let mut groups: BTreeMap<(String, HeritageRelation), Vec<(String, String)>> = BTreeMap::new();
+    for namespace in public_api {
+        for symbol in &namespace.symbols {
+            if !matches!(get_symbol_kind(symbol), Some(SymbolKind::Class { .. })) {
+                continue;
+            }
+            let Some(heritage) = get_heritage(symbol) else {
+                continue;
+            };
+            let bases = heritage
+                .extends
+                .iter()
+                .map(|base| (base, HeritageRelation::Extends))
+                .chain(
+                    heritage
+                        .implements
+                        .iter()
+                        .map(|base| (base, HeritageRelation::Implements)),
+                );
+            for (base, relation) in bases {
+                let base = base.rsplit('.').next().unwrap_or(base);
+                if base == symbol.name || !exported_names.contains(&base) {
+                    continue;
+                }
+                let classes = groups.entry((base.to_string(), relation)).or_default();
+                if !classes.iter().any(|(_, name)| *name == symbol.name) {
+                    classes.push((namespace.name.clone(), symbol.name.clone()));
+                }
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((base, relation), classes)| HeritageGroup {
+            base,
+            relation,
+            classes,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daipendency_extractor::Symbol;
+
+    fn make_namespace(name: &str, symbols: &[(&str, &str)]) -> Namespace {
+        Namespace {
+            name: name.to_string(),
+            symbols: symbols
+                .iter()
+                .map(|(name, source_code)| Symbol {
+                    name: name.to_string(),
+                    source_code: source_code.to_string(),
+                })
+                .collect(),
+            doc_comment: None,
+        }
+    }
+
+    fn make_class(namespace: &str, name: &str) -> (String, String) {
+        (namespace.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn implementations_of_interface() {
+        let public_api = vec![make_namespace(
+            "pkg",
+            &[
+                ("Transport", "export interface Transport {}"),
+                (
+                    "HttpTransport",
+                    "export declare class HttpTransport implements Transport {}",
+                ),
+                (
+                    "WsTransport",
+                    "export declare class WsTransport implements Transport<string> {}",
+                ),
+            ],
+        )];
+
+        let groups = group_by_heritage(&public_api);
+
+        assert_eq!(
+            groups,
+            vec![HeritageGroup {
+                base: "Transport".to_string(),
+                relation: HeritageRelation::Implements,
+                classes: vec![
+                    make_class("pkg", "HttpTransport"),
+                    make_class("pkg", "WsTransport")
+                ],
+            }]
+        );
+        assert_eq!(groups[0].to_string(), "implementations of `Transport`");
+    }
+
+    #[test]
+    fn subclasses_across_namespaces() {
+        let public_api = vec![
+            make_namespace("pkg", &[("Plugin", "export declare class Plugin {}")]),
+            make_namespace(
+                "pkg/plugins",
+                &[(
+                    "LogPlugin",
+                    "export declare class LogPlugin extends core.Plugin implements Disposable {}",
+                )],
+            ),
+        ];
+
+        let groups = group_by_heritage(&public_api);
+
+        assert_eq!(
+            groups,
+            vec![HeritageGroup {
+                base: "Plugin".to_string(),
+                relation: HeritageRelation::Extends,
+                classes: vec![make_class("pkg/plugins", "LogPlugin")],
+            }]
+        );
+        assert_eq!(groups[0].to_string(), "subclasses of `Plugin`");
+    }
+
+    #[test]
+    fn class_in_several_namespaces() {
+        let class = (
+            "HttpTransport",
+            "export declare class HttpTransport implements Transport {}",
+        );
+        let public_api = vec![
+            make_namespace(
+                "pkg",
+                &[("Transport", "export interface Transport {}"), class],
+            ),
+            make_namespace("pkg/http", &[class]),
+        ];
+
+        let groups = group_by_heritage(&public_api);
+
+        assert_eq!(groups[0].classes, vec![make_class("pkg", "HttpTransport")]);
+    }
+
+    #[test]
+    fn interfaces_extending_interfaces() {
+        let public_api = vec![make_namespace(
+            "pkg",
+            &[
+                ("Base", "export interface Base {}"),
+                ("Derived", "export interface Derived extends Base {}"),
+            ],
+        )];
+
+        assert_eq!(group_by_heritage(&public_api), vec![]);
+    }
+}
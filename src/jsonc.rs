@@ -0,0 +1,136 @@
+use serde::de::DeserializeOwned;
+
+/// Parses JSON as found in manifests and TypeScript configuration files, which may have
+/// comments, trailing commas and a byte order mark even though strict JSON doesn't allow them.
+///
+/// Strict JSON is parsed as is, so errors are reported against the original content.
+pub(crate) fn parse_jsonc<T: DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    serde_json::from_str(content).or_else(|error| {
+        let sanitised_content = sanitise_jsonc(content);
+        if sanitised_content == content {
+            return Err(error);
+        }
+        serde_json::from_str(&sanitised_content).map_err(|_| error)
+    })
+}
+
+/// Blanks out comments and trailing commas outside strings, keeping line breaks so that line
+/// numbers don't change.
+fn sanitise_jsonc(content: &str) -> String {
+    let mut sanitised_content = String::with_capacity(content.len());
+    // The position of the last comma, if only whitespace and comments have followed it
+    let mut pending_comma: Option<usize> = None;
+    let mut chars = content.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' => {
+                pending_comma = None;
+                sanitised_content.push(char);
+                while let Some(char) = chars.next() {
+                    sanitised_content.push(char);
+                    match char {
+                        '\\' => sanitised_content.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        sanitised_content.push(char);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous_char = None;
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        sanitised_content.push(char);
+                    }
+                    if previous_char == Some('*') && char == '/' {
+                        break;
+                    }
+                    previous_char = Some(char);
+                }
+                sanitised_content.push(' ');
+            }
+            ',' => {
+                pending_comma = Some(sanitised_content.len());
+                sanitised_content.push(char);
+            }
+            '}' | ']' => {
+                if let Some(comma_position) = pending_comma.take() {
+                    sanitised_content.replace_range(comma_position..comma_position + 1, " ");
+                }
+                sanitised_content.push(char);
+            }
+            _ => {
+                if !char.is_whitespace() {
+                    pending_comma = None;
+                }
+                sanitised_content.push(char);
+            }
+        }
+    }
+    sanitised_content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn strict_json() {
+        let value: Value = parse_jsonc(r#"{"name": "pkg", "files": ["dist"]}"#).unwrap();
+
+        assert_eq!(value, json!({"name": "pkg", "files": ["dist"]}));
+    }
+
+    #[test]
+    fn comments() {
+        let value: Value = parse_jsonc(
+            "{\n  // The name\n  \"name\": \"pkg\", /* inline */\n  /* Multi-line\n  comment */\n  \"version\": \"1.0.0\"\n}",
+        )
+        .unwrap();
+
+        assert_eq!(value, json!({"name": "pkg", "version": "1.0.0"}));
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let value: Value =
+            parse_jsonc("{\"files\": [\"dist\", \"lib\",], \"name\": \"pkg\", // Last\n}").unwrap();
+
+        assert_eq!(value, json!({"files": ["dist", "lib"], "name": "pkg"}));
+    }
+
+    #[test]
+    fn comment_markers_in_strings() {
+        let value: Value =
+            parse_jsonc(r#"{"homepage": "https://example.com/*", "quote": "\",]", }"#).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"homepage": "https://example.com/*", "quote": "\",]"})
+        );
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let value: Value = parse_jsonc("\u{feff}{\"name\": \"pkg\"}").unwrap();
+
+        assert_eq!(value, json!({"name": "pkg"}));
+    }
+
+    #[test]
+    fn invalid_json() {
+        let error = parse_jsonc::<Value>("{\"name\": // Unterminated\n").unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 10));
+    }
+}
@@ -1,6 +1,54 @@
 mod api;
+mod capabilities;
 mod dependencies;
+mod diagnostics;
 mod extractor;
+mod formats;
+mod implementations;
+mod jsonc;
 mod metadata;
+mod options;
+mod paths;
+mod report;
+mod signature;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod version_range;
+mod workspaces;
 
+pub use api::{
+    check_entry_points, check_readme, collect_examples, extract_call_signatures, extract_examples,
+    extract_links, find_export_locations, find_file_extension, find_internal_symbols, get_heritage,
+    get_members, get_stability, get_symbol_kind, get_type_alias_target, get_value_shape,
+    load_module_set, pretty_print, reduce_detail, resolve_links, resolve_specifier, visit_modules,
+    Accessibility, ApiExample, AssetReference, Augmentation, CallSignature, Capture,
+    CrossReference, CustomSymbol, DependencyWeight, EntryPointHealth, EntryPointStatus, Example,
+    ExportLocation, ExportTarget, ExportedItem, ExternalExport, FileExtension, FileKind, FileKinds,
+    Generator, Grammar, Heritage, ImportHint, ImportTarget, ImportedItem, InternalSymbol, Link,
+    LinkKind, LinkResolution, Member, MemberKind, Module, ModuleSet, Parameter, Pragma,
+    PublicApiIter, QueryExtension, ReadmeMismatch, ReadmeReference, ReadmeReport, ReadmeSnippet,
+    ResolvedExport, Specifier, Stability, SymbolKind, SymbolParts, SymbolVisitor, TypeAliasTarget,
+    TypeScriptSymbol, UnresolvedExternal, UnresolvedReason, ValueShape, VisitContext,
+    FILE_EXTENSIONS,
+};
+pub use capabilities::{Capability, CapabilitySummary, EntryPointCapabilities};
+pub use diagnostics::Diagnostic;
 pub use extractor::TypeScriptExtractor;
+pub use formats::{
+    estimate_api_tokens, estimate_tokens, render, render_compact, render_summary,
+    render_typedoc_json, summarise_api, CompactFormatter, FormatterRegistry, JsonFormatter,
+    MarkdownFormatter, NamespaceSummary, NamespaceTokens, OutputFormat, OutputFormatter,
+    SummaryOptions, SymbolGroup, SymbolTokens, TokenReport,
+};
+pub use implementations::{HeritageGroup, HeritageRelation};
+pub use metadata::{
+    ExtraDoc, PackageType, PublishedFiles, TSEntryPoint, TSEntryPointSet, TSLibraryMetadata,
+};
+pub use options::{DependencyFallback, DetailLevel, ExtractionOptions, UnpublishedFiles};
+pub use report::{
+    AugmentationReport, EntryPointReport, ExtractionReport, ExtractionStats, ModuleMetrics,
+    ModuleReport,
+};
+pub use signature::normalize_signature;
+pub use version_range::TypeScriptVersion;
+pub use workspaces::{extract_workspace_metadata, find_workspace_packages};
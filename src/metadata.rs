@@ -1,9 +1,30 @@
 use daipendency_extractor::{LibraryMetadata, LibraryMetadataError};
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
 
+use crate::api::synthesis::is_source_file;
+use crate::api::Stability;
+use crate::diagnostics::Diagnostic;
+use crate::jsonc::parse_jsonc;
+use crate::options::{ExtractionOptions, UnpublishedFiles};
+use crate::version_range::{TypeScriptVersion, VersionRange};
+use crate::workspaces::find_workspace_member;
+
+/// Where declaration files conventionally live, in order of preference.
+const CONVENTIONAL_ENTRY_POINTS: [&str; 4] = [
+    "dist/index.d.ts",
+    "lib/index.d.ts",
+    "types/index.d.ts",
+    "index.d.ts",
+];
+
+/// The declaration file that TypeScript loads from the root of DefinitelyTyped packages, which
+/// don't point to it.
+const IMPLICIT_ENTRY_POINT: &str = "index.d.ts";
+
 /// A TypeScript entrypoint mapping external package paths to internal file paths.
 #[derive(Debug, Clone)]
 pub struct TSEntryPoint {
@@ -11,6 +32,12 @@ pub struct TSEntryPoint {
     pub external_path: String,
     /// The internal filesystem path to the module
     pub internal_path: PathBuf,
+    /// The target of every export condition for this subpath (e.g. `./dist/index.mjs` for
+    /// `import`), keyed by the nested conditions joined with `.` (e.g. `import.types`).
+    ///
+    /// The wildcards of subpath patterns are substituted. This is empty if the entry point
+    /// doesn't come from the `exports` field. It's ignored when comparing entry points.
+    pub conditions: BTreeMap<String, String>,
 }
 
 impl PartialEq for TSEntryPoint {
@@ -28,42 +55,430 @@ impl Hash for TSEntryPoint {
     }
 }
 
-/// A set of TypeScript entrypoints.
-pub type TSEntryPointSet = HashSet<TSEntryPoint>;
+/// A set of TypeScript entrypoints, along with any diagnostics from working them out.
+///
+/// This also carries package-level information that [LibraryMetadata] has no field for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TSEntryPointSet {
+    entry_points: HashSet<TSEntryPoint>,
+    /// Problems and assumptions encountered whilst determining the entry points.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The documents that weren't picked as the documentation (e.g. READMEs in other languages).
+    pub extra_docs: Vec<ExtraDoc>,
+    /// Whether the package only provides types (e.g. `@types/node`), so there's nothing to import
+    /// at runtime and its API can only be imported with `import type`.
+    pub is_type_only: bool,
+    /// The canonical path of the package, which the [ExtractionOptions::excluded_files] are
+    /// relative to.
+    pub package_path: PathBuf,
+    /// The stability of the package as a whole, as set in the `stability` field of `package.json`.
+    pub stability: Option<Stability>,
+    /// The declaration files replaced by others in the browser build, keyed by canonical path, if
+    /// [ExtractionOptions::browser] is set. These come from the `browser` field of `package.json`
+    /// (e.g. `{"./lib/node.js": "./lib/browser.js"}`).
+    pub browser_remappings: BTreeMap<PathBuf, PathBuf>,
+    /// The module format of the package, as set by the `type` field of its `package.json`.
+    ///
+    /// This applies to the files of the package that aren't covered by nested manifests (e.g.
+    /// `dist/cjs/package.json`), and decides how their extensionless and `.js` imports resolve.
+    pub package_type: PackageType,
+    /// The files that the package publishes, which the declaration files are checked against if
+    /// [ExtractionOptions::unpublished_files] is set.
+    pub published_files: PublishedFiles,
+}
+
+/// Directories whose declarations are tests or demos rather than part of the API.
+const TEST_DIRECTORIES: [&str; 8] = [
+    "__tests__",
+    "__mocks__",
+    "test",
+    "tests",
+    "example",
+    "examples",
+    "demo",
+    "demos",
+];
+
+/// Checks whether a declaration file is left out by the [ExtractionOptions], going by its path
+/// within the package at `package_path`.
+///
+/// Files outside the package are never excluded.
+pub(crate) fn is_excluded_file(
+    path: &Path,
+    package_path: &Path,
+    options: &ExtractionOptions,
+) -> bool {
+    if package_path.as_os_str().is_empty() {
+        return false;
+    }
+    let Ok(relative_path) = path.strip_prefix(package_path) else {
+        return false;
+    };
+    let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+    (options.exclude_test_files && is_test_file(&relative_path))
+        || options
+            .excluded_files
+            .iter()
+            .any(|pattern| is_file_included(&relative_path, pattern))
+}
+
+/// The files that a package publishes, as listed in the `files` field of its `package.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishedFiles {
+    /// The canonical path of the package, which the patterns are relative to.
+    pub package_path: PathBuf,
+    /// The entries of the `files` field, or None if every file is published (e.g. because the
+    /// manifest has no such field).
+    pub patterns: Option<Vec<String>>,
+}
+
+impl PublishedFiles {
+    /// Checks whether a file wouldn't be published, going by its path within the package.
+    ///
+    /// Files outside the package are never checked.
+    pub(crate) fn is_unpublished(&self, path: &Path) -> bool {
+        let Some(patterns) = &self.patterns else {
+            return false;
+        };
+        let Ok(relative_path) = path.strip_prefix(&self.package_path) else {
+            return false;
+        };
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        !patterns
+            .iter()
+            .any(|pattern| is_file_included(&relative_path, pattern))
+    }
+}
+
+/// Checks whether a file looks like a test or a demo: it's in a directory like `__tests__` or
+/// `examples`, or it's named like `*.test.d.ts` or `*.spec.d.ts`.
+fn is_test_file(relative_path: &str) -> bool {
+    let mut segments = relative_path.split('/').collect::<Vec<_>>();
+    let file_name = segments.pop().unwrap_or_default();
+    segments
+        .iter()
+        .any(|segment| TEST_DIRECTORIES.contains(segment))
+        || file_name.contains(".test.")
+        || file_name.contains(".spec.")
+}
+
+/// A document in the package other than the one used as its documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraDoc {
+    /// The path of the document relative to the package (e.g. `README.zh-CN.md`).
+    pub path: PathBuf,
+    /// The language of the document (e.g. `zh-CN`), if it's localised.
+    pub locale: Option<String>,
+    pub content: String,
+}
+
+/// Provides HashSet-like access semantics without needing to reference the inner field
+impl Deref for TSEntryPointSet {
+    type Target = HashSet<TSEntryPoint>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entry_points
+    }
+}
+
+impl DerefMut for TSEntryPointSet {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entry_points
+    }
+}
+
+impl From<HashSet<TSEntryPoint>> for TSEntryPointSet {
+    fn from(entry_points: HashSet<TSEntryPoint>) -> Self {
+        Self {
+            entry_points,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<TSEntryPoint> for TSEntryPointSet {
+    fn from_iter<I: IntoIterator<Item = TSEntryPoint>>(iter: I) -> Self {
+        HashSet::from_iter(iter).into()
+    }
+}
+
+impl<'a> IntoIterator for &'a TSEntryPointSet {
+    type Item = &'a TSEntryPoint;
+    type IntoIter = std::collections::hash_set::Iter<'a, TSEntryPoint>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entry_points.iter()
+    }
+}
 
 /// TypeScript library metadata.
 pub type TSLibraryMetadata = LibraryMetadata<TSEntryPointSet>;
 
+/// The module format of a package, as set by the `type` field of its `package.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PackageType {
+    /// `.js` and `.d.ts` files are CommonJS modules (the default).
+    #[default]
+    CommonJs,
+    /// `.js` and `.d.ts` files are ES modules.
+    Module,
+}
+
+impl PackageType {
+    /// Parses the `type` field of a manifest, falling back to CommonJS for missing or
+    /// unrecognised values like Node.js does.
+    fn from_type_field(type_field: Option<&str>) -> Self {
+        match type_field {
+            Some("module") => PackageType::Module,
+            _ => PackageType::CommonJs,
+        }
+    }
+}
+
+/// The subset of a `package.json` needed to determine the package type.
+///
+/// This is separate from [PackageJson] because nested manifests (e.g. `dist/esm/package.json`)
+/// often contain nothing but the `type` field.
+#[derive(Debug, Deserialize)]
+struct PackageTypeManifest {
+    #[serde(default, rename = "type")]
+    package_type: Option<String>,
+}
+
+/// The `imports` field of a manifest, which maps specifiers starting with `#` to files within the
+/// package (e.g. `"#internal/*": "./src/internal/*.js"`).
+#[derive(Debug, Deserialize)]
+struct PackageImportsManifest {
+    #[serde(default)]
+    imports: HashMap<String, ExportConfig>,
+}
+
+/// Resolves a subpath import (e.g. `#internal/foo.js`) with the `imports` field of the nearest
+/// `package.json` to the importing file, returning the path of the target within the package.
+///
+/// Only targets within the package are supported, so those referring to other packages are
+/// ignored, and so are the conditions that TypeScript wouldn't match.
+pub(crate) fn resolve_subpath_import(importer_path: &Path, specifier: &str) -> Option<PathBuf> {
+    let (package_path, content) = importer_path.ancestors().skip(1).find_map(|directory| {
+        let content = std::fs::read_to_string(directory.join("package.json")).ok()?;
+        Some((directory, content))
+    })?;
+    let manifest = parse_jsonc::<PackageImportsManifest>(&content).ok()?;
+    let (config, substitution) = get_matching_subpath(specifier, &manifest.imports)?;
+    let target = get_import_target(config)?;
+    let target = match substitution {
+        Some(substitution) => target.replace('*', substitution),
+        None => target.clone(),
+    };
+    Some(package_path.join(target.strip_prefix("./")?))
+}
+
+/// Gets the target of a subpath import, going through its `types` condition or else the runtime
+/// conditions that TypeScript matches.
+fn get_import_target(config: &ExportConfig) -> Option<&String> {
+    match config {
+        ExportConfig::Simple(target) => Some(target),
+        ExportConfig::Map(conditions) => std::iter::once("types")
+            .chain(RUNTIME_CONDITIONS)
+            .find_map(|condition| get_import_target(conditions.get(condition)?)),
+        ExportConfig::Excluded => None,
+    }
+}
+
+/// Finds the type of the package that owns the given file, using the nearest `package.json`.
+///
+/// Unreadable manifests and unrecognised values fall back to CommonJS, like Node.js does. The types
+/// already known for some package directories are used and updated, so that each manifest is read
+/// at most once.
+pub(crate) fn find_package_type_with(
+    file_path: &Path,
+    known_package_types: &mut HashMap<PathBuf, PackageType>,
+) -> PackageType {
+    for directory in file_path.ancestors().skip(1) {
+        if let Some(package_type) = known_package_types.get(directory) {
+            return *package_type;
+        }
+        let Ok(content) = std::fs::read_to_string(directory.join("package.json")) else {
+            continue;
+        };
+        let manifest = parse_jsonc::<PackageTypeManifest>(&content).ok();
+        let package_type =
+            PackageType::from_type_field(manifest.and_then(|m| m.package_type).as_deref());
+        known_package_types.insert(directory.to_path_buf(), package_type);
+        return package_type;
+    }
+    PackageType::CommonJs
+}
+
 #[derive(Debug, Deserialize)]
 struct PackageJson {
     name: String,
     version: String,
     #[serde(default)]
-    types: Option<String>,
+    types: Option<TypesField>,
+    #[serde(default)]
+    typings: Option<TypesField>,
     #[serde(default)]
-    typings: Option<String>,
+    main: Option<String>,
+    #[serde(default)]
+    module: Option<String>,
     #[serde(default)]
     exports: Option<ExportConfig>,
+    #[serde(default)]
+    browser: Option<BrowserField>,
+    /// The path mappings for each range of TypeScript versions (e.g. `{">=4.2": {"*": ["ts4.2/*"]}}`).
+    #[serde(default, rename = "typesVersions")]
+    types_versions: Option<HashMap<String, TypesVersionsPaths>>,
+    #[serde(default)]
+    files: Option<Vec<String>>,
+    #[serde(default)]
+    stability: Option<String>,
+    #[serde(default, rename = "type")]
+    package_type: Option<String>,
+    /// The content of the README, which registries embed in the manifests of some tarballs.
+    #[serde(default)]
+    readme: Option<String>,
+}
+
+/// The `browser` field of a manifest, which either replaces the `main` file or remaps individual
+/// files and packages in the browser build.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BrowserField {
+    Entry(String),
+    Remappings(HashMap<String, BrowserTarget>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BrowserTarget {
+    Path(String),
+    /// `false`, which replaces the file or package with an empty module.
+    Disabled(serde::de::IgnoredAny),
 }
 
+/// The `types` or `typings` field of a manifest, which is occasionally a list of paths rather
+/// than a single one.
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
+enum TypesField {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl TypesField {
+    fn paths(&self) -> &[String] {
+        match self {
+            TypesField::Single(path) => std::slice::from_ref(path),
+            TypesField::Multiple(paths) => paths,
+        }
+    }
+}
+
+impl PackageJson {
+    /// Lists the runtime files the package is loaded from, in order of preference: the `main`
+    /// and `module` files, preceded by their replacements in the browser build if applicable.
+    fn get_runtime_entries(&self, is_browser: bool) -> Vec<&str> {
+        let entries = [&self.main, &self.module]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|entry| !entry.is_empty())
+            .collect::<Vec<_>>();
+        let browser_entries = match self.browser.as_ref().filter(|_| is_browser) {
+            Some(BrowserField::Entry(entry)) => vec![entry.as_str()],
+            Some(BrowserField::Remappings(remappings)) => entries
+                .iter()
+                .filter_map(|entry| match get_browser_remapping(remappings, entry)? {
+                    BrowserTarget::Path(target) => Some(target.as_str()),
+                    BrowserTarget::Disabled(_) => None,
+                })
+                .collect(),
+            None => vec![],
+        };
+        browser_entries.into_iter().chain(entries).collect()
+    }
+
+    /// Checks whether the package comes from DefinitelyTyped (e.g. `@types/node`).
+    fn is_definitely_typed(&self) -> bool {
+        self.name.starts_with("@types/")
+    }
+
+    /// Gets the paths in the `types` field, or else in the `typings` field, if any.
+    fn get_types_paths(&self) -> Option<&[String]> {
+        self.types
+            .as_ref()
+            .or(self.typings.as_ref())
+            .map(TypesField::paths)
+            .filter(|paths| !paths.is_empty())
+    }
+}
+
+/// The paths that a `typesVersions` selector maps, each to the targets to try in order (e.g.
+/// `{"*": ["ts4.2/*"]}`).
+type TypesVersionsPaths = HashMap<String, Vec<String>>;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
 enum ExportConfig {
     Simple(String),
     Map(HashMap<String, ExportConfig>),
+    /// A `null` target, which hides the subpaths it matches (e.g. `"./internal/*": null`).
+    Excluded,
 }
 
-pub fn extract_metadata(path: &Path) -> Result<TSLibraryMetadata, LibraryMetadataError> {
+pub fn extract_metadata(
+    path: &Path,
+    options: &ExtractionOptions,
+) -> Result<TSLibraryMetadata, LibraryMetadataError> {
+    if let Some(member_name) = &options.workspace_member {
+        let member_path = find_workspace_member(path, member_name)?;
+        let member_options = ExtractionOptions {
+            workspace_member: None,
+            ..options.clone()
+        };
+        return extract_metadata(&member_path, &member_options);
+    }
+
     let package_json_path = path.join("package.json");
     let content = std::fs::read_to_string(&package_json_path)
         .map_err(LibraryMetadataError::MissingManifest)?;
 
-    let package_json: PackageJson = serde_json::from_str(&content)
+    let package_json: PackageJson = parse_jsonc(&content)
         .map_err(|e| LibraryMetadataError::MalformedManifest(e.to_string()))?;
 
-    let entry_point = get_entry_point_set(&package_json, path);
+    let typescript_version = options
+        .typescript_version
+        .unwrap_or(TypeScriptVersion::LATEST);
+    let runtime_conditions = get_runtime_conditions(options.browser);
+    let mut entry_point = get_entry_point_set(
+        &package_json,
+        path,
+        &typescript_version,
+        &runtime_conditions,
+        options.browser,
+    );
+    entry_point.is_type_only = is_type_only_package(&package_json, path);
+    entry_point.package_type = PackageType::from_type_field(package_json.package_type.as_deref());
+    entry_point.stability = package_json.stability.as_deref().and_then(Stability::parse);
+    if options.browser {
+        entry_point.browser_remappings = get_browser_remappings(&package_json, path);
+    }
+    entry_point.package_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    entry_point.published_files = PublishedFiles {
+        package_path: entry_point.package_path.clone(),
+        patterns: package_json.files.clone(),
+    };
+    if options.unpublished_files != UnpublishedFiles::Include {
+        check_published_entry_points(&mut entry_point, options.unpublished_files);
+    }
 
-    let documentation = read_readme(path);
+    let (documentation, extra_docs) = read_readmes(
+        path,
+        options.locale.as_deref(),
+        package_json.readme.as_deref(),
+    );
+    entry_point.extra_docs = extra_docs;
 
     Ok(TSLibraryMetadata {
         name: package_json.name,
@@ -73,51 +488,1082 @@ pub fn extract_metadata(path: &Path) -> Result<TSLibraryMetadata, LibraryMetadat
     })
 }
 
-fn read_readme(path: &Path) -> String {
-    let readme_paths = ["README.md", "README.txt", "README"];
-    for readme_path in readme_paths {
-        if let Ok(content) = std::fs::read_to_string(path.join(readme_path)) {
-            return content;
+/// The directory where packages without a README in their root may have one.
+const DOCS_DIRECTORY: &str = "docs";
+
+/// What npm puts in the `readme` field of manifests when the package has no README.
+const MISSING_README_PLACEHOLDER: &str = "ERROR: No README data found!";
+
+/// A README file in a package.
+struct Readme {
+    /// The path of the file relative to the package.
+    path: PathBuf,
+    locale: Option<String>,
+    content: String,
+}
+
+/// Reads the READMEs in the package, returning the content of the one to use as documentation
+/// and the others as extra documents.
+///
+/// The README in the preferred locale is used if there is one, falling back to one in the same
+/// language (e.g. `zh` for `zh-CN`) and then to the default README.
+///
+/// If the root of the package has no README, the `readme` field of the manifest is used instead,
+/// or else the READMEs in the `docs` directory.
+fn read_readmes(
+    path: &Path,
+    locale: Option<&str>,
+    readme_field: Option<&str>,
+) -> (String, Vec<ExtraDoc>) {
+    let mut readmes = find_readmes(path, Path::new(""));
+    if readmes.is_empty() {
+        let readme_field = readme_field
+            .map(str::trim)
+            .filter(|readme| !readme.is_empty() && *readme != MISSING_README_PLACEHOLDER);
+        if let Some(readme) = readme_field {
+            return (readme.to_string(), vec![]);
         }
+        readmes = find_readmes(path, Path::new(DOCS_DIRECTORY));
+    }
+    let chosen_index = locale
+        .and_then(|locale| find_localised_readme(&readmes, locale))
+        .or_else(|| readmes.iter().position(|readme| readme.locale.is_none()))
+        .or((!readmes.is_empty()).then_some(0));
+
+    let documentation = match chosen_index {
+        Some(index) => readmes.remove(index).content,
+        None => String::new(),
+    };
+    let extra_docs = readmes
+        .into_iter()
+        .map(|readme| ExtraDoc {
+            path: readme.path,
+            locale: readme.locale,
+            content: readme.content,
+        })
+        .collect();
+    (documentation, extra_docs)
+}
+
+/// Finds the READMEs in a directory of the package, with the default ones first (in order of
+/// preference) followed by the localised ones sorted by file name.
+fn find_readmes(path: &Path, directory: &Path) -> Vec<Readme> {
+    let Ok(entries) = std::fs::read_dir(path.join(directory)) else {
+        return vec![];
+    };
+    let mut file_names = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file_name| {
+            parse_readme_file_name(&file_name).map(|(rank, locale)| (rank, locale, file_name))
+        })
+        .collect::<Vec<_>>();
+    file_names.sort();
+
+    file_names
+        .into_iter()
+        .filter_map(|(_, locale, file_name)| {
+            let relative_path = directory.join(file_name);
+            let content = std::fs::read_to_string(path.join(&relative_path)).ok()?;
+            Some(Readme {
+                path: relative_path,
+                locale,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Parses file names like `README.md`, `Readme.rst` or `README.zh-CN.md`, returning a rank to
+/// sort them by and the locale (if any). Both the name and the extension are case-insensitive.
+fn parse_readme_file_name(file_name: &str) -> Option<(usize, Option<String>)> {
+    const PREFIX: &str = "README";
+    const EXTENSIONS: [&str; 6] = [".md", ".markdown", ".rst", ".adoc", ".txt", ""];
+
+    let prefix = file_name.get(..PREFIX.len())?;
+    if !prefix.eq_ignore_ascii_case(PREFIX) {
+        return None;
+    }
+    let rest = &file_name[PREFIX.len()..];
+    let lowercase_rest = rest.to_ascii_lowercase();
+    let (extension_rank, stem) = EXTENSIONS
+        .iter()
+        .enumerate()
+        .find_map(|(rank, extension)| {
+            lowercase_rest
+                .ends_with(extension)
+                .then(|| (rank, &rest[..rest.len() - extension.len()]))
+        })?;
+    if stem.is_empty() {
+        return Some((extension_rank, None));
+    }
+
+    let locale = stem.strip_prefix(['.', '-', '_'])?;
+    if !is_locale(locale) {
+        return None;
     }
-    String::new()
+    Some((EXTENSIONS.len(), Some(locale.replace('_', "-"))))
+}
+
+/// Checks whether the text looks like a language tag such as `ja`, `zh-CN` or `pt_BR`.
+fn is_locale(text: &str) -> bool {
+    let mut subtags = text.split(['-', '_']);
+    let language = subtags.next().unwrap_or("");
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+fn find_localised_readme(readmes: &[Readme], locale: &str) -> Option<usize> {
+    let locale = locale.replace('_', "-");
+    let language = locale.split('-').next().unwrap_or(&locale);
+    let readme_locales = readmes
+        .iter()
+        .map(|readme| readme.locale.as_deref().unwrap_or(""))
+        .collect::<Vec<_>>();
+
+    readme_locales
+        .iter()
+        .position(|readme_locale| readme_locale.eq_ignore_ascii_case(&locale))
+        .or_else(|| {
+            readme_locales
+                .iter()
+                .position(|readme_locale| readme_locale.eq_ignore_ascii_case(language))
+        })
+        .or_else(|| {
+            readme_locales.iter().position(|readme_locale| {
+                readme_locale
+                    .split('-')
+                    .next()
+                    .is_some_and(|readme_language| readme_language.eq_ignore_ascii_case(language))
+            })
+        })
 }
 
-fn get_entry_point_set(package_json: &PackageJson, path: &Path) -> TSEntryPointSet {
-    let mut entry_point = HashSet::new();
+fn get_entry_point_set(
+    package_json: &PackageJson,
+    path: &Path,
+    typescript_version: &TypeScriptVersion,
+    runtime_conditions: &[&str],
+    is_browser: bool,
+) -> TSEntryPointSet {
+    let mut entry_point = TSEntryPointSet::default();
 
     // Handle exports
     if let Some(export_config) = &package_json.exports {
         match export_config {
             ExportConfig::Map(export_map) => {
                 for (subpath, config) in export_map {
-                    if let ExportConfig::Map(conditions) = config {
-                        if let Some(ExportConfig::Simple(types_path)) = conditions.get("types") {
-                            entry_point.insert(TSEntryPoint {
-                                external_path: subpath.clone(),
-                                internal_path: path.join(types_path.trim_start_matches("./")),
-                            });
+                    let string_conditions;
+                    let conditions = match config {
+                        ExportConfig::Map(conditions) => conditions,
+                        ExportConfig::Simple(target) => {
+                            // A string target is a `default` condition, unless it points to
+                            // the declarations themselves
+                            let condition = if is_declaration_file(target) {
+                                "types"
+                            } else {
+                                "default"
+                            };
+                            string_conditions =
+                                HashMap::from([(condition.to_string(), config.clone())]);
+                            &string_conditions
                         }
+                        ExportConfig::Excluded => continue,
+                    };
+                    let types_path =
+                        match find_active_types(conditions, typescript_version, runtime_conditions)
+                        {
+                            Some(types_path) => Some(types_path.clone()),
+                            None => find_nested_types(conditions, typescript_version)
+                                .map(|(fallback, types_path)| {
+                                    entry_point.diagnostics.push(Diagnostic::FallbackCondition {
+                                        external_path: subpath.clone(),
+                                        conditions: fallback,
+                                    });
+                                    types_path.clone()
+                                })
+                                .or_else(|| {
+                                    let (fallback, types_path) = find_sibling_declarations(
+                                        conditions,
+                                        runtime_conditions,
+                                        path,
+                                    )?;
+                                    entry_point
+                                        .diagnostics
+                                        .push(Diagnostic::SiblingDeclarations {
+                                            external_path: subpath.clone(),
+                                            conditions: fallback,
+                                            declarations: types_path.clone(),
+                                        });
+                                    Some(types_path)
+                                }),
+                        };
+                    let Some(types_path) = types_path else {
+                        continue;
+                    };
+                    let condition_targets = flatten_conditions(conditions);
+                    if subpath.contains('*') {
+                        entry_point.extend(expand_subpath_pattern(
+                            subpath,
+                            &types_path,
+                            &condition_targets,
+                            path,
+                        ));
+                    } else {
+                        let internal_path = resolve_directory_target(
+                            path.join(types_path.trim_start_matches("./")),
+                            subpath,
+                            &mut entry_point.diagnostics,
+                        );
+                        entry_point.insert(TSEntryPoint {
+                            external_path: subpath.clone(),
+                            internal_path,
+                            conditions: condition_targets,
+                        });
                     }
                 }
+                entry_point.retain(|entry_point| {
+                    !matches!(
+                        get_matching_subpath(&entry_point.external_path, export_map),
+                        Some((ExportConfig::Excluded, _))
+                    )
+                });
             }
-            ExportConfig::Simple(_) => {}
+            ExportConfig::Simple(_) | ExportConfig::Excluded => {}
         }
-    } else if let Some(types) = package_json
-        .types
+    } else if let Some(paths) = select_types_versions(package_json, typescript_version) {
+        // Like the types/typings fields, typesVersions only applies if there's no exports field
+        entry_point.extend(get_types_versions_entry_points(package_json, paths, path));
+    } else if let Some(types_paths) = package_json.get_types_paths() {
+        // Only use types/typings if there's no exports field
+        let entry_points = get_types_entry_points(types_paths, path, &mut entry_point.diagnostics);
+        entry_point.extend(entry_points);
+    } else if let Some(main) = package_json
+        .main
         .as_ref()
-        .or(package_json.typings.as_ref())
+        .filter(|main| is_source_file(Path::new(main)))
     {
-        // Only use types/typings if there's no exports field
+        // Unbuilt packages may point `main` to their TypeScript sources
         entry_point.insert(TSEntryPoint {
             external_path: ".".to_string(),
-            internal_path: path.join(types),
+            internal_path: path.join(main),
+            conditions: BTreeMap::new(),
         });
     }
 
+    if entry_point.is_empty() {
+        if let Some(implicit_entry_point) = get_implicit_entry_point(package_json, path) {
+            entry_point.insert(implicit_entry_point);
+        } else if let Some(inferred_entry_point) = infer_entry_point(package_json, path, is_browser)
+        {
+            entry_point
+                .diagnostics
+                .push(Diagnostic::InferredEntryPoint {
+                    external_path: inferred_entry_point.external_path.clone(),
+                    internal_path: inferred_entry_point.internal_path.clone(),
+                });
+            entry_point.insert(inferred_entry_point);
+        }
+    }
+
+    let mut source_entry_points = entry_point
+        .iter()
+        .filter(|entry_point| is_source_file(&entry_point.internal_path))
+        .map(|entry_point| Diagnostic::SourceEntryPoint {
+            external_path: entry_point.external_path.clone(),
+            internal_path: entry_point.internal_path.clone(),
+        })
+        .collect::<Vec<_>>();
+    source_entry_points.sort_by_key(|diagnostic| diagnostic.to_string());
+    entry_point.diagnostics.extend(source_entry_points);
+
     entry_point
 }
 
+/// Turns the paths of the `types` or `typings` field into entry points.
+///
+/// The first path is the root entry point, and any other is exposed under its path within the
+/// package (e.g. `./dist/extra` for `dist/extra.d.ts`), as that's how it'd be deep-imported.
+/// Directories without an index file contribute every declaration file in them likewise, even if
+/// they come first.
+fn get_types_entry_points(
+    types_paths: &[String],
+    path: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<TSEntryPoint> {
+    let mut entry_points: Vec<TSEntryPoint> = vec![];
+    for (index, types_path) in types_paths.iter().enumerate() {
+        let internal_path = if index == 0 {
+            resolve_directory_target(path.join(types_path), ".", diagnostics)
+        } else {
+            path.join(types_path)
+        };
+        let (internal_paths, is_root) = if internal_path.is_dir() {
+            (list_declaration_files(&internal_path), false)
+        } else {
+            (vec![internal_path], index == 0)
+        };
+        for internal_path in internal_paths {
+            let external_path = if is_root {
+                ".".to_string()
+            } else {
+                get_deep_import_path(&internal_path, path)
+            };
+            if entry_points
+                .iter()
+                .any(|entry_point| entry_point.external_path == external_path)
+            {
+                continue;
+            }
+            entry_points.push(TSEntryPoint {
+                external_path,
+                internal_path,
+                conditions: BTreeMap::new(),
+            });
+        }
+    }
+    entry_points
+}
+
+/// Lists the declaration files in a directory and its subdirectories, sorted by path.
+fn list_declaration_files(directory: &Path) -> Vec<PathBuf> {
+    let mut files = list_files(directory)
+        .into_iter()
+        .filter(|file| file.to_str().is_some_and(is_declaration_file))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Gets the specifier path that a declaration file would be imported with (e.g. `./dist/extra`
+/// for `dist/extra.d.ts`), with an index file standing for its directory.
+fn get_deep_import_path(internal_path: &Path, path: &Path) -> String {
+    let relative_path = internal_path
+        .strip_prefix(path)
+        .unwrap_or(internal_path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let module_path = [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .find_map(|extension| relative_path.strip_suffix(extension))
+        .unwrap_or(&relative_path);
+    let module_path = module_path
+        .strip_suffix("/index")
+        .unwrap_or(module_path)
+        .trim_start_matches("./");
+    format!("./{module_path}")
+}
+
+/// The files used in place of a directory that declarations point to, in order of preference.
+const DIRECTORY_INDEX_FILES: [&str; 3] = ["index.d.ts", "index.d.mts", "index.d.cts"];
+
+/// Replaces a directory that declarations point to (e.g. `./dist/types`) with the index file in
+/// it, as many packages do so even though only files are valid targets.
+///
+/// Paths that aren't directories, or directories without an index file, are left alone.
+fn resolve_directory_target(
+    internal_path: PathBuf,
+    external_path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> PathBuf {
+    if !internal_path.is_dir() {
+        return internal_path;
+    }
+    let Some(index_path) = DIRECTORY_INDEX_FILES
+        .iter()
+        .map(|file_name| internal_path.join(file_name))
+        .find(|index_path| index_path.is_file())
+    else {
+        return internal_path;
+    };
+    diagnostics.push(Diagnostic::DirectoryTarget {
+        external_path: external_path.to_string(),
+        directory: internal_path,
+        internal_path: index_path.clone(),
+    });
+    index_path
+}
+
+/// Lists the target of every condition, keyed by the nested conditions joined with `.` (e.g.
+/// `import.types`).
+fn flatten_conditions(conditions: &HashMap<String, ExportConfig>) -> BTreeMap<String, String> {
+    let mut targets = BTreeMap::new();
+    for (condition, config) in conditions {
+        match config {
+            ExportConfig::Simple(target) => {
+                targets.insert(condition.clone(), target.clone());
+            }
+            ExportConfig::Map(nested_conditions) => {
+                for (nested_condition, target) in flatten_conditions(nested_conditions) {
+                    targets.insert(format!("{condition}.{nested_condition}"), target);
+                }
+            }
+            ExportConfig::Excluded => {}
+        }
+    }
+    targets
+}
+
+/// Gets the target of the `types` condition, preferring the version-qualified conditions (e.g.
+/// `types@>=5.0`) whose range includes the TypeScript version over the unqualified one.
+///
+/// Conditions are unordered once parsed, so amongst several matching ranges, the one with the
+/// highest minimum version is picked as the most specific.
+fn get_types_target<'a>(
+    conditions: &'a HashMap<String, ExportConfig>,
+    typescript_version: &TypeScriptVersion,
+) -> Option<&'a String> {
+    let versioned_target = conditions
+        .iter()
+        .filter_map(|(condition, config)| {
+            let range = VersionRange::parse(condition.strip_prefix("types@")?)?;
+            let ExportConfig::Simple(target) = config else {
+                return None;
+            };
+            range
+                .matches(typescript_version)
+                .then(|| (range.get_minimum(), condition, target))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+        .map(|(_, _, target)| target);
+    versioned_target.or(match conditions.get("types") {
+        Some(ExportConfig::Simple(target)) => Some(target),
+        _ => None,
+    })
+}
+
+/// Finds the `types` target that TypeScript would resolve, looking into the conditions it matches
+/// (e.g. `{"import": {"types": "./index.d.mts"}}`) however deeply nested they are.
+///
+/// The order of the conditions is lost when parsing the manifest, so a `types` target at any
+/// level is preferred over the nested conditions, and those are tried in the order of the
+/// runtime conditions (see [get_runtime_conditions]).
+fn find_active_types<'a>(
+    conditions: &'a HashMap<String, ExportConfig>,
+    typescript_version: &TypeScriptVersion,
+    runtime_conditions: &[&str],
+) -> Option<&'a String> {
+    get_types_target(conditions, typescript_version).or_else(|| {
+        runtime_conditions.iter().find_map(|condition_name| {
+            let ExportConfig::Map(nested_conditions) = conditions.get(*condition_name)? else {
+                return None;
+            };
+            find_active_types(nested_conditions, typescript_version, runtime_conditions)
+        })
+    })
+}
+
+fn is_types_condition(condition: &str) -> bool {
+    condition == "types" || condition.starts_with("types@")
+}
+
+/// Finds a `types` leaf nested in any condition (e.g. `{"browser": {"types": "./index.d.ts"}}`),
+/// returning the conditions leading to it along with its path.
+///
+/// This is a last resort for exports that only provide declarations for some environments, so
+/// conditions are tried in alphabetical order to keep the choice deterministic.
+fn find_nested_types<'a>(
+    conditions: &'a HashMap<String, ExportConfig>,
+    typescript_version: &TypeScriptVersion,
+) -> Option<(Vec<String>, &'a String)> {
+    let mut condition_names = conditions.keys().collect::<Vec<_>>();
+    condition_names.sort();
+    condition_names.into_iter().find_map(|condition_name| {
+        let ExportConfig::Map(nested_conditions) = &conditions[condition_name] else {
+            return None;
+        };
+        let (mut path, types_path) = match get_types_target(nested_conditions, typescript_version) {
+            Some(types_path) => (vec![], types_path),
+            None => find_nested_types(nested_conditions, typescript_version)?,
+        };
+        path.insert(0, condition_name.clone());
+        Some((path, types_path))
+    })
+}
+
+/// The runtime conditions that TypeScript matches, in order of preference. Their targets may be
+/// nested conditions with their own `types`, or JavaScript files with declarations next to them.
+const RUNTIME_CONDITIONS: [&str; 4] = ["import", "require", "node", "default"];
+
+/// Gets the runtime conditions to resolve exports with, in order of preference: the
+/// [RUNTIME_CONDITIONS], preceded by `browser` when extracting the browser build.
+fn get_runtime_conditions(is_browser: bool) -> Vec<&'static str> {
+    let browser_condition = is_browser.then_some("browser");
+    browser_condition
+        .into_iter()
+        .chain(RUNTIME_CONDITIONS)
+        .collect()
+}
+
+/// Finds the declarations next to the JavaScript file that a runtime condition points to (e.g.
+/// `./index.d.mts` for `"import": "./index.mjs"`), returning the conditions leading to it along
+/// with its path.
+///
+/// This is for exports without any `types` condition, which rely on TypeScript looking for
+/// declarations next to the runtime files. The declarations of subpath patterns aren't checked
+/// here, as only the files matching the pattern become entry points.
+fn find_sibling_declarations(
+    conditions: &HashMap<String, ExportConfig>,
+    runtime_conditions: &[&str],
+    path: &Path,
+) -> Option<(Vec<String>, String)> {
+    runtime_conditions.iter().find_map(|condition_name| {
+        let (mut conditions, declarations) = match conditions.get(*condition_name)? {
+            ExportConfig::Simple(target) => (vec![], get_sibling_declarations(target, path)?),
+            ExportConfig::Map(nested_conditions) => {
+                find_sibling_declarations(nested_conditions, runtime_conditions, path)?
+            }
+            ExportConfig::Excluded => return None,
+        };
+        conditions.insert(0, condition_name.to_string());
+        Some((conditions, declarations))
+    })
+}
+
+/// Gets the path of the declarations next to a JavaScript file, if they exist.
+///
+/// Extensionless targets (e.g. `./dist/index`) may also be completed with `.d.ts` or
+/// `/index.d.ts`.
+fn get_sibling_declarations(target: &str, path: &Path) -> Option<String> {
+    let (stem, extensions): (&str, &[&str]) = if let Some(stem) = target.strip_suffix(".mjs") {
+        (stem, &[".d.mts", ".d.ts"])
+    } else if let Some(stem) = target.strip_suffix(".cjs") {
+        (stem, &[".d.cts", ".d.ts"])
+    } else if let Some(stem) = target.strip_suffix(".js") {
+        (stem, &[".d.ts"])
+    } else if Path::new(target).extension().is_none() {
+        if target.contains('*') {
+            return get_pattern_declarations(target, path);
+        }
+        (target, &[".d.ts", "/index.d.ts"])
+    } else {
+        return None;
+    };
+    extensions
+        .iter()
+        .map(|extension| format!("{stem}{extension}"))
+        .find(|declarations| {
+            declarations.contains('*') || path.join(declarations.trim_start_matches("./")).is_file()
+        })
+}
+
+/// Gets the declarations of an extensionless subpath pattern target (e.g. `./dist/esm/*`): the
+/// declaration files matching it (`./dist/esm/*.d.ts`), or the index files of the directories
+/// matching it (`./dist/esm/*/index.d.ts`) if those are the only declarations.
+fn get_pattern_declarations(target: &str, path: &Path) -> Option<String> {
+    let files = format!("{target}.d.ts");
+    let index_files = format!("{target}/index.d.ts");
+    let file_count = list_pattern_matches(&files, path).len();
+    let index_file_count = list_pattern_matches(&index_files, path).len();
+    if 0 < index_file_count && index_file_count == file_count {
+        Some(index_files)
+    } else if 0 < file_count {
+        Some(files)
+    } else {
+        None
+    }
+}
+
+/// Expands a subpath pattern (e.g. `./features/*`) into an entry point for each file matching its
+/// target (e.g. `./dist/features/*/index.d.ts`).
+///
+/// Like in Node.js, every `*` in the target stands for the same text, which may span several
+/// directories. Subpaths with more than one `*` are invalid, so they're ignored, and so are
+/// targets without a `*`, as there's no telling which subpaths they'd be imported with.
+fn expand_subpath_pattern(
+    subpath: &str,
+    target: &str,
+    conditions: &BTreeMap<String, String>,
+    path: &Path,
+) -> Vec<TSEntryPoint> {
+    if subpath.matches('*').count() != 1 || !target.contains('*') {
+        return vec![];
+    }
+    let mut entry_points = list_pattern_matches(target, path)
+        .into_iter()
+        .map(|(internal_path, substitution)| {
+            let conditions = conditions
+                .iter()
+                .map(|(condition, target)| (condition.clone(), target.replace('*', &substitution)))
+                .collect();
+            TSEntryPoint {
+                external_path: subpath.replace('*', &substitution),
+                internal_path,
+                conditions,
+            }
+        })
+        .collect::<Vec<_>>();
+    entry_points.sort_by(|a, b| a.external_path.cmp(&b.external_path));
+    entry_points
+}
+
+/// Lists the files matching a pattern with a `*` (e.g. `./dist/*.d.ts`), along with the text that
+/// the `*` stands for in each.
+fn list_pattern_matches(pattern: &str, path: &Path) -> Vec<(PathBuf, String)> {
+    let pattern = pattern.trim_start_matches("./");
+    let pattern_parts = pattern.split('*').collect::<Vec<_>>();
+    let base_directory = match pattern_parts[0].rfind('/') {
+        Some(index) => &pattern_parts[0][..index],
+        None => "",
+    };
+
+    list_files(&path.join(base_directory))
+        .into_iter()
+        .filter_map(|file_path| {
+            let relative_path = file_path.strip_prefix(path).ok()?.to_str()?;
+            let substitution = match_pattern(relative_path, &pattern_parts)?.to_string();
+            Some((file_path, substitution))
+        })
+        .collect()
+}
+
+/// Gets the export or import that Node.js would resolve a subpath with, along with the text that
+/// the `*` stands for if it's matched by a pattern: the subpath itself if it's a key, or else the
+/// matching pattern with the longest prefix before its `*`.
+fn get_matching_subpath<'a, 'b>(
+    subpath: &'b str,
+    subpath_map: &'a HashMap<String, ExportConfig>,
+) -> Option<(&'a ExportConfig, Option<&'b str>)> {
+    if let Some(config) = subpath_map.get(subpath) {
+        return Some((config, None));
+    }
+    subpath_map
+        .iter()
+        .filter(|(pattern, _)| pattern.matches('*').count() == 1)
+        .filter_map(|(pattern, config)| {
+            let pattern_parts = pattern.split('*').collect::<Vec<_>>();
+            let substitution = match_pattern(subpath, &pattern_parts)?;
+            Some((pattern, config, substitution))
+        })
+        .max_by_key(|(pattern, _, _)| (pattern.find('*'), pattern.len()))
+        .map(|(_, config, substitution)| (config, Some(substitution)))
+}
+
+/// Picks the `typesVersions` mappings for a version of TypeScript.
+///
+/// TypeScript uses the first matching selector, but the order of the fields is lost when
+/// parsing the manifest, so the selector with the highest minimum version is used instead. This
+/// is the same as long as selectors are listed from the newest to the oldest, as is customary.
+fn select_types_versions<'a>(
+    package_json: &'a PackageJson,
+    typescript_version: &TypeScriptVersion,
+) -> Option<&'a TypesVersionsPaths> {
+    package_json
+        .types_versions
+        .as_ref()?
+        .iter()
+        .filter_map(|(selector, paths)| {
+            let range = VersionRange::parse(selector)?;
+            range
+                .matches(typescript_version)
+                .then(|| (range.get_minimum(), selector, paths))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+        .map(|(_, _, paths)| paths)
+}
+
+/// Turns the `typesVersions` mappings of a package into entry points: the root one, whose
+/// declarations are those of the `types` field (or `index.d.ts`) after mapping, followed by one
+/// per mapped subpath, with patterns expanded into every declaration file they match.
+fn get_types_versions_entry_points(
+    package_json: &PackageJson,
+    paths: &TypesVersionsPaths,
+    path: &Path,
+) -> Vec<TSEntryPoint> {
+    let root_types = package_json
+        .get_types_paths()
+        .map_or("index.d.ts", |types_paths| types_paths[0].as_str())
+        .trim_start_matches("./");
+    let root_path =
+        map_types_versions_path(root_types, paths, path).unwrap_or_else(|| path.join(root_types));
+    let mut entry_points = vec![TSEntryPoint {
+        external_path: ".".to_string(),
+        internal_path: root_path.clone(),
+        conditions: BTreeMap::new(),
+    }];
+
+    let mut subpath_patterns = paths
+        .iter()
+        .filter(|(subpath_pattern, _)| !is_declaration_file(subpath_pattern))
+        .collect::<Vec<_>>();
+    subpath_patterns.sort_by_key(|(subpath_pattern, _)| *subpath_pattern);
+    let mut subpaths = vec![];
+    for (subpath_pattern, targets) in subpath_patterns {
+        let candidates = match subpath_pattern.matches('*').count() {
+            0 => map_types_versions_path(subpath_pattern, paths, path)
+                .map(|internal_path| vec![(subpath_pattern.clone(), internal_path)])
+                .unwrap_or_default(),
+            1 => targets
+                .iter()
+                .flat_map(|target| expand_types_versions_target(subpath_pattern, target, path))
+                .collect(),
+            _ => vec![],
+        };
+        for (subpath, internal_path) in candidates {
+            if internal_path == root_path || subpaths.contains(&subpath) {
+                continue;
+            }
+            entry_points.push(TSEntryPoint {
+                external_path: format!("./{subpath}"),
+                internal_path,
+                conditions: BTreeMap::new(),
+            });
+            subpaths.push(subpath);
+        }
+    }
+    entry_points
+}
+
+/// Maps a path within the package (e.g. `index.d.ts`) with the first `typesVersions` mapping
+/// that matches it, returning the first target that exists.
+///
+/// Exact mappings take precedence over patterns, and patterns with longer prefixes over shorter
+/// ones, like in TypeScript.
+fn map_types_versions_path(
+    relative_path: &str,
+    paths: &TypesVersionsPaths,
+    path: &Path,
+) -> Option<PathBuf> {
+    let (substitution, targets) = match paths.get(relative_path) {
+        Some(targets) => ("", targets),
+        None => paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let substitution = relative_path
+                    .strip_prefix(prefix)?
+                    .strip_suffix(suffix)
+                    .filter(|substitution| !substitution.is_empty())?;
+                Some((prefix.len(), substitution, targets))
+            })
+            .max_by_key(|(prefix_length, _, _)| *prefix_length)
+            .map(|(_, substitution, targets)| (substitution, targets))?,
+    };
+    targets.iter().find_map(|target| {
+        let target = target.replace('*', substitution);
+        let target = target.trim_start_matches("./");
+        [
+            target.to_string(),
+            format!("{target}.d.ts"),
+            format!("{target}/index.d.ts"),
+        ]
+        .into_iter()
+        .map(|candidate| path.join(candidate))
+        .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Lists the subpaths matching a `typesVersions` pattern (e.g. `*`) that a target (e.g.
+/// `ts4.2/*`) provides declarations for, along with the declaration files.
+///
+/// Directories with an index file count as subpaths themselves (e.g. `fs` for
+/// `ts4.2/fs/index.d.ts`).
+fn expand_types_versions_target(
+    subpath_pattern: &str,
+    target: &str,
+    path: &Path,
+) -> Vec<(String, PathBuf)> {
+    let target = target.trim_start_matches("./");
+    let target_parts = target.split('*').collect::<Vec<_>>();
+    if target_parts.len() != 2 {
+        return vec![];
+    }
+    let base_directory = match target_parts[0].rfind('/') {
+        Some(index) => &target_parts[0][..index],
+        None => "",
+    };
+
+    let mut subpaths = list_files(&path.join(base_directory))
+        .into_iter()
+        .filter_map(|internal_path| {
+            let relative_path = internal_path.strip_prefix(path).ok()?.to_str()?;
+            let relative_path = relative_path.replace('\\', "/");
+            if !relative_path.ends_with(".d.ts") {
+                return None;
+            }
+            let substitution = [
+                relative_path.strip_suffix("/index.d.ts"),
+                relative_path.strip_suffix(".d.ts"),
+                Some(relative_path.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .find_map(|candidate| match_pattern(candidate, &target_parts))?;
+            Some((subpath_pattern.replace('*', substitution), internal_path))
+        })
+        .collect::<Vec<_>>();
+    subpaths.sort();
+    subpaths
+}
+
+/// Finds the text that every `*` in a pattern (split into the parts around each `*`) stands for,
+/// so that the pattern matches `text`.
+fn match_pattern<'a>(text: &'a str, pattern_parts: &[&str]) -> Option<&'a str> {
+    let wildcard_count = pattern_parts.len() - 1;
+    let literal_length = pattern_parts.iter().map(|part| part.len()).sum::<usize>();
+    let substitution_length = text
+        .len()
+        .checked_sub(literal_length)
+        .filter(|length| length % wildcard_count == 0)?
+        / wildcard_count;
+
+    let substitution =
+        text.get(pattern_parts[0].len()..pattern_parts[0].len() + substitution_length)?;
+    if substitution.is_empty() || pattern_parts.join(substitution) != text {
+        return None;
+    }
+    Some(substitution)
+}
+
+/// Lists the files in a directory and its subdirectories, other than dependencies.
+fn list_files(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return vec![];
+    };
+    let mut files = vec![];
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            if entry.file_name() != "node_modules" {
+                files.extend(list_files(&entry_path));
+            }
+        } else {
+            files.push(entry_path);
+        }
+    }
+    files
+}
+
+/// Checks whether a package only provides types: either it's a DefinitelyTyped package or
+/// nothing in it can be loaded at runtime.
+fn is_type_only_package(package_json: &PackageJson, path: &Path) -> bool {
+    if package_json.is_definitely_typed() {
+        return true;
+    }
+
+    let has_runtime_main = [&package_json.main, &package_json.module]
+        .into_iter()
+        .flatten()
+        .any(|target| !target.is_empty() && !is_declaration_file(target));
+    let has_runtime_exports = package_json
+        .exports
+        .as_ref()
+        .is_some_and(has_runtime_export);
+    // Node.js falls back to `index.js` when there's no `main`
+    let has_default_main = package_json.exports.is_none() && path.join("index.js").is_file();
+    !(has_runtime_main || has_runtime_exports || has_default_main)
+}
+
+/// Checks whether an export (or any of its conditions, other than `types` ones) resolves to a file
+/// that can be loaded at runtime.
+fn has_runtime_export(export_config: &ExportConfig) -> bool {
+    match export_config {
+        ExportConfig::Simple(target) => !is_declaration_file(target) && !target.ends_with(".json"),
+        ExportConfig::Map(conditions) => conditions.iter().any(|(condition, config)| {
+            !is_types_condition(condition) && has_runtime_export(config)
+        }),
+        ExportConfig::Excluded => false,
+    }
+}
+
+fn is_declaration_file(path: &str) -> bool {
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+/// Gets the `index.d.ts` file at the root of DefinitelyTyped packages that don't specify any
+/// declarations, which TypeScript resolves to without any guessing.
+fn get_implicit_entry_point(package_json: &PackageJson, path: &Path) -> Option<TSEntryPoint> {
+    let internal_path = path.join(IMPLICIT_ENTRY_POINT);
+    (package_json.is_definitely_typed() && internal_path.is_file()).then(|| TSEntryPoint {
+        external_path: ".".to_string(),
+        internal_path,
+        conditions: BTreeMap::new(),
+    })
+}
+
+/// Reports the entry points that the package doesn't publish, leaving them out if the policy is
+/// to skip unpublished files.
+fn check_published_entry_points(entry_point_set: &mut TSEntryPointSet, policy: UnpublishedFiles) {
+    let published_files = &entry_point_set.published_files;
+    let unpublished_entry_points = entry_point_set
+        .iter()
+        .filter(|entry_point| {
+            let internal_path = entry_point
+                .internal_path
+                .canonicalize()
+                .unwrap_or_else(|_| entry_point.internal_path.clone());
+            published_files.is_unpublished(&internal_path)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if unpublished_entry_points.is_empty() {
+        return;
+    }
+
+    let is_skipped = policy == UnpublishedFiles::Skip;
+    let mut paths = unpublished_entry_points
+        .iter()
+        .map(|entry_point| entry_point.internal_path.clone())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    if is_skipped {
+        for entry_point in &unpublished_entry_points {
+            entry_point_set.remove(entry_point);
+        }
+    }
+    entry_point_set
+        .diagnostics
+        .push(Diagnostic::UnpublishedFiles { paths, is_skipped });
+}
+
+/// Looks for declarations for manifests that don't specify any: next to the `main` or `module`
+/// files (or their replacements in the browser build), like TypeScript does, or else in
+/// conventional locations.
+///
+/// If the manifest lists the `files` to publish, only conventional locations covered by them are
+/// considered.
+fn infer_entry_point(
+    package_json: &PackageJson,
+    path: &Path,
+    is_browser: bool,
+) -> Option<TSEntryPoint> {
+    let runtime_declarations = package_json
+        .get_runtime_entries(is_browser)
+        .into_iter()
+        .find_map(|target| get_runtime_declarations(target, path));
+    runtime_declarations
+        .or_else(|| {
+            CONVENTIONAL_ENTRY_POINTS
+                .iter()
+                .filter(|candidate| match &package_json.files {
+                    Some(files) => files
+                        .iter()
+                        .any(|pattern| is_file_included(candidate, pattern)),
+                    None => true,
+                })
+                .map(|candidate| path.join(candidate))
+                .find(|candidate| candidate.is_file())
+        })
+        .map(|internal_path| TSEntryPoint {
+            external_path: ".".to_string(),
+            internal_path,
+            conditions: BTreeMap::new(),
+        })
+}
+
+/// Gets the remapping of a file in the `browser` field, whose keys may or may not start with `./`.
+fn get_browser_remapping<'a>(
+    remappings: &'a HashMap<String, BrowserTarget>,
+    file: &str,
+) -> Option<&'a BrowserTarget> {
+    let file = file.trim_start_matches("./");
+    remappings
+        .iter()
+        .find(|(source, _)| source.trim_start_matches("./") == file)
+        .map(|(_, target)| target)
+}
+
+/// Maps the declarations of the files remapped by the `browser` field to those of their
+/// replacements, by canonical path.
+///
+/// Packages and files replaced with empty modules are left alone, as there are no declarations to
+/// use instead.
+fn get_browser_remappings(package_json: &PackageJson, path: &Path) -> BTreeMap<PathBuf, PathBuf> {
+    let Some(BrowserField::Remappings(remappings)) = &package_json.browser else {
+        return BTreeMap::new();
+    };
+    let canonicalise = |path: PathBuf| path.canonicalize().unwrap_or(path);
+    remappings
+        .iter()
+        .filter(|(source, _)| source.starts_with('.'))
+        .filter_map(|(source, target)| {
+            let BrowserTarget::Path(target) = target else {
+                return None;
+            };
+            let source_declarations = get_runtime_declarations(source, path)?;
+            let target_declarations = get_runtime_declarations(target, path)?;
+            Some((
+                canonicalise(source_declarations),
+                canonicalise(target_declarations),
+            ))
+        })
+        .collect()
+}
+
+/// Finds the declarations of a runtime file (e.g. `lib/index.d.ts` for `lib/index.js`), which
+/// may also be referred to without its extension (e.g. `lib/index` or `lib`).
+fn get_runtime_declarations(target: &str, path: &Path) -> Option<PathBuf> {
+    let target = target.trim_start_matches("./");
+    get_sibling_declarations(target, path).map(|declarations| path.join(declarations))
+}
+
+/// Checks whether an entry in the `files` field of a manifest covers the file at `relative_path`.
+///
+/// Entries can be files, directories (which include everything in them) or globs.
+fn is_file_included(relative_path: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+
+    if !pattern.contains('*') {
+        return relative_path == pattern
+            || relative_path
+                .strip_prefix(pattern)
+                .is_some_and(|rest| rest.starts_with('/'));
+    }
+
+    let path_segments = relative_path.split('/').collect::<Vec<_>>();
+    let pattern_segments = pattern.split('/').collect::<Vec<_>>();
+    // Like directories, globs that match a directory include everything in it
+    (1..=path_segments.len())
+        .any(|length| is_glob_match(&path_segments[..length], &pattern_segments))
+}
+
+pub(crate) fn is_glob_match(path_segments: &[&str], pattern_segments: &[&str]) -> bool {
+    match (pattern_segments.first(), path_segments.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            is_glob_match(path_segments, &pattern_segments[1..])
+                || (!path_segments.is_empty()
+                    && is_glob_match(&path_segments[1..], pattern_segments))
+        }
+        (Some(pattern_segment), Some(path_segment)) => {
+            is_segment_match(path_segment, pattern_segment)
+                && is_glob_match(&path_segments[1..], &pattern_segments[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Matches a single path segment against a pattern where `*` matches any characters.
+pub(crate) fn is_segment_match(segment: &str, pattern: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first_part = parts.next().unwrap_or("");
+    let Some(mut remainder) = segment.strip_prefix(first_part) else {
+        return false;
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last_part, middle_parts)) = parts.split_last() else {
+        return remainder.is_empty();
+    };
+    for part in middle_parts {
+        match remainder.find(part) {
+            Some(index) => remainder = &remainder[index + part.len()..],
+            None => return false,
+        }
+    }
+    remainder.ends_with(last_part)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,7 +1574,7 @@ mod tests {
     fn missing_manifest() {
         let temp_dir = TempDir::new();
 
-        let result = extract_metadata(&temp_dir.path);
+        let result = extract_metadata(&temp_dir.path, &ExtractionOptions::default());
 
         assert_matches!(result, Err(LibraryMetadataError::MissingManifest(ref e)) if e.kind() == std::io::ErrorKind::NotFound);
     }
@@ -138,11 +1584,26 @@ mod tests {
         let temp_dir = TempDir::new();
         temp_dir.create_file("package.json", "not json").unwrap();
 
-        let result = extract_metadata(&temp_dir.path);
+        let result = extract_metadata(&temp_dir.path, &ExtractionOptions::default());
 
         assert_matches!(result, Err(LibraryMetadataError::MalformedManifest(ref e)) if e.contains("expected ident"));
     }
 
+    #[test]
+    fn manifest_with_comments_and_trailing_commas() {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "package.json",
+                "{\n  // Published from CI\n  \"name\": \"test-pkg\",\n  \"version\": \"1.0.0\",\n}",
+            )
+            .unwrap();
+
+        let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+        assert_eq!(metadata.name, "test-pkg");
+    }
+
     #[test]
     fn missing_package_name() {
         let temp_dir = TempDir::new();
@@ -150,7 +1611,7 @@ mod tests {
             .create_file("package.json", r#"{"version": "1.0.0"}"#)
             .unwrap();
 
-        let result = extract_metadata(&temp_dir.path);
+        let result = extract_metadata(&temp_dir.path, &ExtractionOptions::default());
 
         assert_matches!(result, Err(LibraryMetadataError::MalformedManifest(ref s)) if s.contains("missing field `name`"));
     }
@@ -165,7 +1626,7 @@ mod tests {
             )
             .unwrap();
 
-        let result = extract_metadata(&temp_dir.path);
+        let result = extract_metadata(&temp_dir.path, &ExtractionOptions::default());
 
         assert_matches!(result, Err(LibraryMetadataError::MalformedManifest(ref s)) if s.contains("missing field `version`"));
     }
@@ -180,7 +1641,7 @@ mod tests {
             )
             .unwrap();
 
-        let metadata = extract_metadata(&temp_dir.path).unwrap();
+        let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
         assert_eq!(metadata.name, "test-pkg");
         assert_eq!(metadata.version, Some("1.0.0".to_string()));
@@ -189,6 +1650,7 @@ mod tests {
             &TSEntryPoint {
                 external_path: ".".to_string(),
                 internal_path: temp_dir.path.join("dist/index.d.ts"),
+                conditions: BTreeMap::new(),
             }
         );
     }
@@ -205,7 +1667,7 @@ mod tests {
             let temp_dir = TempDir::new();
             temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_eq!(metadata.documentation, "");
         }
@@ -216,7 +1678,7 @@ mod tests {
             temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
             temp_dir.create_file("README.md", README_CONTENT).unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_eq!(metadata.documentation, README_CONTENT);
         }
@@ -227,7 +1689,7 @@ mod tests {
             temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
             temp_dir.create_file("README.txt", README_CONTENT).unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_eq!(metadata.documentation, README_CONTENT);
         }
@@ -238,14 +1700,251 @@ mod tests {
             temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
             temp_dir.create_file("README", README_CONTENT).unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_eq!(metadata.documentation, README_CONTENT);
         }
-    }
-
-    mod entry_point {
-        use super::*;
+
+        #[test]
+        fn readme_case_variations() {
+            for file_name in ["Readme.md", "readme.MD", "README.rst", "readme.adoc"] {
+                let temp_dir = TempDir::new();
+                temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
+                temp_dir.create_file(file_name, README_CONTENT).unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.documentation, README_CONTENT, "{file_name}");
+            }
+        }
+
+        #[test]
+        fn markdown_preferred_over_other_formats() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
+            temp_dir.create_file("README.rst", "Test Package").unwrap();
+            temp_dir.create_file("README.md", README_CONTENT).unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.documentation, README_CONTENT);
+        }
+
+        #[test]
+        fn docs_directory_fallback() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
+            temp_dir
+                .create_file("docs/README.md", README_CONTENT)
+                .unwrap();
+            temp_dir.create_file("docs/README.ja.md", "ja").unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.documentation, README_CONTENT);
+            assert_eq!(
+                metadata.entry_point.extra_docs,
+                vec![ExtraDoc {
+                    path: PathBuf::from("docs/README.ja.md"),
+                    locale: Some("ja".to_string()),
+                    content: "ja".to_string(),
+                }]
+            );
+        }
+
+        #[test]
+        fn root_readme_preferred_over_docs_directory() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
+            temp_dir.create_file("README.md", README_CONTENT).unwrap();
+            temp_dir.create_file("docs/README.md", "Docs").unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.documentation, README_CONTENT);
+            assert!(metadata.entry_point.extra_docs.is_empty());
+        }
+
+        mod readme_field {
+            use super::*;
+
+            fn make_package(readme: &str) -> TempDir {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        &format!(
+                            r#"{{"name": "test-pkg", "version": "1.0.0", "types": "dist/index.d.ts", "readme": "{readme}"}}"#
+                        ),
+                    )
+                    .unwrap();
+                temp_dir
+            }
+
+            #[test]
+            fn without_readme_file() {
+                let temp_dir = make_package(README_CONTENT);
+                temp_dir.create_file("docs/README.md", "Docs").unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.documentation, README_CONTENT);
+            }
+
+            #[test]
+            fn readme_file_preferred() {
+                let temp_dir = make_package("Field");
+                temp_dir.create_file("README.md", README_CONTENT).unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.documentation, README_CONTENT);
+            }
+
+            #[test]
+            fn missing_readme_placeholder() {
+                let temp_dir = make_package(MISSING_README_PLACEHOLDER);
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.documentation, "");
+            }
+        }
+
+        mod localised {
+            use super::*;
+
+            fn make_package(readmes: &[&str]) -> TempDir {
+                let temp_dir = TempDir::new();
+                temp_dir.create_file("package.json", PACKAGE_JSON).unwrap();
+                for readme in readmes {
+                    temp_dir.create_file(readme, readme).unwrap();
+                }
+                temp_dir
+            }
+
+            fn extract_with_locale(temp_dir: &TempDir, locale: Option<&str>) -> TSLibraryMetadata {
+                let options = ExtractionOptions {
+                    locale: locale.map(str::to_string),
+                    ..ExtractionOptions::default()
+                };
+                extract_metadata(&temp_dir.path, &options).unwrap()
+            }
+
+            #[test]
+            fn default_readme_without_locale() {
+                let temp_dir = make_package(&["README.md", "README.zh-CN.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, None);
+
+                assert_eq!(metadata.documentation, "README.md");
+                assert_eq!(
+                    metadata.entry_point.extra_docs,
+                    vec![ExtraDoc {
+                        path: PathBuf::from("README.zh-CN.md"),
+                        locale: Some("zh-CN".to_string()),
+                        content: "README.zh-CN.md".to_string(),
+                    }]
+                );
+            }
+
+            #[test]
+            fn exact_locale() {
+                let temp_dir = make_package(&["README.md", "README.zh-CN.md", "README.zh-TW.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, Some("zh-TW"));
+
+                assert_eq!(metadata.documentation, "README.zh-TW.md");
+                let extra_paths = metadata
+                    .entry_point
+                    .extra_docs
+                    .iter()
+                    .map(|doc| doc.path.to_str().unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(extra_paths, vec!["README.md", "README.zh-CN.md"]);
+            }
+
+            #[test]
+            fn locale_is_case_insensitive() {
+                let temp_dir = make_package(&["README.md", "README-pt_BR.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, Some("PT-br"));
+
+                assert_eq!(metadata.documentation, "README-pt_BR.md");
+            }
+
+            #[test]
+            fn language_fallback() {
+                let temp_dir = make_package(&["README.md", "README.zh.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, Some("zh-CN"));
+
+                assert_eq!(metadata.documentation, "README.zh.md");
+            }
+
+            #[test]
+            fn regional_fallback() {
+                let temp_dir = make_package(&["README.md", "README.zh-CN.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, Some("zh"));
+
+                assert_eq!(metadata.documentation, "README.zh-CN.md");
+            }
+
+            #[test]
+            fn missing_locale() {
+                let temp_dir = make_package(&["README.md", "README.ja.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, Some("ko"));
+
+                assert_eq!(metadata.documentation, "README.md");
+                assert_eq!(metadata.entry_point.extra_docs.len(), 1);
+            }
+
+            #[test]
+            fn only_localised_readme() {
+                let temp_dir = make_package(&["README.ja.md"]);
+
+                let metadata = extract_with_locale(&temp_dir, None);
+
+                assert_eq!(metadata.documentation, "README.ja.md");
+                assert!(metadata.entry_point.extra_docs.is_empty());
+            }
+
+            #[test]
+            fn other_readme_variants_are_ignored() {
+                let temp_dir = make_package(&["README.md", "README.backup.md", "READMEFIRST"]);
+
+                let metadata = extract_with_locale(&temp_dir, None);
+
+                assert!(metadata.entry_point.extra_docs.is_empty());
+            }
+
+            #[test]
+            fn default_readmes_are_extra_docs() {
+                let temp_dir = make_package(&["README.md", "README.txt"]);
+
+                let metadata = extract_with_locale(&temp_dir, None);
+
+                assert_eq!(metadata.documentation, "README.md");
+                assert_eq!(
+                    metadata.entry_point.extra_docs,
+                    vec![ExtraDoc {
+                        path: PathBuf::from("README.txt"),
+                        locale: None,
+                        content: "README.txt".to_string(),
+                    }]
+                );
+            }
+        }
+    }
+
+    mod entry_point {
+        use super::*;
 
         #[test]
         fn missing_types() {
@@ -257,7 +1956,7 @@ mod tests {
                 )
                 .unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert!(metadata.entry_point.is_empty());
         }
@@ -272,13 +1971,14 @@ mod tests {
                 )
                 .unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_contains!(
                 metadata.entry_point,
                 &TSEntryPoint {
                     external_path: ".".to_string(),
                     internal_path: temp_dir.path.join("dist/index.d.ts"),
+                    conditions: BTreeMap::new(),
                 }
             );
         }
@@ -293,15 +1993,109 @@ mod tests {
                 )
                 .unwrap();
 
-            let metadata = extract_metadata(&temp_dir.path).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
             assert_contains!(
                 metadata.entry_point,
                 &TSEntryPoint {
                     external_path: ".".to_string(),
                     internal_path: temp_dir.path.join("dist/types.d.ts"),
+                    conditions: BTreeMap::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn types_directory() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0", "types": "dist"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file("dist/index.d.mts", "export {};")
+                .unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_contains!(
+                metadata.entry_point,
+                &TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("dist/index.d.mts"),
+                    conditions: BTreeMap::new(),
+                }
+            );
+            assert_eq!(metadata.entry_point.diagnostics.len(), 1);
+        }
+
+        #[test]
+        fn types_source_file() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0", "types": "src/index.ts"}"#,
+                )
+                .unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            let internal_path = temp_dir.path.join("src/index.ts");
+            assert_contains!(
+                metadata.entry_point,
+                &TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: internal_path.clone(),
+                    conditions: BTreeMap::new(),
                 }
             );
+            assert_eq!(
+                metadata.entry_point.diagnostics,
+                vec![Diagnostic::SourceEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path,
+                }]
+            );
+        }
+
+        #[test]
+        fn main_source_file() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0", "main": "src/index.tsx"}"#,
+                )
+                .unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_contains!(
+                metadata.entry_point,
+                &TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("src/index.tsx"),
+                    conditions: BTreeMap::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn main_javascript_file() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0", "main": "dist/index.js"}"#,
+                )
+                .unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert!(metadata.entry_point.is_empty());
         }
 
         mod exports {
@@ -317,13 +2111,15 @@ mod tests {
                     )
                     .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
                 assert_contains!(
                     metadata.entry_point,
                     &TSEntryPoint {
                         external_path: ".".to_string(),
                         internal_path: temp_dir.path.join("dist/index.d.ts"),
+                        conditions: BTreeMap::new(),
                     }
                 );
             }
@@ -347,11 +2143,88 @@ mod tests {
                     )
                     .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
                 assert!(metadata.entry_point.is_empty());
             }
 
+            #[test]
+            fn sibling_declarations() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "import": "./dist/index.mjs",
+                                    "require": "./dist/index.cjs"
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+                temp_dir.create_file("dist/index.d.cts", "").unwrap();
+                temp_dir.create_file("dist/index.d.mts", "").unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.entry_point.len(), 1);
+                assert_contains!(
+                    metadata.entry_point,
+                    &TSEntryPoint {
+                        external_path: ".".to_string(),
+                        internal_path: temp_dir.path.join("dist/index.d.mts"),
+                        conditions: BTreeMap::new(),
+                    }
+                );
+                assert_eq!(
+                    metadata.entry_point.diagnostics,
+                    vec![Diagnostic::SiblingDeclarations {
+                        external_path: ".".to_string(),
+                        conditions: vec!["import".to_string()],
+                        declarations: "./dist/index.d.mts".to_string(),
+                    }]
+                );
+            }
+
+            #[test]
+            fn nested_sibling_declarations() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "import": "./dist/index.mjs",
+                                    "node": {"default": "./dist/node.js"}
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+                temp_dir.create_file("dist/node.d.ts", "").unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(
+                    metadata.entry_point.diagnostics,
+                    vec![Diagnostic::SiblingDeclarations {
+                        external_path: ".".to_string(),
+                        conditions: vec!["node".to_string(), "default".to_string()],
+                        declarations: "./dist/node.d.ts".to_string(),
+                    }]
+                );
+            }
+
             #[test]
             fn single_type_export() {
                 let temp_dir = TempDir::new();
@@ -371,7 +2244,8 @@ mod tests {
                     )
                     .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
                 assert_eq!(metadata.entry_point.len(), 1);
                 assert_contains!(
@@ -379,6 +2253,7 @@ mod tests {
                     &TSEntryPoint {
                         external_path: ".".to_string(),
                         internal_path: temp_dir.path.join("dist/index.d.ts"),
+                        conditions: BTreeMap::new(),
                     }
                 );
             }
@@ -405,7 +2280,8 @@ mod tests {
                     )
                     .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
                 assert_eq!(metadata.entry_point.len(), 2);
                 assert_contains!(
@@ -413,6 +2289,7 @@ mod tests {
                     &TSEntryPoint {
                         external_path: ".".to_string(),
                         internal_path: temp_dir.path.join("dist/index.d.ts"),
+                        conditions: BTreeMap::new(),
                     }
                 );
                 assert_contains!(
@@ -420,12 +2297,13 @@ mod tests {
                     &TSEntryPoint {
                         external_path: "./utils".to_string(),
                         internal_path: temp_dir.path.join("dist/utils.d.ts"),
+                        conditions: BTreeMap::new(),
                     }
                 );
             }
 
             #[test]
-            fn export_as_string() {
+            fn directory_target() {
                 let temp_dir = TempDir::new();
                 temp_dir
                     .create_file(
@@ -433,21 +2311,37 @@ mod tests {
                         r#"{
                             "name": "test-pkg",
                             "version": "1.0.0",
-                            "types": "dist/index.d.ts",
                             "exports": {
-                                ".": "./dist/index.js"
+                                ".": {"types": "./dist/types", "default": "./dist/index.js"}
                             }
                         }"#,
                     )
                     .unwrap();
+                temp_dir
+                    .create_file("dist/types/index.d.ts", "export {};")
+                    .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
-                assert!(metadata.entry_point.is_empty());
+                let internal_path = temp_dir.path.join("dist/types/index.d.ts");
+                assert_eq!(metadata.entry_point.len(), 1);
+                assert_eq!(
+                    metadata.entry_point.iter().next().unwrap().internal_path,
+                    internal_path
+                );
+                assert_eq!(
+                    metadata.entry_point.diagnostics,
+                    vec![Diagnostic::DirectoryTarget {
+                        external_path: ".".to_string(),
+                        directory: temp_dir.path.join("dist/types"),
+                        internal_path,
+                    }]
+                );
             }
 
             #[test]
-            fn exports_as_string() {
+            fn directory_target_without_index() {
                 let temp_dir = TempDir::new();
                 temp_dir
                     .create_file(
@@ -455,16 +2349,1803 @@ mod tests {
                         r#"{
                             "name": "test-pkg",
                             "version": "1.0.0",
-                            "types": "dist/index.d.ts",
-                            "exports": "./dist/index.js"
+                            "exports": {".": {"types": "./dist/types"}}
                         }"#,
                     )
                     .unwrap();
+                temp_dir
+                    .create_file("dist/types/main.d.ts", "export {};")
+                    .unwrap();
 
-                let metadata = extract_metadata(&temp_dir.path).unwrap();
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
 
-                assert!(metadata.entry_point.is_empty());
+                assert_eq!(
+                    metadata.entry_point.iter().next().unwrap().internal_path,
+                    temp_dir.path.join("dist/types")
+                );
+                assert!(metadata.entry_point.diagnostics.is_empty());
+            }
+
+            #[test]
+            fn types_nested_in_matched_conditions() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "node": {
+                                        "types": "./dist/node.d.ts",
+                                        "import": "./dist/node.mjs"
+                                    },
+                                    "default": "./dist/index.js"
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(
+                    metadata.entry_point.iter().next().unwrap().internal_path,
+                    temp_dir.path.join("dist/node.d.ts")
+                );
+                assert!(metadata.entry_point.diagnostics.is_empty());
+            }
+
+            #[test]
+            fn condition_priority() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "require": {
+                                        "types": "./dist/index.d.cts",
+                                        "default": "./dist/index.cjs"
+                                    },
+                                    "node": {
+                                        "import": {
+                                            "types": "./dist/node.d.mts",
+                                            "default": "./dist/node.mjs"
+                                        }
+                                    },
+                                    "import": {
+                                        "types": "./dist/index.d.mts",
+                                        "default": "./dist/index.mjs"
+                                    }
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(
+                    metadata.entry_point.iter().next().unwrap().internal_path,
+                    temp_dir.path.join("dist/index.d.mts")
+                );
+                assert!(metadata.entry_point.diagnostics.is_empty());
+            }
+
+            #[test]
+            fn browser_condition() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "browser": {
+                                        "types": "./dist/browser.d.ts",
+                                        "default": "./dist/browser.js"
+                                    },
+                                    "import": {
+                                        "types": "./dist/index.d.ts",
+                                        "default": "./dist/index.js"
+                                    }
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+                let options = ExtractionOptions {
+                    browser: true,
+                    ..Default::default()
+                };
+
+                let browser_metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+                let default_metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(
+                    browser_metadata
+                        .entry_point
+                        .iter()
+                        .next()
+                        .unwrap()
+                        .internal_path,
+                    temp_dir.path.join("dist/browser.d.ts")
+                );
+                assert_eq!(
+                    default_metadata
+                        .entry_point
+                        .iter()
+                        .next()
+                        .unwrap()
+                        .internal_path,
+                    temp_dir.path.join("dist/index.d.ts")
+                );
+            }
+
+            #[test]
+            fn deeply_nested_matched_conditions() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "node": {
+                                        "require": {
+                                            "default": {
+                                                "types": "./dist/node.d.cts"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(
+                    metadata.entry_point.iter().next().unwrap().internal_path,
+                    temp_dir.path.join("dist/node.d.cts")
+                );
+                assert!(metadata.entry_point.diagnostics.is_empty());
+            }
+
+            #[test]
+            fn browser_only_types() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "browser": {
+                                        "types": "./dist/browser.d.ts",
+                                        "default": "./dist/browser.js"
+                                    }
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_eq!(metadata.entry_point.len(), 1);
+                assert_contains!(
+                    metadata.entry_point,
+                    &TSEntryPoint {
+                        external_path: ".".to_string(),
+                        internal_path: temp_dir.path.join("dist/browser.d.ts"),
+                        conditions: BTreeMap::new(),
+                    }
+                );
+                assert_eq!(
+                    metadata.entry_point.diagnostics,
+                    vec![Diagnostic::FallbackCondition {
+                        external_path: ".".to_string(),
+                        conditions: vec!["browser".to_string()],
+                    }]
+                );
+            }
+
+            #[test]
+            fn deeply_nested_types() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "worker": "./dist/worker.js",
+                                    "browser": {
+                                        "import": {
+                                            "types": "./dist/browser.d.mts"
+                                        }
+                                    },
+                                    "deno": {
+                                        "types": "./dist/deno.d.ts"
+                                    }
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_contains!(
+                    metadata.entry_point,
+                    &TSEntryPoint {
+                        external_path: ".".to_string(),
+                        internal_path: temp_dir.path.join("dist/browser.d.mts"),
+                        conditions: BTreeMap::new(),
+                    }
+                );
+                assert_eq!(
+                    metadata.entry_point.diagnostics,
+                    vec![Diagnostic::FallbackCondition {
+                        external_path: ".".to_string(),
+                        conditions: vec!["browser".to_string(), "import".to_string()],
+                    }]
+                );
+            }
+
+            #[test]
+            fn top_level_types_preferred() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "browser": {
+                                        "types": "./dist/browser.d.ts"
+                                    },
+                                    "types": "./dist/index.d.ts"
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert_contains!(
+                    metadata.entry_point,
+                    &TSEntryPoint {
+                        external_path: ".".to_string(),
+                        internal_path: temp_dir.path.join("dist/index.d.ts"),
+                        conditions: BTreeMap::new(),
+                    }
+                );
+                assert!(metadata.entry_point.diagnostics.is_empty());
+            }
+
+            #[test]
+            fn conditions() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "exports": {
+                                ".": {
+                                    "types": "./dist/index.d.ts",
+                                    "import": {
+                                        "types": "./dist/index.d.mts",
+                                        "default": "./dist/index.mjs"
+                                    },
+                                    "require": "./dist/index.cjs"
+                                }
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                let entry_point = metadata.entry_point.iter().next().unwrap();
+                assert_eq!(
+                    entry_point.conditions,
+                    BTreeMap::from([
+                        ("import.default".to_string(), "./dist/index.mjs".to_string()),
+                        ("import.types".to_string(), "./dist/index.d.mts".to_string()),
+                        ("require".to_string(), "./dist/index.cjs".to_string()),
+                        ("types".to_string(), "./dist/index.d.ts".to_string()),
+                    ])
+                );
+            }
+
+            #[test]
+            fn no_conditions_without_exports() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{"name": "test-pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                let entry_point = metadata.entry_point.iter().next().unwrap();
+                assert!(entry_point.conditions.is_empty());
+            }
+
+            mod versioned_types {
+                use super::*;
+                use crate::version_range::TypeScriptVersion;
+
+                const MANIFEST: &str = r#"{
+                    "name": "test-pkg",
+                    "version": "1.0.0",
+                    "exports": {
+                        ".": {
+                            "types@>=5.0": "./ts5/index.d.ts",
+                            "types@>=4.7 <5.0": "./ts4.7/index.d.ts",
+                            "types": "./legacy/index.d.ts",
+                            "default": "./index.js"
+                        }
+                    }
+                }"#;
+
+                fn get_types_path(
+                    manifest: &str,
+                    typescript_version: Option<TypeScriptVersion>,
+                ) -> PathBuf {
+                    let temp_dir = TempDir::new();
+                    temp_dir.create_file("package.json", manifest).unwrap();
+                    let options = ExtractionOptions {
+                        typescript_version,
+                        ..Default::default()
+                    };
+
+                    let metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+
+                    let entry_point = metadata.entry_point.iter().next().unwrap();
+                    entry_point
+                        .internal_path
+                        .strip_prefix(&temp_dir.path)
+                        .unwrap()
+                        .to_path_buf()
+                }
+
+                #[test]
+                fn latest_version_by_default() {
+                    assert_eq!(
+                        get_types_path(MANIFEST, None),
+                        PathBuf::from("ts5/index.d.ts")
+                    );
+                }
+
+                #[test]
+                fn matching_range() {
+                    assert_eq!(
+                        get_types_path(MANIFEST, Some(TypeScriptVersion::new(4, 9, 5))),
+                        PathBuf::from("ts4.7/index.d.ts")
+                    );
+                }
+
+                #[test]
+                fn fallback_to_unqualified_types() {
+                    assert_eq!(
+                        get_types_path(MANIFEST, Some(TypeScriptVersion::new(4, 5, 0))),
+                        PathBuf::from("legacy/index.d.ts")
+                    );
+                }
+
+                #[test]
+                fn highest_minimum_preferred() {
+                    let manifest = r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "exports": {
+                            ".": {
+                                "types@>=4.0": "./ts4/index.d.ts",
+                                "types@>=5.2": "./ts5.2/index.d.ts"
+                            }
+                        }
+                    }"#;
+
+                    assert_eq!(
+                        get_types_path(manifest, Some(TypeScriptVersion::new(5, 4, 0))),
+                        PathBuf::from("ts5.2/index.d.ts")
+                    );
+                }
+
+                #[test]
+                fn malformed_range_ignored() {
+                    let manifest = r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "exports": {
+                            ".": {
+                                "types@~5.0": "./ts5/index.d.ts",
+                                "types": "./index.d.ts"
+                            }
+                        }
+                    }"#;
+
+                    assert_eq!(get_types_path(manifest, None), PathBuf::from("index.d.ts"));
+                }
+
+                #[test]
+                fn nested_versioned_types() {
+                    let manifest = r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "exports": {
+                            ".": {
+                                "import": {
+                                    "types@>=5.0": "./ts5/index.d.mts",
+                                    "types": "./index.d.mts"
+                                }
+                            }
+                        }
+                    }"#;
+
+                    assert_eq!(
+                        get_types_path(manifest, None),
+                        PathBuf::from("ts5/index.d.mts")
+                    );
+                }
+            }
+
+            mod subpath_patterns {
+                use super::*;
+
+                fn make_package(exports: &str, files: &[&str]) -> TempDir {
+                    let temp_dir = TempDir::new();
+                    temp_dir
+                        .create_file(
+                            "package.json",
+                            &format!(
+                                r#"{{"name": "test-pkg", "version": "1.0.0", "exports": {exports}}}"#
+                            ),
+                        )
+                        .unwrap();
+                    for file in files {
+                        temp_dir.create_file(file, "").unwrap();
+                    }
+                    temp_dir
+                }
+
+                fn get_entry_points(temp_dir: &TempDir) -> Vec<(String, PathBuf)> {
+                    let metadata =
+                        extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+                    let mut entry_points = metadata
+                        .entry_point
+                        .iter()
+                        .map(|entry_point| {
+                            (
+                                entry_point.external_path.clone(),
+                                entry_point.internal_path.clone(),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    entry_points.sort();
+                    entry_points
+                }
+
+                #[test]
+                fn single_wildcard() {
+                    let temp_dir = make_package(
+                        r#"{"./*": {"types": "./dist/*.d.ts"}}"#,
+                        &["dist/a.d.ts", "dist/b.d.ts", "dist/a.js"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![
+                            ("./a".to_string(), temp_dir.path.join("dist/a.d.ts")),
+                            ("./b".to_string(), temp_dir.path.join("dist/b.d.ts")),
+                        ]
+                    );
+                }
+
+                #[test]
+                fn wildcard_spanning_directories() {
+                    let temp_dir = make_package(
+                        r#"{"./features/*": {"types": "./dist/features/*.d.ts"}}"#,
+                        &["dist/features/auth/login.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![(
+                            "./features/auth/login".to_string(),
+                            temp_dir.path.join("dist/features/auth/login.d.ts")
+                        )]
+                    );
+                }
+
+                #[test]
+                fn wildcard_in_middle_of_subpath() {
+                    let temp_dir = make_package(
+                        r#"{"./features/*/index": {"types": "./dist/features/*/index.d.ts"}}"#,
+                        &[
+                            "dist/features/auth/index.d.ts",
+                            "dist/features/auth/other.d.ts",
+                        ],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![(
+                            "./features/auth/index".to_string(),
+                            temp_dir.path.join("dist/features/auth/index.d.ts")
+                        )]
+                    );
+                }
+
+                #[test]
+                fn multiple_wildcards_in_target() {
+                    let temp_dir = make_package(
+                        r#"{"./locales/*": {"types": "./dist/*/*.d.ts"}}"#,
+                        &["dist/en/en.d.ts", "dist/fr/en.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![(
+                            "./locales/en".to_string(),
+                            temp_dir.path.join("dist/en/en.d.ts")
+                        )]
+                    );
+                }
+
+                #[test]
+                fn conditions() {
+                    let temp_dir = make_package(
+                        r#"{"./*": {"types": "./dist/*.d.ts", "import": "./dist/*.js"}}"#,
+                        &["dist/a.d.ts"],
+                    );
+
+                    let metadata =
+                        extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                    let entry_point = metadata.entry_point.iter().next().unwrap();
+                    assert_eq!(
+                        entry_point.conditions,
+                        BTreeMap::from([
+                            ("import".to_string(), "./dist/a.js".to_string()),
+                            ("types".to_string(), "./dist/a.d.ts".to_string()),
+                        ])
+                    );
+                }
+
+                #[test]
+                fn multiple_wildcards_in_subpath() {
+                    let temp_dir =
+                        make_package(r#"{"./*/*": {"types": "./dist/*.d.ts"}}"#, &["dist/a.d.ts"]);
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(entry_points, vec![]);
+                }
+
+                #[test]
+                fn target_without_wildcard() {
+                    let temp_dir =
+                        make_package(r#"{"./*": {"types": "./index.d.ts"}}"#, &["index.d.ts"]);
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    // The pattern expands to nothing, so the conventional entry point is used
+                    assert_eq!(
+                        entry_points,
+                        vec![(".".to_string(), temp_dir.path.join("index.d.ts"))]
+                    );
+                }
+
+                #[test]
+                fn string_target() {
+                    let temp_dir = make_package(
+                        r#"{"./*": "./dist/*.d.ts"}"#,
+                        &["dist/a.d.ts", "dist/b.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![
+                            ("./a".to_string(), temp_dir.path.join("dist/a.d.ts")),
+                            ("./b".to_string(), temp_dir.path.join("dist/b.d.ts")),
+                        ]
+                    );
+                }
+
+                #[test]
+                fn string_target_with_sibling_declarations() {
+                    let temp_dir =
+                        make_package(r#"{"./*": "./dist/*.js"}"#, &["dist/a.js", "dist/a.d.ts"]);
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![("./a".to_string(), temp_dir.path.join("dist/a.d.ts"))]
+                    );
+                }
+
+                #[test]
+                fn extensionless_runtime_target() {
+                    let temp_dir = make_package(
+                        r#"{"./*": {"import": "./dist/esm/*"}}"#,
+                        &["dist/esm/a.js", "dist/esm/a.d.ts", "dist/esm/b.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![
+                            ("./a".to_string(), temp_dir.path.join("dist/esm/a.d.ts")),
+                            ("./b".to_string(), temp_dir.path.join("dist/esm/b.d.ts")),
+                        ]
+                    );
+                }
+
+                #[test]
+                fn extensionless_runtime_target_with_index_files() {
+                    let temp_dir = make_package(
+                        r#"{"./*": {"import": "./dist/esm/*"}}"#,
+                        &["dist/esm/a/index.js", "dist/esm/a/index.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![(
+                            "./a".to_string(),
+                            temp_dir.path.join("dist/esm/a/index.d.ts")
+                        )]
+                    );
+                }
+
+                #[test]
+                fn excluded_subpaths() {
+                    let temp_dir = make_package(
+                        r#"{
+                            "./*": {"types": "./dist/*.d.ts"},
+                            "./internal/*": null,
+                            "./internal/public": {"types": "./dist/internal/public.d.ts"}
+                        }"#,
+                        &[
+                            "dist/a.d.ts",
+                            "dist/internal/private.d.ts",
+                            "dist/internal/public.d.ts",
+                        ],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![
+                            ("./a".to_string(), temp_dir.path.join("dist/a.d.ts")),
+                            (
+                                "./internal/public".to_string(),
+                                temp_dir.path.join("dist/internal/public.d.ts")
+                            ),
+                        ]
+                    );
+                }
+
+                #[test]
+                fn dependencies_are_skipped() {
+                    let temp_dir = make_package(
+                        r#"{"./*": {"types": "./*.d.ts"}}"#,
+                        &["index.d.ts", "node_modules/dep/index.d.ts"],
+                    );
+
+                    let entry_points = get_entry_points(&temp_dir);
+
+                    assert_eq!(
+                        entry_points,
+                        vec![("./index".to_string(), temp_dir.path.join("index.d.ts"))]
+                    );
+                }
+            }
+
+            #[test]
+            fn export_as_string() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "types": "dist/index.d.ts",
+                            "exports": {
+                                ".": "./dist/index.js"
+                            }
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert!(metadata.entry_point.is_empty());
+            }
+
+            #[test]
+            fn exports_as_string() {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        r#"{
+                            "name": "test-pkg",
+                            "version": "1.0.0",
+                            "types": "dist/index.d.ts",
+                            "exports": "./dist/index.js"
+                        }"#,
+                    )
+                    .unwrap();
+
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+                assert!(metadata.entry_point.is_empty());
+            }
+        }
+
+        mod types_arrays {
+            use super::*;
+
+            fn get_entry_points(types: &str, files: &[&str]) -> (TempDir, Vec<(String, PathBuf)>) {
+                let temp_dir = TempDir::new();
+                temp_dir
+                    .create_file(
+                        "package.json",
+                        &format!(r#"{{"name": "test-pkg", "version": "1.0.0", "types": {types}}}"#),
+                    )
+                    .unwrap();
+                for file in files {
+                    temp_dir.create_file(file, "").unwrap();
+                }
+                let metadata =
+                    extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+                let mut entry_points = metadata
+                    .entry_point
+                    .iter()
+                    .map(|entry_point| {
+                        (
+                            entry_point.external_path.clone(),
+                            entry_point.internal_path.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                entry_points.sort();
+                (temp_dir, entry_points)
+            }
+
+            #[test]
+            fn array_of_files() {
+                let (temp_dir, entry_points) = get_entry_points(
+                    r#"["index.d.ts", "dist/extra.d.ts"]"#,
+                    &["index.d.ts", "dist/extra.d.ts"],
+                );
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        (".".to_string(), temp_dir.path.join("index.d.ts")),
+                        (
+                            "./dist/extra".to_string(),
+                            temp_dir.path.join("dist/extra.d.ts")
+                        ),
+                    ]
+                );
+            }
+
+            #[test]
+            fn directory_without_index() {
+                let (temp_dir, entry_points) = get_entry_points(
+                    r#""typings""#,
+                    &["typings/a.d.ts", "typings/b/index.d.ts", "typings/c.js"],
+                );
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        (
+                            "./typings/a".to_string(),
+                            temp_dir.path.join("typings/a.d.ts")
+                        ),
+                        (
+                            "./typings/b".to_string(),
+                            temp_dir.path.join("typings/b/index.d.ts")
+                        ),
+                    ]
+                );
+            }
+
+            #[test]
+            fn array_of_directories() {
+                let (temp_dir, entry_points) = get_entry_points(
+                    r#"["types", "extra"]"#,
+                    &["types/index.d.ts", "extra/a.d.ts"],
+                );
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        (".".to_string(), temp_dir.path.join("types/index.d.ts")),
+                        ("./extra/a".to_string(), temp_dir.path.join("extra/a.d.ts")),
+                    ]
+                );
+            }
+
+            #[test]
+            fn empty_array() {
+                let (temp_dir, entry_points) = get_entry_points("[]", &["index.d.ts"]);
+
+                assert_eq!(
+                    entry_points,
+                    vec![(".".to_string(), temp_dir.path.join("index.d.ts"))]
+                );
+            }
+        }
+
+        mod types_versions {
+            use super::*;
+            use crate::version_range::TypeScriptVersion;
+
+            fn make_package(manifest: &str, files: &[&str]) -> TempDir {
+                let temp_dir = TempDir::new();
+                temp_dir.create_file("package.json", manifest).unwrap();
+                for file in files {
+                    temp_dir.create_file(file, "").unwrap();
+                }
+                temp_dir
+            }
+
+            fn get_entry_points(
+                temp_dir: &TempDir,
+                typescript_version: Option<TypeScriptVersion>,
+            ) -> Vec<(String, PathBuf)> {
+                let options = ExtractionOptions {
+                    typescript_version,
+                    ..Default::default()
+                };
+                let metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+                let mut entry_points = metadata
+                    .entry_point
+                    .iter()
+                    .map(|entry_point| {
+                        (
+                            entry_point.external_path.clone(),
+                            entry_point
+                                .internal_path
+                                .strip_prefix(&temp_dir.path)
+                                .unwrap()
+                                .to_path_buf(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                entry_points.sort();
+                entry_points
+            }
+
+            fn make_entry_point(external_path: &str, internal_path: &str) -> (String, PathBuf) {
+                (external_path.to_string(), PathBuf::from(internal_path))
+            }
+
+            const MANIFEST: &str = r#"{
+                "name": "test-pkg",
+                "version": "1.0.0",
+                "types": "index.d.ts",
+                "typesVersions": {
+                    ">=4.2": {"*": ["ts4.2/*"]},
+                    "*": {"*": ["legacy/*"]}
+                }
+            }"#;
+
+            const FILES: [&str; 6] = [
+                "index.d.ts",
+                "ts4.2/index.d.ts",
+                "ts4.2/fs.d.ts",
+                "ts4.2/http/index.d.ts",
+                "legacy/index.d.ts",
+                "legacy/fs.d.ts",
+            ];
+
+            #[test]
+            fn wildcard_mapping() {
+                let temp_dir = make_package(MANIFEST, &FILES);
+
+                let entry_points = get_entry_points(&temp_dir, None);
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        make_entry_point(".", "ts4.2/index.d.ts"),
+                        make_entry_point("./fs", "ts4.2/fs.d.ts"),
+                        make_entry_point("./http", "ts4.2/http/index.d.ts"),
+                    ]
+                );
+            }
+
+            #[test]
+            fn older_version() {
+                let temp_dir = make_package(MANIFEST, &FILES);
+
+                let entry_points =
+                    get_entry_points(&temp_dir, Some(TypeScriptVersion::new(4, 0, 0)));
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        make_entry_point(".", "legacy/index.d.ts"),
+                        make_entry_point("./fs", "legacy/fs.d.ts"),
+                    ]
+                );
+            }
+
+            #[test]
+            fn explicit_subpaths() {
+                let temp_dir = make_package(
+                    r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "types": "dist/index.d.ts",
+                        "typesVersions": {
+                            "*": {
+                                "operators": ["dist/operators/index.d.ts"],
+                                "testing/*": ["dist/testing/*"]
+                            }
+                        }
+                    }"#,
+                    &[
+                        "dist/index.d.ts",
+                        "dist/operators/index.d.ts",
+                        "dist/testing/mocks.d.ts",
+                    ],
+                );
+
+                let entry_points = get_entry_points(&temp_dir, None);
+
+                assert_eq!(
+                    entry_points,
+                    vec![
+                        make_entry_point(".", "dist/index.d.ts"),
+                        make_entry_point("./operators", "dist/operators/index.d.ts"),
+                        make_entry_point("./testing/mocks", "dist/testing/mocks.d.ts"),
+                    ]
+                );
+            }
+
+            #[test]
+            fn no_matching_selector() {
+                let temp_dir = make_package(
+                    r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "types": "index.d.ts",
+                        "typesVersions": {"<4.0": {"*": ["ts3/*"]}}
+                    }"#,
+                    &["index.d.ts", "ts3/index.d.ts"],
+                );
+
+                let entry_points = get_entry_points(&temp_dir, None);
+
+                assert_eq!(entry_points, vec![make_entry_point(".", "index.d.ts")]);
+            }
+
+            #[test]
+            fn ignored_with_exports() {
+                let temp_dir = make_package(
+                    r#"{
+                        "name": "test-pkg",
+                        "version": "1.0.0",
+                        "exports": {".": {"types": "./index.d.ts"}},
+                        "typesVersions": {"*": {"*": ["ts4/*"]}}
+                    }"#,
+                    &["index.d.ts", "ts4/index.d.ts"],
+                );
+
+                let entry_points = get_entry_points(&temp_dir, None);
+
+                assert_eq!(entry_points, vec![make_entry_point(".", "index.d.ts")]);
+            }
+        }
+    }
+    mod stability {
+        use super::*;
+
+        fn get_stability(manifest: &str) -> Option<Stability> {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", manifest).unwrap();
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+            metadata.entry_point.stability
+        }
+
+        #[test]
+        fn stability_field() {
+            assert_eq!(
+                get_stability(
+                    r#"{"name": "pkg", "version": "1.0.0", "stability": "experimental"}"#
+                ),
+                Some(Stability::Experimental)
+            );
+        }
+
+        #[test]
+        fn unknown_stability() {
+            assert_eq!(
+                get_stability(r#"{"name": "pkg", "version": "1.0.0", "stability": "wobbly"}"#),
+                None
+            );
+        }
+
+        #[test]
+        fn no_stability() {
+            assert_eq!(
+                get_stability(r#"{"name": "pkg", "version": "1.0.0"}"#),
+                None
+            );
+        }
+    }
+
+    mod type_only {
+        use super::*;
+
+        fn is_type_only(manifest: &str, files: &[&str]) -> bool {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", manifest).unwrap();
+            for file in files {
+                temp_dir.create_file(file, "").unwrap();
+            }
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+            metadata.entry_point.is_type_only
+        }
+
+        #[test]
+        fn versioned_types_only() {
+            assert!(is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "exports": {".": {"types@>=5.0": "./ts5/index.d.ts", "types": "./index.d.ts"}}}"#,
+                &["index.d.ts", "ts5/index.d.ts"],
+            ));
+        }
+
+        #[test]
+        fn definitely_typed_package() {
+            assert!(is_type_only(
+                r#"{"name": "@types/node", "version": "1.0.0", "main": "", "types": "index.d.ts"}"#,
+                &["index.d.ts"],
+            ));
+        }
+
+        #[test]
+        fn main_field() {
+            assert!(!is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "main": "lib/index.js", "types": "index.d.ts"}"#,
+                &[],
+            ));
+        }
+
+        #[test]
+        fn module_field() {
+            assert!(!is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "module": "lib/index.mjs"}"#,
+                &[],
+            ));
+        }
+
+        #[test]
+        fn declarations_only() {
+            assert!(is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+                &["index.d.ts"],
+            ));
+        }
+
+        #[test]
+        fn default_main() {
+            assert!(!is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+                &["index.d.ts", "index.js"],
+            ));
+        }
+
+        #[test]
+        fn runtime_exports() {
+            assert!(!is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "exports": {".": {"types": "./index.d.ts", "import": "./index.mjs"}}}"#,
+                &[],
+            ));
+        }
+
+        #[test]
+        fn types_only_exports() {
+            assert!(is_type_only(
+                r#"{"name": "pkg", "version": "1.0.0", "exports": {".": {"types": "./index.d.ts"}, "./package.json": "./package.json"}}"#,
+                &["index.js"],
+            ));
+        }
+    }
+
+    mod inferred_entry_point {
+        use super::*;
+
+        fn make_package(manifest: &str, files: &[&str]) -> TempDir {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", manifest).unwrap();
+            for file in files {
+                temp_dir.create_file(file, "").unwrap();
+            }
+            temp_dir
+        }
+
+        #[test]
+        fn conventional_location() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0"}"#,
+                &["lib/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            let internal_path = temp_dir.path.join("lib/index.d.ts");
+            assert_eq!(
+                metadata.entry_point.iter().collect::<Vec<_>>(),
+                vec![&TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: internal_path.clone(),
+                    conditions: BTreeMap::new(),
+                }]
+            );
+            assert_eq!(
+                metadata.entry_point.diagnostics,
+                vec![Diagnostic::InferredEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path,
+                }]
+            );
+        }
+
+        #[test]
+        fn preferred_location() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0"}"#,
+                &["index.d.ts", "dist/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_contains!(
+                metadata.entry_point,
+                &TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("dist/index.d.ts"),
+                    conditions: BTreeMap::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn next_to_main() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": "./lib/main.js"}"#,
+                &["lib/main.js", "lib/main.d.ts", "index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            let internal_path = temp_dir.path.join("lib/main.d.ts");
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                internal_path
+            );
+            assert_eq!(
+                metadata.entry_point.diagnostics,
+                vec![Diagnostic::InferredEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path,
+                }]
+            );
+        }
+
+        #[test]
+        fn next_to_module() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": "lib/main.cjs", "module": "esm/main.mjs"}"#,
+                &["lib/main.cjs", "esm/main.mjs", "esm/main.d.mts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                temp_dir.path.join("esm/main.d.mts")
+            );
+        }
+
+        #[test]
+        fn main_without_extension() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": "lib"}"#,
+                &["lib/index.js", "lib/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                temp_dir.path.join("lib/index.d.ts")
+            );
+        }
+
+        #[test]
+        fn main_without_declarations() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": "lib/main.js"}"#,
+                &["lib/main.js", "index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                temp_dir.path.join("index.d.ts")
+            );
+        }
+
+        #[test]
+        fn next_to_browser_entry() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": "lib/main.js", "browser": "lib/browser.js"}"#,
+                &[
+                    "lib/main.js",
+                    "lib/main.d.ts",
+                    "lib/browser.js",
+                    "lib/browser.d.ts",
+                ],
+            );
+            let options = ExtractionOptions {
+                browser: true,
+                ..Default::default()
+            };
+
+            let browser_metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+            let default_metadata =
+                extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                browser_metadata
+                    .entry_point
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .internal_path,
+                temp_dir.path.join("lib/browser.d.ts")
+            );
+            assert_eq!(
+                default_metadata
+                    .entry_point
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .internal_path,
+                temp_dir.path.join("lib/main.d.ts")
+            );
+        }
+
+        #[test]
+        fn browser_remappings() {
+            let temp_dir = make_package(
+                r#"{
+                    "name": "test-pkg",
+                    "version": "1.0.0",
+                    "main": "lib/main.js",
+                    "browser": {
+                        "./lib/main.js": "./lib/browser.js",
+                        "./lib/fs.js": "./lib/fs-shim.js",
+                        "./lib/net.js": false,
+                        "crypto": false
+                    }
+                }"#,
+                &[
+                    "lib/main.js",
+                    "lib/main.d.ts",
+                    "lib/browser.js",
+                    "lib/browser.d.ts",
+                    "lib/fs.js",
+                    "lib/fs.d.ts",
+                    "lib/fs-shim.js",
+                    "lib/fs-shim.d.ts",
+                    "lib/net.js",
+                    "lib/net.d.ts",
+                ],
+            );
+            let options = ExtractionOptions {
+                browser: true,
+                ..Default::default()
+            };
+
+            let browser_metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+            let default_metadata =
+                extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            let root = temp_dir.path.canonicalize().unwrap();
+            assert_eq!(
+                browser_metadata
+                    .entry_point
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .internal_path,
+                temp_dir.path.join("lib/browser.d.ts")
+            );
+            assert_eq!(
+                browser_metadata.entry_point.browser_remappings,
+                BTreeMap::from([
+                    (root.join("lib/fs.d.ts"), root.join("lib/fs-shim.d.ts")),
+                    (root.join("lib/main.d.ts"), root.join("lib/browser.d.ts")),
+                ])
+            );
+            assert!(default_metadata.entry_point.browser_remappings.is_empty());
+        }
+
+        #[test]
+        fn definitely_typed_package() {
+            let temp_dir = make_package(
+                r#"{"name": "@types/node", "version": "22.0.0", "main": ""}"#,
+                &["index.d.ts", "dist/index.d.ts", "ts5.6/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata.entry_point.iter().collect::<Vec<_>>(),
+                vec![&TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("index.d.ts"),
+                    conditions: BTreeMap::new(),
+                }]
+            );
+            assert!(metadata.entry_point.diagnostics.is_empty());
+        }
+
+        #[test]
+        fn empty_main() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "main": ""}"#,
+                &["index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                temp_dir.path.join("index.d.ts")
+            );
+        }
+
+        #[test]
+        fn location_outside_files() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "files": ["types"]}"#,
+                &["dist/index.d.ts", "types/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_contains!(
+                metadata.entry_point,
+                &TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: temp_dir.path.join("types/index.d.ts"),
+                    conditions: BTreeMap::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn no_location_in_files() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "files": ["src"]}"#,
+                &["dist/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert!(metadata.entry_point.is_empty());
+            assert!(metadata.entry_point.diagnostics.is_empty());
+        }
+
+        #[test]
+        fn declared_types_are_not_inferred() {
+            let temp_dir = make_package(
+                r#"{"name": "test-pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+                &["index.d.ts", "dist/index.d.ts"],
+            );
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.entry_point.len(), 1);
+            assert!(metadata.entry_point.diagnostics.is_empty());
+        }
+
+        mod files_patterns {
+            use super::*;
+
+            #[test]
+            fn file() {
+                assert!(is_file_included("index.d.ts", "index.d.ts"));
+                assert!(is_file_included("index.d.ts", "./index.d.ts"));
+                assert!(!is_file_included("lib/index.d.ts", "index.d.ts"));
+            }
+
+            #[test]
+            fn directory() {
+                assert!(is_file_included("dist/index.d.ts", "dist"));
+                assert!(is_file_included("dist/index.d.ts", "dist/"));
+                assert!(!is_file_included("distribution/index.d.ts", "dist"));
+            }
+
+            #[test]
+            fn globs() {
+                assert!(is_file_included("index.d.ts", "*.d.ts"));
+                assert!(is_file_included("dist/index.d.ts", "dist/*.d.ts"));
+                assert!(is_file_included("dist/index.d.ts", "**/*.d.ts"));
+                assert!(is_file_included("dist/index.d.ts", "di*"));
+                assert!(!is_file_included("dist/index.d.ts", "*.js"));
+                assert!(!is_file_included("lib/index.d.ts", "dist/**"));
+            }
+        }
+    }
+
+    mod file_exclusions {
+        use super::*;
+
+        fn make_options(exclude_test_files: bool, patterns: &[&str]) -> ExtractionOptions {
+            ExtractionOptions {
+                exclude_test_files,
+                excluded_files: patterns.iter().map(ToString::to_string).collect(),
+                ..ExtractionOptions::default()
+            }
+        }
+
+        fn is_excluded(path: &str, options: &ExtractionOptions) -> bool {
+            is_excluded_file(Path::new(path), Path::new("/pkg"), options)
+        }
+
+        #[test]
+        fn nothing_excluded_by_default() {
+            let options = ExtractionOptions::default();
+
+            assert!(!is_excluded("/pkg/__tests__/index.d.ts", &options));
+        }
+
+        #[test]
+        fn test_directories() {
+            let options = make_options(true, &[]);
+
+            assert!(is_excluded("/pkg/dist/__tests__/utils.d.ts", &options));
+            assert!(is_excluded("/pkg/examples/basic.d.ts", &options));
+            assert!(!is_excluded("/pkg/dist/testing.d.ts", &options));
+        }
+
+        #[test]
+        fn test_file_names() {
+            let options = make_options(true, &[]);
+
+            assert!(is_excluded("/pkg/dist/utils.test.d.ts", &options));
+            assert!(is_excluded("/pkg/dist/utils.spec.d.mts", &options));
+            assert!(!is_excluded("/pkg/dist/utils.d.ts", &options));
+        }
+
+        #[test]
+        fn heuristics_disabled() {
+            let options = make_options(false, &[]);
+
+            assert!(!is_excluded("/pkg/dist/utils.test.d.ts", &options));
+        }
+
+        #[test]
+        fn patterns() {
+            let options = make_options(false, &["dist/internal", "**/*.fixture.d.ts"]);
+
+            assert!(is_excluded("/pkg/dist/internal/state.d.ts", &options));
+            assert!(is_excluded("/pkg/dist/user.fixture.d.ts", &options));
+            assert!(!is_excluded("/pkg/dist/user.d.ts", &options));
+        }
+
+        #[test]
+        fn outside_package() {
+            let options = make_options(true, &["**"]);
+
+            assert!(!is_excluded("/examples/pkg/index.d.ts", &options));
+        }
+
+        #[test]
+        fn unknown_package_path() {
+            let options = make_options(true, &[]);
+
+            assert!(!is_excluded_file(
+                Path::new("/pkg/__tests__/index.d.ts"),
+                Path::new(""),
+                &options
+            ));
+        }
+    }
+
+    mod published_files {
+        use super::*;
+
+        fn make_package(files: &str, file_paths: &[&str]) -> TempDir {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    &format!(
+                        r#"{{"name": "pkg", "version": "1.0.0", "types": "src/index.d.ts", "files": {files}}}"#
+                    ),
+                )
+                .unwrap();
+            for file_path in file_paths {
+                temp_dir.create_file(file_path, "export {};").unwrap();
+            }
+            temp_dir
+        }
+
+        fn make_options(unpublished_files: UnpublishedFiles) -> ExtractionOptions {
+            ExtractionOptions {
+                unpublished_files,
+                ..Default::default()
             }
         }
+
+        #[test]
+        fn everything_published_without_patterns() {
+            let published_files = PublishedFiles {
+                package_path: PathBuf::from("/pkg"),
+                patterns: None,
+            };
+
+            assert!(!published_files.is_unpublished(Path::new("/pkg/src/index.d.ts")));
+        }
+
+        #[test]
+        fn patterns() {
+            let published_files = PublishedFiles {
+                package_path: PathBuf::from("/pkg"),
+                patterns: Some(vec!["dist".to_string()]),
+            };
+
+            assert!(!published_files.is_unpublished(Path::new("/pkg/dist/index.d.ts")));
+            assert!(published_files.is_unpublished(Path::new("/pkg/src/index.d.ts")));
+            assert!(!published_files.is_unpublished(Path::new("/other/src/index.d.ts")));
+        }
+
+        #[test]
+        fn ignored_by_default() {
+            let temp_dir = make_package(r#"["dist"]"#, &["src/index.d.ts"]);
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.entry_point.len(), 1);
+            assert!(metadata.entry_point.diagnostics.is_empty());
+        }
+
+        #[test]
+        fn unpublished_entry_point_warning() {
+            let temp_dir = make_package(r#"["dist"]"#, &["src/index.d.ts"]);
+
+            let metadata =
+                extract_metadata(&temp_dir.path, &make_options(UnpublishedFiles::Warn)).unwrap();
+
+            assert_eq!(metadata.entry_point.len(), 1);
+            assert_eq!(
+                metadata.entry_point.diagnostics,
+                vec![Diagnostic::UnpublishedFiles {
+                    paths: vec![temp_dir.path.join("src/index.d.ts")],
+                    is_skipped: false,
+                }]
+            );
+        }
+
+        #[test]
+        fn unpublished_entry_point_skipped() {
+            let temp_dir = make_package(r#"["dist"]"#, &["src/index.d.ts"]);
+
+            let metadata =
+                extract_metadata(&temp_dir.path, &make_options(UnpublishedFiles::Skip)).unwrap();
+
+            assert!(metadata.entry_point.is_empty());
+            assert_eq!(
+                metadata.entry_point.diagnostics,
+                vec![Diagnostic::UnpublishedFiles {
+                    paths: vec![temp_dir.path.join("src/index.d.ts")],
+                    is_skipped: true,
+                }]
+            );
+        }
+
+        #[test]
+        fn published_entry_point() {
+            let temp_dir = make_package(r#"["src/*.d.ts"]"#, &["src/index.d.ts"]);
+
+            let metadata =
+                extract_metadata(&temp_dir.path, &make_options(UnpublishedFiles::Skip)).unwrap();
+
+            assert_eq!(metadata.entry_point.len(), 1);
+            assert!(metadata.entry_point.diagnostics.is_empty());
+        }
+    }
+
+    mod package_type {
+        use super::*;
+
+        #[test]
+        fn missing_manifest() {
+            let temp_dir = TempDir::new();
+            let file_path = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::CommonJs
+            );
+        }
+
+        #[test]
+        fn missing_type() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            let file_path = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::CommonJs
+            );
+        }
+
+        #[test]
+        fn module_type() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"type": "module"}"#)
+                .unwrap();
+            let file_path = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::Module
+            );
+        }
+
+        #[test]
+        fn unrecognised_type() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"type": "esm"}"#)
+                .unwrap();
+            let file_path = temp_dir.create_file("index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::CommonJs
+            );
+        }
+
+        #[test]
+        fn nearest_manifest_wins() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"type": "module"}"#)
+                .unwrap();
+            temp_dir
+                .create_file("dist/cjs/package.json", r#"{"type": "commonjs"}"#)
+                .unwrap();
+            let file_path = temp_dir.create_file("dist/cjs/index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::CommonJs
+            );
+        }
+
+        #[test]
+        fn known_package_type() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"type": "module"}"#)
+                .unwrap();
+            temp_dir
+                .create_file("dist/cjs/package.json", r#"{"type": "commonjs"}"#)
+                .unwrap();
+            let file_path = temp_dir.create_file("dist/index.d.ts", "").unwrap();
+            let nested_file_path = temp_dir.create_file("dist/cjs/index.d.ts", "").unwrap();
+            let mut known_package_types =
+                HashMap::from([(temp_dir.path.clone(), PackageType::CommonJs)]);
+
+            let package_type = find_package_type_with(&file_path, &mut known_package_types);
+            let nested_package_type =
+                find_package_type_with(&nested_file_path, &mut known_package_types);
+
+            assert_eq!(package_type, PackageType::CommonJs);
+            assert_eq!(nested_package_type, PackageType::CommonJs);
+            assert_eq!(
+                known_package_types.get(&temp_dir.path.join("dist/cjs")),
+                Some(&PackageType::CommonJs)
+            );
+        }
+
+        #[test]
+        fn package_type_in_metadata() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "pkg", "version": "1.0.0", "type": "module", "types": "index.d.ts"}"#,
+                )
+                .unwrap();
+
+            let metadata = extract_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(metadata.entry_point.package_type, PackageType::Module);
+        }
+
+        #[test]
+        fn ancestor_manifest() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"type": "module"}"#)
+                .unwrap();
+            let file_path = temp_dir.create_file("dist/types/index.d.ts", "").unwrap();
+
+            assert_eq!(
+                find_package_type_with(&file_path, &mut HashMap::new()),
+                PackageType::Module
+            );
+        }
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::api::{FileKinds, QueryExtension};
+use crate::version_range::TypeScriptVersion;
+
+/// Settings that change how packages are extracted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractionOptions {
+    /// The preferred language of the documentation, as a BCP 47 tag (e.g. `zh-CN`).
+    ///
+    /// If the package has a README in this language (e.g. `README.zh-CN.md`), it's used as the
+    /// documentation instead of the default README.
+    pub locale: Option<String>,
+    /// Additional queries run on every declaration file, whose handlers can add custom symbols
+    /// to the public API.
+    pub query_extensions: Vec<QueryExtension>,
+    /// Where to look for dependencies that aren't in the `node_modules` directory of the
+    /// dependant or any of its ancestors, in order of preference. None are used by default.
+    pub dependency_fallbacks: Vec<DependencyFallback>,
+    /// The directories of packages already located by the caller (e.g. by a monorepo tool),
+    /// keyed by package name.
+    ///
+    /// These take precedence over `node_modules`, so that packages in the same repository can be
+    /// resolved without being installed. Relative directories are resolved from the working
+    /// directory, and those that don't exist are ignored.
+    pub workspace_packages: BTreeMap<String, PathBuf>,
+    /// The root of the project the extracted packages are installed in, if any.
+    ///
+    /// Peer dependencies (e.g. `vite` for a Vite plugin) are provided by the host project, so
+    /// they're resolved from there before falling back to the dependant package.
+    pub host_root: Option<PathBuf>,
+    /// The version of TypeScript the declarations will be used with, which selects between
+    /// version-qualified `types` conditions in `exports` (e.g. `types@>=5.0`).
+    ///
+    /// The newest version is assumed by default.
+    pub typescript_version: Option<TypeScriptVersion>,
+    /// Whether to leave out the declaration files that look like tests or demos (e.g. in
+    /// `__tests__` or `examples` directories, or named `*.test.d.ts`), which sloppy barrels
+    /// sometimes re-export.
+    pub exclude_test_files: bool,
+    /// The declaration files to leave out, as files, directories or globs relative to the
+    /// package (e.g. `dist/internal/**`).
+    ///
+    /// Entry points are never left out.
+    pub excluded_files: Vec<String>,
+    /// Whether to leave the `protected` members of classes out of the public API.
+    ///
+    /// They're kept by default, as they're part of the API for subclasses, but those who only
+    /// use the classes have no access to them.
+    pub exclude_protected_members: bool,
+    /// Whether to leave the private members of classes out of the public API, whether they're
+    /// marked `private` or their names start with `#` (e.g. the `#private;` brand that TypeScript
+    /// emits).
+    ///
+    /// They're kept by default, as they affect the compatibility between class types even though
+    /// they can't be used.
+    pub exclude_private_members: bool,
+    /// How much of the source code of each symbol to keep in the public API.
+    pub detail_level: DetailLevel,
+    /// Whether to extract the API of the browser build: the `browser` condition of `exports` is
+    /// then preferred, and the `browser` field of `package.json` replaces the `main` file and the
+    /// modules it remaps.
+    pub browser: bool,
+    /// The name of the workspace member to extract when the path is the root of a monorepo (e.g.
+    /// `@acme/core`), as declared by the `workspaces` field or `pnpm-workspace.yaml`.
+    ///
+    /// The root package itself is extracted by default.
+    pub workspace_member: Option<String>,
+    /// Whether to drop the modules that contribute nothing to the public API once the exports
+    /// are resolved (e.g. barrels and internal-only files), so that the module reports, metrics
+    /// and dependency weights only cover what's exported, and the memory they take is freed early.
+    ///
+    /// Every module reachable from the entry points is kept by default.
+    pub prune_unreachable_modules: bool,
+    /// The kinds of files that imports are followed to (e.g. only declaration files, to ignore
+    /// the sources that some packages ship alongside them).
+    ///
+    /// Every kind is followed by default, and entry points are loaded whatever their kind.
+    pub file_kinds: FileKinds,
+    /// What to do with the declaration files that the `files` field of `package.json` doesn't
+    /// publish, which only exist when extracting from a source checkout rather than a published
+    /// tarball.
+    ///
+    /// The `files` field is ignored by default.
+    pub unpublished_files: UnpublishedFiles,
+}
+
+/// How much of each symbol is kept, to trade completeness for size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailLevel {
+    /// Only the names, with empty source code.
+    NamesOnly,
+    /// The declarations without any comments.
+    Signatures,
+    /// The declarations with their JSDoc comments and those of their members, but no other
+    /// comments.
+    SignaturesWithDocs,
+    /// The declarations as written, with every comment.
+    #[default]
+    FullSource,
+}
+
+/// What to do with the files that a package wouldn't publish, as set in
+/// [ExtractionOptions::unpublished_files].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnpublishedFiles {
+    /// Use them like any other file.
+    #[default]
+    Include,
+    /// Use them, but report them with [crate::Diagnostic::UnpublishedFiles].
+    Warn,
+    /// Leave them out, including the entry points that point to them, and report them with
+    /// [crate::Diagnostic::UnpublishedFiles].
+    Skip,
+}
+
+/// A location outside `node_modules` directories where dependencies may be installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyFallback {
+    /// The directories listed in the `NODE_PATH` environment variable.
+    NodePath,
+    /// The directories where packages are installed globally: `lib/node_modules` in the npm
+    /// prefix (set with `NPM_CONFIG_PREFIX`), followed by `.node_modules` and `.node_libraries` in
+    /// the home directory.
+    GlobalInstallation,
+}
+
+impl fmt::Display for DependencyFallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyFallback::NodePath => write!(f, "NODE_PATH"),
+            DependencyFallback::GlobalInstallation => write!(f, "the global installation"),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Makes a path relative to the root of a package (e.g. `dist/index.d.ts`), so that it can be
+/// shown to users without the location of the package on disk.
+///
+/// Paths outside the package that share an ancestor directory with it, such as dependencies
+/// hoisted to a parent `node_modules` directory, are made relative with `..` segments. Paths with
+/// nothing in common with the package other than the filesystem root are returned unchanged.
+pub(crate) fn relativise_path(path: &Path, root: &Path) -> PathBuf {
+    if let Ok(relative_path) = path.strip_prefix(root) {
+        return relative_path.to_path_buf();
+    }
+
+    // Module paths are canonical whereas the root may not be (e.g. if it's behind a symlink)
+    let canonicalise = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let (path, root) = (canonicalise(path), canonicalise(root));
+    if let Ok(relative_path) = path.strip_prefix(&root) {
+        return relative_path.to_path_buf();
+    }
+
+    let common_component_count = path
+        .components()
+        .zip(root.components())
+        .take_while(|(path_component, root_component)| path_component == root_component)
+        .count();
+    let has_common_directory = path
+        .components()
+        .take(common_component_count)
+        .any(|component| matches!(component, Component::Normal(_)));
+    if !has_common_directory {
+        return path;
+    }
+
+    let mut relative_path = PathBuf::new();
+    for _ in common_component_count..root.components().count() {
+        relative_path.push("..");
+    }
+    relative_path.extend(path.components().skip(common_component_count));
+    relative_path
+}
+
+/// Formats a path with `/` as the separator on every platform.
+pub(crate) fn to_portable_string(path: &Path) -> String {
+    path.components()
+        .map(|component| match component {
+            Component::RootDir => String::new(),
+            component => component.as_os_str().to_string_lossy().into_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod relativise_path {
+        use super::*;
+
+        #[test]
+        fn path_in_package() {
+            let path = relativise_path(Path::new("/pkg/dist/index.d.ts"), Path::new("/pkg"));
+
+            assert_eq!(path, PathBuf::from("dist/index.d.ts"));
+        }
+
+        #[test]
+        fn hoisted_dependency() {
+            let path = relativise_path(
+                Path::new("/app/node_modules/zod/index.d.ts"),
+                Path::new("/app/node_modules/pkg"),
+            );
+
+            assert_eq!(path, PathBuf::from("../zod/index.d.ts"));
+        }
+
+        #[test]
+        fn unrelated_path() {
+            let path = relativise_path(Path::new("/usr/lib/node/index.d.ts"), Path::new("/pkg"));
+
+            assert_eq!(path, PathBuf::from("/usr/lib/node/index.d.ts"));
+        }
+    }
+
+    mod to_portable_string {
+        use super::*;
+
+        #[test]
+        fn relative_path() {
+            assert_eq!(
+                to_portable_string(Path::new("../zod/index.d.ts")),
+                "../zod/index.d.ts"
+            );
+        }
+
+        #[test]
+        fn absolute_path() {
+            assert_eq!(
+                to_portable_string(Path::new("/pkg/index.d.ts")),
+                "/pkg/index.d.ts"
+            );
+        }
+    }
+}
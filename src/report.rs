@@ -0,0 +1,1053 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use daipendency_extractor::{ExtractionError, Namespace, Symbol};
+use serde_json::{json, Value};
+use tree_sitter::Parser;
+
+use crate::api::{
+    extract_public_api_with_modules, get_entry_point_namespace_name, get_stability,
+    DependencyWeight, ExtractedPublicApi, Generator, ImportHint, Stability, SymbolKind,
+    UnresolvedExternal, UnresolvedReason,
+};
+use crate::capabilities::{summarise_capabilities, Capability, CapabilitySummary};
+use crate::diagnostics::Diagnostic;
+use crate::formats::{estimate_api_tokens, TokenReport};
+use crate::implementations::{group_by_heritage, HeritageGroup, HeritageRelation};
+use crate::metadata::{TSEntryPoint, TSLibraryMetadata};
+use crate::options::ExtractionOptions;
+use crate::paths::{relativise_path, to_portable_string};
+
+/// Everything extracted from a package, as returned by [`crate::TypeScriptExtractor::extract`].
+#[derive(Debug)]
+pub struct ExtractionReport {
+    pub metadata: TSLibraryMetadata,
+    /// The root of the package, as passed to the extractor. Every other path in the report is
+    /// absolute: see [ExtractionReport::relativise_path] to make them relative to the root.
+    pub package_path: PathBuf,
+    /// The entry points, sorted by external path.
+    pub entry_points: Vec<EntryPointReport>,
+    /// The modules reachable from the entry points, sorted by path.
+    pub modules: Vec<ModuleReport>,
+    pub public_api: Vec<Namespace>,
+    /// The diagnostics from working out the entry points, followed by those from loading the
+    /// modules.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The bare specifiers whose types are missing from the public API, sorted by specifier.
+    pub unresolved_externals: Vec<UnresolvedExternal>,
+    pub stats: ExtractionStats,
+    /// The estimated number of tokens of the public API, broken down by namespace and symbol.
+    pub tokens: TokenReport,
+    /// The size of every module and the time it took to parse, sorted by path.
+    pub module_metrics: Vec<ModuleMetrics>,
+    /// The README headings and the main classes and functions of each entry point.
+    pub capabilities: CapabilitySummary,
+    /// The modules augmented with `declare module` blocks, sorted by specifier.
+    pub augmentations: Vec<AugmentationReport>,
+    /// The exported classes grouped by the exported classes and interfaces they build upon.
+    pub heritage_groups: Vec<HeritageGroup>,
+    /// The packages whose types the modules refer to in their declarations, sorted by package
+    /// name.
+    pub dependency_weights: Vec<DependencyWeight>,
+    /// Whether the package exports nothing but declares globals (e.g. a polyfill), in which case
+    /// the public API is the `global` namespace with those declarations.
+    pub is_global_only: bool,
+}
+
+/// An entry point along with the namespace its exports are in.
+#[derive(Debug, Clone)]
+pub struct EntryPointReport {
+    pub entry_point: TSEntryPoint,
+    /// The name of the namespace in the public API (e.g. `pkg/utils`).
+    pub namespace: String,
+    /// How to import the main classes and functions of the entry point, in the order of
+    /// [CapabilitySummary::entry_points].
+    pub import_hints: Vec<ImportHint>,
+    /// The names of the namespaces the entry point exports (e.g. `Utils` for
+    /// `export namespace Utils {}`), as they're named in the public API.
+    pub exported_namespaces: Vec<String>,
+}
+
+/// A declaration file that was parsed during the extraction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleReport {
+    pub path: PathBuf,
+    /// The standard libraries the module assumes (e.g. `dom`).
+    pub lib_references: Vec<String>,
+    /// Whether the module replaces the default standard library.
+    pub no_default_lib: bool,
+    /// The tool that generated the module, if it looks machine-generated.
+    pub generator: Option<Generator>,
+}
+
+/// The declarations added to a module by `declare module` blocks, which are the real API of
+/// plugins (e.g. the methods a Fastify plugin adds to `FastifyInstance`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AugmentationReport {
+    /// The augmented module (e.g. `fastify`).
+    pub specifier: String,
+    /// The modules with blocks augmenting it, sorted by path.
+    pub declared_in: Vec<PathBuf>,
+    /// The declarations of every block, in the order of `declared_in`.
+    pub symbols: Vec<Symbol>,
+}
+
+/// How big a declaration file is and how long it took to parse, to help find the files that
+/// make a package slow to extract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleMetrics {
+    pub path: PathBuf,
+    /// The time taken to parse the file and collect its symbols, excluding reading it.
+    pub parse_time: Duration,
+    /// The size of the source code, in bytes.
+    pub byte_count: usize,
+    /// The number of top-level symbols in the module, including imports and exports.
+    pub symbol_count: usize,
+}
+
+/// Picks the import hint of each highlighted symbol, in order.
+fn select_import_hints(hints: Vec<ImportHint>, highlights: &[Capability]) -> Vec<ImportHint> {
+    highlights
+        .iter()
+        .filter_map(|highlight| hints.iter().find(|hint| hint.name == highlight.name))
+        .cloned()
+        .collect()
+}
+
+/// Counts of what was extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtractionStats {
+    pub entry_point_count: usize,
+    pub module_count: usize,
+    pub namespace_count: usize,
+    pub symbol_count: usize,
+}
+
+impl ExtractionReport {
+    /// Extracts the public API of a library and gathers it with everything else we know about it.
+    pub(crate) fn build(
+        metadata: TSLibraryMetadata,
+        package_path: &Path,
+        parser: &mut Parser,
+        options: &ExtractionOptions,
+    ) -> Result<Self, ExtractionError> {
+        let ExtractedPublicApi {
+            namespaces: public_api,
+            modules,
+            diagnostics: module_diagnostics,
+            unresolved_externals,
+            module_metrics,
+            augmentations,
+            mut import_hints,
+            mut exported_namespaces,
+            dependency_weights,
+            is_global_only,
+        } = extract_public_api_with_modules(&metadata, parser, options)?;
+
+        let mut entry_points = metadata
+            .entry_point
+            .iter()
+            .map(|entry_point| EntryPointReport {
+                entry_point: entry_point.clone(),
+                namespace: get_entry_point_namespace_name(&metadata.name, entry_point),
+                import_hints: vec![],
+                exported_namespaces: exported_namespaces
+                    .remove(&entry_point.external_path)
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        entry_points.sort_by(|a, b| {
+            a.entry_point
+                .external_path
+                .cmp(&b.entry_point.external_path)
+        });
+
+        let stats = ExtractionStats {
+            entry_point_count: entry_points.len(),
+            module_count: modules.len(),
+            namespace_count: public_api.len(),
+            symbol_count: public_api
+                .iter()
+                .map(|namespace| namespace.symbols.len())
+                .sum(),
+        };
+        let tokens = estimate_api_tokens(&public_api);
+
+        let capabilities =
+            summarise_capabilities(&metadata.documentation, &entry_points, &public_api);
+        for (report, entry_point_capabilities) in
+            entry_points.iter_mut().zip(&capabilities.entry_points)
+        {
+            let hints = import_hints
+                .remove(&report.entry_point.external_path)
+                .unwrap_or_default();
+            report.import_hints = select_import_hints(hints, &entry_point_capabilities.highlights);
+        }
+
+        let heritage_groups = group_by_heritage(&public_api);
+
+        let mut diagnostics = metadata.entry_point.diagnostics.clone();
+        diagnostics.extend(module_diagnostics);
+
+        Ok(Self {
+            diagnostics,
+            metadata,
+            package_path: package_path.to_path_buf(),
+            entry_points,
+            modules,
+            public_api,
+            unresolved_externals,
+            stats,
+            tokens,
+            module_metrics,
+            capabilities,
+            augmentations,
+            heritage_groups,
+            dependency_weights,
+            is_global_only,
+        })
+    }
+
+    /// Gets the stability of a symbol: that in its JSDoc if any, or else that of the package.
+    pub fn get_stability(&self, symbol: &Symbol) -> Option<Stability> {
+        get_stability(symbol).or(self.metadata.entry_point.stability)
+    }
+
+    /// Makes a path relative to the root of the package (e.g. `dist/index.d.ts`), using `..`
+    /// segments for dependencies outside it (e.g. `../zod/index.d.ts` when the package is itself
+    /// in a `node_modules` directory).
+    pub fn relativise_path(&self, path: &Path) -> PathBuf {
+        relativise_path(path, &self.package_path)
+    }
+
+    /// Formats a path relative to the root of the package, with `/` as the separator.
+    fn format_path(&self, path: &Path) -> String {
+        to_portable_string(&self.relativise_path(path))
+    }
+
+    /// Lists up to `count` modules that took the longest to parse, slowest first.
+    pub fn slowest_modules(&self, count: usize) -> Vec<&ModuleMetrics> {
+        self.get_top_modules(count, |metrics| metrics.parse_time)
+    }
+
+    /// Lists up to `count` modules with the most source code, largest first.
+    pub fn largest_modules(&self, count: usize) -> Vec<&ModuleMetrics> {
+        self.get_top_modules(count, |metrics| metrics.byte_count)
+    }
+
+    /// Lists up to `count` modules in descending order of a metric, breaking ties by path.
+    fn get_top_modules<K: Ord>(
+        &self,
+        count: usize,
+        get_metric: impl Fn(&ModuleMetrics) -> K,
+    ) -> Vec<&ModuleMetrics> {
+        let mut modules = self.module_metrics.iter().collect::<Vec<_>>();
+        modules.sort_by(|a, b| get_metric(b).cmp(&get_metric(a)).then(a.path.cmp(&b.path)));
+        modules.truncate(count);
+        modules
+    }
+
+    /// Converts the report to JSON, with paths relative to the root of the package and
+    /// diagnostics as messages.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "metadata": {
+                "name": self.metadata.name,
+                "version": self.metadata.version,
+                "documentation": self.metadata.documentation,
+                "isTypeOnly": self.metadata.entry_point.is_type_only,
+                "stability": self.metadata.entry_point.stability.map(|stability| stability.to_string()),
+                "isGlobalOnly": self.is_global_only,
+            },
+            "entryPoints": self.entry_points.iter().map(|report| json!({
+                "externalPath": report.entry_point.external_path,
+                "internalPath": self.format_path(&report.entry_point.internal_path),
+                "conditions": report.entry_point.conditions,
+                "namespace": report.namespace,
+                "importHints": report.import_hints.iter().map(|hint| json!({
+                    "name": hint.name,
+                    "statement": hint.statement,
+                    "requireStatement": hint.require_statement,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "modules": self.modules.iter().map(|module| json!({
+                "path": self.format_path(&module.path),
+                "libReferences": module.lib_references,
+                "noDefaultLib": module.no_default_lib,
+                "generator": module.generator.map(|generator| generator.to_string()),
+            })).collect::<Vec<_>>(),
+            "publicApi": self.public_api.iter().map(|namespace| json!({
+                "name": namespace.name,
+                "docComment": namespace.doc_comment,
+                "symbols": namespace.symbols.iter().map(|symbol| json!({
+                    "name": symbol.name,
+                    "sourceCode": symbol.source_code,
+                    "stability": self.get_stability(symbol).map(|stability| stability.to_string()),
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "diagnostics": self.diagnostics.iter().map(|diagnostic| diagnostic.relativise_paths(&self.package_path).to_string()).collect::<Vec<_>>(),
+            "unresolvedExternals": self.unresolved_externals.iter().map(|external| json!({
+                "specifier": external.specifier,
+                "packagePath": match &external.reason {
+                    UnresolvedReason::Missing => None,
+                    UnresolvedReason::NotFollowed { package_path, .. } => Some(self.format_path(package_path)),
+                    UnresolvedReason::Builtin { types_package_path } => types_package_path.as_deref().map(|path| self.format_path(path)),
+                },
+                "packageVersion": match &external.reason {
+                    UnresolvedReason::NotFollowed { package_version, .. } => package_version.as_deref(),
+                    _ => None,
+                },
+                "isBuiltin": matches!(external.reason, UnresolvedReason::Builtin { .. }),
+                "referenceCount": external.reference_count,
+                "referencingFiles": external.referencing_files.iter().map(|path| self.format_path(path)).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "stats": {
+                "entryPointCount": self.stats.entry_point_count,
+                "moduleCount": self.stats.module_count,
+                "namespaceCount": self.stats.namespace_count,
+                "symbolCount": self.stats.symbol_count,
+            },
+            "tokens": {
+                "total": self.tokens.total,
+                "namespaces": self.tokens.namespaces.iter().map(|namespace| json!({
+                    "name": namespace.name,
+                    "tokens": namespace.tokens,
+                    "symbols": namespace.symbols.iter().map(|symbol| json!({
+                        "name": symbol.name,
+                        "tokens": symbol.tokens,
+                    })).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            },
+            "moduleMetrics": self.module_metrics.iter().map(|metrics| json!({
+                "path": self.format_path(&metrics.path),
+                "parseTimeMicros": u64::try_from(metrics.parse_time.as_micros()).unwrap_or(u64::MAX),
+                "byteCount": metrics.byte_count,
+                "symbolCount": metrics.symbol_count,
+            })).collect::<Vec<_>>(),
+            "capabilities": {
+                "headings": self.capabilities.headings,
+                "entryPoints": self.capabilities.entry_points.iter().map(|entry_point| json!({
+                    "externalPath": entry_point.external_path,
+                    "namespace": entry_point.namespace,
+                    "highlights": entry_point.highlights.iter().map(|capability| json!({
+                        "name": capability.name,
+                        "kind": match capability.kind {
+                            SymbolKind::Class { .. } => "class",
+                            _ => "function",
+                        },
+                        "summary": capability.summary,
+                    })).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            },
+            "augmentations": self.augmentations.iter().map(|augmentation| (
+                augmentation.specifier.clone(),
+                json!({
+                    "declaredIn": augmentation.declared_in.iter().map(|path| self.format_path(path)).collect::<Vec<_>>(),
+                    "symbols": augmentation.symbols.iter().map(|symbol| json!({
+                        "name": symbol.name,
+                        "sourceCode": symbol.source_code,
+                    })).collect::<Vec<_>>(),
+                }),
+            )).collect::<serde_json::Map<_, _>>(),
+            "heritageGroups": self.heritage_groups.iter().map(|group| json!({
+                "base": group.base,
+                "relation": match group.relation {
+                    HeritageRelation::Extends => "extends",
+                    HeritageRelation::Implements => "implements",
+                },
+                "label": group.to_string(),
+                "classes": group.classes.iter().map(|(namespace, name)| json!({
+                    "namespace": namespace,
+                    "name": name,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "dependencyWeights": self.dependency_weights.iter().map(|weight| json!({
+                "packageName": weight.package_name,
+                "symbolCount": weight.symbol_count,
+                "referencingFiles": weight.referencing_files.iter().map(|path| self.format_path(path)).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeScriptExtractor;
+    use daipendency_testing::tempdir::TempDir;
+
+    fn make_package() -> TempDir {
+        let temp_dir = TempDir::new();
+        temp_dir
+            .create_file(
+                "package.json",
+                r#"{
+                    "name": "test-pkg",
+                    "version": "1.0.0",
+                    "exports": {
+                        ".": {"types": "./index.d.ts", "import": "./index.js"},
+                        "./utils": {"types": "./utils.d.ts"}
+                    }
+                }"#,
+            )
+            .unwrap();
+        temp_dir
+            .create_file(
+                "index.d.ts",
+                "/// <reference lib=\"dom\" />\nexport * from './helpers';\nexport declare function main(): void;",
+            )
+            .unwrap();
+        temp_dir
+            .create_file("helpers.d.ts", "export declare const helper: number;")
+            .unwrap();
+        temp_dir
+            .create_file("utils.d.ts", "export type Util = string;")
+            .unwrap();
+        temp_dir
+    }
+
+    mod extract {
+        use super::*;
+
+        #[test]
+        fn metadata() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(report.metadata.name, "test-pkg");
+            assert_eq!(report.metadata.version, Some("1.0.0".to_string()));
+        }
+
+        #[test]
+        fn entry_points() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let entry_points = report
+                .entry_points
+                .iter()
+                .map(|report| {
+                    (
+                        report.entry_point.external_path.as_str(),
+                        report.namespace.as_str(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                entry_points,
+                vec![(".", "test-pkg"), ("./utils", "test-pkg/utils")]
+            );
+        }
+
+        #[test]
+        fn modules() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.modules,
+                vec![
+                    ModuleReport {
+                        path: temp_dir.path.join("helpers.d.ts"),
+                        lib_references: vec![],
+                        no_default_lib: false,
+                        generator: None,
+                    },
+                    ModuleReport {
+                        path: temp_dir.path.join("index.d.ts"),
+                        lib_references: vec!["dom".to_string()],
+                        no_default_lib: false,
+                        generator: None,
+                    },
+                    ModuleReport {
+                        path: temp_dir.path.join("utils.d.ts"),
+                        lib_references: vec![],
+                        no_default_lib: false,
+                        generator: None,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn public_api() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let namespace = report
+                .public_api
+                .iter()
+                .find(|namespace| namespace.name == "test-pkg")
+                .unwrap();
+            assert!(namespace.get_symbol("main").is_some());
+            assert!(namespace.get_symbol("helper").is_some());
+        }
+
+        #[test]
+        fn stability() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0", "types": "index.d.ts", "stability": "stable"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "index.d.ts",
+                    "/** @stability experimental */\nexport declare class Bucket {}\nexport declare class Queue {}",
+                )
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let namespace = &report.public_api[0];
+            assert_eq!(
+                report.get_stability(namespace.get_symbol("Bucket").unwrap()),
+                Some(Stability::Experimental)
+            );
+            assert_eq!(
+                report.get_stability(namespace.get_symbol("Queue").unwrap()),
+                Some(Stability::Stable)
+            );
+            let json = report.to_json();
+            assert_eq!(json["metadata"]["stability"], "stable");
+            assert_eq!(
+                json["publicApi"][0]["symbols"][0]["stability"],
+                "experimental"
+            );
+        }
+
+        #[test]
+        fn diagnostics() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file("index.d.ts", "export declare const a: number;")
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(report.diagnostics, report.metadata.entry_point.diagnostics);
+            assert_eq!(report.diagnostics.len(), 1);
+        }
+
+        #[test]
+        fn module_diagnostics() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "index.d.ts",
+                    "import { Plugin } from 'optional-dep';\nexport declare const plugin: Plugin;",
+                )
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.diagnostics,
+                vec![Diagnostic::UnresolvedExternal {
+                    specifier: "optional-dep".to_string(),
+                    importer: temp_dir.path.join("index.d.ts"),
+                }]
+            );
+        }
+
+        #[test]
+        fn unresolved_externals() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "index.d.ts",
+                    "import { Plugin } from 'vite';\nexport * from 'optional-dep';\nexport * from './helpers';\nexport declare const plugin: Plugin;",
+                )
+                .unwrap();
+            temp_dir
+                .create_file("helpers.d.ts", "import type { Config } from 'vite';")
+                .unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", r#"{"version": "5.0.0"}"#)
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.unresolved_externals,
+                vec![
+                    UnresolvedExternal {
+                        specifier: "optional-dep".to_string(),
+                        reason: UnresolvedReason::Missing,
+                        reference_count: 1,
+                        referencing_files: vec![temp_dir.path.join("index.d.ts")],
+                    },
+                    UnresolvedExternal {
+                        specifier: "vite".to_string(),
+                        reason: UnresolvedReason::NotFollowed {
+                            package_path: temp_dir.path.join("node_modules/vite"),
+                            package_version: Some("5.0.0".to_string()),
+                        },
+                        reference_count: 2,
+                        referencing_files: vec![
+                            temp_dir.path.join("helpers.d.ts"),
+                            temp_dir.path.join("index.d.ts"),
+                        ],
+                    },
+                ]
+            );
+            let json = report.to_json();
+            assert_eq!(json["unresolvedExternals"][0]["packagePath"], Value::Null);
+            assert_eq!(json["unresolvedExternals"][1]["referenceCount"], 2);
+            assert_eq!(json["unresolvedExternals"][1]["packageVersion"], "5.0.0");
+            assert_eq!(
+                json["unresolvedExternals"][1]["packagePath"],
+                "node_modules/vite"
+            );
+            assert_eq!(
+                json["unresolvedExternals"][1]["referencingFiles"],
+                json!(["helpers.d.ts", "index.d.ts"])
+            );
+        }
+
+        #[test]
+        fn external_copies() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "index.d.ts",
+                    "export * from './vendor/index';\nexport type { Plugin } from 'vite';",
+                )
+                .unwrap();
+            temp_dir
+                .create_file("vendor/index.d.ts", "export type { Config } from 'vite';")
+                .unwrap();
+            temp_dir.create_file("vendor/package.json", "{}").unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", r#"{"version": "5.0.0"}"#)
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "vendor/node_modules/vite/package.json",
+                    r#"{"version": "4.0.0"}"#,
+                )
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let versions = report
+                .unresolved_externals
+                .iter()
+                .map(|external| match &external.reason {
+                    UnresolvedReason::NotFollowed {
+                        package_version, ..
+                    } => (external.referencing_files.clone(), package_version.clone()),
+                    reason => panic!("Unexpected reason {reason:?}"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                versions,
+                vec![
+                    (
+                        vec![temp_dir.path.join("index.d.ts")],
+                        Some("5.0.0".to_string())
+                    ),
+                    (
+                        vec![temp_dir.path.join("vendor/index.d.ts")],
+                        Some("4.0.0".to_string())
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn stats() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.stats,
+                ExtractionStats {
+                    entry_point_count: 2,
+                    module_count: 3,
+                    namespace_count: 2,
+                    symbol_count: 3,
+                }
+            );
+        }
+
+        #[test]
+        fn tokens() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(report.tokens, estimate_api_tokens(&report.public_api));
+            assert_eq!(report.tokens.namespaces.len(), 2);
+            assert!(report.tokens.total > 0);
+        }
+
+        #[test]
+        fn module_metrics() {
+            let temp_dir = make_package();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let metrics = report
+                .module_metrics
+                .iter()
+                .map(|metrics| {
+                    (
+                        metrics.path.clone(),
+                        metrics.byte_count,
+                        metrics.symbol_count,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                metrics,
+                vec![
+                    (temp_dir.path.join("helpers.d.ts"), 36, 1),
+                    (temp_dir.path.join("index.d.ts"), 92, 2),
+                    (temp_dir.path.join("utils.d.ts"), 26, 1),
+                ]
+            );
+        }
+
+        #[test]
+        fn largest_modules() {
+            let temp_dir = make_package();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let largest_modules = report.largest_modules(2);
+
+            assert_eq!(
+                largest_modules
+                    .iter()
+                    .map(|metrics| metrics.path.clone())
+                    .collect::<Vec<_>>(),
+                vec![
+                    temp_dir.path.join("index.d.ts"),
+                    temp_dir.path.join("helpers.d.ts"),
+                ]
+            );
+        }
+
+        #[test]
+        fn slowest_modules() {
+            let temp_dir = make_package();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let slowest_modules = report.slowest_modules(5);
+
+            assert_eq!(slowest_modules.len(), 3);
+            assert!(slowest_modules
+                .windows(2)
+                .all(|pair| pair[0].parse_time >= pair[1].parse_time));
+        }
+
+        #[test]
+        fn capabilities() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file("README.md", "# test-pkg\n\n## Running `main`")
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.capabilities.headings,
+                vec!["test-pkg", "Running `main`"]
+            );
+            let entry_point = &report.capabilities.entry_points[0];
+            assert_eq!(entry_point.namespace, "test-pkg");
+            assert_eq!(entry_point.highlights[0].name, "main");
+        }
+
+        #[test]
+        fn global_only_package() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "polyfill", "version": "1.0.0", "types": "index.d.ts"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "index.d.ts",
+                    "export {};\ndeclare global { interface Array<T> { findLast(): T; } }",
+                )
+                .unwrap();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert!(report.is_global_only);
+            assert_eq!(json["metadata"]["isGlobalOnly"], true);
+            assert_eq!(
+                json["publicApi"].as_array().unwrap().last().unwrap()["name"],
+                "global"
+            );
+        }
+
+        #[test]
+        fn heritage_groups() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "utils.d.ts",
+                    "export interface Transport {}\nexport declare class HttpTransport implements Transport {}",
+                )
+                .unwrap();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert_eq!(
+                json["heritageGroups"],
+                json!([{
+                    "base": "Transport",
+                    "relation": "implements",
+                    "label": "implementations of `Transport`",
+                    "classes": [{"namespace": "test-pkg/utils", "name": "HttpTransport"}],
+                }])
+            );
+        }
+
+        #[test]
+        fn import_hints() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "utils.d.ts",
+                    "export type Util = string;\nexport declare function format(): string;",
+                )
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(
+                report.entry_points[0].import_hints,
+                vec![ImportHint {
+                    name: "main".to_string(),
+                    statement: "import { main } from 'test-pkg';".to_string(),
+                    require_statement: Some("const { main } = require('test-pkg');".to_string()),
+                }]
+            );
+            let json = report.to_json();
+            assert_eq!(
+                json["entryPoints"][1]["importHints"][0]["statement"],
+                "import { format } from 'test-pkg/utils';"
+            );
+        }
+
+        #[test]
+        fn augmentations() {
+            let temp_dir = make_package();
+            temp_dir
+                .create_file(
+                    "helpers.d.ts",
+                    "declare module 'host' {\n  interface Host { helper(): void; }\n}\nexport declare const helper: number;",
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "utils.d.ts",
+                    "declare module 'host' {\n  interface Host { util(): void; }\n}\nexport type Util = string;",
+                )
+                .unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            assert_eq!(report.augmentations.len(), 1);
+            let augmentation = &report.augmentations[0];
+            assert_eq!(augmentation.specifier, "host");
+            assert_eq!(
+                augmentation.declared_in,
+                vec![
+                    temp_dir.path.join("helpers.d.ts"),
+                    temp_dir.path.join("utils.d.ts")
+                ]
+            );
+            let names = augmentation
+                .symbols
+                .iter()
+                .map(|symbol| symbol.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["Host", "Host"]);
+            assert_eq!(
+                report.to_json()["augmentations"]["host"]["symbols"][1]["sourceCode"],
+                "interface Host { util(): void; }"
+            );
+        }
+
+        #[test]
+        fn missing_manifest() {
+            let temp_dir = TempDir::new();
+
+            let result = TypeScriptExtractor::default().extract(&temp_dir.path);
+
+            assert!(matches!(result, Err(ExtractionError::Io(_))));
+        }
+
+        #[test]
+        fn malformed_manifest() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{").unwrap();
+
+            let result = TypeScriptExtractor::default().extract(&temp_dir.path);
+
+            assert!(matches!(result, Err(ExtractionError::Malformed(_))));
+        }
+    }
+
+    mod to_json {
+        use super::*;
+
+        #[test]
+        fn sections() {
+            let temp_dir = make_package();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert_eq!(json["metadata"]["name"], "test-pkg");
+            assert_eq!(json["entryPoints"][0]["namespace"], "test-pkg");
+            assert_eq!(json["entryPoints"][0]["conditions"]["import"], "./index.js");
+            assert_eq!(json["modules"][1]["libReferences"][0], "dom");
+            assert_eq!(json["modules"][1]["generator"], Value::Null);
+            assert_eq!(json["publicApi"].as_array().unwrap().len(), 2);
+            assert_eq!(json["diagnostics"], json!([]));
+            assert_eq!(json["stats"]["symbolCount"], 3);
+            assert_eq!(json["tokens"]["total"], report.tokens.total);
+            assert_eq!(json["moduleMetrics"][0]["byteCount"], 36);
+            assert_eq!(
+                json["capabilities"]["entryPoints"][0]["highlights"],
+                json!([{"name": "main", "kind": "function", "summary": null}])
+            );
+        }
+
+        #[test]
+        fn diagnostics_as_messages() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"name": "test-pkg", "version": "1.0.0"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file("index.d.ts", "export declare const a: number;")
+                .unwrap();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert_eq!(
+                json["diagnostics"][0],
+                "Inferred entry point '.' from 'index.d.ts' as the manifest doesn't specify any types"
+            );
+        }
+
+        #[test]
+        fn relative_paths() {
+            let temp_dir = make_package();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert_eq!(json["entryPoints"][0]["internalPath"], "index.d.ts");
+            assert_eq!(
+                json["modules"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|module| module["path"].as_str().unwrap())
+                    .collect::<Vec<_>>(),
+                vec!["helpers.d.ts", "index.d.ts", "utils.d.ts"]
+            );
+            assert_eq!(json["moduleMetrics"][0]["path"], "helpers.d.ts");
+        }
+
+        #[test]
+        fn hoisted_dependency_paths() {
+            let temp_dir = TempDir::new();
+            temp_dir.create_file("package.json", "{}").unwrap();
+            temp_dir
+                .create_file(
+                    "node_modules/pkg/package.json",
+                    r#"{"name": "pkg", "version": "1.0.0", "types": "index.d.ts"}"#,
+                )
+                .unwrap();
+            temp_dir
+                .create_file(
+                    "node_modules/pkg/index.d.ts",
+                    "export type { Plugin } from 'vite';",
+                )
+                .unwrap();
+            temp_dir
+                .create_file("node_modules/vite/package.json", r#"{"version": "5.0.0"}"#)
+                .unwrap();
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path.join("node_modules/pkg"))
+                .unwrap();
+
+            let json = report.to_json();
+
+            assert_eq!(json["unresolvedExternals"][0]["packagePath"], "../vite");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use tree_sitter::Node;
+
+use crate::api::queries::make_parser;
+
+/// Tokens that close a list, after which trailing separators are dropped.
+const CLOSING_TOKENS: [&str; 4] = [")", "]", "}", ">"];
+
+/// Tokens that never have a space before them.
+const UNSPACED_BEFORE_TOKENS: [&str; 9] = [",", ";", ":", ")", "]", ">", ".", "?", "!"];
+
+/// Tokens that never have a space after them.
+const UNSPACED_AFTER_TOKENS: [&str; 6] = ["(", "[", "<", ".", "...", "@"];
+
+/// Tokens surrounded by spaces, unless overridden by the rules above.
+const SPACED_TOKENS: [&str; 6] = ["=", "=>", "|", "&", "{", "}"];
+
+/// Nodes whose text is kept verbatim, since whitespace inside them is significant.
+const VERBATIM_NODE_KINDS: [&str; 4] = [
+    "string",
+    "template_string",
+    "template_literal_type",
+    "regex",
+];
+
+/// Normalises the source code of a declaration, so that formatting-only changes don't change it.
+///
+/// Comments and default parameter values are removed (making the parameters optional), trailing
+/// separators in lists are dropped, and tokens are separated by a canonical amount of whitespace.
+/// Source code that doesn't parse only has its whitespace collapsed.
+pub fn normalize_signature(source_code: &str) -> String {
+    let mut parser = make_parser();
+    let tree = parser
+        .parse(source_code, None)
+        .filter(|tree| !tree.root_node().has_error());
+    let Some(tree) = tree else {
+        return source_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    };
+
+    normalize_node(tree.root_node(), source_code)
+}
+
+/// Normalises the source code of a node, like [normalize_signature].
+pub(crate) fn normalize_node(node: Node, source_code: &str) -> String {
+    let mut tokens = vec![];
+    collect_tokens(node, source_code, &mut tokens);
+
+    let mut signature = String::new();
+    let mut previous: Option<&Token> = None;
+    for (index, token) in tokens.iter().enumerate() {
+        let next_text = tokens.get(index + 1).map(|next| next.text);
+        let is_trailing_separator = matches!(token.text, "," | ";")
+            && next_text.is_some_and(|next| CLOSING_TOKENS.contains(&next));
+        if is_trailing_separator {
+            continue;
+        }
+        if let Some(previous) = previous {
+            if needs_space(previous, token) {
+                signature.push(' ');
+            }
+        }
+        signature.push_str(token.text);
+        previous = Some(token);
+    }
+    signature
+}
+
+struct Token<'a> {
+    text: &'a str,
+    /// The kind of the parent node, to tell operators apart from punctuation (e.g. `?`).
+    parent_kind: &'a str,
+}
+
+/// An entry of the stack of [collect_tokens].
+enum StackItem<'a> {
+    /// A node along with the kind of its parent.
+    Node(Node<'a>, &'a str),
+    /// A token that isn't in the source code, such as the `?` of parameters whose default value
+    /// was removed.
+    Token(Token<'a>),
+}
+
+/// Collects the tokens of the source code, skipping comments and default parameter values.
+///
+/// Parameters that lose their default value are marked as optional instead (e.g. `b = 1` becomes
+/// `b?`), so that the signature still tells callers they can leave them out.
+///
+/// The tree is walked with a stack rather than recursively, as generated code can be nested
+/// deeply enough to overflow the call stack.
+fn collect_tokens<'a>(node: Node<'a>, source_code: &'a str, tokens: &mut Vec<Token<'a>>) {
+    // The parent of each node is kept alongside it, as looking it up takes a walk from the root.
+    // That of the node itself is irrelevant: it only matters if the node is a single token.
+    let mut stack = vec![StackItem::Node(node, "")];
+    while let Some(item) = stack.pop() {
+        let (node, parent_kind) = match item {
+            StackItem::Node(node, parent_kind) => (node, parent_kind),
+            StackItem::Token(token) => {
+                tokens.push(token);
+                continue;
+            }
+        };
+        if node.child_count() == 0 || VERBATIM_NODE_KINDS.contains(&node.kind()) {
+            let text = &source_code[node.byte_range()];
+            if !text.is_empty() {
+                tokens.push(Token { text, parent_kind });
+            }
+            continue;
+        }
+
+        let is_parameter = matches!(node.kind(), "required_parameter" | "optional_parameter");
+        let default_value = is_parameter
+            .then(|| node.child_by_field_name("value"))
+            .flatten();
+        let mut cursor = node.walk();
+        let mut children = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() != "comment")
+            .filter(|child| {
+                default_value.is_none_or(|default_value| {
+                    *child != default_value
+                        && !(child.kind() == "=" && child.end_byte() <= default_value.start_byte())
+                })
+            })
+            .map(|child| StackItem::Node(child, node.kind()))
+            .collect::<Vec<_>>();
+        // Optional parameters already have their `?`
+        let is_defaulted = default_value.is_some() && node.kind() == "required_parameter";
+        let pattern_index = node
+            .child_by_field_name("pattern")
+            .filter(|_| is_defaulted)
+            .and_then(|pattern| {
+                children.iter().position(
+                    |child| matches!(child, StackItem::Node(child, _) if *child == pattern),
+                )
+            });
+        if let Some(pattern_index) = pattern_index {
+            let optional_marker = Token {
+                text: "?",
+                parent_kind: "optional_parameter",
+            };
+            children.insert(pattern_index + 1, StackItem::Token(optional_marker));
+        }
+        stack.extend(children.into_iter().rev());
+    }
+}
+
+fn needs_space(previous: &Token, next: &Token) -> bool {
+    let is_word_boundary =
+        previous.text.ends_with(is_word_character) && next.text.starts_with(is_word_character);
+    if is_word_boundary {
+        return true;
+    }
+    if previous.parent_kind == "conditional_type" || next.parent_kind == "conditional_type" {
+        return true;
+    }
+    if (previous.text, next.text) == ("{", "}") {
+        return false;
+    }
+    if UNSPACED_BEFORE_TOKENS.contains(&next.text) || UNSPACED_AFTER_TOKENS.contains(&previous.text)
+    {
+        return false;
+    }
+    matches!(previous.text, "," | ";" | ":")
+        || SPACED_TOKENS.contains(&previous.text)
+        || SPACED_TOKENS.contains(&next.text)
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric() || matches!(character, '_' | '$' | '\'' | '"' | '`')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_signature() {
+        let signature = "export declare function f(a: string, b?: number): void;";
+
+        assert_eq!(normalize_signature(signature), signature);
+    }
+
+    #[test]
+    fn whitespace() {
+        let output = normalize_signature(
+            "export   declare function f(\n  a :string,\n  b?:number\n) :void;",
+        );
+
+        assert_eq!(
+            output,
+            "export declare function f(a: string, b?: number): void;"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let output = normalize_signature(
+            "/** Doc */\nexport interface A {\n  // The name\n  name: string; /* Trailing */\n}",
+        );
+
+        assert_eq!(output, "export interface A { name: string }");
+    }
+
+    #[test]
+    fn default_parameter_values() {
+        let output = normalize_signature("function f(a: number = 1, b = 'x', c?: string) {}");
+
+        assert_eq!(output, "function f(a?: number, b?, c?: string) {}");
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let with_commas =
+            normalize_signature("function f(\n  a: string,\n  b: number,\n): [a, b,];");
+        let without_commas = normalize_signature("function f(a: string, b: number): [a, b];");
+
+        assert_eq!(with_commas, without_commas);
+    }
+
+    #[test]
+    fn trailing_semicolons() {
+        let with_semicolon = normalize_signature("type A = { a: string; };");
+        let without_semicolon = normalize_signature("type A = { a: string };");
+
+        assert_eq!(with_semicolon, without_semicolon);
+    }
+
+    #[test]
+    fn string_whitespace() {
+        let output = normalize_signature("type A = 'a  b' | `c  ${string}`;");
+
+        assert_eq!(output, "type A = 'a  b' | `c  ${string}`;");
+    }
+
+    #[test]
+    fn conditional_types() {
+        let output = normalize_signature("type A<T> = T extends string?'s':'o';");
+
+        assert_eq!(output, "type A<T> = T extends string ? 's' : 'o';");
+    }
+
+    #[test]
+    fn generics_and_arrows() {
+        let output = normalize_signature("declare const f: <T>(value: T)=>Array<T>;");
+
+        assert_eq!(output, "declare const f: <T>(value: T) => Array<T>;");
+    }
+
+    #[test]
+    fn unparsable_source_code() {
+        let output = normalize_signature("export const = ;\n  oops");
+
+        assert_eq!(output, "export const = ; oops");
+    }
+}
@@ -0,0 +1,440 @@
+//! Helpers to render extraction results in a normalised, stable format for snapshot tests.
+//!
+//! Paths are made relative to the library root and everything is sorted, so the output only
+//! changes when the extraction results do.
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use daipendency_extractor::Namespace;
+
+use crate::diagnostics::Diagnostic;
+use crate::metadata::TSLibraryMetadata;
+use crate::paths::{self, to_portable_string};
+
+/// The real-world packages checked into `tests/fixtures/corpus`, from smallest to largest.
+pub const CORPUS_FIXTURES: [&str; 3] = ["react", "zod", "mcp-sdk"];
+
+/// Gets the directory of a corpus fixture, which contains the package (in `package`) and the
+/// snapshot of its extraction (in `expected.txt`).
+pub fn get_corpus_fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/corpus")
+        .join(name)
+}
+
+/// Writes a package whose only declaration file has `declaration_count` groups of documented
+/// interfaces, functions and classes, to stand in for huge single-file bundles like
+/// `typescript/lib/typescript.d.ts`.
+pub fn write_single_file_package(root: &Path, declaration_count: usize) -> io::Result<()> {
+    fs::write(
+        root.join("package.json"),
+        r#"{"name": "single-file", "version": "1.0.0", "types": "index.d.ts"}"#,
+    )?;
+
+    let mut declarations = String::new();
+    for index in 0..declaration_count {
+        write!(
+            declarations,
+            r#"/**
+ * Options for widget {index}.
+ */
+export interface Options{index} {{
+    name: string;
+    size?: number;
+}}
+
+/**
+ * Creates widget {index}.
+ * @param options The options of the widget
+ */
+export declare function create{index}(options: Options{index}): Widget{index};
+
+export declare class Widget{index} {{
+    constructor(options: Options{index});
+    render(): string;
+}}
+
+"#
+        )
+        .expect("Writing to a string should succeed");
+    }
+    fs::write(root.join("index.d.ts"), declarations)
+}
+
+/// Renders the library metadata, with entry point paths relative to `root`.
+pub fn render_library_metadata(metadata: &TSLibraryMetadata, root: &Path) -> String {
+    let mut output = format!("name: {}\n", metadata.name);
+    if let Some(version) = &metadata.version {
+        output.push_str(&format!("version: {version}\n"));
+    }
+
+    if metadata.entry_point.is_type_only {
+        output.push_str("types only\n");
+    }
+
+    let mut entry_points = metadata
+        .entry_point
+        .iter()
+        .map(|entry_point| {
+            (
+                entry_point.external_path.as_str(),
+                relativise_path(&entry_point.internal_path, root),
+                &entry_point.conditions,
+            )
+        })
+        .collect::<Vec<_>>();
+    entry_points.sort();
+
+    output.push_str("entry points:");
+    for (external_path, internal_path, conditions) in entry_points {
+        output.push_str(&format!("\n  {external_path} -> {internal_path}"));
+        for (condition, target) in conditions {
+            output.push_str(&format!("\n    {condition}: {target}"));
+        }
+    }
+
+    if !metadata.entry_point.diagnostics.is_empty() {
+        output.push_str("\ndiagnostics:");
+        for diagnostic in &metadata.entry_point.diagnostics {
+            output.push_str(&format!("\n  {}", render_diagnostic(diagnostic, root)));
+        }
+    }
+
+    output
+}
+
+/// Renders a diagnostic with paths relative to `root`.
+fn render_diagnostic(diagnostic: &Diagnostic, root: &Path) -> String {
+    match diagnostic {
+        Diagnostic::InferredEntryPoint {
+            external_path,
+            internal_path,
+        } => format!(
+            "inferred entry point {external_path} -> {}",
+            relativise_path(internal_path, root)
+        ),
+        Diagnostic::FallbackCondition {
+            external_path,
+            conditions,
+        } => format!(
+            "fallback condition {} for {external_path}",
+            conditions.join(".")
+        ),
+        Diagnostic::SiblingDeclarations {
+            external_path,
+            conditions,
+            declarations,
+        } => format!(
+            "sibling declarations {declarations} of {} for {external_path}",
+            conditions.join(".")
+        ),
+        Diagnostic::DirectoryTarget {
+            external_path,
+            internal_path,
+            ..
+        } => format!(
+            "directory target {external_path} -> {}",
+            relativise_path(internal_path, root)
+        ),
+        Diagnostic::SourceEntryPoint {
+            external_path,
+            internal_path,
+        } => format!(
+            "source entry point {external_path} -> {}",
+            relativise_path(internal_path, root)
+        ),
+        Diagnostic::UnresolvedExternal {
+            specifier,
+            importer,
+        } => format!(
+            "unresolved external {specifier} in {}",
+            relativise_path(importer, root)
+        ),
+        Diagnostic::DependencyFallback {
+            name,
+            fallback,
+            path,
+        } => format!(
+            "dependency {name} from {fallback} -> {}",
+            relativise_path(path, root)
+        ),
+        Diagnostic::ExcludedFiles { paths } => {
+            let paths = paths
+                .iter()
+                .map(|path| relativise_path(path, root))
+                .collect::<Vec<_>>();
+            format!("excluded {}", paths.join(", "))
+        }
+        Diagnostic::NamespacesTooDeep { path } => {
+            format!("namespaces too deep in {}", relativise_path(path, root))
+        }
+        Diagnostic::CircularDefaultExport { path, name } => {
+            format!("circular default {name} in {}", relativise_path(path, root))
+        }
+        Diagnostic::AssetReferences { paths } => {
+            let paths = paths
+                .iter()
+                .map(|path| relativise_path(path, root))
+                .collect::<Vec<_>>();
+            format!("assets {}", paths.join(", "))
+        }
+        Diagnostic::UnpublishedFiles { paths, is_skipped } => {
+            let paths = paths
+                .iter()
+                .map(|path| relativise_path(path, root))
+                .collect::<Vec<_>>();
+            let action = if *is_skipped { "skipped" } else { "used" };
+            format!("{action} unpublished {}", paths.join(", "))
+        }
+    }
+}
+
+/// Renders the public API, with namespaces and symbols sorted by name.
+pub fn render_public_api(namespaces: &[Namespace]) -> String {
+    let mut namespaces = namespaces.iter().collect::<Vec<_>>();
+    namespaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let outlines = namespaces
+        .iter()
+        .map(|namespace| {
+            let mut outline = format!("namespace {}", namespace.name);
+            if let Some(doc_comment) = &namespace.doc_comment {
+                outline.push_str(&indent(doc_comment, 1));
+            }
+
+            let mut symbols = namespace.symbols.iter().collect::<Vec<_>>();
+            symbols.sort_by(|a, b| {
+                a.name
+                    .cmp(&b.name)
+                    .then_with(|| a.source_code.cmp(&b.source_code))
+            });
+            for symbol in symbols {
+                outline.push_str(&format!("\n  symbol {}", symbol.name));
+                outline.push_str(&indent(&symbol.source_code, 2));
+            }
+            outline
+        })
+        .collect::<Vec<_>>();
+
+    outlines.join("\n\n")
+}
+
+/// Makes `path` relative to `root` (if possible) and uses forward slashes regardless of the platform.
+fn relativise_path(path: &Path, root: &Path) -> String {
+    to_portable_string(&paths::relativise_path(path, root))
+}
+
+/// Prefixes every line with the indentation, normalising line endings and trailing whitespace.
+fn indent(text: &str, depth: usize) -> String {
+    let prefix = "  ".repeat(depth);
+    text.lines()
+        .map(|line| format!("\n{prefix}{}", line.trim_end()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Diagnostic;
+    use crate::metadata::{TSEntryPoint, TSEntryPointSet};
+    use std::collections::BTreeMap;
+
+    mod fixtures {
+        use super::*;
+        use crate::TypeScriptExtractor;
+        use daipendency_testing::tempdir::TempDir;
+
+        #[test]
+        fn corpus_fixtures_exist() {
+            for name in CORPUS_FIXTURES {
+                let fixture_path = get_corpus_fixture_path(name);
+
+                assert!(fixture_path.join("package/package.json").is_file());
+            }
+        }
+
+        #[test]
+        fn single_file_package() {
+            let temp_dir = TempDir::new();
+
+            write_single_file_package(&temp_dir.path, 2).unwrap();
+
+            let report = TypeScriptExtractor::default()
+                .extract(&temp_dir.path)
+                .unwrap();
+            assert_eq!(report.stats.module_count, 1);
+            assert_eq!(report.stats.symbol_count, 6);
+        }
+    }
+
+    mod library_metadata {
+        use super::*;
+
+        #[test]
+        fn entry_points_are_relative_and_sorted() {
+            let root = PathBuf::from("/tmp/package");
+            let metadata = TSLibraryMetadata {
+                name: "test-pkg".to_string(),
+                version: Some("1.0.0".to_string()),
+                documentation: String::new(),
+                entry_point: TSEntryPointSet::from_iter([
+                    TSEntryPoint {
+                        external_path: "./utils".to_string(),
+                        internal_path: root.join("dist/utils.d.ts"),
+                        conditions: BTreeMap::new(),
+                    },
+                    TSEntryPoint {
+                        external_path: ".".to_string(),
+                        internal_path: root.join("dist/index.d.ts"),
+                        conditions: BTreeMap::new(),
+                    },
+                ]),
+            };
+
+            let output = render_library_metadata(&metadata, &root);
+
+            assert_eq!(
+                output,
+                "name: test-pkg\nversion: 1.0.0\nentry points:\n  . -> dist/index.d.ts\n  ./utils -> dist/utils.d.ts"
+            );
+        }
+
+        #[test]
+        fn conditions() {
+            let root = PathBuf::from("/tmp/package");
+            let metadata = TSLibraryMetadata {
+                name: "test-pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point: TSEntryPointSet::from_iter([TSEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: root.join("dist/index.d.ts"),
+                    conditions: BTreeMap::from([
+                        ("import".to_string(), "./dist/index.mjs".to_string()),
+                        ("types".to_string(), "./dist/index.d.ts".to_string()),
+                    ]),
+                }]),
+            };
+
+            let output = render_library_metadata(&metadata, &root);
+
+            assert_eq!(
+                output,
+                "name: test-pkg\nentry points:\n  . -> dist/index.d.ts\n    import: ./dist/index.mjs\n    types: ./dist/index.d.ts"
+            );
+        }
+
+        #[test]
+        fn missing_version() {
+            let metadata = TSLibraryMetadata {
+                name: "test-pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point: TSEntryPointSet::default(),
+            };
+
+            let output = render_library_metadata(&metadata, Path::new("/tmp/package"));
+
+            assert_eq!(output, "name: test-pkg\nentry points:");
+        }
+
+        #[test]
+        fn type_only() {
+            let mut entry_point = TSEntryPointSet::default();
+            entry_point.is_type_only = true;
+            let metadata = TSLibraryMetadata {
+                name: "@types/pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point,
+            };
+
+            let output = render_library_metadata(&metadata, Path::new("/tmp/package"));
+
+            assert_eq!(output, "name: @types/pkg\ntypes only\nentry points:");
+        }
+
+        #[test]
+        fn diagnostics() {
+            let root = PathBuf::from("/tmp/package");
+            let entry_point = TSEntryPoint {
+                external_path: ".".to_string(),
+                internal_path: root.join("lib/index.d.ts"),
+                conditions: BTreeMap::new(),
+            };
+            let mut entry_points = TSEntryPointSet::from_iter([entry_point]);
+            entry_points
+                .diagnostics
+                .push(Diagnostic::InferredEntryPoint {
+                    external_path: ".".to_string(),
+                    internal_path: root.join("lib/index.d.ts"),
+                });
+            let metadata = TSLibraryMetadata {
+                name: "test-pkg".to_string(),
+                version: None,
+                documentation: String::new(),
+                entry_point: entry_points,
+            };
+
+            let output = render_library_metadata(&metadata, &root);
+
+            assert_eq!(
+                output,
+                "name: test-pkg\nentry points:\n  . -> lib/index.d.ts\ndiagnostics:\n  inferred entry point . -> lib/index.d.ts"
+            );
+        }
+    }
+
+    mod public_api {
+        use super::*;
+        use crate::api::test_helpers::make_symbol;
+
+        #[test]
+        fn namespaces_and_symbols_are_sorted() {
+            let namespaces = vec![
+                Namespace {
+                    name: "b".to_string(),
+                    symbols: vec![
+                        make_symbol("Zed", "type Zed = string;"),
+                        make_symbol("Alpha", "type Alpha = number;"),
+                    ],
+                    doc_comment: None,
+                },
+                Namespace {
+                    name: "a".to_string(),
+                    symbols: vec![],
+                    doc_comment: Some("/** The A namespace */".to_string()),
+                },
+            ];
+
+            let output = render_public_api(&namespaces);
+
+            assert_eq!(
+                output,
+                "namespace a\n  /** The A namespace */\n\nnamespace b\n  symbol Alpha\n    type Alpha = number;\n  symbol Zed\n    type Zed = string;"
+            );
+        }
+
+        #[test]
+        fn line_endings_are_normalised() {
+            let namespaces = vec![Namespace {
+                name: "a".to_string(),
+                symbols: vec![make_symbol(
+                    "Foo",
+                    "interface Foo {  \r\n  bar: string;\r\n}",
+                )],
+                doc_comment: None,
+            }];
+
+            let output = render_public_api(&namespaces);
+
+            assert_eq!(
+                output,
+                "namespace a\n  symbol Foo\n    interface Foo {\n      bar: string;\n    }"
+            );
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+
+/// A version of the TypeScript compiler, used to pick between declarations published for
+/// different versions of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TypeScriptVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl TypeScriptVersion {
+    /// Stands in for the newest version when none is set, so that every range without an upper
+    /// bound matches.
+    pub(crate) const LATEST: Self = Self::new(u64::MAX, u64::MAX, u64::MAX);
+
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for TypeScriptVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A range of versions, in the subset of the semver range syntax that TypeScript supports in
+/// `typesVersions` and versioned `types` conditions (e.g. `>=4.7 <5.0 || >=5.2`).
+///
+/// Each alternative is a list of comparators that must all match. Versions may be partial, where
+/// the missing components are wildcards (e.g. `5` and `5.x` both stand for `>=5.0.0 <6.0.0`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VersionRange {
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Less,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Comparator {
+    operator: Operator,
+    version: TypeScriptVersion,
+}
+
+impl Comparator {
+    fn matches(&self, version: &TypeScriptVersion) -> bool {
+        match self.operator {
+            Operator::Less => version < &self.version,
+            Operator::GreaterOrEqual => version >= &self.version,
+        }
+    }
+}
+
+impl VersionRange {
+    /// Parses a range, or returns `None` if it's malformed.
+    pub fn parse(range: &str) -> Option<Self> {
+        let alternatives = range
+            .split("||")
+            .map(parse_comparators)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { alternatives })
+    }
+
+    pub fn matches(&self, version: &TypeScriptVersion) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            comparators
+                .iter()
+                .all(|comparator| comparator.matches(version))
+        })
+    }
+
+    /// Gets the lowest version in the range, which is `0.0.0` if it has no lower bound.
+    pub fn get_minimum(&self) -> TypeScriptVersion {
+        self.alternatives
+            .iter()
+            .map(|comparators| {
+                comparators
+                    .iter()
+                    .filter(|comparator| comparator.operator == Operator::GreaterOrEqual)
+                    .map(|comparator| comparator.version)
+                    .max()
+                    .unwrap_or_default()
+            })
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+/// Parses the space-separated comparators of an alternative, where an empty alternative matches
+/// any version.
+fn parse_comparators(alternative: &str) -> Option<Vec<Comparator>> {
+    let mut comparators = vec![];
+    for comparator in alternative.split_whitespace() {
+        comparators.extend(parse_comparator(comparator)?);
+    }
+    Some(comparators)
+}
+
+/// Parses a comparator into comparators using `>=` and `<` only, which turns into two comparators
+/// if it has no operator or `=` (e.g. `5.0` stands for `>=5.0.0 <5.1.0`).
+fn parse_comparator(comparator: &str) -> Option<Vec<Comparator>> {
+    let (operator, version) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|operator| Some((operator, comparator.strip_prefix(operator)?)))
+        .unwrap_or(("", comparator));
+    let components = parse_partial_version(version)?;
+    let lower = TypeScriptVersion::new(
+        components.first().copied().unwrap_or(0),
+        components.get(1).copied().unwrap_or(0),
+        components.get(2).copied().unwrap_or(0),
+    );
+    // The first version past the partial version (e.g. `5.1.0` for `5.0`).
+    let upper = match components.len() {
+        0 => None,
+        1 => Some(TypeScriptVersion::new(lower.major.saturating_add(1), 0, 0)),
+        2 => Some(TypeScriptVersion::new(
+            lower.major,
+            lower.minor.saturating_add(1),
+            0,
+        )),
+        _ => Some(TypeScriptVersion::new(
+            lower.major,
+            lower.minor,
+            lower.patch.saturating_add(1),
+        )),
+    };
+
+    let make = |operator, version| Comparator { operator, version };
+    let comparators = match (operator, upper) {
+        ("", None) | ("=", None) | (">=", None) | ("<=", None) => vec![],
+        (">", None) | ("<", None) => vec![make(Operator::Less, TypeScriptVersion::default())],
+        ("" | "=", Some(upper)) => vec![
+            make(Operator::GreaterOrEqual, lower),
+            make(Operator::Less, upper),
+        ],
+        (">=", Some(_)) => vec![make(Operator::GreaterOrEqual, lower)],
+        (">", Some(upper)) => vec![make(Operator::GreaterOrEqual, upper)],
+        ("<", Some(_)) => vec![make(Operator::Less, lower)],
+        ("<=", Some(upper)) => vec![make(Operator::Less, upper)],
+        _ => return None,
+    };
+    Some(comparators)
+}
+
+/// Parses the numeric components of a version up to the first wildcard (e.g. `[5]` for `5.x`).
+fn parse_partial_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let mut components = vec![];
+    for component in version.split('.') {
+        if matches!(component, "*" | "x" | "X") {
+            break;
+        }
+        components.push(component.parse().ok()?);
+    }
+    if components.len() > 3 {
+        return None;
+    }
+    Some(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(range: &str, version: (u64, u64, u64)) -> bool {
+        let version = TypeScriptVersion::new(version.0, version.1, version.2);
+        VersionRange::parse(range).unwrap().matches(&version)
+    }
+
+    #[test]
+    fn greater_or_equal() {
+        assert!(matches(">=5.0", (5, 0, 0)));
+        assert!(matches(">=5.0", (5, 4, 2)));
+        assert!(!matches(">=5.0", (4, 9, 5)));
+    }
+
+    #[test]
+    fn less_than() {
+        assert!(matches("<4.8", (4, 7, 4)));
+        assert!(!matches("<4.8", (4, 8, 0)));
+    }
+
+    #[test]
+    fn greater_than_partial_version() {
+        assert!(!matches(">5.0", (5, 0, 3)));
+        assert!(matches(">5.0", (5, 1, 0)));
+    }
+
+    #[test]
+    fn less_or_equal_partial_version() {
+        assert!(matches("<=4.9", (4, 9, 5)));
+        assert!(!matches("<=4.9", (5, 0, 0)));
+    }
+
+    #[test]
+    fn exact_partial_version() {
+        assert!(matches("5.0", (5, 0, 4)));
+        assert!(!matches("5.0", (5, 1, 0)));
+        assert!(matches("=5", (5, 3, 0)));
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("5.x", (5, 2, 0)));
+        assert!(!matches("5.x", (6, 0, 0)));
+        assert!(matches("*", (1, 0, 0)));
+    }
+
+    #[test]
+    fn intersection() {
+        assert!(matches(">=4.7 <5.0", (4, 9, 0)));
+        assert!(!matches(">=4.7 <5.0", (5, 0, 0)));
+    }
+
+    #[test]
+    fn alternatives() {
+        assert!(matches("<4.0 || >=5.0", (3, 9, 0)));
+        assert!(matches("<4.0 || >=5.0", (5, 1, 0)));
+        assert!(!matches("<4.0 || >=5.0", (4, 5, 0)));
+    }
+
+    #[test]
+    fn latest_version() {
+        assert!(VersionRange::parse(">=5.0")
+            .unwrap()
+            .matches(&TypeScriptVersion::LATEST));
+        assert!(!VersionRange::parse("<5.0")
+            .unwrap()
+            .matches(&TypeScriptVersion::LATEST));
+    }
+
+    #[test]
+    fn malformed_ranges() {
+        assert_eq!(VersionRange::parse(">=five"), None);
+        assert_eq!(VersionRange::parse("~5.0"), None);
+        assert_eq!(VersionRange::parse("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn minimum() {
+        let get_minimum = |range: &str| VersionRange::parse(range).unwrap().get_minimum();
+
+        assert_eq!(get_minimum(">=4.7 <5.0"), TypeScriptVersion::new(4, 7, 0));
+        assert_eq!(get_minimum("<5.0"), TypeScriptVersion::new(0, 0, 0));
+        assert_eq!(
+            get_minimum(">=5.2 || >=4.0 <4.5"),
+            TypeScriptVersion::new(4, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use daipendency_extractor::LibraryMetadataError;
+use serde::Deserialize;
+
+use crate::jsonc::parse_jsonc;
+use crate::metadata::{extract_metadata, is_glob_match, is_segment_match, TSLibraryMetadata};
+use crate::options::ExtractionOptions;
+
+/// The file where pnpm declares the packages of a workspace, as it ignores `package.json`.
+const PNPM_WORKSPACE_FILE: &str = "pnpm-workspace.yaml";
+
+/// The parts of a manifest that matter to workspaces: its name if it's a member, and its members
+/// if it's the root.
+#[derive(Debug, Deserialize)]
+struct WorkspaceManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    workspaces: Option<WorkspacesField>,
+}
+
+/// The `workspaces` field of a manifest: a list of globs as used by npm, or an object with the
+/// globs under `packages` as also allowed by Yarn.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum WorkspacesField {
+    Globs(Vec<String>),
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl WorkspacesField {
+    fn globs(&self) -> &[String] {
+        match self {
+            WorkspacesField::Globs(globs) | WorkspacesField::Object { packages: globs } => globs,
+        }
+    }
+}
+
+/// Finds the members of the workspace rooted at `path`, keyed by package name, as declared in the
+/// `workspaces` field of its `package.json` (npm and Yarn) or in its `pnpm-workspace.yaml`.
+///
+/// The result can be used as [ExtractionOptions::workspace_packages] so that members resolve each
+/// other without being installed. Members without a name are skipped, and a directory that isn't
+/// a workspace root has no members.
+pub fn find_workspace_packages(
+    path: &Path,
+) -> Result<BTreeMap<String, PathBuf>, LibraryMetadataError> {
+    let globs = read_workspace_globs(path)?;
+    let (exclusions, inclusions): (Vec<_>, Vec<_>) =
+        globs.iter().partition(|glob| glob.starts_with('!'));
+
+    let mut member_paths = inclusions
+        .iter()
+        .flat_map(|glob| expand_workspace_glob(path, glob))
+        .filter(|member_path| {
+            let relative_path = get_relative_path(member_path, path);
+            let path_segments = relative_path.split('/').collect::<Vec<_>>();
+            !exclusions.iter().any(|exclusion| {
+                let pattern_segments = get_glob_segments(&exclusion[1..]);
+                is_glob_match(&path_segments, &pattern_segments)
+            })
+        })
+        .collect::<Vec<_>>();
+    member_paths.sort();
+    member_paths.dedup();
+
+    let mut members = BTreeMap::new();
+    for member_path in member_paths {
+        let Ok(content) = std::fs::read_to_string(member_path.join("package.json")) else {
+            continue;
+        };
+        let Ok(WorkspaceManifest {
+            name: Some(name), ..
+        }) = parse_jsonc(&content)
+        else {
+            continue;
+        };
+        members.entry(name).or_insert(member_path);
+    }
+    Ok(members)
+}
+
+/// Extracts the metadata of every member of the workspace rooted at `path`, in order of package
+/// name, so that callers don't have to pick one.
+///
+/// The members are made available to each other as [ExtractionOptions::workspace_packages],
+/// unless the caller already located them.
+pub fn extract_workspace_metadata(
+    path: &Path,
+    options: &ExtractionOptions,
+) -> Result<Vec<TSLibraryMetadata>, LibraryMetadataError> {
+    let members = find_workspace_packages(path)?;
+    let mut member_options = options.clone();
+    member_options.workspace_member = None;
+    for (name, member_path) in &members {
+        member_options
+            .workspace_packages
+            .entry(name.clone())
+            .or_insert_with(|| member_path.clone());
+    }
+    members
+        .values()
+        .map(|member_path| extract_metadata(member_path, &member_options))
+        .collect()
+}
+
+/// Finds the directory of a workspace member by package name.
+pub(crate) fn find_workspace_member(
+    path: &Path,
+    name: &str,
+) -> Result<PathBuf, LibraryMetadataError> {
+    find_workspace_packages(path)?.remove(name).ok_or_else(|| {
+        LibraryMetadataError::MissingManifest(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No workspace member named '{name}' in {}", path.display()),
+        ))
+    })
+}
+
+/// Reads the globs of the workspace members from `package.json` and `pnpm-workspace.yaml`, of
+/// which at least one must exist.
+fn read_workspace_globs(path: &Path) -> Result<Vec<String>, LibraryMetadataError> {
+    let pnpm_globs = std::fs::read_to_string(path.join(PNPM_WORKSPACE_FILE))
+        .ok()
+        .map(|content| parse_pnpm_workspace(&content));
+    let manifest = match std::fs::read_to_string(path.join("package.json")) {
+        Ok(content) => Some(
+            parse_jsonc::<WorkspaceManifest>(&content)
+                .map_err(|error| LibraryMetadataError::MalformedManifest(error.to_string()))?,
+        ),
+        Err(_) if pnpm_globs.is_some() => None,
+        Err(error) => return Err(LibraryMetadataError::MissingManifest(error)),
+    };
+
+    let manifest_globs = manifest
+        .and_then(|manifest| manifest.workspaces)
+        .map(|workspaces| workspaces.globs().to_vec());
+    Ok(manifest_globs
+        .into_iter()
+        .chain(pnpm_globs)
+        .flatten()
+        .collect())
+}
+
+/// Gets the `packages` listed in a `pnpm-workspace.yaml` file.
+///
+/// Only the block list that pnpm documents is supported, which avoids depending on a YAML parser
+/// for a single list of strings.
+fn parse_pnpm_workspace(content: &str) -> Vec<String> {
+    let mut globs = vec![];
+    let mut is_in_packages = false;
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim_end();
+        let trimmed_line = line.trim_start();
+        if trimmed_line.is_empty() || trimmed_line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && !line.starts_with('-') {
+            is_in_packages = line == "packages:";
+            continue;
+        }
+        if !is_in_packages {
+            continue;
+        }
+        if let Some(item) = trimmed_line.strip_prefix('-') {
+            let item = item.trim().trim_matches(|char| char == '"' || char == '\'');
+            if !item.is_empty() {
+                globs.push(item.to_string());
+            }
+        }
+    }
+    globs
+}
+
+/// Lists the directories with a `package.json` that a workspace glob matches (e.g.
+/// `packages/*`), skipping `node_modules` directories.
+fn expand_workspace_glob(root: &Path, glob: &str) -> Vec<PathBuf> {
+    let mut member_paths = vec![];
+    expand_glob_segments(root, &get_glob_segments(glob), &mut member_paths);
+    member_paths
+}
+
+fn expand_glob_segments(directory: &Path, segments: &[&str], member_paths: &mut Vec<PathBuf>) {
+    let Some((segment, remaining_segments)) = segments.split_first() else {
+        if directory.join("package.json").is_file() {
+            member_paths.push(directory.to_path_buf());
+        }
+        return;
+    };
+    match *segment {
+        "**" => {
+            expand_glob_segments(directory, remaining_segments, member_paths);
+            for subdirectory in list_subdirectories(directory) {
+                expand_glob_segments(&subdirectory, segments, member_paths);
+            }
+        }
+        "." => expand_glob_segments(directory, remaining_segments, member_paths),
+        _ if segment.contains('*') => {
+            for subdirectory in list_subdirectories(directory) {
+                let is_match = subdirectory
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| is_segment_match(name, segment));
+                if is_match {
+                    expand_glob_segments(&subdirectory, remaining_segments, member_paths);
+                }
+            }
+        }
+        _ => {
+            let subdirectory = directory.join(segment);
+            if subdirectory.is_dir() {
+                expand_glob_segments(&subdirectory, remaining_segments, member_paths);
+            }
+        }
+    }
+}
+
+fn list_subdirectories(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return vec![];
+    };
+    let mut subdirectories = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name != "node_modules" && name != ".git")
+        })
+        .collect::<Vec<_>>();
+    subdirectories.sort();
+    subdirectories
+}
+
+fn get_glob_segments(glob: &str) -> Vec<&str> {
+    glob.trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn get_relative_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use daipendency_testing::tempdir::TempDir;
+
+    fn make_member(temp_dir: &TempDir, directory: &str, name: &str) {
+        temp_dir
+            .create_file(
+                &format!("{directory}/package.json"),
+                &format!(r#"{{"name": "{name}", "version": "1.0.0", "types": "index.d.ts"}}"#),
+            )
+            .unwrap();
+        temp_dir
+            .create_file(
+                &format!("{directory}/index.d.ts"),
+                "export declare const foo: string;",
+            )
+            .unwrap();
+    }
+
+    fn get_member_names(temp_dir: &TempDir) -> Vec<(String, String)> {
+        find_workspace_packages(&temp_dir.path)
+            .unwrap()
+            .into_iter()
+            .map(|(name, path)| (name, get_relative_path(&path, &temp_dir.path)))
+            .collect()
+    }
+
+    mod find_workspace_packages {
+        use super::*;
+
+        #[test]
+        fn npm_workspaces() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"private": true, "workspaces": ["packages/*", "tools/cli"]}"#,
+                )
+                .unwrap();
+            make_member(&temp_dir, "packages/core", "@acme/core");
+            make_member(&temp_dir, "packages/utils", "@acme/utils");
+            make_member(&temp_dir, "tools/cli", "acme-cli");
+            make_member(&temp_dir, "tools/other", "acme-other");
+
+            let members = get_member_names(&temp_dir);
+
+            assert_eq!(
+                members,
+                vec![
+                    ("@acme/core".to_string(), "packages/core".to_string()),
+                    ("@acme/utils".to_string(), "packages/utils".to_string()),
+                    ("acme-cli".to_string(), "tools/cli".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn yarn_workspaces_object() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"private": true, "workspaces": {"packages": ["packages/*"], "nohoist": ["**/react"]}}"#,
+                )
+                .unwrap();
+            make_member(&temp_dir, "packages/core", "core");
+
+            let members = get_member_names(&temp_dir);
+
+            assert_eq!(
+                members,
+                vec![("core".to_string(), "packages/core".to_string())]
+            );
+        }
+
+        #[test]
+        fn pnpm_workspace() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    PNPM_WORKSPACE_FILE,
+                    "# Members\npackages:\n  - 'packages/**'\n  - \"!packages/**/test\" # Fixtures\ncatalog:\n  react: ^18.0.0\n",
+                )
+                .unwrap();
+            make_member(&temp_dir, "packages/core", "core");
+            make_member(&temp_dir, "packages/nested/utils", "utils");
+            make_member(&temp_dir, "packages/nested/test", "test");
+            make_member(&temp_dir, "packages/core/node_modules/dep", "dep");
+
+            let members = get_member_names(&temp_dir);
+
+            assert_eq!(
+                members,
+                vec![
+                    ("core".to_string(), "packages/core".to_string()),
+                    ("utils".to_string(), "packages/nested/utils".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn negated_globs() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file(
+                    "package.json",
+                    r#"{"workspaces": ["packages/*", "!packages/internal-*"]}"#,
+                )
+                .unwrap();
+            make_member(&temp_dir, "packages/core", "core");
+            make_member(&temp_dir, "packages/internal-tools", "internal-tools");
+
+            let members = get_member_names(&temp_dir);
+
+            assert_eq!(
+                members,
+                vec![("core".to_string(), "packages/core".to_string())]
+            );
+        }
+
+        #[test]
+        fn directories_without_names_or_manifests() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"workspaces": ["packages/*"]}"#)
+                .unwrap();
+            temp_dir
+                .create_file("packages/unnamed/package.json", r#"{"private": true}"#)
+                .unwrap();
+            temp_dir
+                .create_file("packages/empty/README.md", "# Empty")
+                .unwrap();
+
+            let members = get_member_names(&temp_dir);
+
+            assert!(members.is_empty());
+        }
+
+        #[test]
+        fn not_a_workspace() {
+            let temp_dir = TempDir::new();
+            make_member(&temp_dir, ".", "pkg");
+
+            let members = get_member_names(&temp_dir);
+
+            assert!(members.is_empty());
+        }
+
+        #[test]
+        fn missing_manifest() {
+            let temp_dir = TempDir::new();
+
+            let result = find_workspace_packages(&temp_dir.path);
+
+            assert_matches!(result, Err(LibraryMetadataError::MissingManifest(_)));
+        }
+    }
+
+    mod extract_workspace_metadata {
+        use super::*;
+
+        #[test]
+        fn every_member() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"workspaces": ["packages/*"]}"#)
+                .unwrap();
+            make_member(&temp_dir, "packages/b", "b");
+            make_member(&temp_dir, "packages/a", "a");
+
+            let metadata =
+                extract_workspace_metadata(&temp_dir.path, &ExtractionOptions::default()).unwrap();
+
+            assert_eq!(
+                metadata
+                    .iter()
+                    .map(|metadata| metadata.name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["a", "b"]
+            );
+        }
+    }
+
+    mod workspace_member_option {
+        use super::*;
+
+        #[test]
+        fn selected_member() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"workspaces": ["packages/*"]}"#)
+                .unwrap();
+            make_member(&temp_dir, "packages/core", "@acme/core");
+            let options = ExtractionOptions {
+                workspace_member: Some("@acme/core".to_string()),
+                ..Default::default()
+            };
+
+            let metadata = extract_metadata(&temp_dir.path, &options).unwrap();
+
+            assert_eq!(metadata.name, "@acme/core");
+            assert_eq!(
+                metadata.entry_point.iter().next().unwrap().internal_path,
+                temp_dir.path.join("packages/core/index.d.ts")
+            );
+        }
+
+        #[test]
+        fn unknown_member() {
+            let temp_dir = TempDir::new();
+            temp_dir
+                .create_file("package.json", r#"{"workspaces": ["packages/*"]}"#)
+                .unwrap();
+            let options = ExtractionOptions {
+                workspace_member: Some("missing".to_string()),
+                ..Default::default()
+            };
+
+            let result = extract_metadata(&temp_dir.path, &options);
+
+            assert_matches!(result, Err(LibraryMetadataError::MissingManifest(ref error)) if error.kind() == io::ErrorKind::NotFound);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Extracts real-world package layouts checked into `tests/fixtures/corpus` and compares the
+//! results with the snapshot next to each package (`expected.txt`).
+//!
+//! Run with `cargo test --features corpus`. Set `UPDATE_CORPUS=1` to overwrite the snapshots
+//! after reviewing the differences.
+
+use std::fs;
+use std::path::Path;
+
+use daipendency_extractor::{get_parser, Extractor};
+use daipendency_extractor_typescript::testing::{
+    get_corpus_fixture_path, render_library_metadata, render_public_api,
+};
+use daipendency_extractor_typescript::TypeScriptExtractor;
+
+fn extract(package_path: &Path) -> String {
+    let extractor = TypeScriptExtractor::default();
+    let metadata = match extractor.get_library_metadata(package_path) {
+        Ok(metadata) => metadata,
+        Err(error) => return format!("metadata error: {error}\n"),
+    };
+    let mut output = render_library_metadata(&metadata, package_path);
+
+    let mut parser = get_parser(&extractor.get_parser_language()).unwrap();
+    match extractor.extract_public_api(&metadata, &mut parser) {
+        Ok(namespaces) => output.push_str(&format!("\n\n{}\n", render_public_api(&namespaces))),
+        Err(error) => output.push_str(&format!("\n\nextraction error: {error}\n")),
+    }
+    output
+}
+
+fn check_fixture(name: &str) {
+    let fixture_path = get_corpus_fixture_path(name);
+    let snapshot_path = fixture_path.join("expected.txt");
+
+    let output = extract(&fixture_path.join("package"));
+
+    if std::env::var_os("UPDATE_CORPUS").is_some() {
+        fs::write(&snapshot_path, &output).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&snapshot_path).unwrap_or_default();
+    assert_eq!(
+        output,
+        expected,
+        "Extraction of '{name}' differs from {}",
+        snapshot_path.display()
+    );
+}
+
+#[test]
+fn react() {
+    check_fixture("react");
+}
+
+#[test]
+fn zod() {
+    check_fixture("zod");
+}
+
+#[test]
+fn mcp_sdk() {
+    check_fixture("mcp-sdk");
+}
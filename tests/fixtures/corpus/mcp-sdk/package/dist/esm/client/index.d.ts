@@ -0,0 +1,15 @@
+import { Protocol, ProtocolOptions } from "../shared/protocol.js";
+import { Transport } from "../shared/transport.js";
+import { ClientCapabilities, Implementation, ServerCapabilities } from "../types.js";
+export type ClientOptions = ProtocolOptions & {
+    capabilities?: ClientCapabilities;
+};
+/**
+ * An MCP client on top of a pluggable transport.
+ */
+export declare class Client extends Protocol {
+    private _clientInfo;
+    constructor(_clientInfo: Implementation, options?: ClientOptions);
+    connect(transport: Transport): Promise<void>;
+    getServerCapabilities(): ServerCapabilities | undefined;
+}
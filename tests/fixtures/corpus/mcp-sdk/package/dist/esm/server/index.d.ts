@@ -0,0 +1,13 @@
+import { Protocol, ProtocolOptions } from "../shared/protocol.js";
+import { Implementation, ServerCapabilities } from "../types.js";
+export type ServerOptions = ProtocolOptions & {
+    capabilities?: ServerCapabilities;
+    instructions?: string;
+};
+/**
+ * An MCP server on top of a pluggable transport.
+ */
+export declare class Server extends Protocol {
+    private _serverInfo;
+    constructor(_serverInfo: Implementation, options?: ServerOptions);
+}
@@ -0,0 +1,12 @@
+import { Transport } from "./transport.js";
+export type ProtocolOptions = {
+    enforceStrictCapabilities?: boolean;
+};
+/**
+ * Implements MCP protocol framing on top of a pluggable transport.
+ */
+export declare abstract class Protocol {
+    constructor(options?: ProtocolOptions);
+    connect(transport: Transport): Promise<void>;
+    close(): Promise<void>;
+}
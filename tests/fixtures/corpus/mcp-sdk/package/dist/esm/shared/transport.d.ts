@@ -0,0 +1,9 @@
+/**
+ * Describes the minimal contract for a MCP transport that a client or server can communicate over.
+ */
+export interface Transport {
+    start(): Promise<void>;
+    send(message: unknown): Promise<void>;
+    close(): Promise<void>;
+    onmessage?: (message: unknown) => void;
+}
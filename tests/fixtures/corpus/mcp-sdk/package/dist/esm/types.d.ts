@@ -0,0 +1,13 @@
+export declare const LATEST_PROTOCOL_VERSION = "2024-11-05";
+export interface Implementation {
+    name: string;
+    version: string;
+}
+export interface ServerCapabilities {
+    tools?: {
+        listChanged?: boolean;
+    };
+}
+export interface ClientCapabilities {
+    sampling?: object;
+}
@@ -0,0 +1 @@
+export const LATEST_PROTOCOL_VERSION = "2024-11-05";
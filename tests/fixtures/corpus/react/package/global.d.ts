@@ -0,0 +1,2 @@
+interface Event {}
+interface HTMLElement {}
@@ -0,0 +1,43 @@
+/**
+ * @fileoverview Type definitions for React
+ */
+/// <reference path="global.d.ts" />
+
+import * as CSS from "csstype";
+
+export = React;
+export as namespace React;
+
+declare namespace React {
+    type ReactNode = ReactElement | string | number | boolean | null | undefined;
+
+    interface ReactElement<P = any> {
+        type: string;
+        props: P;
+        key: string | null;
+    }
+
+    type Dispatch<A> = (value: A) => void;
+    type SetStateAction<S> = S | ((prevState: S) => S);
+
+    /**
+     * Returns a stateful value, and a function to update it.
+     */
+    function useState<S>(initialState: S | (() => S)): [S, Dispatch<SetStateAction<S>>];
+
+    function useEffect(effect: () => void | (() => void), deps?: ReadonlyArray<unknown>): void;
+
+    interface FunctionComponent<P = {}> {
+        (props: P): ReactNode;
+        displayName?: string | undefined;
+    }
+    type FC<P = {}> = FunctionComponent<P>;
+
+    class Component<P, S> {
+        constructor(props: P);
+        setState(state: S): void;
+        render(): ReactNode;
+    }
+
+    interface CSSProperties extends CSS.Properties<string | number> {}
+}
@@ -0,0 +1,2 @@
+export * from "./lib";
+export { default } from "./lib";
@@ -0,0 +1,15 @@
+export interface ZodIssue {
+    code: string;
+    path: (string | number)[];
+    message: string;
+}
+export declare type ZodErrorMap = (issue: ZodIssue, ctx: {
+    defaultError: string;
+}) => {
+    message: string;
+};
+export declare class ZodError<T = any> extends Error {
+    issues: ZodIssue[];
+    get errors(): ZodIssue[];
+    constructor(issues: ZodIssue[]);
+}
@@ -0,0 +1,3 @@
+import { ZodErrorMap } from "./ZodError";
+export declare function setErrorMap(map: ZodErrorMap): void;
+export declare function getErrorMap(): ZodErrorMap;
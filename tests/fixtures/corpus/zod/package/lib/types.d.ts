@@ -0,0 +1,29 @@
+import { ZodError } from "./ZodError";
+export declare type RawCreateParams = {
+    description?: string;
+} | undefined;
+export declare type SafeParseReturnType<Input, Output> = {
+    success: true;
+    data: Output;
+} | {
+    success: false;
+    error: ZodError<Input>;
+};
+export declare abstract class ZodType<Output = any, Input = Output> {
+    readonly _output: Output;
+    parse(data: unknown): Output;
+    safeParse(data: unknown): SafeParseReturnType<Input, Output>;
+    optional(): ZodOptional<this>;
+}
+export declare type ZodTypeAny = ZodType<any, any>;
+export declare type TypeOf<T extends ZodType<any, any>> = T["_output"];
+export declare class ZodString extends ZodType<string> {
+    min(minLength: number, message?: string): ZodString;
+    email(message?: string): ZodString;
+}
+export declare class ZodOptional<T extends ZodTypeAny> extends ZodType<T["_output"] | undefined> {
+    unwrap(): T;
+}
+declare const stringType: (params?: RawCreateParams) => ZodString;
+declare const optionalType: <T extends ZodTypeAny>(type: T) => ZodOptional<T>;
+export { stringType as string, optionalType as optional };